@@ -93,9 +93,9 @@ fn main() {
     };
 
     match fmod.init() {
-        rfmod::Status::Ok => {}
-        e => {
-            panic!("FmodSys.init failed : {:?}", e);
+        Ok(()) => {}
+        Err(e) => {
+            panic!("FmodSys.init failed : {}", e);
         }
     };
 
@@ -134,7 +134,7 @@ fn main() {
         }
     };
 
-    dsp.set_bypass(true);
+    dsp.set_bypass(true).unwrap();
     let connection = match fmod.add_DSP(&dsp) {
         Ok(c) => c,
         Err(e) => {
@@ -146,9 +146,9 @@ fn main() {
     loop {
         match get_key() as char {
             'f' => {
-                dsp.set_bypass(active);
+                dsp.set_bypass(active).unwrap();
                 active = !active;
-                fmod.update();
+                fmod.update().unwrap();
             }
             c if c == 27u8 as char => break,
             _ => {}
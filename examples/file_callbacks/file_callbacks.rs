@@ -75,20 +75,20 @@ fn main() {
     };
 
     match fmod.init_with_parameters(1i32, rfmod::InitFlag(rfmod::INIT_NORMAL)) {
-        rfmod::Status::Ok => {}
-        e => {
-            panic!("Sys::init() failed : {:?}", e);
+        Ok(()) => {}
+        Err(e) => {
+            panic!("Sys::init() failed : {}", e);
         }
     };
 
-    match fmod.set_file_system(Some(my_open as fn(&_, _) -> _),
-        Some(my_close as fn(&mut _, Option<&mut _>)),
-        Some(my_read as fn(&mut _, &mut _, _, Option<&mut _>) -> _),
-        Some(my_seek as fn(&mut _, _, Option<&mut _>)),
+    match fmod.set_file_system(Some(Box::new(my_open)),
+        Some(Box::new(my_close)),
+        Some(Box::new(my_read)),
+        Some(Box::new(my_seek)),
         2048i32) {
-        rfmod::Status::Ok => {}
-        e => {
-            panic!("FmodSys.set_file_system failed : {:?}", e);
+        Ok(()) => {}
+        Err(e) => {
+            panic!("FmodSys.set_file_system failed : {}", e);
         }
     };
 
@@ -123,7 +123,7 @@ fn main() {
                 match nb {
                     -1 => return,
                     nb if nb < num_drivers as isize => {
-                        fmod.set_driver(nb as i32);
+                        fmod.set_driver(nb as i32).unwrap();
                         break;
                     }
                     _ => {
@@ -138,9 +138,9 @@ fn main() {
     }
 
     match fmod.init() {
-        rfmod::Status::Ok => {}
-        e => {
-            panic!("FmodSys.init failed : {:?}", e);
+        Ok(()) => {}
+        Err(e) => {
+            panic!("FmodSys.init failed : {}", e);
         }
     };
 
@@ -173,7 +173,7 @@ fn main() {
                 match match nb {
                     0 => {
                         match fmod.start_record(record_driver, &sound, false) {
-                            rfmod::Status::Ok => {
+                            Ok(()) => {
                                 while match fmod.is_recording(record_driver) {
                                     Ok(r) => r,
                                     Err(e) => {
@@ -188,18 +188,18 @@ fn main() {
                                             return;
                                         }
                                     });
-                                    fmod.update();
+                                    fmod.update().unwrap();
                                     sleep(Duration::from_millis(15))
                                 }
-                                Some(rfmod::Status::Ok)
+                                None
                             }
-                            e => Some(e)
+                            Err(e) => Some(e)
                         }
                     },
                     1 => {
                         match sound.play() {
                             Ok(chan) => {
-                                fmod.update();
+                                fmod.update().unwrap();
                                 while match chan.is_playing() {
                                     Ok(p) => p,
                                     Err(e) => {
@@ -220,10 +220,10 @@ fn main() {
                                             return;
                                         }
                                     });
-                                    fmod.update();
+                                    fmod.update().unwrap();
                                     sleep(Duration::from_millis(15));
                                 }
-                                Some(rfmod::Status::Ok)
+                                None
                             }
                             Err(e) => Some(e)
                         }
@@ -238,12 +238,9 @@ fn main() {
                             Ok(_) => {
                                 name.pop().unwrap();
                                 match sound.save_to_wav(&name) {
-                                    Ok(b) => if b {
+                                    Ok(()) => {
                                         println!("export succeeded");
                                         None
-                                    } else {
-                                        println!("export failed");
-                                        None
                                     },
                                     Err(e) => {
                                         println!("save_to_wav error: {}", e);
@@ -257,9 +254,8 @@ fn main() {
                     -1 => break,
                     _ => None
                 } {
-                    Some(r) if r == rfmod::Status::Ok => {}
                     Some(e) => {
-                        println!("Error : {:?}", e);
+                        println!("Error : {}", e);
                         break;
                     }
                     None => {}
@@ -29,7 +29,7 @@ extern crate rfmod;
 use std::thread::sleep;
 use std::time::Duration;
 
-fn play_to_the_end(sound: rfmod::Sound, len: usize) -> rfmod::Status {
+fn play_to_the_end(sound: rfmod::Sound, len: usize) -> Result<(), rfmod::FmodError> {
     let length = match sound.get_length(rfmod::TIMEUNIT_MS) {
         Ok(l) => l,
         Err(e) => panic!("sound.get_length error: {:?}", e)
@@ -63,12 +63,12 @@ fn play_to_the_end(sound: rfmod::Sound, len: usize) -> rfmod::Status {
                             break;
                         }
                     },
-                    Err(e) => return e,
+                    Err(e) => return Err(e),
                 }
             }
-            rfmod::Status::Ok
+            Ok(())
         }
-        Err(err) => err,
+        Err(err) => Err(err),
     }
 }
 
@@ -92,9 +92,9 @@ fn main() {
     };
 
     match fmod.init() {
-        rfmod::Status::Ok => {}
-        e => {
-            panic!("Sys::init() failed : {:?}", e);
+        Ok(()) => {}
+        Err(e) => {
+            panic!("Sys::init() failed : {}", e);
         }
     };
 
@@ -108,11 +108,11 @@ fn main() {
     };
 
     match play_to_the_end(sound, arg1.len()) {
-        rfmod::Status::Ok => {
+        Ok(()) => {
             println!("Ok !");
         },
-        err => {
-            panic!("Sys::play_to_the_end() : {:?}", err);
+        Err(err) => {
+            panic!("Sys::play_to_the_end() : {}", err);
         }
     };
 }
\ No newline at end of file
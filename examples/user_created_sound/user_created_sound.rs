@@ -87,9 +87,9 @@ fn main() {
     };
 
     match fmod.init_with_parameters(32i32, rfmod::InitFlag(rfmod::INIT_NORMAL)) {
-        rfmod::Status::Ok => {}
-        e => {
-            panic!("Sys::init() failed : {:?}", e);
+        Ok(()) => {}
+        Err(e) => {
+            panic!("Sys::init() failed : {}", e);
         }
     };
 
@@ -22,61 +22,247 @@
 * 3. This notice may not be removed or altered from any source distribution.
 */
 
-use FmodFile;
-use ffi::FMOD_ASYNCREADINFO;
+use ffi;
+use file_system::FmodFileStream;
+use libc::c_void;
+use std::collections::VecDeque;
+use std::io::{self, SeekFrom};
+use std::ptr;
+use std::slice;
+use std::sync::{Arc, Mutex, Condvar};
+use std::sync::atomic::{fence, Ordering};
+use std::thread::{self, JoinHandle};
 
-use libc;
+/// File handle given to FMOD by the virtual file system callbacks.
+pub type VfsFile = Mutex<Box<dyn FmodFileStream>>;
 
+/// Reads `buffer.len()` bytes from the current position of `stream`. Returns the number of bytes
+/// read and the status to give back to FMOD.
+pub fn read_full(stream: &mut dyn FmodFileStream, buffer: &mut [u8]) -> (usize, ::Status) {
+    let mut read = 0usize;
+
+    // a Read implementation may return less than asked before the end of the stream
+    while read < buffer.len() {
+        match stream.read(&mut buffer[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(_) => return (read, ::Status::FileBad),
+        }
+    }
+    if read < buffer.len() {
+        (read, ::Status::FileEOF)
+    } else {
+        (read, ::Status::Ok)
+    }
+}
+
+/// Same as [`read_full`](fn.read_full.html), from `offset`.
+pub fn read_at(stream: &mut dyn FmodFileStream, offset: u64,
+               buffer: &mut [u8]) -> (usize, ::Status) {
+    match stream.seek(SeekFrom::Start(offset)) {
+        Ok(_) => read_full(stream, buffer),
+        Err(_) => (0, ::Status::FileCouldNotSeek),
+    }
+}
+
+/// A read request made by FMOD through the asynchronous read callback. FMOD waits for its result
+/// to be set, which has to be done last.
 pub struct AsyncReadInfo {
-    /// [r] The file handle that was filled out in the open callback.
-    pub handle     : FmodFile,
-    /// [r] Seek position, make sure you read from this file offset.
-    pub offset     : u32,
-    /// [r] how many bytes requested for read.
-    pub sizebytes  : u32,
-    /// [r] 0 = low importance. 100 = extremely important (ie 'must read now or stuttering may
-    /// occur')
-    pub priority   : u32,
-    /// [w] Buffer to read file data into.
-    pub buffer     : Vec<u8>,
-    /// [r/w] Result code, Ok tells the system it is ready to consume the data. Set this last!
-    ///
-    /// Default value = FMOD_ERR_NOTREADY.
-    pub result     : ::Status,
-    #[doc(hidden)]
-    ffi: *mut ffi::FMOD_ASYNCREADINFO,
+    info: *mut ffi::FMOD_ASYNCREADINFO
 }
 
+// FMOD expects the request to be completed from another thread
+unsafe impl Send for AsyncReadInfo {}
+
 impl AsyncReadInfo {
-    #[doc(hidden)]
-    pub fn new(a: *mut ffi::FMOD_ASYNCREADINFO) -> AsyncReadInfo {
-        unsafe {
-            AsyncReadInfo {
-                handle: file::from_ffi((*a).handle),
-                offset: (*a).offset,
-                sizebytes: (*a).sizebytes,
-                priority: (*a).priority,
-                buffer: Vec::new(),
-                result: (*a).result,
+    pub fn new(info: *mut ffi::FMOD_ASYNCREADINFO) -> AsyncReadInfo {
+        AsyncReadInfo {
+            info: info
+        }
+    }
+
+    /// The file handle that was filled out in the open callback.
+    pub fn get_handle(&self) -> *mut c_void {
+        unsafe { (*self.info).handle }
+    }
+
+    /// Position in the file to read from.
+    pub fn get_offset(&self) -> u32 {
+        unsafe { (*self.info).offset }
+    }
+
+    /// Number of bytes requested.
+    pub fn get_size_bytes(&self) -> u32 {
+        unsafe { (*self.info).sizebytes }
+    }
+
+    /// 0 = low importance. 100 = extremely important (ie 'must read now or stuttering may
+    /// occur').
+    pub fn get_priority(&self) -> i32 {
+        unsafe { (*self.info).priority }
+    }
+
+    /// Reads the requested data from `file` and hands it to FMOD.
+    pub fn complete(self, file: &VfsFile) {
+        let buffer = unsafe { slice::from_raw_parts_mut((*self.info).buffer as *mut u8,
+                                                        (*self.info).sizebytes as usize) };
+        let (read, result) = match file.lock() {
+            Ok(mut stream) => read_at(&mut **stream, self.get_offset() as u64, buffer),
+            Err(_) => (0, ::Status::FileBad),
+        };
+
+        unsafe { (*self.info).bytesread = read as u32 };
+        self.set_result(result);
+    }
+
+    /// Tells FMOD the request won't be serviced.
+    pub fn cancel(self) {
+        self.set_result(::Status::FileDiskEjected);
+    }
+
+    fn set_result(self, result: ::Status) {
+        // the data has to be visible to FMOD before the result
+        fence(Ordering::Release);
+        unsafe { ptr::write_volatile(&mut (*self.info).result, result) };
+    }
+}
+
+struct QueueState {
+    pending: VecDeque<AsyncReadInfo>,
+    /// Handles of the files being read by a worker.
+    in_progress: Vec<usize>,
+    stopped: bool
+}
+
+/// Queue of the read requests of the files opened by
+/// [`Sys::set_async_file_system`](../struct.Sys.html#method.set_async_file_system), serviced by
+/// worker threads. Most important requests are serviced first.
+pub struct AsyncReadQueue {
+    state: Mutex<QueueState>,
+    available: Condvar,
+    done: Condvar
+}
+
+impl AsyncReadQueue {
+    fn new() -> AsyncReadQueue {
+        AsyncReadQueue {
+            state: Mutex::new(QueueState {
+                pending: VecDeque::new(),
+                in_progress: Vec::new(),
+                stopped: false
+            }),
+            available: Condvar::new(),
+            done: Condvar::new()
+        }
+    }
+
+    /// Queues a request. Once the workers are stopped, the request is serviced right away.
+    pub fn push(&self, info: AsyncReadInfo) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.stopped {
+            drop(state);
+            let handle = info.get_handle();
+
+            info.complete(unsafe { &*(handle as *const VfsFile) });
+            return;
+        }
+        let priority = info.get_priority();
+        let pos = state.pending.iter().position(|p| p.get_priority() < priority)
+                                      .unwrap_or(state.pending.len());
+
+        state.pending.insert(pos, info);
+        self.available.notify_one();
+    }
+
+    /// Cancels the pending requests on `handle` and waits for the ones being serviced.
+    pub fn cancel(&self, handle: *mut c_void) {
+        let mut state = self.state.lock().unwrap();
+        let mut kept = VecDeque::with_capacity(state.pending.len());
+
+        while let Some(info) = state.pending.pop_front() {
+            if info.get_handle() == handle {
+                info.cancel();
+            } else {
+                kept.push_back(info);
             }
         }
+        state.pending = kept;
+        while state.in_progress.contains(&(handle as usize)) {
+            state = self.done.wait(state).unwrap();
+        }
     }
 
-    #[doc(hidden)]
-    pub fn to_ffi(self) -> *mut ffi::FMOD_ASYNCREADINFO {
-        let mut out = self.ffi;
+    fn pop(&self) -> Option<AsyncReadInfo> {
+        let mut state = self.state.lock().unwrap();
 
-        unsafe {
-            (*out).offset = self.offset;
-            (*out).sizebytes = self.sizebytes;
-            (*out).priority = self.priority;
-            (*out).buffer = ::libc::malloc(self.buffer.len());
-            for (pos, i) in self.buffer.iter().enumerate() {
-                *(out.offset(pos)) = i;
+        loop {
+            if let Some(info) = state.pending.pop_front() {
+                state.in_progress.push(info.get_handle() as usize);
+                return Some(info);
             }
-            (*out).bytesread = self.buffer.len();
-            (*out).result = self.result;
+            if state.stopped {
+                return None;
+            }
+            state = self.available.wait(state).unwrap();
+        }
+    }
+
+    fn finish(&self, handle: *mut c_void) {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(pos) = state.in_progress.iter().position(|h| *h == handle as usize) {
+            state.in_progress.remove(pos);
+        }
+        self.done.notify_all();
+    }
+
+    fn stop(&self) {
+        self.state.lock().unwrap().stopped = true;
+        self.available.notify_all();
+    }
+}
+
+/// Worker threads servicing an [`AsyncReadQueue`](struct.AsyncReadQueue.html). When dropped, the
+/// pending requests are serviced before the workers are stopped.
+pub struct AsyncReader {
+    queue: Arc<AsyncReadQueue>,
+    workers: Vec<JoinHandle<()>>
+}
+
+impl AsyncReader {
+    pub fn new(worker_count: usize) -> AsyncReader {
+        let queue = Arc::new(AsyncReadQueue::new());
+        let workers = (0..if worker_count > 0 { worker_count } else { 1 }).map(|_| {
+            let queue = queue.clone();
+
+            thread::spawn(move || {
+                while let Some(info) = queue.pop() {
+                    let handle = info.get_handle();
+
+                    info.complete(unsafe { &*(handle as *const VfsFile) });
+                    queue.finish(handle);
+                }
+            })
+        }).collect();
+
+        AsyncReader {
+            queue: queue,
+            workers: workers
+        }
+    }
+
+    pub fn get_queue(&self) -> &Arc<AsyncReadQueue> {
+        &self.queue
+    }
+}
+
+impl Drop for AsyncReader {
+    fn drop(&mut self) {
+        self.queue.stop();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
         }
-        out
     }
 }
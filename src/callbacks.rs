@@ -32,10 +32,10 @@ use file;
     command_data2: *mut c_void) -> ::Status>;*/
 
 /* file callbacks */
-pub type FileOpenCallback = Option<fn(name: &str, unicode: i32) -> Option<(file::FmodFile, Option<fmod_sys::UserData>)>>;
-pub type FileCloseCallback = Option<fn(handle: &mut file::FmodFile, user_data: Option<&mut fmod_sys::UserData>)>;
-pub type FileReadCallback = Option<fn(handle: &mut file::FmodFile, buffer: &mut [u8], size_to_read: u32, user_data: Option<&mut fmod_sys::UserData>) -> usize>;
-pub type FileSeekCallback = Option<fn(handle: &mut file::FmodFile, pos: u32, user_data: Option<&mut fmod_sys::UserData>)>;
+pub type FileOpenCallback = Option<Box<dyn Fn(&str, i32) -> Option<(file::FmodFile, Option<fmod_sys::UserData>)> + Send + Sync>>;
+pub type FileCloseCallback = Option<Box<dyn Fn(&mut file::FmodFile, Option<&mut fmod_sys::UserData>) + Send + Sync>>;
+pub type FileReadCallback = Option<Box<dyn Fn(&mut file::FmodFile, &mut [u8], u32, Option<&mut fmod_sys::UserData>) -> usize + Send + Sync>>;
+pub type FileSeekCallback = Option<Box<dyn Fn(&mut file::FmodFile, u32, Option<&mut fmod_sys::UserData>) + Send + Sync>>;
 /*pub type FMOD_FILE_ASYNCREADCALLBACK = Option<extern "C" fn(arg1: *mut FMOD_ASYNCREADINFO, arg2: *mut c_void) -> ::Status>;
 pub type FMOD_FILE_ASYNCCANCELCALLBACK = Option<extern "C" fn(arg1: *mut c_void, arg2: *mut c_void, arg3: c_uint) -> ::Status>;*/
 
@@ -56,8 +56,7 @@ pub type FMOD_CODEC_GETPOSITIONCALLBACK = Option<extern "C" fn(codec_state: *mut
 pub type FMOD_CODEC_SOUNDCREATECALLBACK = Option<extern "C" fn(codec_state: *mut FMOD_CODEC_STATE, sub_sound: c_int, sound: *mut FMOD_SOUND) -> ::Status>;
 pub type FMOD_CODEC_METADATACALLBACK = Option<extern "C" fn(codec_state: *mut FMOD_CODEC_STATE, tag_type:TagType, name: *mut c_char, data: *mut c_void,
         data_len: c_uint, data_type:TagDataType, unique: c_int) -> ::Status>;
-pub type FMOD_CODEC_GETWAVEFORMAT = Option<extern "C" fn(codec_state: *mut FMOD_CODEC_STATE, index: c_int, wave_format: *mut FMOD_CODEC_WAVEFORMAT) -> ::Status>;
-pub type FMOD_3D_ROLLOFFCALLBACK = Option<extern "C" fn(channel: *mut FMOD_CHANNEL, distance: c_float) -> ::Status>;*/
+pub type FMOD_CODEC_GETWAVEFORMAT = Option<extern "C" fn(codec_state: *mut FMOD_CODEC_STATE, index: c_int, wave_format: *mut FMOD_CODEC_WAVEFORMAT) -> ::Status>;*/
 
 /// notify the user that the DSP has been created
 pub type DspCreateCallback = Option<fn(dsp_state: &dsp::DspState) -> ::Status>;
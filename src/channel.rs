@@ -27,18 +27,159 @@ use libc::{c_int, c_void};
 use ffi;
 use dsp::Dsp;
 use dsp_connection::DspConnection;
-use channel_group::ChannelGroup;
+use channel_group::{self, ChannelGroup};
+use dsp;
+use dsp_connection;
 use fmod_sys;
-use fmod_sys::{MemoryUsageDetails, Sys};
+use fmod_sys::{MemoryUsageDetails, Sys, SystemRef};
 use vector;
-use sound::Sound;
+use sound::{self, Sound, FmodSyncPoint};
 use std::mem::transmute;
 use std::default::Default;
+use std::sync::{Arc, Mutex};
+
+type ChannelCallback = Arc<Mutex<Box<dyn FnMut(&Channel, ChannelEvent) + Send>>>;
+
+/// Closures set with [`Channel::set_callback`](struct.Channel.html#method.set_callback), with
+/// the system, the channel index and the raw channel of their voice. The channel user data
+/// belongs to the user, so they are kept here.
+static CHANNEL_CALLBACKS : Mutex<Vec<(usize, i32, usize, ChannelCallback)>> =
+    Mutex::new(Vec::new());
+
+/// Event received by the closure given to
+/// [`Channel::set_callback`](struct.Channel.html#method.set_callback).
+pub enum ChannelEvent<'a> {
+    /// The sound ended, or the channel was stopped. This is the last event of the channel.
+    End,
+    /// The voice was swapped between virtual and real.
+    VirtualVoice {
+        /// true if the voice became virtual, false if it became real.
+        is_virtual: bool
+    },
+    /// A sync point of the playing sound has been reached.
+    SyncPoint {
+        /// Index of the sync point in the sound.
+        index: i32,
+        /// The sync point, as returned by
+        /// [`Sound::get_sync_point`](struct.Sound.html#method.get_sync_point).
+        sync_point: Option<FmodSyncPoint>
+    },
+    /// The geometry occlusion of the channel has been calculated. The values can be changed.
+    Occlusion {
+        direct: &'a mut f32,
+        reverb: &'a mut f32
+    },
+}
+
+fn get_channel_callback(channel: *mut ffi::FMOD_CHANNEL, remove: bool) -> Option<ChannelCallback> {
+    let mut callbacks = match CHANNEL_CALLBACKS.lock() {
+        Ok(c) => c,
+        Err(_) => return None,
+    };
+
+    match callbacks.iter().position(|&(_, _, c, _)| c == channel as usize) {
+        Some(pos) => if remove {
+            Some(callbacks.remove(pos).3)
+        } else {
+            Some(callbacks[pos].3.clone())
+        },
+        None => None,
+    }
+}
+
+/// Drops the closures of the voices of `system` which no longer receive events: the ones
+/// playing on `index` when another voice starts on it, or all of them when `index` is `None`
+/// and the system is released.
+pub fn remove_callbacks(system: usize, index: Option<i32>) {
+    // the closures are dropped outside of the lock, as they may own channels
+    let removed : Vec<ChannelCallback> = match CHANNEL_CALLBACKS.lock() {
+        Ok(mut callbacks) => {
+            let mut removed = Vec::new();
+            let mut pos = 0;
+
+            while pos < callbacks.len() {
+                let (s, i, _, _) = callbacks[pos];
+
+                if s == system && index.map_or(true, |index| index == i) {
+                    removed.push(callbacks.remove(pos).3);
+                } else {
+                    pos += 1;
+                }
+            }
+            removed
+        }
+        Err(_) => return,
+    };
+
+    drop(removed);
+}
+
+extern "C" fn channel_callback(channel: *mut ffi::FMOD_CHANNEL, _type: ::ChannelCallbackType,
+                               command_data1: *mut c_void,
+                               command_data2: *mut c_void) -> ::Status {
+    // the closure is removed on End: the channel handle isn't valid anymore
+    let callback = match get_channel_callback(channel,
+                                              _type == ::ChannelCallbackType::End) {
+        Some(c) => c,
+        None => return ::Status::Ok,
+    };
+    let mut direct = 0f32;
+    let mut reverb = 0f32;
+    let event = match _type {
+        ::ChannelCallbackType::End => ChannelEvent::End,
+        ::ChannelCallbackType::VirtualVoice => ChannelEvent::VirtualVoice {
+            is_virtual: command_data1 as usize != 0
+        },
+        ::ChannelCallbackType::SyncPoint => {
+            let index = command_data1 as usize as i32;
+            let mut sound = ::std::ptr::null_mut();
+            let mut sync_point = ::std::ptr::null_mut();
+            let point = match unsafe { ffi::FMOD_Channel_GetCurrentSound(channel, &mut sound) } {
+                ::Status::Ok => match unsafe { ffi::FMOD_Sound_GetSyncPoint(sound, index,
+                                                                            &mut sync_point) } {
+                    ::Status::Ok => Some(FmodSyncPoint::from_ptr(sync_point)),
+                    _ => None
+                },
+                _ => None
+            };
+
+            ChannelEvent::SyncPoint {
+                index: index,
+                sync_point: point
+            }
+        }
+        ::ChannelCallbackType::Occlusion => {
+            if command_data1.is_null() || command_data2.is_null() {
+                return ::Status::Ok;
+            }
+            direct = unsafe { *(command_data1 as *mut f32) };
+            reverb = unsafe { *(command_data2 as *mut f32) };
+            ChannelEvent::Occlusion {
+                direct: &mut direct,
+                reverb: &mut reverb
+            }
+        }
+        _ => return ::Status::Ok,
+    };
+    let is_occlusion = _type == ::ChannelCallbackType::Occlusion;
+
+    if let Ok(mut callback) = callback.lock() {
+        (*callback)(&from_callback_ptr(channel), event);
+    }
+    if is_occlusion {
+        unsafe {
+            *(command_data1 as *mut f32) = direct;
+            *(command_data2 as *mut f32) = reverb;
+        }
+    }
+    ::Status::Ok
+}
 
 /// Structure which contains data for
 /// [`Channel::set_speaker_mix`](struct.Channel.html#method.set_speaker_mix) and
 /// [`Channel::get_speaker_mix`](struct.Channel.html#method.get_speaker_mix)
 #[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SpeakerMixOptions {
     pub front_left : f32,
     pub front_right: f32,
@@ -66,6 +207,10 @@ impl Default for SpeakerMixOptions {
 }
 
 /// Structure defining the properties for a reverb source, related to a FMOD channel.
+///
+/// The connection point isn't serialized: deserialized properties get a null DSP, which makes FMOD
+/// use the default connection point.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ReverbChannelProperties {
     /// [r/w] MIN: -10000 MAX: 1000 DEFAULT: 0
     /// Direct path level
@@ -78,12 +223,19 @@ pub struct ReverbChannelProperties {
     pub flags           : u32,
     /// [r/w] See remarks.
     /// DSP network location to connect reverb for this channel.
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_connection_point"))]
     pub connection_point: Dsp
 }
 
+#[cfg(feature = "serde")]
+fn default_connection_point() -> Dsp {
+    ffi::FFI::wrap(::std::ptr::null_mut())
+}
+
 /// Channel Object
 pub struct Channel {
-    channel: *mut ffi::FMOD_CHANNEL
+    channel: *mut ffi::FMOD_CHANNEL,
+    system_ref: Option<Arc<SystemRef>>
 }
 
 impl Drop for Channel {
@@ -94,7 +246,7 @@ impl Drop for Channel {
 
 impl ffi::FFI<ffi::FMOD_CHANNEL> for Channel {
     fn wrap(channel: *mut ffi::FMOD_CHANNEL) -> Channel {
-        Channel {channel: channel}
+        Channel {channel: channel, system_ref: get_system_ref(channel)}
     }
 
     fn unwrap(c: &Channel) -> *mut ffi::FMOD_CHANNEL {
@@ -102,30 +254,62 @@ impl ffi::FFI<ffi::FMOD_CHANNEL> for Channel {
     }
 }
 
+/// Wraps a channel returned by an object sharing `system_ref`, without looking it up again.
+pub fn from_ptr(channel: *mut ffi::FMOD_CHANNEL, system_ref: Option<Arc<SystemRef>>) -> Channel {
+    Channel {channel: channel, system_ref: system_ref}
+}
+
+/// Wraps a channel given to a callback. It doesn't keep the system alive, the last reference
+/// mustn't be dropped from an FMOD thread.
+pub fn from_callback_ptr(channel: *mut ffi::FMOD_CHANNEL) -> Channel {
+    Channel {channel: channel, system_ref: None}
+}
+
+fn get_system_ref(channel: *mut ffi::FMOD_CHANNEL) -> Option<Arc<SystemRef>> {
+    let mut system = ::std::ptr::null_mut();
+
+    if channel.is_null() {
+        return None;
+    }
+    match unsafe { ffi::FMOD_Channel_GetSystemObject(channel, &mut system) } {
+        ::Status::Ok => fmod_sys::get_system_ref(system),
+        _ => None,
+    }
+}
+
 impl Channel {
     pub fn new() -> Channel {
-        Channel {channel: ::std::ptr::null_mut()}
+        Channel {channel: ::std::ptr::null_mut(), system_ref: None}
     }
 
     pub fn release(&mut self) {
         self.channel = ::std::ptr::null_mut();
+        self.system_ref = None;
     }
 
-    pub fn get_system_object(&self) -> Result<Sys, ::Status> {
+    pub fn get_system_object(&self) -> Result<Sys, ::FmodError> {
         let mut system = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_GetSystemObject(self.channel, &mut system) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(system)),
-            e => Err(e)
+            ::Status::Ok => Ok(fmod_sys::from_ptr(system, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_Channel_GetSystemObject", e))
         }
     }
 
-    pub fn stop(&self) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Stop(self.channel) }
+    pub fn stop(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_Stop(self.channel) } {
+            ::Status::Ok => {
+                // in case FMOD didn't send the End event to the closure
+                get_channel_callback(self.channel, true);
+                Ok(())
+            }
+            e => Err(::FmodError::new("FMOD_Channel_Stop", e)),
+        }
     }
 
     /// channel_offset:  0/1 -> left channel/right channel
-    pub fn get_spectrum(&self, spectrum_size: usize, channel_offset: Option<i32>, window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::Status> {
+    pub fn get_spectrum(&self, spectrum_size: usize, channel_offset: Option<i32>, window_type: Option<::DspFftWindow>)
+                       -> Result<Vec<f32>, ::FmodError> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(spectrum_size).collect();
         let c_window_type = match window_type {
             Some(wt) => wt,
@@ -138,16 +322,17 @@ impl Channel {
 
         match unsafe { ffi::FMOD_Channel_GetSpectrum(self.channel, ptr.as_mut_ptr(), spectrum_size as c_int, c_channel_offset, c_window_type) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetSpectrum", e)),
         }
     }
 
-    pub fn get_wave_data(&self, wave_size: usize, channel_offset: i32) -> Result<Vec<f32>, ::Status> {
+    pub fn get_wave_data(&self, wave_size: usize, channel_offset: i32)
+                        -> Result<Vec<f32>, ::FmodError> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(wave_size).collect();
 
         match unsafe { ffi::FMOD_Channel_GetWaveData(self.channel, ptr.as_mut_ptr(), wave_size as c_int, channel_offset) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_GetWaveData", e))
         }
     }
 
@@ -155,99 +340,111 @@ impl Channel {
         !self.channel.is_null()
     }
 
-    pub fn is_playing(&self) -> Result<bool, ::Status> {
+    pub fn is_playing(&self) -> Result<bool, ::FmodError> {
         let mut is_playing = 0;
 
         match unsafe { ffi::FMOD_Channel_IsPlaying(self.channel, &mut is_playing) } {
             ::Status::Ok => Ok(is_playing == 1),
-            err => Err(err),
+            err => Err(::FmodError::new("FMOD_Channel_IsPlaying", err)),
         }
     }
 
-    pub fn is_virtual(&self) -> Result<bool, ::Status> {
+    pub fn is_virtual(&self) -> Result<bool, ::FmodError> {
         let mut is_virtual = 0i32;
 
         match unsafe { ffi::FMOD_Channel_IsVirtual(self.channel, &mut is_virtual) } {
             ::Status::Ok => Ok(is_virtual == 1),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_IsVirtual", e))
         }
     }
 
-    pub fn get_audibility(&self) -> Result<f32, ::Status> {
+    pub fn get_audibility(&self) -> Result<f32, ::FmodError> {
         let mut audibility = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetAudibility(self.channel, &mut audibility) } {
             ::Status::Ok => Ok(audibility),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_GetAudibility", e))
         }
     }
 
-    pub fn get_current_sound(&self) -> Result<Sound, ::Status> {
+    pub fn get_current_sound(&self) -> Result<Sound, ::FmodError> {
         let mut sound = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_GetCurrentSound(self.channel, &mut sound) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound)),
-            e => Err(e)
+            ::Status::Ok => Ok(sound::from_ptr(sound, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_Channel_GetCurrentSound", e))
         }
     }
 
-    pub fn get_index(&self) -> Result<i32, ::Status> {
+    pub fn get_index(&self) -> Result<i32, ::FmodError> {
         let mut index = 0i32;
 
         match unsafe { ffi::FMOD_Channel_GetIndex(self.channel, &mut index) } {
             ::Status::Ok => Ok(index),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_GetIndex", e))
         }
     }
 
-    pub fn set_volume(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetVolume(self.channel, volume) }
+    pub fn set_volume(&self, volume: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetVolume(self.channel, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetVolume", e)),
+        }
     }
 
-    pub fn get_volume(&self) -> Result<f32, ::Status> {
+    pub fn get_volume(&self) -> Result<f32, ::FmodError> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetVolume(self.channel, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetVolume", e)),
         }
     }
 
-    pub fn set_frequency(&self, frequency: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetFrequency(self.channel, frequency) }
+    pub fn set_frequency(&self, frequency: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetFrequency(self.channel, frequency) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetFrequency", e)),
+        }
     }
 
-    pub fn get_frequency(&self) -> Result<f32, ::Status> {
+    pub fn get_frequency(&self) -> Result<f32, ::FmodError> {
         let mut frequency = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetFrequency(self.channel, &mut frequency) } {
             ::Status::Ok => Ok(frequency),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetFrequency", e)),
         }
     }
 
-    pub fn set_pan(&self, pan: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetPan(self.channel, pan) }
+    pub fn set_pan(&self, pan: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetPan(self.channel, pan) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetPan", e)),
+        }
     }
 
-    pub fn get_pan(&self) -> Result<f32, ::Status> {
+    pub fn get_pan(&self) -> Result<f32, ::FmodError> {
         let mut pan = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetPan(self.channel, &mut pan) } {
             ::Status::Ok => Ok(pan),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetPan", e)),
         }
     }
 
-    pub fn set_mute(&self, mute: bool) -> ::Status {
+    pub fn set_mute(&self, mute: bool) -> Result<(), ::FmodError> {
         let t = match mute {
             true => 1,
             false => 0,
         };
-        unsafe { ffi::FMOD_Channel_SetMute(self.channel, t) }
+        match unsafe { ffi::FMOD_Channel_SetMute(self.channel, t) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetMute", e)),
+        }
     }
 
-    pub fn get_mute(&self) -> Result<bool, ::Status> {
+    pub fn get_mute(&self) -> Result<bool, ::FmodError> {
         let mut mute = 0;
 
         match unsafe { ffi::FMOD_Channel_GetMute(self.channel, &mut mute) } {
@@ -255,19 +452,22 @@ impl Channel {
                 1 => true,
                 _ => false,
             }),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetMute", e)),
         }
     }
 
-    pub fn set_paused(&self, paused: bool) -> ::Status {
+    pub fn set_paused(&self, paused: bool) -> Result<(), ::FmodError> {
         let t: ffi::FMOD_BOOL = match paused {
             true => 1,
             false => 0,
         };
-        unsafe { ffi::FMOD_Channel_SetPaused(self.channel, t) }
+        match unsafe { ffi::FMOD_Channel_SetPaused(self.channel, t) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetPaused", e)),
+        }
     }
 
-    pub fn get_paused(&self) -> Result<bool, ::Status> {
+    pub fn get_paused(&self) -> Result<bool, ::FmodError> {
         let mut t = 0;
 
         match unsafe { ffi::FMOD_Channel_GetPaused(self.channel, &mut t) } {
@@ -275,35 +475,41 @@ impl Channel {
                 1 => true,
                 _ => false,
             }),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetPaused", e)),
         }
     }
 
     pub fn set_delay(&self, delay_type: ::DelayType, delay_hi: usize,
-                     delay_lo: usize) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetDelay(self.channel, delay_type, delay_hi as u32,
-                                            delay_lo as u32) }
+                     delay_lo: usize) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetDelay(self.channel, delay_type, delay_hi as u32,
+                                                  delay_lo as u32) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetDelay", e)),
+        }
     }
 
     pub fn get_delay(&self, delay_type: ::DelayType)
-                    -> Result<(::DelayType, usize, usize), ::Status> {
+                    -> Result<(::DelayType, usize, usize), ::FmodError> {
         let mut delaylo = 0u32;
         let mut delayhi = 0u32;
 
         match unsafe { ffi::FMOD_Channel_GetDelay(self.channel, delay_type, &mut delayhi,
                                                   &mut delaylo) } {
             ::Status::Ok => Ok((delay_type, delayhi as usize, delaylo as usize)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetDelay", e)),
         }
     }
 
-    pub fn set_speaker_mix(&self, smo: &SpeakerMixOptions) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetSpeakerMix(self.channel, smo.front_left, smo.front_right,
-                                                 smo.center, smo.lfe, smo.back_left, smo.back_right,
-                                                 smo.side_left, smo.side_right) }
+    pub fn set_speaker_mix(&self, smo: &SpeakerMixOptions) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetSpeakerMix(self.channel, smo.front_left, smo.front_right,
+                                                       smo.center, smo.lfe, smo.back_left, smo.back_right,
+                                                       smo.side_left, smo.side_right) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetSpeakerMix", e)),
+        }
     }
 
-    pub fn get_speaker_mix(&self) -> Result<SpeakerMixOptions, ::Status> {
+    pub fn get_speaker_mix(&self) -> Result<SpeakerMixOptions, ::FmodError> {
         let mut smo = SpeakerMixOptions{
                           front_left: 0f32,
                           front_right: 0f32,
@@ -321,68 +527,82 @@ impl Channel {
                                                        &mut smo.back_right, &mut smo.side_left,
                                                        &mut smo.side_right) } {
             ::Status::Ok => Ok(smo),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetSpeakerMix", e)),
         }
     }
 
-    pub fn set_speaker_level(&self, speaker: ::Speaker, levels: &mut Vec<f32>) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetSpeakerLevels(self.channel, speaker, levels.as_mut_ptr(),
-                                                    levels.len() as i32) }
+    pub fn set_speaker_level(&self, speaker: ::Speaker, levels: &mut Vec<f32>)
+                            -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetSpeakerLevels(self.channel, speaker, levels.as_mut_ptr(),
+                                                          levels.len() as i32) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetSpeakerLevels", e)),
+        }
     }
 
     pub fn get_speaker_level(&self, speaker: ::Speaker,
-                             num_levels: usize) -> Result<Vec<f32>, ::Status> {
+                             num_levels: usize) -> Result<Vec<f32>, ::FmodError> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(num_levels).collect();
 
         match unsafe { ffi::FMOD_Channel_GetSpeakerLevels(self.channel, speaker, ptr.as_mut_ptr(),
                                                           num_levels as i32) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetSpeakerLevels", e)),
         }
     }
 
-    pub fn set_input_channel_mix(&self, levels: &mut Vec<f32>) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetInputChannelMix(self.channel, levels.as_mut_ptr(),
-                                                      levels.len() as i32) }
+    pub fn set_input_channel_mix(&self, levels: &mut Vec<f32>) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetInputChannelMix(self.channel, levels.as_mut_ptr(),
+                                                            levels.len() as i32) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetInputChannelMix", e)),
+        }
     }
 
-    pub fn get_input_channel_mix(&self, num_levels: usize) -> Result<Vec<f32>, ::Status> {
+    pub fn get_input_channel_mix(&self, num_levels: usize) -> Result<Vec<f32>, ::FmodError> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(num_levels).collect();
 
         match unsafe { ffi::FMOD_Channel_GetInputChannelMix(self.channel, ptr.as_mut_ptr(),
                                                             num_levels as i32) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetInputChannelMix", e)),
         }
     }
 
-    pub fn set_priority(&self, priority: i32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetPriority(self.channel, priority) }
+    pub fn set_priority(&self, priority: i32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetPriority(self.channel, priority) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetPriority", e)),
+        }
     }
 
-    pub fn get_priority(&self) -> Result<i32, ::Status> {
+    pub fn get_priority(&self) -> Result<i32, ::FmodError> {
         let mut t = 0i32;
 
         match unsafe { ffi::FMOD_Channel_GetPriority(self.channel, &mut t) } {
             ::Status::Ok => Ok(t),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetPriority", e)),
         }
     }
 
-    pub fn set_position(&self, position: usize, TimeUnit(postype): TimeUnit) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetPosition(self.channel, position as u32, postype) }
+    pub fn set_position(&self, position: usize, TimeUnit(postype): TimeUnit)
+                       -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetPosition(self.channel, position as u32, postype) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetPosition", e)),
+        }
     }
 
-    pub fn get_position(&self, TimeUnit(postype): TimeUnit) -> Result<usize, ::Status> {
+    pub fn get_position(&self, TimeUnit(postype): TimeUnit) -> Result<usize, ::FmodError> {
         let mut t = 0u32;
 
         match unsafe { ffi::FMOD_Channel_GetPosition(self.channel, &mut t, postype) } {
             ::Status::Ok => Ok(t as usize),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetPosition", e)),
         }
     }
 
-    pub fn set_reverb_properties(&self, prop: &ReverbChannelProperties) -> ::Status {
+    pub fn set_reverb_properties(&self, prop: &ReverbChannelProperties) -> Result<(), ::FmodError> {
         let t = ffi::FMOD_REVERB_CHANNELPROPERTIES{
                     Direct: prop.direct,
                     Room: prop.room,
@@ -390,10 +610,16 @@ impl Channel {
                     ConnectionPoint: ::std::ptr::null_mut()
                 };
 
-        unsafe { ffi::FMOD_Channel_SetReverbProperties(self.channel, &t) }
+        match unsafe { ffi::FMOD_Channel_SetReverbProperties(self.channel, &t) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Channel_SetReverbProperties", e)),
+
+        }
     }
 
-    pub fn get_reverb_properties(&self) -> Result<ReverbChannelProperties, ::Status> {
+    pub fn get_reverb_properties(&self) -> Result<ReverbChannelProperties, ::FmodError> {
         let mut t = ffi::FMOD_REVERB_CHANNELPROPERTIES{
                         Direct: 0,
                         Room: 0,
@@ -406,78 +632,97 @@ impl Channel {
                 direct: t.Direct,
                 room: t.Room,
                 flags: t.Flags,
-                connection_point: ffi::FFI::wrap(t.ConnectionPoint)}),
-            e => Err(e),
+                connection_point: dsp::from_ptr(t.ConnectionPoint, self.system_ref.clone())}),
+            e => Err(::FmodError::new("FMOD_Channel_GetReverbProperties", e)),
         }
     }
 
-    pub fn set_low_pass_gain(&self, gain: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetLowPassGain(self.channel, gain) }
+    pub fn set_low_pass_gain(&self, gain: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetLowPassGain(self.channel, gain) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetLowPassGain", e)),
+        }
     }
 
-    pub fn get_low_pass_gain(&self) -> Result<f32, ::Status> {
+    pub fn get_low_pass_gain(&self) -> Result<f32, ::FmodError> {
         let mut t = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetLowPassGain(self.channel, &mut t) } {
             ::Status::Ok => Ok(t),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Channel_GetLowPassGain", e)),
         }
     }
 
-    pub fn set_channel_group(&mut self, channel_group: &ChannelGroup) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetChannelGroup(self.channel, ffi::FFI::unwrap(channel_group)) }
+    pub fn set_channel_group(&mut self, channel_group: &ChannelGroup) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetChannelGroup(self.channel, ffi::FFI::unwrap(channel_group)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetChannelGroup", e)),
+        }
     }
 
-    pub fn get_channel_group(&self) -> Result<ChannelGroup, ::Status> {
+    pub fn get_channel_group(&self) -> Result<ChannelGroup, ::FmodError> {
         let mut channel_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_GetChannelGroup(self.channel, &mut channel_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel_group)),
-            e => Err(e)
+            ::Status::Ok => Ok(channel_group::from_ptr(channel_group, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_Channel_GetChannelGroup", e))
         }
     }
 
     pub fn set_3D_attributes(&self, position: &vector::Vector,
-                             velocity: &vector::Vector) -> ::Status {
+                             velocity: &vector::Vector) -> Result<(), ::FmodError> {
         let mut t_position = vector::get_ffi(position);
         let mut t_velocity = vector::get_ffi(velocity);
 
-        unsafe { ffi::FMOD_Channel_Set3DAttributes(self.channel, &mut t_position, &mut t_velocity) }
+        match unsafe { ffi::FMOD_Channel_Set3DAttributes(self.channel, &mut t_position, &mut t_velocity) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Channel_Set3DAttributes", e)),
+
+        }
     }
 
-    pub fn get_3D_attributes(&self) -> Result<(vector::Vector, vector::Vector), ::Status> {
+    pub fn get_3D_attributes(&self) -> Result<(vector::Vector, vector::Vector), ::FmodError> {
         let mut position = vector::get_ffi(&vector::Vector::new());
         let mut velocity = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Channel_Get3DAttributes(self.channel, &mut position,
                                                          &mut velocity) } {
             ::Status::Ok => Ok((vector::from_ptr(position), vector::from_ptr(velocity))),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_Get3DAttributes", e))
         }
     }
 
-    pub fn set_3D_min_max_distance(&self, min_distance: f32, max_distance: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DMinMaxDistance(self.channel, min_distance, max_distance) }
+    pub fn set_3D_min_max_distance(&self, min_distance: f32, max_distance: f32)
+                                  -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_Set3DMinMaxDistance(self.channel, min_distance, max_distance) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_Set3DMinMaxDistance", e)),
+        }
     }
 
-    pub fn get_3D_min_max_distance(&self) -> Result<(f32, f32), ::Status> {
+    pub fn get_3D_min_max_distance(&self) -> Result<(f32, f32), ::FmodError> {
         let mut min_distance = 0f32;
         let mut max_distance = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DMinMaxDistance(self.channel, &mut min_distance,
                                                              &mut max_distance) } {
             ::Status::Ok => Ok((min_distance, max_distance)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_Get3DMinMaxDistance", e))
         }
     }
 
     pub fn set_3D_cone_settings(&self, inside_cone_angle: f32, outside_cone_angle: f32,
-                                outside_volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DConeSettings(self.channel, inside_cone_angle,
-                                                     outside_cone_angle, outside_volume) }
+                                outside_volume: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_Set3DConeSettings(self.channel, inside_cone_angle,
+                                                           outside_cone_angle, outside_volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_Set3DConeSettings", e)),
+        }
     }
 
-    pub fn get_3D_cone_settings(&self) -> Result<(f32, f32, f32), ::Status> {
+    pub fn get_3D_cone_settings(&self) -> Result<(f32, f32, f32), ::FmodError> {
         let mut inside_cone_angle = 0f32;
         let mut outside_cone_angle = 0f32;
         let mut outside_volume = 0f32;
@@ -486,36 +731,45 @@ impl Channel {
                                                            &mut outside_cone_angle,
                                                            &mut outside_volume) } {
             ::Status::Ok => Ok((inside_cone_angle, outside_cone_angle, outside_volume)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_Get3DConeSettings", e))
         }
     }
 
-    pub fn set_3D_cone_orientation(&self, orientation: &vector::Vector) -> ::Status {
+    pub fn set_3D_cone_orientation(&self, orientation: &vector::Vector) -> Result<(), ::FmodError> {
         let mut t_orientation = vector::get_ffi(orientation);
 
-        unsafe { ffi::FMOD_Channel_Set3DConeOrientation(self.channel, &mut t_orientation) }
+        match unsafe { ffi::FMOD_Channel_Set3DConeOrientation(self.channel, &mut t_orientation) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Channel_Set3DConeOrientation", e)),
+
+        }
     }
 
-    pub fn get_3D_cone_orientation(&self) -> Result<vector::Vector, ::Status> {
+    pub fn get_3D_cone_orientation(&self) -> Result<vector::Vector, ::FmodError> {
         let mut orientation = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Channel_Get3DConeOrientation(self.channel, &mut orientation) } {
             ::Status::Ok => Ok(vector::from_ptr(orientation)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_Get3DConeOrientation", e))
         }
     }
 
-    pub fn set_3D_custom_rolloff(&self, points: &Vec<vector::Vector>) -> ::Status {
+    pub fn set_3D_custom_rolloff(&self, points: &Vec<vector::Vector>) -> Result<(), ::FmodError> {
         let mut t_points = Vec::new();
 
         for tmp in points.iter() {
             t_points.push(vector::get_ffi(tmp));
         }
-        unsafe { ffi::FMOD_Channel_Set3DCustomRolloff(self.channel, t_points.as_mut_ptr(),
-                                                      points.len() as c_int) }
+        match unsafe { ffi::FMOD_Channel_Set3DCustomRolloff(self.channel, t_points.as_mut_ptr(),
+                                                            points.len() as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_Set3DCustomRolloff", e)),
+        }
     }
 
-    pub fn get_3D_custom_rolloff(&self) -> Result<Vec<vector::Vector>, ::Status> {
+    pub fn get_3D_custom_rolloff(&self) -> Result<Vec<vector::Vector>, ::FmodError> {
         let mut points = ::std::ptr::null_mut();
         let mut num_points = 0i32;
 
@@ -530,76 +784,92 @@ impl Channel {
                     }
                     Ok(ret_points)
                 }
-                e => Err(e)
+                e => Err(::FmodError::new("FMOD_Channel_Get3DCustomRolloff", e))
             }
         }
     }
 
-    pub fn set_3D_occlusion(&self, direct_occlusion: f32, reverb_occlusion: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DOcclusion(self.channel, direct_occlusion,
-                                                  reverb_occlusion) }
+    pub fn set_3D_occlusion(&self, direct_occlusion: f32, reverb_occlusion: f32)
+                           -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_Set3DOcclusion(self.channel, direct_occlusion,
+                                                        reverb_occlusion) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_Set3DOcclusion", e)),
+        }
     }
 
-    pub fn get_3D_occlusion(&self) -> Result<(f32, f32), ::Status> {
+    pub fn get_3D_occlusion(&self) -> Result<(f32, f32), ::FmodError> {
         let mut direct_occlusion = 0f32;
         let mut reverb_occlusion = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DOcclusion(self.channel, &mut direct_occlusion,
                                                         &mut reverb_occlusion) } {
             ::Status::Ok => Ok((direct_occlusion, reverb_occlusion)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_Get3DOcclusion", e))
         }
     }
 
-    pub fn set_3D_spread(&self, angle: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DSpread(self.channel, angle) }
+    pub fn set_3D_spread(&self, angle: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_Set3DSpread(self.channel, angle) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_Set3DSpread", e)),
+        }
     }
 
-    pub fn get_3D_spread(&self) -> Result<f32, ::Status> {
+    pub fn get_3D_spread(&self) -> Result<f32, ::FmodError> {
         let mut angle = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DSpread(self.channel, &mut angle) } {
             ::Status::Ok => Ok(angle),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_Get3DSpread", e))
         }
     }
 
-    pub fn set_3D_pan_level(&self, level: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DPanLevel(self.channel, level) }
+    pub fn set_3D_pan_level(&self, level: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_Set3DPanLevel(self.channel, level) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_Set3DPanLevel", e)),
+        }
     }
 
-    pub fn get_3D_pan_level(&self) -> Result<f32, ::Status> {
+    pub fn get_3D_pan_level(&self) -> Result<f32, ::FmodError> {
         let mut level = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DPanLevel(self.channel, &mut level) } {
             ::Status::Ok => Ok(level),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_Get3DPanLevel", e))
         }
     }
 
-    pub fn set_3D_doppler_level(&self, level: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DDopplerLevel(self.channel, level) }
+    pub fn set_3D_doppler_level(&self, level: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_Set3DDopplerLevel(self.channel, level) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_Set3DDopplerLevel", e)),
+        }
     }
 
-    pub fn get_3D_doppler_level(&self) -> Result<f32, ::Status> {
+    pub fn get_3D_doppler_level(&self) -> Result<f32, ::FmodError> {
         let mut level = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DDopplerLevel(self.channel, &mut level) } {
             ::Status::Ok => Ok(level),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_Get3DDopplerLevel", e))
         }
     }
 
     pub fn set_3D_distance_filter(&self, custom: bool, custom_level: f32,
-                                  center_freq: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DDistanceFilter(self.channel, if custom {
-                1
-            } else {
-                0
-            }, custom_level, center_freq) }
+                                  center_freq: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_Set3DDistanceFilter(self.channel, if custom {
+                      1
+                  } else {
+                      0
+                  }, custom_level, center_freq) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_Set3DDistanceFilter", e)),
+        }
     }
 
-    pub fn get_3D_distance_filter(&self) -> Result<(bool, f32, f32), ::Status> {
+    pub fn get_3D_distance_filter(&self) -> Result<(bool, f32, f32), ::FmodError> {
         let mut custom = 0i32;
         let mut custom_level = 0f32;
         let mut center_freq = 0f32;
@@ -608,63 +878,72 @@ impl Channel {
                                                              &mut custom_level,
                                                              &mut center_freq) } {
             ::Status::Ok => Ok((custom == 1, custom_level, center_freq)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_Get3DDistanceFilter", e))
         }
     }
 
-    pub fn get_DSP_head(&self) -> Result<Dsp, ::Status> {
+    pub fn get_DSP_head(&self) -> Result<Dsp, ::FmodError> {
         let mut dsp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_GetDSPHead(self.channel, &mut dsp) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(dsp)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp::from_ptr(dsp, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_Channel_GetDSPHead", e))
         }
     }
 
-    pub fn add_DSP(&self, dsp: &Dsp) -> Result<DspConnection, ::Status> {
+    pub fn add_DSP(&self, dsp: &Dsp) -> Result<DspConnection, ::FmodError> {
         let mut connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_AddDSP(self.channel, ffi::FFI::unwrap(dsp),
                                                 &mut connection) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(connection)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp_connection::from_ptr(connection, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_Channel_AddDSP", e))
         }
     }
 
-    pub fn set_mode(&self, Mode(mode): Mode) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetMode(self.channel, mode) }
+    pub fn set_mode(&self, Mode(mode): Mode) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetMode(self.channel, mode) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetMode", e)),
+        }
     }
 
-    pub fn get_mode(&self) -> Result<Mode, ::Status> {
+    pub fn get_mode(&self) -> Result<Mode, ::FmodError> {
         let mut mode = 0u32;
 
         match unsafe { ffi::FMOD_Channel_GetMode(self.channel, &mut mode) } {
             ::Status::Ok => Ok(Mode(mode)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_GetMode", e))
         }
     }
 
-    pub fn set_loop_count(&self, loop_count: i32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetLoopCount(self.channel, loop_count) }
+    pub fn set_loop_count(&self, loop_count: i32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetLoopCount(self.channel, loop_count) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetLoopCount", e)),
+        }
     }
 
-    pub fn get_loop_count(&self) -> Result<i32, ::Status> {
+    pub fn get_loop_count(&self) -> Result<i32, ::FmodError> {
         let mut loop_count = 0i32;
 
         match unsafe { ffi::FMOD_Channel_GetLoopCount(self.channel, &mut loop_count) } {
             ::Status::Ok => Ok(loop_count),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_GetLoopCount", e))
         }
     }
 
     pub fn set_loop_points(&self, loop_start: u32, TimeUnit(loop_start_type): TimeUnit,
-        loop_end: u32, TimeUnit(loop_end_type): TimeUnit) -> ::Status {
-            unsafe { ffi::FMOD_Channel_SetLoopPoints(self.channel, loop_start, loop_start_type,
-                                                     loop_end, loop_end_type) }
+        loop_end: u32, TimeUnit(loop_end_type): TimeUnit) -> Result<(), ::FmodError> {
+            match unsafe { ffi::FMOD_Channel_SetLoopPoints(self.channel, loop_start, loop_start_type,
+                                                           loop_end, loop_end_type) } {
+                ::Status::Ok => Ok(()),
+                e => Err(::FmodError::new("FMOD_Channel_SetLoopPoints", e)),
+            }
     }
 
     pub fn get_loop_points(&self, TimeUnit(loop_start_type): TimeUnit,
-                           TimeUnit(loop_end_type): TimeUnit) -> Result<(u32, u32), ::Status> {
+                           TimeUnit(loop_end_type): TimeUnit) -> Result<(u32, u32), ::FmodError> {
         let mut loop_start = 0u32;
         let mut loop_end = 0u32;
 
@@ -672,39 +951,90 @@ impl Channel {
                                                        loop_start_type, &mut loop_end,
                                                        loop_end_type) } {
             ::Status::Ok => Ok((loop_start, loop_end)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_GetLoopPoints", e))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetUserData(self.channel, transmute(user_data)) }
+    /// Sets a closure receiving the events of this channel, in place of the previous one. It is
+    /// dropped after the [`End`](enum.ChannelEvent.html) event, when the channel is stopped,
+    /// when another voice is started on it and when the system is released.
+    ///
+    /// Events are sent from [`Sys::update`](struct.Sys.html#method.update).
+    pub fn set_callback<F>(&self, callback: F) -> Result<(), ::FmodError>
+        where F: FnMut(&Channel, ChannelEvent) + Send + 'static {
+        let mut system = ::std::ptr::null_mut();
+        let mut index = 0i32;
+
+        match unsafe { ffi::FMOD_Channel_GetSystemObject(self.channel, &mut system) } {
+            ::Status::Ok => {}
+            e => return Err(::FmodError::new("FMOD_Channel_GetSystemObject", e)),
+        }
+        match unsafe { ffi::FMOD_Channel_GetIndex(self.channel, &mut index) } {
+            ::Status::Ok => {}
+            e => return Err(::FmodError::new("FMOD_Channel_GetIndex", e)),
+        }
+        if let Ok(mut callbacks) = CHANNEL_CALLBACKS.lock() {
+            let callback : ChannelCallback = Arc::new(Mutex::new(Box::new(callback)));
+            let entry = (system as usize, index, self.channel as usize, callback);
+
+            match callbacks.iter().position(|&(_, _, c, _)| c == self.channel as usize) {
+                Some(pos) => callbacks[pos] = entry,
+                None => callbacks.push(entry),
+            }
+        }
+        match unsafe { ffi::FMOD_Channel_SetCallback(self.channel, Some(channel_callback)) } {
+            ::Status::Ok => Ok(()),
+            e => {
+                get_channel_callback(self.channel, true);
+                Err(::FmodError::new("FMOD_Channel_SetCallback", e))
+            }
+        }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
+    /// Removes the closure set with [`set_callback`](#method.set_callback).
+    pub fn remove_callback(&self) -> Result<(), ::FmodError> {
+        get_channel_callback(self.channel, true);
+        match unsafe { ffi::FMOD_Channel_SetCallback(self.channel, None) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetCallback", e)),
+        }
+    }
+
+    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Channel_SetUserData(self.channel, transmute(user_data)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Channel_SetUserData", e)),
+        }
+    }
+
+    /// Returns None if no user data has been set.
+    pub fn get_user_data<'r, T>(&'r self) -> Result<Option<&'r mut T>, ::FmodError> {
         unsafe {
             let mut user_data : *mut c_void = ::std::ptr::null_mut();
 
             match ffi::FMOD_Channel_GetUserData(self.channel, &mut user_data) {
                ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
+                    if user_data.is_null() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(transmute::<*mut c_void, &mut T>(user_data)))
+                    }
                 },
-                e => Err(e)
+                e => Err(::FmodError::new("FMOD_Channel_GetUserData", e))
             }
         }
     }
 
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::FmodError> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_Channel_GetMemoryInfo(self.channel, memory_bits, event_memory_bits,
                                                        &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Channel_GetMemoryInfo", e))
         }
     }
 }
@@ -30,7 +30,8 @@ use dsp_connection;
 use libc::{c_int, c_void};
 use vector;
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
+use fmod_sys::{MemoryUsageDetails, SystemRef};
+use std::sync::Arc;
 use std::mem::transmute;
 use libc::{c_char};
 use std::default::Default;
@@ -38,17 +39,18 @@ use std::default::Default;
 /// ChannelGroup object
 pub struct ChannelGroup {
     channel_group: *mut ffi::FMOD_CHANNELGROUP,
+    system_ref: Option<Arc<SystemRef>>
 }
 
 impl Drop for ChannelGroup {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl ffi::FFI<ffi::FMOD_CHANNELGROUP> for ChannelGroup {
     fn wrap(channel_group: *mut ffi::FMOD_CHANNELGROUP) -> ChannelGroup {
-        ChannelGroup {channel_group: channel_group}
+        ChannelGroup {channel_group: channel_group, system_ref: get_system_ref(channel_group)}
     }
 
     fn unwrap(c: &ChannelGroup) -> *mut ffi::FMOD_CHANNELGROUP {
@@ -56,57 +58,87 @@ impl ffi::FFI<ffi::FMOD_CHANNELGROUP> for ChannelGroup {
     }
 }
 
+/// Wraps a channel group returned by an object sharing `system_ref`, without looking it up again.
+pub fn from_ptr(channel_group: *mut ffi::FMOD_CHANNELGROUP,
+                system_ref: Option<Arc<SystemRef>>) -> ChannelGroup {
+    ChannelGroup {channel_group: channel_group, system_ref: system_ref}
+}
+
+fn get_system_ref(channel_group: *mut ffi::FMOD_CHANNELGROUP) -> Option<Arc<SystemRef>> {
+    let mut system = ::std::ptr::null_mut();
+
+    if channel_group.is_null() {
+        return None;
+    }
+    match unsafe { ffi::FMOD_ChannelGroup_GetSystemObject(channel_group, &mut system) } {
+        ::Status::Ok => fmod_sys::get_system_ref(system),
+        _ => None,
+    }
+}
+
 impl ChannelGroup {
-    pub fn release(&mut self) -> ::Status {
+    pub fn release(&mut self) -> Result<(), ::FmodError> {
         if !self.channel_group.is_null() {
             match unsafe { ffi::FMOD_ChannelGroup_Release(self.channel_group) } {
                ::Status::Ok => {
                     self.channel_group = ::std::ptr::null_mut();
-                   ::Status::Ok
+                   Ok(())
                 }
-                e => e
+                e => Err(::FmodError::new("FMOD_ChannelGroup_Release", e))
             }
         } else {
-           ::Status::Ok
+           Ok(())
         }
     }
 
-    pub fn set_volume(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_SetVolume(self.channel_group, volume) }
+    pub fn set_volume(&self, volume: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_SetVolume(self.channel_group, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_SetVolume", e)),
+        }
     }
 
-    pub fn get_volume(&self) -> Result<f32, ::Status> {
+    pub fn get_volume(&self) -> Result<f32, ::FmodError> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetVolume(self.channel_group, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetVolume", e))
         }
     }
 
-    pub fn set_pitch(&self, pitch: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_SetPitch(self.channel_group, pitch) }
+    pub fn set_pitch(&self, pitch: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_SetPitch(self.channel_group, pitch) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_SetPitch", e)),
+        }
     }
 
-    pub fn get_pitch(&self) -> Result<f32, ::Status> {
+    pub fn get_pitch(&self) -> Result<f32, ::FmodError> {
         let mut pitch = 0f32;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetPitch(self.channel_group, &mut pitch) } {
             ::Status::Ok => Ok(pitch),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetPitch", e))
         }
     }
 
-    pub fn set_paused(&self, paused: bool) -> ::Status {
+    pub fn set_paused(&self, paused: bool) -> Result<(), ::FmodError> {
         let t_paused = match paused {
             true => 1,
             _ => 0
         };
 
-        unsafe { ffi::FMOD_ChannelGroup_SetPaused(self.channel_group, t_paused) }
+        match unsafe { ffi::FMOD_ChannelGroup_SetPaused(self.channel_group, t_paused) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_ChannelGroup_SetPaused", e)),
+
+        }
     }
 
-    pub fn get_paused(&self) -> Result<bool, ::Status> {
+    pub fn get_paused(&self) -> Result<bool, ::FmodError> {
         let mut paused = 0;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetPaused(self.channel_group, &mut paused) } {
@@ -114,20 +146,26 @@ impl ChannelGroup {
                 1 => true,
                 _ => false
             }),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetPaused", e))
         }
     }
 
-    pub fn set_mute(&self, mute: bool) -> ::Status {
+    pub fn set_mute(&self, mute: bool) -> Result<(), ::FmodError> {
         let t_mute = match mute {
             true => 1,
             _ => 0
         };
 
-        unsafe { ffi::FMOD_ChannelGroup_SetMute(self.channel_group, t_mute) }
+        match unsafe { ffi::FMOD_ChannelGroup_SetMute(self.channel_group, t_mute) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_ChannelGroup_SetMute", e)),
+
+        }
     }
 
-    pub fn get_mute(&self) -> Result<bool, ::Status> {
+    pub fn get_mute(&self) -> Result<bool, ::FmodError> {
         let mut mute = 0;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetMute(self.channel_group, &mut mute) } {
@@ -135,16 +173,20 @@ impl ChannelGroup {
                 1 => true,
                 _ => false
             }),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetMute", e))
         }
     }
 
-    pub fn set_3D_occlusion(&self, direct_occlusion: f32, reverb_occlusion: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_Set3DOcclusion(self.channel_group, direct_occlusion,
-                                                       reverb_occlusion) }
+    pub fn set_3D_occlusion(&self, direct_occlusion: f32, reverb_occlusion: f32)
+                           -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_Set3DOcclusion(self.channel_group, direct_occlusion,
+                                                             reverb_occlusion) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_Set3DOcclusion", e)),
+        }
     }
 
-    pub fn get_3D_occlusion(&self) -> Result<(f32, f32), ::Status> {
+    pub fn get_3D_occlusion(&self) -> Result<(f32, f32), ::FmodError> {
         let mut direct_occlusion = 0f32;
         let mut reverb_occlusion = 0f32;
 
@@ -152,28 +194,40 @@ impl ChannelGroup {
                                                              &mut direct_occlusion,
                                                              &mut reverb_occlusion) } {
             ::Status::Ok => Ok((direct_occlusion, reverb_occlusion)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_Get3DOcclusion", e))
         }
     }
 
-    pub fn stop(&self) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_Stop(self.channel_group) }
+    pub fn stop(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_Stop(self.channel_group) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_Stop", e)),
+        }
     }
 
-    pub fn override_volume(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_OverrideVolume(self.channel_group, volume) }
+    pub fn override_volume(&self, volume: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_OverrideVolume(self.channel_group, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_OverrideVolume", e)),
+        }
     }
 
-    pub fn override_frequency(&self, frequency: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_OverrideFrequency(self.channel_group, frequency) }
+    pub fn override_frequency(&self, frequency: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_OverrideFrequency(self.channel_group, frequency) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_OverrideFrequency", e)),
+        }
     }
 
-    pub fn override_pan(&self, pan: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_OverridePan(self.channel_group, pan) }
+    pub fn override_pan(&self, pan: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_OverridePan(self.channel_group, pan) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_OverridePan", e)),
+        }
     }
 
     pub fn override_reverb_properties(&self, properties: &channel::ReverbChannelProperties)
-                                      -> ::Status {
+                                      -> Result<(), ::FmodError> {
         let prop = ffi::FMOD_REVERB_CHANNELPROPERTIES{
             Direct: properties.direct,
             Room: properties.room,
@@ -181,77 +235,96 @@ impl ChannelGroup {
             ConnectionPoint: ffi::FFI::unwrap(&properties.connection_point)
         };
 
-        unsafe { ffi::FMOD_ChannelGroup_OverrideReverbProperties(self.channel_group, &prop) }
+        match unsafe { ffi::FMOD_ChannelGroup_OverrideReverbProperties(self.channel_group, &prop) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_ChannelGroup_OverrideReverbProperties", e)),
+
+        }
     }
 
-    pub fn override_3D_attributes(&self, pos: &vector::Vector, vel: &vector::Vector) -> ::Status {
+    pub fn override_3D_attributes(&self, pos: &vector::Vector, vel: &vector::Vector)
+                                 -> Result<(), ::FmodError> {
         let mut t_pos = vector::get_ffi(pos);
         let mut t_vel = vector::get_ffi(vel);
 
-        unsafe { ffi::FMOD_ChannelGroup_Override3DAttributes(self.channel_group, &mut t_pos,
-                                                             &mut t_vel) }
+        match unsafe { ffi::FMOD_ChannelGroup_Override3DAttributes(self.channel_group, &mut t_pos,
+                                                                   &mut t_vel) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_ChannelGroup_Override3DAttributes", e)),
+
+        }
     }
 
     pub fn override_speaker_mix(&self, front_left: f32, front_right: f32, center: f32, lfe: f32,
                                 back_left: f32, back_right: f32, side_left: f32,
-                                side_right: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_OverrideSpeakerMix(self.channel_group, front_left,
-                                                           front_right, center, lfe, back_left,
-                                                           back_right, side_left, side_right) }
+                                side_right: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_OverrideSpeakerMix(self.channel_group, front_left,
+                                                                 front_right, center, lfe, back_left,
+                                                                 back_right, side_left, side_right) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_OverrideSpeakerMix", e)),
+        }
     }
 
-    pub fn add_group(&self, group: &ChannelGroup) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_AddGroup(self.channel_group, group.channel_group) }
+    pub fn add_group(&self, group: &ChannelGroup) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_AddGroup(self.channel_group, group.channel_group) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_AddGroup", e)),
+        }
     }
 
-    pub fn get_num_groups(&self) -> Result<i32, ::Status> {
+    pub fn get_num_groups(&self) -> Result<i32, ::FmodError> {
         let mut index = 0i32;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetNumGroups(self.channel_group, &mut index) } {
             ::Status::Ok => Ok(index),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetNumGroups", e))
         }
     }
 
-    pub fn get_group(&self, index: i32) -> Result<ChannelGroup, ::Status> {
+    pub fn get_group(&self, index: i32) -> Result<ChannelGroup, ::FmodError> {
         let mut group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetGroup(self.channel_group, index, &mut group) } {
-            ::Status::Ok => Ok(ChannelGroup{channel_group: group}),
-            e => Err(e)
+            ::Status::Ok => Ok(from_ptr(group, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetGroup", e))
         }
     }
 
-    pub fn get_parent_group(&self) -> Result<ChannelGroup, ::Status> {
+    pub fn get_parent_group(&self) -> Result<ChannelGroup, ::FmodError> {
         let mut parent_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetParentGroup(self.channel_group,
                                                              &mut parent_group) } {
-            ::Status::Ok => Ok(ChannelGroup{channel_group: parent_group}),
-            e => Err(e)
+            ::Status::Ok => Ok(from_ptr(parent_group, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetParentGroup", e))
         }
     }
 
-    pub fn get_DSP_head(&self) -> Result<dsp::Dsp, ::Status> {
+    pub fn get_DSP_head(&self) -> Result<dsp::Dsp, ::FmodError> {
         let mut dsp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetDSPHead(self.channel_group, &mut dsp) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(dsp)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp::from_ptr(dsp, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetDSPHead", e))
         }
     }
 
-    pub fn add_DSP(&self, dsp: &dsp::Dsp) -> Result<dsp_connection::DspConnection, ::Status> {
+    pub fn add_DSP(&self, dsp: &dsp::Dsp) -> Result<dsp_connection::DspConnection, ::FmodError> {
         let mut dsp_connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_AddDSP(self.channel_group, ffi::FFI::unwrap(dsp),
                                                      &mut dsp_connection) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(dsp_connection)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp_connection::from_ptr(dsp_connection, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_AddDSP", e))
         }
     }
 
-    pub fn get_name(&self, name_len: usize) -> Result<String, ::Status> {
+    pub fn get_name(&self, name_len: usize) -> Result<String, ::FmodError> {
         let mut c = Vec::with_capacity(name_len + 1);
 
         for _ in 0..(name_len + 1) {
@@ -262,32 +335,32 @@ impl ChannelGroup {
                                                       c.as_mut_ptr() as *mut c_char,
                                                       name_len as i32) } {
             ::Status::Ok => Ok(String::from_utf8(c).unwrap()),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetName", e))
         }
     }
 
-    pub fn get_num_channels(&self) -> Result<u32, ::Status> {
+    pub fn get_num_channels(&self) -> Result<u32, ::FmodError> {
         let mut num_channels = 0i32;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetNumChannels(self.channel_group,
                                                              &mut num_channels) } {
             ::Status::Ok => Ok(num_channels as u32),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetNumChannels", e))
         }
     }
 
-    pub fn get_channel(&self, index: i32) -> Result<channel::Channel, ::Status> {
+    pub fn get_channel(&self, index: i32) -> Result<channel::Channel, ::FmodError> {
         let mut channel = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetChannel(self.channel_group, index,
                                                          &mut channel) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e)
+            ::Status::Ok => Ok(channel::from_ptr(channel, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetChannel", e))
         }
     }
 
     pub fn get_spectrum(&self, spectrum_size: usize, channel_offset: Option<i32>,
-                        window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::Status> {
+                        window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::FmodError> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(spectrum_size).collect();
         let c_window_type = match window_type {
             Some(wt) => wt,
@@ -299,24 +372,24 @@ impl ChannelGroup {
                                                           spectrum_size as c_int, c_channel_offset,
                                                           c_window_type) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetSpectrum", e)),
         }
     }
 
     pub fn get_wave_data(&self, wave_size: usize,
-                         channel_offset: i32) -> Result<Vec<f32>, ::Status> {
+                         channel_offset: i32) -> Result<Vec<f32>, ::FmodError> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(wave_size).collect();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetWaveData(self.channel_group, ptr.as_mut_ptr(),
                                                           wave_size as c_int, channel_offset) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetWaveData", e))
         }
     }
 
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::FmodError> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
@@ -324,24 +397,31 @@ impl ChannelGroup {
                                                             event_memory_bits, &mut memory_used,
                                                             &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_ChannelGroup_GetMemoryInfo", e))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_SetUserData(self.channel_group, transmute(user_data)) }
+    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_ChannelGroup_SetUserData(self.channel_group, transmute(user_data)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_ChannelGroup_SetUserData", e)),
+        }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
+    /// Returns None if no user data has been set.
+    pub fn get_user_data<'r, T>(&'r self) -> Result<Option<&'r mut T>, ::FmodError> {
         unsafe {
             let mut user_data : *mut c_void = ::std::ptr::null_mut();
 
             match ffi::FMOD_ChannelGroup_GetUserData(self.channel_group, &mut user_data) {
                ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    Ok(tmp)
+                    if user_data.is_null() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(transmute::<*mut c_void, &mut T>(user_data)))
+                    }
                 },
-                e => Err(e)
+                e => Err(::FmodError::new("FMOD_ChannelGroup_GetUserData", e))
             }
         }
     }
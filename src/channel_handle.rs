@@ -0,0 +1,277 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use ffi;
+use channel;
+use channel::Channel;
+use error::ChannelError;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Voice last started for each (system, channel index): the raw channel and its generation.
+static GENERATIONS : Mutex<Vec<(usize, i32, usize, usize)>> = Mutex::new(Vec::new());
+
+static NEXT_GENERATION : AtomicUsize = AtomicUsize::new(1);
+
+/// Returns the generation of the voice playing on `raw`, giving it a new one if `new_voice` is
+/// set or if another raw channel was recorded for this index.
+fn generation_of(system: usize, index: i32, raw: usize, new_voice: bool) -> Option<usize> {
+    let mut generations = match GENERATIONS.lock() {
+        Ok(g) => g,
+        Err(_) => return None,
+    };
+
+    match generations.iter_mut().find(|&&mut (s, i, _, _)| s == system && i == index) {
+        // another handle on the same voice shares its generation
+        Some(&mut (_, _, r, g)) if r == raw && !new_voice => Some(g),
+        Some(entry) => {
+            let g = NEXT_GENERATION.fetch_add(1, Ordering::SeqCst);
+
+            *entry = (system, index, raw, g);
+            Some(g)
+        }
+        None => {
+            let g = NEXT_GENERATION.fetch_add(1, Ordering::SeqCst);
+
+            generations.push((system, index, raw, g));
+            Some(g)
+        }
+    }
+}
+
+/// Forgets the voices of `system`, called when it is released: another system may get its
+/// address.
+pub fn remove_generations(system: usize) {
+    if let Ok(mut generations) = GENERATIONS.lock() {
+        generations.retain(|&(s, _, _, _)| s != system);
+    }
+}
+
+/// Records a voice started on `channel`, so that the handles created on the previous voice of
+/// its index see it as stolen, and drops the closure of that voice. Called by every function
+/// which starts a voice.
+pub fn start_voice(channel: *mut ffi::FMOD_CHANNEL) {
+    let mut system = ::std::ptr::null_mut();
+    let mut index = 0i32;
+
+    if unsafe { ffi::FMOD_Channel_GetSystemObject(channel, &mut system) } != ::Status::Ok ||
+       unsafe { ffi::FMOD_Channel_GetIndex(channel, &mut index) } != ::Status::Ok {
+        return;
+    }
+    generation_of(system as usize, index, channel as usize, true);
+    channel::remove_callbacks(system as usize, Some(index));
+}
+
+/// Channel which checks, before every call, that it still refers to the voice it was created
+/// for.
+///
+/// FMOD reuses channels when a voice ends or is stolen by a voice with a higher priority. The
+/// handle records the playing sound, the channel index and a generation, and returns
+/// `ChannelError::Stolen` or `ChannelError::InvalidHandle` instead of acting on another voice.
+///
+/// ```ignore
+/// let handle = rfmod::ChannelHandle::new(sound.play().unwrap()).unwrap();
+///
+/// match handle.set_volume(0.5f32) {
+///     Ok(()) => {}
+///     Err(rfmod::error::ChannelError::Stolen) => println!("the voice has been stolen"),
+///     Err(e) => panic!("{}", e),
+/// }
+/// ```
+pub struct ChannelHandle {
+    channel: Channel,
+    system: usize,
+    sound: usize,
+    index: i32,
+    generation: usize
+}
+
+impl ChannelHandle {
+    /// Creates a handle on the voice currently playing on `channel`.
+    pub fn new(channel: Channel) -> Result<ChannelHandle, ChannelError> {
+        let raw = ffi::FFI::unwrap(&channel);
+        let mut system = ::std::ptr::null_mut();
+        let mut sound = ::std::ptr::null_mut();
+        let mut index = 0i32;
+
+        match unsafe { ffi::FMOD_Channel_GetSystemObject(raw, &mut system) } {
+            ::Status::Ok => {}
+            e => return Err(ChannelError::from(::FmodError::new("FMOD_Channel_GetSystemObject",
+                                                                 e))),
+        }
+        match unsafe { ffi::FMOD_Channel_GetIndex(raw, &mut index) } {
+            ::Status::Ok => {}
+            e => return Err(ChannelError::from(::FmodError::new("FMOD_Channel_GetIndex", e))),
+        }
+        match unsafe { ffi::FMOD_Channel_GetCurrentSound(raw, &mut sound) } {
+            ::Status::Ok => {}
+            e => return Err(ChannelError::from(::FmodError::new("FMOD_Channel_GetCurrentSound",
+                                                                 e))),
+        }
+        let generation = match generation_of(system as usize, index, raw as usize, false) {
+            Some(g) => g,
+            None => return Err(ChannelError::from(::FmodError::new("ChannelHandle::new",
+                                                                    ::Status::Internal))),
+        };
+
+        Ok(ChannelHandle {
+            channel: channel,
+            system: system as usize,
+            sound: sound as usize,
+            index: index,
+            generation: generation
+        })
+    }
+
+    /// Index of the channel, as returned by
+    /// [`Channel::get_index`](struct.Channel.html#method.get_index).
+    pub fn get_index(&self) -> i32 {
+        self.index
+    }
+
+    /// Generation of the voice: it changes every time a voice is started on this channel index,
+    /// by [`Sound::play`](struct.Sound.html#method.play),
+    /// [`Dsp::play`](struct.Dsp.html#method.play) and their variants.
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Checks that the channel still plays the voice of this handle.
+    pub fn verify(&self) -> Result<(), ChannelError> {
+        let raw = ffi::FFI::unwrap(&self.channel);
+        let mut sound = ::std::ptr::null_mut();
+        let mut index = 0i32;
+
+        match unsafe { ffi::FMOD_Channel_GetIndex(raw, &mut index) } {
+            ::Status::Ok => {}
+            e => return Err(ChannelError::from(::FmodError::new("FMOD_Channel_GetIndex", e))),
+        }
+        match unsafe { ffi::FMOD_Channel_GetCurrentSound(raw, &mut sound) } {
+            ::Status::Ok => {}
+            e => return Err(ChannelError::from(::FmodError::new("FMOD_Channel_GetCurrentSound",
+                                                                 e))),
+        }
+        if index != self.index || sound as usize != self.sound {
+            return Err(ChannelError::Stolen);
+        }
+        match GENERATIONS.lock() {
+            Ok(generations) => {
+                match generations.iter().find(|&&(s, i, _, _)| s == self.system && i == index) {
+                    Some(&(_, _, _, g)) if g != self.generation => Err(ChannelError::Stolen),
+                    _ => Ok(()),
+                }
+            }
+            Err(_) => Err(ChannelError::from(::FmodError::new("ChannelHandle::verify",
+                                                               ::Status::Internal))),
+        }
+    }
+
+    /// Returns true if the channel still plays the voice of this handle.
+    pub fn is_valid(&self) -> bool {
+        self.verify().is_ok()
+    }
+
+    /// Returns the channel once checked. The check isn't repeated by the calls made on it, use
+    /// [`call`](#method.call) to run them right after it.
+    pub fn get_channel(&self) -> Result<&Channel, ChannelError> {
+        match self.verify() {
+            Ok(()) => Ok(&self.channel),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Checks the channel then runs `f` on it. The `ChannelStolen` and `InvalidHandle` statuses
+    /// returned by `f` are turned into the matching `ChannelError`.
+    pub fn call<T, F: FnOnce(&Channel) -> Result<T, ::FmodError>>(&self, f: F)
+                                                                  -> Result<T, ChannelError> {
+        match self.verify() {
+            Ok(()) => match f(&self.channel) {
+                Ok(v) => Ok(v),
+                Err(e) => Err(ChannelError::from(e)),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn stop(&self) -> Result<(), ChannelError> {
+        self.call(|c| c.stop())
+    }
+
+    pub fn is_playing(&self) -> Result<bool, ChannelError> {
+        self.call(|c| c.is_playing())
+    }
+
+    pub fn set_paused(&self, paused: bool) -> Result<(), ChannelError> {
+        self.call(|c| c.set_paused(paused))
+    }
+
+    pub fn get_paused(&self) -> Result<bool, ChannelError> {
+        self.call(|c| c.get_paused())
+    }
+
+    pub fn set_volume(&self, volume: f32) -> Result<(), ChannelError> {
+        self.call(|c| c.set_volume(volume))
+    }
+
+    pub fn get_volume(&self) -> Result<f32, ChannelError> {
+        self.call(|c| c.get_volume())
+    }
+
+    pub fn set_frequency(&self, frequency: f32) -> Result<(), ChannelError> {
+        self.call(|c| c.set_frequency(frequency))
+    }
+
+    pub fn get_frequency(&self) -> Result<f32, ChannelError> {
+        self.call(|c| c.get_frequency())
+    }
+
+    pub fn set_position(&self, position: usize, time_unit: ::TimeUnit)
+                        -> Result<(), ChannelError> {
+        self.call(|c| c.set_position(position, time_unit))
+    }
+
+    pub fn get_position(&self, time_unit: ::TimeUnit) -> Result<usize, ChannelError> {
+        self.call(|c| c.get_position(time_unit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generation_of, remove_generations};
+
+    #[test]
+    fn released_systems_lose_their_generations() {
+        // fake addresses, never given to a real system
+        let (system, other) = (usize::max_value() - 1, usize::max_value() - 2);
+        let first = generation_of(system, 0, 42, false).unwrap();
+        let kept = generation_of(other, 0, 42, false).unwrap();
+
+        assert_eq!(generation_of(system, 0, 42, false), Some(first));
+        remove_generations(system);
+        assert!(generation_of(system, 0, 42, false) != Some(first));
+        assert_eq!(generation_of(other, 0, 42, false), Some(kept));
+        remove_generations(system);
+        remove_generations(other);
+    }
+}
@@ -0,0 +1,429 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use ffi;
+use types::{Mode, TimeUnit};
+use fmod_sys::FmodCodecDescription;
+use libc::{c_char, c_int, c_uint, c_void};
+use std::ffi::CString;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::slice;
+use std::sync::{Arc, Mutex};
+
+/// Format of the PCM data a [`CodecStream`](trait.CodecStream.html) decodes to.
+#[derive(Clone)]
+pub struct CodecWaveFormat {
+    /// Name of the sound.
+    pub name: String,
+    /// Format of the decoded data, ie PCM8, PCM16.
+    pub format: ::SoundFormat,
+    /// Number of channels, ie mono = 1, stereo = 2.
+    pub channels: i32,
+    /// Default frequency in hz, ie 44100.
+    pub frequency: i32,
+    /// Length in bytes of the source data.
+    pub length_bytes: u32,
+    /// Length in decompressed PCM samples, ie length in seconds * frequency. Used by
+    /// [`Sound::get_length`](struct.Sound.html#method.get_length) and to allocate the memory of
+    /// static samples.
+    pub length_pcm: u32,
+    /// Optimal decode chunk size, in decompressed PCM samples. The read method is called with
+    /// multiples of this value.
+    pub block_align: i32,
+    /// Loop start in decompressed PCM samples.
+    pub loop_start: i32,
+    /// Loop end in decompressed PCM samples.
+    pub loop_end: i32,
+    /// Default mode of the sound (looping, 2d or 3d...).
+    pub mode: Mode,
+    /// Microsoft speaker channel mask, as defined for WAVEFORMATEXTENSIBLE. Leave at 0 to play
+    /// in natural speaker order.
+    pub channel_mask: u32
+}
+
+impl Default for CodecWaveFormat {
+    fn default() -> CodecWaveFormat {
+        CodecWaveFormat {
+            name: String::new(),
+            format: ::SoundFormat::PCM16,
+            channels: 2,
+            frequency: 44100,
+            length_bytes: 0,
+            length_pcm: 0,
+            block_align: 0,
+            loop_start: 0,
+            loop_end: 0,
+            mode: Mode(::DEFAULT),
+            channel_mask: 0
+        }
+    }
+}
+
+impl CodecWaveFormat {
+    fn convert_to_c(&self) -> ffi::FMOD_CODEC_WAVEFORMAT {
+        let mut name = [0 as c_char; 256];
+
+        for (it, b) in self.name.bytes().take(255).enumerate() {
+            name[it] = b as c_char;
+        }
+        ffi::FMOD_CODEC_WAVEFORMAT {
+            name: name,
+            format: self.format,
+            channels: self.channels,
+            frequency: self.frequency,
+            lengthbytes: self.length_bytes,
+            lengthpcm: self.length_pcm,
+            blockalign: self.block_align,
+            loopstart: self.loop_start,
+            loopend: self.loop_end,
+            mode: self.mode.0,
+            channelmask: self.channel_mask
+        }
+    }
+}
+
+/// The file FMOD is opening with a codec, read through FMOD's file system.
+///
+/// Its position is kept by the decoding state of the sound, from one call of the codec to the
+/// next.
+pub struct CodecFile<'a> {
+    state: *mut ffi::FMOD_CODEC_STATE,
+    position: &'a mut u64
+}
+
+impl<'a> CodecFile<'a> {
+    fn new(state: *mut ffi::FMOD_CODEC_STATE, position: &'a mut u64) -> CodecFile<'a> {
+        CodecFile {
+            state: state,
+            position: position
+        }
+    }
+
+    /// Size of the file in bytes.
+    pub fn get_size(&self) -> u32 {
+        unsafe { (*self.state).filesize }
+    }
+}
+
+impl<'a> Read for CodecFile<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut read = 0u32;
+        let status = match unsafe { (*self.state).fileread } {
+            Some(f) => f(unsafe { (*self.state).filehandle }, buf.as_mut_ptr() as *mut c_void,
+                         buf.len() as c_uint, &mut read, ::std::ptr::null_mut()),
+            None => ::Status::Unsupported,
+        };
+
+        match status {
+            ::Status::Ok | ::Status::FileEOF => {
+                *self.position += read as u64;
+                Ok(read as usize)
+            }
+            e => Err(io::Error::new(io::ErrorKind::Other, ::error::error_string(e))),
+        }
+    }
+}
+
+impl<'a> Seek for CodecFile<'a> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(p) => p as i64,
+            SeekFrom::Current(p) => *self.position as i64 + p,
+            SeekFrom::End(p) => self.get_size() as i64 + p,
+        };
+        if position < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "invalid seek to a negative position"));
+        }
+        let status = match unsafe { (*self.state).fileseek } {
+            Some(f) => f(unsafe { (*self.state).filehandle }, position as c_uint,
+                         ::std::ptr::null_mut()),
+            None => ::Status::Unsupported,
+        };
+
+        match status {
+            ::Status::Ok => {
+                *self.position = position as u64;
+                Ok(position as u64)
+            }
+            e => Err(io::Error::new(io::ErrorKind::Other, ::error::error_string(e))),
+        }
+    }
+}
+
+/// A user defined codec, registered with
+/// [`Sys::register_codec`](struct.Sys.html#method.register_codec).
+///
+/// FMOD tries the registered codecs on every file it opens. A codec recognizing the file returns
+/// a [`CodecStream`](trait.CodecStream.html) holding the decoding state of this sound, so a codec
+/// can decode several sounds at once.
+pub trait Codec: Send + Sync {
+    /// Name and capabilities of the codec.
+    fn get_description(&self) -> FmodCodecDescription;
+    /// Opens `file` if it is in the format of this codec. Returning
+    /// [`Status::Format`](enum.Status.html) lets FMOD try the next codec.
+    fn open(&self, file: &mut CodecFile, mode: Mode) -> Result<Box<dyn CodecStream>, ::Status>;
+}
+
+/// A sound being decoded by a [`Codec`](trait.Codec.html). It is dropped when FMOD closes the
+/// sound.
+pub trait CodecStream: Send {
+    /// Format of the decoded data.
+    fn get_wave_format(&self) -> CodecWaveFormat;
+    /// Decodes data into `buffer`, in the format returned by `get_wave_format`. Returns the number
+    /// of bytes written, 0 once the end of the sound is reached.
+    fn read(&mut self, file: &mut CodecFile, buffer: &mut [u8]) -> Result<usize, ::Status>;
+    /// Length of the sound in `time_unit`.
+    fn get_length(&mut self, _time_unit: TimeUnit) -> Result<u32, ::Status> {
+        Err(::Status::Unsupported)
+    }
+    /// Seeks to `position`, in one of the time units of the codec description.
+    fn set_position(&mut self, file: &mut CodecFile, sub_sound: i32, position: u32,
+                    time_unit: TimeUnit) -> ::Status;
+    /// Current position in `time_unit`.
+    fn get_position(&mut self, _time_unit: TimeUnit) -> Result<u32, ::Status> {
+        Err(::Status::Unsupported)
+    }
+}
+
+/// Maximum number of codecs registered at the same time.
+pub const MAX_CODECS : usize = 8;
+
+/// A registered codec, with the description FMOD keeps a pointer to.
+struct CodecEntry {
+    codec: Box<dyn Codec>,
+    #[allow(dead_code)]
+    name: CString,
+    description: ffi::FMOD_CODEC_DESCRIPTION
+}
+
+// the description only holds pointers to the name and to the callbacks
+unsafe impl Send for CodecEntry {}
+unsafe impl Sync for CodecEntry {}
+
+/// Decoding state of an opened sound, stored in the plugin data of its codec state.
+struct CodecData {
+    stream: Box<dyn CodecStream>,
+    wave_format: ffi::FMOD_CODEC_WAVEFORMAT,
+    /// Position of the `CodecFile`, left where the last call of the codec put it.
+    file_position: u64
+}
+
+static CODECS : Mutex<[Option<Arc<CodecEntry>>; MAX_CODECS]> =
+    Mutex::new([None, None, None, None, None, None, None, None]);
+
+/// FMOD doesn't give the codec description to the open callback, so each slot has its own one.
+macro_rules! codec_open_callbacks {
+    ($($slot:expr => $name:ident),*) => (
+        $(extern "C" fn $name(codec_state: *mut ffi::FMOD_CODEC_STATE, user_mode: ffi::FMOD_MODE,
+                              _: *mut ffi::FMOD_CREATESOUNDEXINFO) -> ::Status {
+            codec_open($slot, codec_state, user_mode)
+        })*
+
+        static CODEC_OPEN_CALLBACKS : [extern "C" fn(*mut ffi::FMOD_CODEC_STATE, ffi::FMOD_MODE,
+                                                     *mut ffi::FMOD_CREATESOUNDEXINFO)
+                                                     -> ::Status; MAX_CODECS] = [$($name),*];
+    )
+}
+
+codec_open_callbacks!(0 => codec_open_callback0, 1 => codec_open_callback1,
+                      2 => codec_open_callback2, 3 => codec_open_callback3,
+                      4 => codec_open_callback4, 5 => codec_open_callback5,
+                      6 => codec_open_callback6, 7 => codec_open_callback7);
+
+fn codec_open(slot: usize, codec_state: *mut ffi::FMOD_CODEC_STATE,
+              user_mode: ffi::FMOD_MODE) -> ::Status {
+    let entry = match CODECS.lock() {
+        Ok(codecs) => match codecs[slot] {
+            Some(ref e) => e.clone(),
+            None => return ::Status::Format,
+        },
+        Err(_) => return ::Status::Internal,
+    };
+    let mut file_position = 0u64;
+    let opened = entry.codec.open(&mut CodecFile::new(codec_state, &mut file_position),
+                                  Mode(user_mode));
+
+    match opened {
+        Ok(stream) => {
+            let wave_format = stream.get_wave_format().convert_to_c();
+            let data = Box::into_raw(Box::new(CodecData {
+                stream: stream,
+                wave_format: wave_format,
+                file_position: file_position
+            }));
+
+            unsafe {
+                (*codec_state).numsubsounds = 0;
+                (*codec_state).waveformat = &mut (*data).wave_format;
+                (*codec_state).plugindata = data as *mut c_void;
+            }
+            ::Status::Ok
+        }
+        Err(e) => e
+    }
+}
+
+fn get_codec_data<'r>(codec_state: *mut ffi::FMOD_CODEC_STATE) -> Option<&'r mut CodecData> {
+    if codec_state.is_null() {
+        return None;
+    }
+    let data = unsafe { (*codec_state).plugindata };
+
+    if data.is_null() {
+        None
+    } else {
+        Some(unsafe { &mut *(data as *mut CodecData) })
+    }
+}
+
+extern "C" fn codec_close_callback(codec_state: *mut ffi::FMOD_CODEC_STATE) -> ::Status {
+    if get_codec_data(codec_state).is_some() {
+        unsafe {
+            drop(Box::from_raw((*codec_state).plugindata as *mut CodecData));
+            (*codec_state).plugindata = ::std::ptr::null_mut();
+            (*codec_state).waveformat = ::std::ptr::null_mut();
+        }
+    }
+    ::Status::Ok
+}
+
+extern "C" fn codec_read_callback(codec_state: *mut ffi::FMOD_CODEC_STATE, buffer: *mut c_void,
+                                  size_bytes: c_uint, bytes_read: *mut c_uint) -> ::Status {
+    let data = match get_codec_data(codec_state) {
+        Some(d) => d,
+        None => return ::Status::InvalidParam,
+    };
+    let buffer = unsafe { slice::from_raw_parts_mut(buffer as *mut u8, size_bytes as usize) };
+
+    match data.stream.read(&mut CodecFile::new(codec_state, &mut data.file_position), buffer) {
+        Ok(read) => {
+            unsafe { *bytes_read = read as c_uint };
+            if read == 0 {
+                ::Status::FileEOF
+            } else {
+                ::Status::Ok
+            }
+        }
+        Err(e) => e
+    }
+}
+
+extern "C" fn codec_get_length_callback(codec_state: *mut ffi::FMOD_CODEC_STATE,
+                                        length: *mut c_uint,
+                                        length_type: ffi::FMOD_TIMEUNIT) -> ::Status {
+    match get_codec_data(codec_state) {
+        Some(data) => match data.stream.get_length(TimeUnit(length_type)) {
+            Ok(l) => {
+                unsafe { *length = l };
+                ::Status::Ok
+            }
+            Err(e) => e
+        },
+        None => ::Status::InvalidParam
+    }
+}
+
+extern "C" fn codec_set_position_callback(codec_state: *mut ffi::FMOD_CODEC_STATE,
+                                          sub_sound: c_int, position: c_uint,
+                                          postype: ffi::FMOD_TIMEUNIT) -> ::Status {
+    match get_codec_data(codec_state) {
+        Some(data) => data.stream.set_position(&mut CodecFile::new(codec_state,
+                                                                   &mut data.file_position),
+                                               sub_sound, position, TimeUnit(postype)),
+        None => ::Status::InvalidParam
+    }
+}
+
+extern "C" fn codec_get_position_callback(codec_state: *mut ffi::FMOD_CODEC_STATE,
+                                          position: *mut c_uint,
+                                          postype: ffi::FMOD_TIMEUNIT) -> ::Status {
+    match get_codec_data(codec_state) {
+        Some(data) => match data.stream.get_position(TimeUnit(postype)) {
+            Ok(p) => {
+                unsafe { *position = p };
+                ::Status::Ok
+            }
+            Err(e) => e
+        },
+        None => ::Status::InvalidParam
+    }
+}
+
+/// Registers `codec` to FMOD and returns its slot, to be released with
+/// [`unregister`](fn.unregister.html) once the system is released.
+pub fn register(system: *mut ffi::FMOD_SYSTEM, codec: Box<dyn Codec>,
+                priority: u32) -> Result<(usize, u32), ::FmodError> {
+    let mut codecs = match CODECS.lock() {
+        Ok(c) => c,
+        Err(_) => return Err(::FmodError::new("Sys::register_codec", ::Status::Internal)),
+    };
+    let slot = match codecs.iter().position(|c| c.is_none()) {
+        Some(s) => s,
+        None => return Err(::FmodError::new("Sys::register_codec", ::Status::PluginInstances)),
+    };
+    let description = codec.get_description();
+    let name = CString::new(description.name.clone()).unwrap_or(CString::new("").unwrap());
+    let mut entry = Arc::new(CodecEntry {
+        description: ffi::FMOD_CODEC_DESCRIPTION {
+            name: name.as_ptr() as *mut c_char,
+            version: description.version,
+            defaultasstream: description.default_as_stream,
+            timeunits: description.time_units.0,
+            open: Some(CODEC_OPEN_CALLBACKS[slot]),
+            close: Some(codec_close_callback),
+            read: Some(codec_read_callback),
+            getlength: Some(codec_get_length_callback),
+            setposition: Some(codec_set_position_callback),
+            getposition: Some(codec_get_position_callback),
+            soundcreate: None,
+            getwaveformat: None
+        },
+        codec: codec,
+        name: name
+    });
+    let mut handle = 0u32;
+    let status = match Arc::get_mut(&mut entry) {
+        Some(e) => unsafe { ffi::FMOD_System_RegisterCodec(system, &mut e.description,
+                                                           &mut handle, priority) },
+        None => ::Status::Internal,
+    };
+
+    match status {
+        ::Status::Ok => {
+            codecs[slot] = Some(entry);
+            Ok((slot, handle))
+        }
+        e => Err(::FmodError::new("FMOD_System_RegisterCodec", e))
+    }
+}
+
+/// Frees the slot of a codec whose system has been released.
+pub fn unregister(slot: usize) {
+    if let Ok(mut codecs) = CODECS.lock() {
+        if slot < MAX_CODECS {
+            codecs[slot] = None;
+        }
+    }
+}
@@ -27,9 +27,14 @@ use types::*;
 use callbacks::*;
 use dsp_connection;
 use fmod_sys;
-use fmod_sys::{MemoryUsageDetails, Sys};
+use fmod_sys::{MemoryUsageDetails, Sys, SystemRef};
+use std::sync::Arc;
 use std::mem::transmute;
 use channel;
+use channel_handle;
+use dsp_param;
+use dsp_param::DspParam;
+use error::DspParamError;
 use libc::{c_char, c_void, c_uint, c_int, c_float};
 use std::default::Default;
 use c_vec::CVec;
@@ -517,8 +522,17 @@ pub fn get_state_ffi(state: &DspState) -> ffi::FMOD_DSP_STATE {
 }
 
 pub fn from_state_ptr(state: ffi::FMOD_DSP_STATE) -> DspState {
+    // callbacks run on FMOD threads, so the instance doesn't keep the system alive
     DspState {
-        instance: ffi::FFI::wrap(state.instance),
+        instance: Dsp {
+            dsp: state.instance,
+            can_be_deleted: false,
+            user_data: UserData {
+                callbacks: DspCallbacks::new(),
+                user_data: ::std::ptr::null_mut()
+            },
+            system_ref: None
+        },
         plugin_data: state.plugin_data,
         speaker_mask: state.speaker_mask
     }
@@ -535,14 +549,45 @@ pub struct DspState {
     pub speaker_mask: u16,
 }
 
-pub fn from_ptr_first(dsp: *mut ffi::FMOD_DSP) -> Dsp {
+pub fn from_ptr_first(dsp: *mut ffi::FMOD_DSP, system_ref: Option<Arc<SystemRef>>) -> Dsp {
     Dsp {
         dsp: dsp,
         can_be_deleted: true,
         user_data: UserData {
             callbacks: DspCallbacks::new(),
             user_data: ::std::ptr::null_mut()
-        }
+        },
+        system_ref: system_ref
+    }
+}
+
+/// Wraps a DSP returned by an object sharing `system_ref`, without looking it up again.
+pub fn from_ptr(dsp: *mut ffi::FMOD_DSP, system_ref: Option<Arc<SystemRef>>) -> Dsp {
+    Dsp {
+        dsp: dsp,
+        can_be_deleted: false,
+        user_data: UserData {
+            callbacks: DspCallbacks::new(),
+            user_data: ::std::ptr::null_mut()
+        },
+        system_ref: system_ref
+    }
+}
+
+/// Returns another handle on the unit of `dsp`, which doesn't release it.
+pub fn share(dsp: &Dsp) -> Dsp {
+    from_ptr(dsp.dsp, dsp.system_ref.clone())
+}
+
+pub fn get_system_ref(dsp: *mut ffi::FMOD_DSP) -> Option<Arc<SystemRef>> {
+    let mut system = ::std::ptr::null_mut();
+
+    if dsp.is_null() {
+        return None;
+    }
+    match unsafe { ffi::FMOD_DSP_GetSystemObject(dsp, &mut system) } {
+        ::Status::Ok => fmod_sys::get_system_ref(system),
+        _ => None,
     }
 }
 
@@ -550,7 +595,8 @@ pub fn from_ptr_first(dsp: *mut ffi::FMOD_DSP) -> Dsp {
 pub struct Dsp {
     dsp: *mut ffi::FMOD_DSP,
     can_be_deleted: bool,
-    user_data: UserData
+    user_data: UserData,
+    system_ref: Option<Arc<SystemRef>>
 }
 
 impl ffi::FFI<ffi::FMOD_DSP> for Dsp {
@@ -561,7 +607,8 @@ impl ffi::FFI<ffi::FMOD_DSP> for Dsp {
             user_data: UserData {
                 callbacks: DspCallbacks::new(),
                 user_data: ::std::ptr::null_mut()
-            }
+            },
+            system_ref: get_system_ref(dsp)
         }
     }
 
@@ -572,79 +619,73 @@ impl ffi::FFI<ffi::FMOD_DSP> for Dsp {
 
 impl Drop for Dsp {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl Dsp {
-    pub fn get_system_object(&self) -> Result<Sys, ::Status> {
+    pub fn get_system_object(&self) -> Result<Sys, ::FmodError> {
         let mut system = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSP_GetSystemObject(self.dsp, &mut system) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(system)),
-            e => Err(e)
+            ::Status::Ok => Ok(fmod_sys::from_ptr(system, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_DSP_GetSystemObject", e))
         }
     }
 
-    pub fn release(&mut self) -> ::Status {
+    pub fn release(&mut self) -> Result<(), ::FmodError> {
         if self.can_be_deleted && !self.dsp.is_null() {
             match unsafe { ffi::FMOD_DSP_Release(self.dsp) } {
                ::Status::Ok => {
                     self.dsp =::std::ptr::null_mut();
-                   ::Status::Ok
+                   Ok(())
                 }
-                e => e
+                e => Err(::FmodError::new("FMOD_DSP_Release", e))
             }
         } else {
-           ::Status::Ok
+           Ok(())
         }
     }
 
-    pub fn play(&self) -> Result<channel::Channel, ::Status> {
-        let mut channel = ::std::ptr::null_mut();
-
-        match match self.get_system_object() {
-            Ok(s) => { 
-                unsafe { ffi::FMOD_System_PlayDSP(ffi::FFI::unwrap(&s), ::ChannelIndex::Free,
-                                                  self.dsp, 0, &mut channel) }
-            }
-            Err(e) => e
-        } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e)
-        }
+    pub fn play(&self) -> Result<channel::Channel, ::FmodError> {
+        self.play_with_parameters(::ChannelIndex::Free)
     }
 
     pub fn play_with_parameters(&self, channel_id: ::ChannelIndex)
-                                -> Result<channel::Channel, ::Status> {
+                                -> Result<channel::Channel, ::FmodError> {
         let mut channel = ::std::ptr::null_mut();
-        
-        match match self.get_system_object() {
-            Ok(s) => { 
-                unsafe { ffi::FMOD_System_PlayDSP(ffi::FFI::unwrap(&s), channel_id, self.dsp, 0,
-                                                  &mut channel) }
+        let system = match self.get_system_object() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        match unsafe { ffi::FMOD_System_PlayDSP(ffi::FFI::unwrap(&system), channel_id, self.dsp, 0,
+                                                &mut channel) } {
+            ::Status::Ok => {
+                channel_handle::start_voice(channel);
+                Ok(channel::from_ptr(channel, self.system_ref.clone()))
             }
-            Err(e) => e
-        } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_System_PlayDSP", e))
         }
     }
 
-    pub fn add_input(&self, target: Dsp) -> Result<dsp_connection::DspConnection, ::Status> {
+    pub fn add_input(&self, target: Dsp) -> Result<dsp_connection::DspConnection, ::FmodError> {
         let mut connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSP_AddInput(self.dsp, target.dsp, &mut connection) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(connection)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp_connection::from_ptr(connection, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_DSP_AddInput", e))
         }
     }
 
-    pub fn disconnect_from(&self, target: Dsp) -> ::Status {
-        unsafe { ffi::FMOD_DSP_DisconnectFrom(self.dsp, target.dsp) }
+    pub fn disconnect_from(&self, target: Dsp) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_DSP_DisconnectFrom(self.dsp, target.dsp) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_DSP_DisconnectFrom", e)),
+        }
     }
 
-    pub fn disconnect_all(&self, inputs: bool, outputs: bool) -> ::Status {
+    pub fn disconnect_all(&self, inputs: bool, outputs: bool) -> Result<(), ::FmodError> {
         let t_inputs = if inputs == true {
             1
         } else {
@@ -656,112 +697,148 @@ impl Dsp {
             0
         };
 
-        unsafe { ffi::FMOD_DSP_DisconnectAll(self.dsp, t_inputs, t_outputs) }
+        match unsafe { ffi::FMOD_DSP_DisconnectAll(self.dsp, t_inputs, t_outputs) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_DSP_DisconnectAll", e)),
+
+        }
     }
 
-    pub fn remove(&self) -> ::Status {
-        unsafe { ffi::FMOD_DSP_Remove(self.dsp) }
+    pub fn remove(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_DSP_Remove(self.dsp) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_DSP_Remove", e)),
+        }
     }
 
-    pub fn get_num_inputs(&self) -> Result<i32, ::Status> {
+    pub fn get_num_inputs(&self) -> Result<i32, ::FmodError> {
         let mut inputs = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetNumInputs(self.dsp, &mut inputs) } {
             ::Status::Ok => Ok(inputs),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetNumInputs", e))
         }
     }
 
-    pub fn get_num_outputs(&self) -> Result<i32, ::Status> {
+    pub fn get_num_outputs(&self) -> Result<i32, ::FmodError> {
         let mut outputs = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetNumOutputs(self.dsp, &mut outputs) } {
             ::Status::Ok => Ok(outputs),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetNumOutputs", e))
         }
     }
 
-    pub fn get_input(&self, index: i32) -> Result<(Dsp, dsp_connection::DspConnection), ::Status> {
+    pub fn get_input(&self, index: i32)
+                    -> Result<(Dsp, dsp_connection::DspConnection), ::FmodError> {
         let mut input = ::std::ptr::null_mut();
         let mut input_connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSP_GetInput(self.dsp, index, &mut input,
                                               &mut input_connection) } {
-            ::Status::Ok => Ok((ffi::FFI::wrap(input), ffi::FFI::wrap(input_connection))),
-            e => Err(e)
+            ::Status::Ok => Ok((from_ptr(input, self.system_ref.clone()),
+                                dsp_connection::from_ptr(input_connection,
+                                                         self.system_ref.clone()))),
+            e => Err(::FmodError::new("FMOD_DSP_GetInput", e))
         }
     }
 
-    pub fn get_output(&self, index: i32) -> Result<(Dsp, dsp_connection::DspConnection), ::Status> {
+    pub fn get_output(&self, index: i32)
+                     -> Result<(Dsp, dsp_connection::DspConnection), ::FmodError> {
         let mut output = ::std::ptr::null_mut();
         let mut output_connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSP_GetOutput(self.dsp, index, &mut output,
                                                &mut output_connection) } {
-            ::Status::Ok => Ok((ffi::FFI::wrap(output), ffi::FFI::wrap(output_connection ))),
-            e => Err(e)
+            ::Status::Ok => Ok((from_ptr(output, self.system_ref.clone()),
+                                dsp_connection::from_ptr(output_connection,
+                                                         self.system_ref.clone()))),
+            e => Err(::FmodError::new("FMOD_DSP_GetOutput", e))
         }
     }
 
-    pub fn set_active(&self, active: bool) -> ::Status {
+    pub fn set_active(&self, active: bool) -> Result<(), ::FmodError> {
         let t_active = if active == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_DSP_SetActive(self.dsp, t_active) }
+        match unsafe { ffi::FMOD_DSP_SetActive(self.dsp, t_active) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_DSP_SetActive", e)),
+
+        }
     }
 
-    pub fn get_active(&self) -> Result<bool, ::Status> {
+    pub fn get_active(&self) -> Result<bool, ::FmodError> {
         let mut active = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetActive(self.dsp, &mut active) } {
             ::Status::Ok => Ok(active != 0i32),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetActive", e))
         }
     }
 
-    pub fn set_bypass(&self, bypass: bool) -> ::Status {
+    pub fn set_bypass(&self, bypass: bool) -> Result<(), ::FmodError> {
         let t_bypass = if bypass == true {
             1i32
         } else {
             0i32
         };
 
-        unsafe { ffi::FMOD_DSP_SetBypass(self.dsp, t_bypass) }
+        match unsafe { ffi::FMOD_DSP_SetBypass(self.dsp, t_bypass) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_DSP_SetBypass", e)),
+
+        }
     }
 
-    pub fn get_bypass(&self) -> Result<bool, ::Status> {
+    pub fn get_bypass(&self) -> Result<bool, ::FmodError> {
         let mut bypass = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetBypass(self.dsp, &mut bypass) } {
             ::Status::Ok => Ok(bypass == 1i32),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetBypass", e))
         }
     }
 
-    pub fn set_speaker_active(&self, speaker: ::Speaker, active: bool) -> ::Status {
+    pub fn set_speaker_active(&self, speaker: ::Speaker, active: bool) -> Result<(), ::FmodError> {
         let t_active = if active == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_DSP_SetSpeakerActive(self.dsp, speaker, t_active) }
+        match unsafe { ffi::FMOD_DSP_SetSpeakerActive(self.dsp, speaker, t_active) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_DSP_SetSpeakerActive", e)),
+
+        }
     }
 
-    pub fn get_speaker_active(&self, speaker: ::Speaker) -> Result<bool, ::Status> {
+    pub fn get_speaker_active(&self, speaker: ::Speaker) -> Result<bool, ::FmodError> {
         let mut active = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetSpeakerActive(self.dsp, speaker, &mut active) } {
             ::Status::Ok => Ok(active == 1i32),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetSpeakerActive", e))
         }
     }
 
-    pub fn reset(&self) -> ::Status {
-        unsafe { ffi::FMOD_DSP_Reset(self.dsp) }
+    pub fn reset(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_DSP_Reset(self.dsp) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_DSP_Reset", e)),
+        }
     }
 
     /// value argument depends directly on the index argument,
@@ -786,8 +863,11 @@ impl Dsp {
     /// * [`DspSfxReverb`](enums/fmod/type.DspSfxReverb.html)
     /// * [`DspLowPassSimple`](enums/fmod/type.DspLowPassSimple.html)
     /// * [`DspHighPassSimple`](enums/fmod/type.DspHighPassSimple.html)
-    pub fn set_parameter(&self, index: i32, value: f32) -> ::Status {
-        unsafe { ffi::FMOD_DSP_SetParameter(self.dsp, index, value) }
+    pub fn set_parameter(&self, index: i32, value: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_DSP_SetParameter(self.dsp, index, value) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_DSP_SetParameter", e)),
+        }
     }
 
     /// value result depends directly on the index argument,
@@ -813,7 +893,7 @@ impl Dsp {
     /// * [`DspLowPassSimple`](enums/fmod/type.DspLowPassSimple.html)
     /// * [`DspHighPassSimple`](enums/fmod/type.DspHighPassSimple.html)
     pub fn get_parameter(&self, index: i32, value_str_len: usize)
-                        -> Result<(f32, String), ::Status> {
+                        -> Result<(f32, String), ::FmodError> {
         let mut value = 0f32;
         let mut c = Vec::with_capacity(value_str_len + 1);
 
@@ -825,21 +905,21 @@ impl Dsp {
                                                   c.as_mut_ptr() as *mut c_char,
                                                   value_str_len as i32) } {
            ::Status::Ok => Ok((value, String::from_utf8(c).unwrap())),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetParameter", e))
         }
     }
 
-    pub fn get_num_parameters(&self) -> Result<i32, ::Status> {
+    pub fn get_num_parameters(&self) -> Result<i32, ::FmodError> {
         let mut num_param = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetNumParameters(self.dsp, &mut num_param) } {
             ::Status::Ok => Ok(num_param),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetNumParameters", e))
         }
     }
 
     pub fn get_parameter_info(&self, index: i32, name: &str, label: &str,
-                              description_len: usize) -> Result<(String, f32, f32), ::Status> {
+                              description_len: usize) -> Result<(String, f32, f32), ::FmodError> {
         let mut min = 0f32;
         let mut max = 0f32;
         let t_name = name.clone();
@@ -857,11 +937,55 @@ impl Dsp {
                                                       description_len as i32, &mut min,
                                                       &mut max) } {
             ::Status::Ok => Ok((String::from_utf8(description).unwrap(), min, max)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetParameterInfo", e))
+        }
+    }
+
+    /// Returns the (min, max) range of the parameter at `index`.
+    pub fn get_parameter_range(&self, index: i32) -> Result<(f32, f32), ::FmodError> {
+        let mut min = 0f32;
+        let mut max = 0f32;
+        let mut name = [0 as c_char; 16];
+        let mut label = [0 as c_char; 16];
+
+        match unsafe { ffi::FMOD_DSP_GetParameterInfo(self.dsp, index, name.as_mut_ptr(),
+                                                      label.as_mut_ptr(),
+                                                      ::std::ptr::null_mut(), 0, &mut min,
+                                                      &mut max) } {
+            ::Status::Ok => Ok((min, max)),
+            e => Err(::FmodError::new("FMOD_DSP_GetParameterInfo", e))
+        }
+    }
+
+    /// Sets a parameter of a builtin effect, checking the type of the DSP unit and the range of
+    /// `value` first:
+    ///
+    /// ```ignore
+    /// let echo = fmod.create_DSP_by_type(rfmod::DspType::Echo).unwrap();
+    ///
+    /// echo.set_param(rfmod::DspTypeEcho::Delay, 500f32).unwrap();
+    /// ```
+    ///
+    /// Use a [`TypedDsp`](struct.TypedDsp.html) to check the type only once.
+    pub fn set_param<P: DspParam>(&self, param: P, value: f32) -> Result<(), DspParamError> {
+        match dsp_param::check_type::<P>(self) {
+            Ok(()) => dsp_param::set_checked(self, param, value),
+            Err(e) => Err(e),
         }
     }
 
-    pub fn get_info(&self, name: &str) -> Result<(u32, i32, i32, i32), ::Status> {
+    /// Returns a parameter of a builtin effect, checking the type of the DSP unit first.
+    pub fn get_param<P: DspParam>(&self, param: P) -> Result<f32, DspParamError> {
+        match dsp_param::check_type::<P>(self) {
+            Ok(()) => match self.get_parameter(param.index(), 16) {
+                Ok((value, _)) => Ok(value),
+                Err(e) => Err(DspParamError::Fmod(e)),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn get_info(&self, name: &str) -> Result<(u32, i32, i32, i32), ::FmodError> {
         let mut version = 0u32;
         let mut channels = 0i32;
         let mut config_width = 0i32;
@@ -872,24 +996,28 @@ impl Dsp {
                                              &mut channels, &mut config_width,
             &mut config_height) } {
             ::Status::Ok => Ok((version, channels, config_width, config_height)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetInfo", e))
         }
     }
 
-    pub fn set_defaults(&self, frequency: f32, volume: f32, pan: f32, priority: i32) -> ::Status {
-        unsafe { ffi::FMOD_DSP_SetDefaults(self.dsp, frequency, volume, pan, priority) }
+    pub fn set_defaults(&self, frequency: f32, volume: f32, pan: f32, priority: i32)
+                       -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_DSP_SetDefaults(self.dsp, frequency, volume, pan, priority) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_DSP_SetDefaults", e)),
+        }
     }
 
-    pub fn get_type(&self) -> Result<::DspType, ::Status> {
+    pub fn get_type(&self) -> Result<::DspType, ::FmodError> {
         let mut _type = ::DspType::Unknown;
 
         match unsafe { ffi::FMOD_DSP_GetType(self.dsp, &mut _type) } {
             ::Status::Ok => Ok(_type),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetType", e))
         }
     }
 
-    pub fn get_defaults(&self) -> Result<(f32, f32, f32, i32), ::Status> {
+    pub fn get_defaults(&self) -> Result<(f32, f32, f32, i32), ::FmodError> {
         let mut frequency = 0f32;
         let mut volume = 0f32;
         let mut pan = 0f32;
@@ -898,27 +1026,27 @@ impl Dsp {
         match unsafe { ffi::FMOD_DSP_GetDefaults(self.dsp, &mut frequency, &mut volume, &mut pan,
                                                  &mut priority) } {
             ::Status::Ok => Ok((frequency, volume, pan, priority)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetDefaults", e))
         }
     }
 
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::FmodError> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_DSP_GetMemoryInfo(self.dsp, memory_bits, event_memory_bits,
                                                    &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSP_GetMemoryInfo", e))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r mut self, user_data: &'r mut T) -> ::Status {
+    pub fn set_user_data<'r, T>(&'r mut self, user_data: &'r mut T) -> Result<(), ::FmodError> {
         let mut data: *mut c_void = ::std::ptr::null_mut();
 
-        unsafe {
+        let status = unsafe {
             match ffi::FMOD_DSP_GetUserData(self.dsp, &mut data) {
                ::Status::Ok => {
                     if data.is_null() {
@@ -938,10 +1066,16 @@ impl Dsp {
                     ffi::FMOD_DSP_SetUserData(self.dsp, transmute(&mut self.user_data))
                 }
             }
+        };
+
+        match status {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_DSP_SetUserData", e)),
         }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
+    /// Returns None if no user data has been set.
+    pub fn get_user_data<'r, T>(&'r self) -> Result<Option<&'r mut T>, ::FmodError> {
         unsafe {
             let mut user_data : *mut c_void = ::std::ptr::null_mut();
 
@@ -949,14 +1083,16 @@ impl Dsp {
                ::Status::Ok => {
                     if !user_data.is_null() {
                         let tmp: &mut UserData = transmute::<*mut c_void, &mut UserData>(user_data);
-                        let tmp2: &mut T = transmute::<*mut c_void, &mut T>(tmp.user_data);
-
-                        Ok(tmp2)
+                        if tmp.user_data.is_null() {
+                            Ok(None)
+                        } else {
+                            Ok(Some(transmute::<*mut c_void, &mut T>(tmp.user_data)))
+                        }
                     } else {
-                        Err(::Status::Ok)
+                        Ok(None)
                     }
                 },
-                e => Err(e)
+                e => Err(::FmodError::new("FMOD_DSP_GetUserData", e))
             }
         }
     }
@@ -0,0 +1,491 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use dsp::{self, Dsp};
+use dsp_param::{self, DspParam};
+use channel::Channel;
+use channel_group::ChannelGroup;
+use error::DspParamError;
+
+/// Builtin effect of a [`DspChain`](struct.DspChain.html).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DspEffect {
+    pub dsp_type: ::DspType,
+    /// (index, value) of the parameters to set, the others keep the FMOD defaults.
+    pub parameters: Vec<(i32, f32)>,
+    /// Mix of the connection from the previous effect, or from the head, to this one.
+    pub mix: f32,
+}
+
+impl DspEffect {
+    /// Creates an effect without parameters, whose mix is 1.
+    pub fn new(dsp_type: ::DspType) -> DspEffect {
+        DspEffect {
+            dsp_type: dsp_type,
+            parameters: Vec::new(),
+            mix: 1f32,
+        }
+    }
+
+    pub fn set_parameter(&mut self, index: i32, value: f32) {
+        match self.parameters.iter_mut().find(|&&mut (i, _)| i == index) {
+            Some(p) => p.1 = value,
+            None => self.parameters.push((index, value)),
+        }
+    }
+
+    /// Sets a typed parameter, returning `DspParamError::WrongType` if it doesn't belong to the
+    /// type of this effect. The range is checked when the chain is applied.
+    pub fn set_param<P: DspParam>(&mut self, param: P, value: f32) -> Result<(), DspParamError> {
+        if P::dsp_type() != self.dsp_type {
+            return Err(DspParamError::WrongType { expected: P::dsp_type(), found: self.dsp_type });
+        }
+        self.set_parameter(param.index(), value);
+        Ok(())
+    }
+
+    pub fn get_parameter(&self, index: i32) -> Option<f32> {
+        self.parameters.iter().find(|&&(i, _)| i == index).map(|&(_, v)| v)
+    }
+
+    pub fn set_mix(&mut self, mix: f32) {
+        self.mix = mix;
+    }
+}
+
+/// Ordered list of builtin effects, applied between the DSP head of a channel or a channel
+/// group and its outputs, so the channels played in the group after it is applied go through
+/// it too. The first effect processes the sound first.
+///
+/// Applying it returns an [`AppliedDspChain`](struct.AppliedDspChain.html), which can be
+/// updated with another chain: only the effects which changed are created, moved, removed or
+/// have their parameters set.
+///
+/// ```ignore
+/// let mut echo = rfmod::DspEffect::new(rfmod::DspType::Echo);
+/// let mut chain = rfmod::DspChain::new();
+///
+/// echo.set_param(rfmod::DspTypeEcho::Delay, 300f32).unwrap();
+/// chain.push(rfmod::DspEffect::new(rfmod::DspType::LowPass));
+/// chain.push(echo);
+/// let mut applied = chain.apply_to_channel_group(&group).unwrap();
+///
+/// // only the low pass is removed
+/// chain.remove(0);
+/// applied.update(&chain).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DspChain {
+    effects: Vec<DspEffect>,
+}
+
+impl Default for DspChain {
+    fn default() -> DspChain {
+        DspChain::new()
+    }
+}
+
+impl DspChain {
+    pub fn new() -> DspChain {
+        DspChain { effects: Vec::new() }
+    }
+
+    pub fn push(&mut self, effect: DspEffect) {
+        self.effects.push(effect);
+    }
+
+    pub fn insert(&mut self, index: usize, effect: DspEffect) {
+        self.effects.insert(index, effect);
+    }
+
+    pub fn remove(&mut self, index: usize) -> DspEffect {
+        self.effects.remove(index)
+    }
+
+    pub fn get_effects(&self) -> &[DspEffect] {
+        &self.effects
+    }
+
+    pub fn get_effects_mut(&mut self) -> &mut Vec<DspEffect> {
+        &mut self.effects
+    }
+
+    /// Inserts the effects between `head` and its outputs. `head` needs an output: the head of
+    /// the system has none, apply the chain to the master channel group instead.
+    pub fn apply(&self, head: &Dsp) -> Result<AppliedDspChain, DspParamError> {
+        match head.get_num_outputs() {
+            Ok(0) => return Err(DspParamError::Fmod(::FmodError::new("DspChain::apply",
+                                                                      ::Status::InvalidParam))),
+            Ok(_) => {}
+            Err(e) => return Err(DspParamError::Fmod(e)),
+        }
+        let mut applied = AppliedDspChain {
+            head: dsp::share(head),
+            units: Vec::new(),
+        };
+
+        match applied.update(self) {
+            Ok(()) => Ok(applied),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn apply_to_channel(&self, channel: &Channel) -> Result<AppliedDspChain, DspParamError> {
+        match channel.get_DSP_head() {
+            Ok(head) => self.apply(&head),
+            Err(e) => Err(DspParamError::Fmod(e)),
+        }
+    }
+
+    pub fn apply_to_channel_group(&self, channel_group: &ChannelGroup)
+                                  -> Result<AppliedDspChain, DspParamError> {
+        match channel_group.get_DSP_head() {
+            Ok(head) => self.apply(&head),
+            Err(e) => Err(DspParamError::Fmod(e)),
+        }
+    }
+}
+
+/// Effects of a [`DspChain`](struct.DspChain.html) inserted in the DSP network. They are
+/// removed, and the outputs of the head reconnected to it, when it is dropped.
+pub struct AppliedDspChain {
+    head: Dsp,
+    units: Vec<(DspEffect, Dsp)>,
+}
+
+/// Returns the (old index, new index) pairs of the longest common subsequence of effect types.
+fn match_effects(old: &[DspEffect], new: &[DspEffect]) -> Vec<(usize, usize)> {
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    let mut pairs = Vec::new();
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i].dsp_type == new[j].dsp_type {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                ::std::cmp::max(lengths[i + 1][j], lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() && j < new.len() {
+        if old[i].dsp_type == new[j].dsp_type {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+/// Returns the (old index, new index) pairs of the effects outside of `pairs` which can be
+/// moved rather than created again, because they have the same type.
+fn match_moves(old: &[DspEffect], new: &[DspEffect],
+                   pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut moves : Vec<(usize, usize)> = Vec::new();
+
+    for (j, effect) in new.iter().enumerate() {
+        if pairs.iter().any(|&(_, n)| n == j) {
+            continue;
+        }
+        let found = (0..old.len()).find(|&i| {
+            old[i].dsp_type == effect.dsp_type && !pairs.iter().any(|&(o, _)| o == i) &&
+            !moves.iter().any(|&(o, _)| o == i)
+        });
+
+        if let Some(i) = found {
+            moves.push((i, j));
+        }
+    }
+    moves
+}
+
+fn set_parameters(dsp: &Dsp, effect: &DspEffect, old: Option<&DspEffect>)
+                  -> Result<(), DspParamError> {
+    for &(index, value) in effect.parameters.iter() {
+        if old.and_then(|o| o.get_parameter(index)) != Some(value) {
+            if let Err(e) = dsp_param::set_in_range(dsp, index, value) {
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Connects the outputs of `from` to `to`, keeping their mix.
+fn move_outputs(from: &Dsp, to: &Dsp) -> Result<(), ::FmodError> {
+    let num_outputs = match from.get_num_outputs() {
+        Ok(n) => n,
+        Err(e) => return Err(e),
+    };
+    let mut outputs = Vec::with_capacity(num_outputs as usize);
+
+    for index in 0..num_outputs {
+        match from.get_output(index) {
+            Ok((output, connection)) => match connection.get_mix() {
+                Ok(m) => outputs.push((output, m)),
+                Err(e) => return Err(e),
+            },
+            Err(e) => return Err(e),
+        }
+    }
+    for (output, mix) in outputs {
+        if let Err(e) = output.disconnect_from(dsp::share(from)) {
+            return Err(e);
+        }
+        match output.add_input(dsp::share(to)) {
+            Ok(connection) => if let Err(e) = connection.set_mix(mix) {
+                return Err(e);
+            },
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Inserts `unit` between `previous` and its outputs.
+fn insert_unit(previous: &Dsp, unit: &Dsp, mix: f32) -> Result<(), ::FmodError> {
+    if let Err(e) = move_outputs(previous, unit) {
+        return Err(e);
+    }
+    match unit.add_input(dsp::share(previous)) {
+        Ok(connection) => match connection.set_mix(mix) {
+            Ok(()) => unit.set_active(true),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Takes `unit` out of the network, connecting its outputs to `previous`.
+fn remove_unit(previous: &Dsp, unit: &Dsp) -> Result<(), ::FmodError> {
+    if let Err(e) = move_outputs(unit, previous) {
+        return Err(e);
+    }
+    unit.disconnect_from(dsp::share(previous))
+}
+
+impl AppliedDspChain {
+    /// Reconfigures the effects to match `chain`. Effects are matched by type in order and stay
+    /// in place, the unmatched ones are moved if an effect of the same type is needed elsewhere,
+    /// otherwise they are removed or created. The parameters and mixes of the kept effects are
+    /// only set if they changed, and parameters missing from `chain` keep their value.
+    ///
+    /// Changes are made under [`Sys::lock_DSP`](struct.Sys.html#method.lock_DSP) so the mixer
+    /// doesn't run a half built chain. New units are created and their parameters set before,
+    /// so an invalid parameter of a new effect leaves the chain unchanged. Other failures leave
+    /// it partly updated, as this object describes it.
+    pub fn update(&mut self, chain: &DspChain) -> Result<(), DspParamError> {
+        let sys = match self.head.get_system_object() {
+            Ok(s) => s,
+            Err(e) => return Err(DspParamError::Fmod(e)),
+        };
+        let old : Vec<DspEffect> = self.units.iter().map(|&(ref e, _)| e.clone()).collect();
+        let pairs = match_effects(&old, &chain.effects);
+        let moves = match_moves(&old, &chain.effects, &pairs);
+        let mut created = Vec::new();
+
+        for (index, effect) in chain.effects.iter().enumerate() {
+            if pairs.iter().chain(moves.iter()).any(|&(_, n)| n == index) {
+                continue;
+            }
+            let dsp = match sys.create_DSP_by_type(effect.dsp_type) {
+                Ok(d) => d,
+                Err(e) => return Err(DspParamError::Fmod(e)),
+            };
+
+            if let Err(e) = set_parameters(&dsp, effect, None) {
+                return Err(e);
+            }
+            created.push((index, None, dsp));
+        }
+        if let Err(e) = sys.lock_DSP() {
+            return Err(DspParamError::Fmod(e));
+        }
+        let mut removed = Vec::new();
+        let result = self.reconfigure(chain, &pairs, &moves, created, &mut removed);
+        let unlocked = sys.unlock_DSP();
+
+        // units are released once the DSP network is unlocked
+        drop(removed);
+        match (result, unlocked) {
+            (Err(e), _) => Err(e),
+            (Ok(()), Err(e)) => Err(DspParamError::Fmod(e)),
+            (Ok(()), Ok(())) => Ok(()),
+        }
+    }
+
+    fn reconfigure(&mut self, chain: &DspChain, pairs: &[(usize, usize)],
+                   moves: &[(usize, usize)], mut inserted: Vec<(usize, Option<DspEffect>, Dsp)>,
+                   removed: &mut Vec<Dsp>) -> Result<(), DspParamError> {
+        // units are taken out from the last one, so the unit before each of them is in place
+        for old_index in (0..self.units.len()).rev() {
+            if pairs.iter().any(|&(o, _)| o == old_index) {
+                continue;
+            }
+            let result = match old_index {
+                0 => remove_unit(&self.head, &self.units[0].1),
+                i => remove_unit(&self.units[i - 1].1, &self.units[i].1),
+            };
+
+            if let Err(e) = result {
+                return Err(DspParamError::Fmod(e));
+            }
+            let (effect, dsp) = self.units.remove(old_index);
+
+            match moves.iter().find(|&&(o, _)| o == old_index) {
+                Some(&(_, new_index)) => inserted.push((new_index, Some(effect), dsp)),
+                None => removed.push(dsp),
+            }
+        }
+        // self.units now holds the matched units, in the order of pairs
+        for (position, &(_, new_index)) in pairs.iter().enumerate() {
+            let effect = &chain.effects[new_index];
+
+            {
+                let (ref old, ref dsp) = self.units[position];
+
+                if let Err(e) = set_parameters(dsp, effect, Some(old)) {
+                    return Err(e);
+                }
+                if old.mix != effect.mix {
+                    match dsp.get_input(0) {
+                        Ok((_, connection)) => if let Err(e) = connection.set_mix(effect.mix) {
+                            return Err(DspParamError::Fmod(e));
+                        },
+                        Err(e) => return Err(DspParamError::Fmod(e)),
+                    }
+                }
+            }
+            self.units[position].0 = effect.clone();
+        }
+        // units are inserted from the first one: the effects before each of them are in place
+        inserted.sort_by_key(|&(n, _, _)| n);
+        for (new_index, old, dsp) in inserted {
+            let effect = &chain.effects[new_index];
+
+            if old.is_some() {
+                if let Err(e) = set_parameters(&dsp, effect, old.as_ref()) {
+                    return Err(e);
+                }
+            }
+            let result = match new_index {
+                0 => insert_unit(&self.head, &dsp, effect.mix),
+                i => insert_unit(&self.units[i - 1].1, &dsp, effect.mix),
+            };
+
+            if let Err(e) = result {
+                return Err(DspParamError::Fmod(e));
+            }
+            self.units.insert(new_index, (effect.clone(), dsp));
+        }
+        Ok(())
+    }
+
+    /// Returns the effects as they are applied, with their DSP unit.
+    pub fn get_units(&self) -> &[(DspEffect, Dsp)] {
+        &self.units
+    }
+
+    /// Returns the chain as it is applied.
+    pub fn get_chain(&self) -> DspChain {
+        DspChain { effects: self.units.iter().map(|&(ref e, _)| e.clone()).collect() }
+    }
+
+    pub fn get_head(&self) -> &Dsp {
+        &self.head
+    }
+}
+
+impl Drop for AppliedDspChain {
+    fn drop(&mut self) {
+        let sys = self.head.get_system_object().ok();
+
+        if let Some(ref sys) = sys {
+            let _ = sys.lock_DSP();
+        }
+        for i in (0..self.units.len()).rev() {
+            let _ = match i {
+                0 => remove_unit(&self.head, &self.units[0].1),
+                i => remove_unit(&self.units[i - 1].1, &self.units[i].1),
+            };
+        }
+        if let Some(ref sys) = sys {
+            let _ = sys.unlock_DSP();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{match_effects, match_moves, DspEffect};
+    use DspType;
+
+    fn effects(types: &[DspType]) -> Vec<DspEffect> {
+        types.iter().map(|&t| DspEffect::new(t)).collect()
+    }
+
+    #[test]
+    fn match_effects_keeps_the_common_effects() {
+        let old = effects(&[DspType::LowPass, DspType::Echo, DspType::Chorus]);
+        let new = effects(&[DspType::LowPass, DspType::Flange, DspType::Chorus]);
+
+        assert_eq!(match_effects(&old, &new), vec![(0, 0), (2, 2)]);
+        assert_eq!(match_effects(&old, &old), vec![(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(match_effects(&old, &[]), vec![]);
+        assert_eq!(match_effects(&[], &new), vec![]);
+    }
+
+    #[test]
+    fn match_effects_on_insert_and_remove() {
+        let old = effects(&[DspType::LowPass, DspType::Echo]);
+        let new = effects(&[DspType::LowPass, DspType::Chorus, DspType::Echo]);
+
+        assert_eq!(match_effects(&old, &new), vec![(0, 0), (1, 2)]);
+        assert_eq!(match_effects(&new, &old), vec![(0, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn match_moves_reuses_the_unmatched_effects() {
+        let old = effects(&[DspType::LowPass, DspType::Chorus, DspType::Echo]);
+        let new = effects(&[DspType::Echo, DspType::LowPass, DspType::Chorus, DspType::Echo]);
+        let pairs = match_effects(&old, &new);
+
+        assert_eq!(pairs, vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(match_moves(&old, &new, &pairs), vec![]);
+
+        let new = effects(&[DspType::Echo, DspType::LowPass, DspType::Chorus]);
+        let pairs = match_effects(&old, &new);
+
+        assert_eq!(pairs, vec![(0, 1), (1, 2)]);
+        assert_eq!(match_moves(&old, &new, &pairs), vec![(2, 0)]);
+    }
+}
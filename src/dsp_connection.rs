@@ -27,18 +27,20 @@ use types::*;
 use dsp;
 use libc::{c_int, c_void};
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
+use fmod_sys::{MemoryUsageDetails, SystemRef};
+use std::sync::Arc;
 use std::mem::transmute;
 use std::default::Default;
 
 /// DspConnection object
 pub struct DspConnection {
-    dsp_connection: *mut ffi::FMOD_DSPCONNECTION
+    dsp_connection: *mut ffi::FMOD_DSPCONNECTION,
+    system_ref: Option<Arc<SystemRef>>
 }
 
 impl ffi::FFI<ffi::FMOD_DSPCONNECTION> for DspConnection {
     fn wrap(d: *mut ffi::FMOD_DSPCONNECTION) -> DspConnection {
-        DspConnection {dsp_connection: d}
+        DspConnection {dsp_connection: d, system_ref: get_system_ref(d)}
     }
 
     fn unwrap(d: &DspConnection) -> *mut ffi::FMOD_DSPCONNECTION {
@@ -46,6 +48,25 @@ impl ffi::FFI<ffi::FMOD_DSPCONNECTION> for DspConnection {
     }
 }
 
+/// Wraps a connection returned by an object sharing `system_ref`, without looking it up again.
+pub fn from_ptr(dsp_connection: *mut ffi::FMOD_DSPCONNECTION,
+                system_ref: Option<Arc<SystemRef>>) -> DspConnection {
+    DspConnection {dsp_connection: dsp_connection, system_ref: system_ref}
+}
+
+/// Connections have no system object, the one of their input is used.
+fn get_system_ref(dsp_connection: *mut ffi::FMOD_DSPCONNECTION) -> Option<Arc<SystemRef>> {
+    let mut input = ::std::ptr::null_mut();
+
+    if dsp_connection.is_null() {
+        return None;
+    }
+    match unsafe { ffi::FMOD_DSPConnection_GetInput(dsp_connection, &mut input) } {
+        ::Status::Ok => dsp::get_system_ref(input),
+        _ => None,
+    }
+}
+
 impl Drop for DspConnection {
     fn drop(&mut self) {
         self.release();
@@ -57,56 +78,63 @@ impl DspConnection {
         self.dsp_connection = ::std::ptr::null_mut();
     }
 
-    pub fn get_input(&self) -> Result<dsp::Dsp, ::Status> {
+    pub fn get_input(&self) -> Result<dsp::Dsp, ::FmodError> {
         let mut input = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSPConnection_GetInput(self.dsp_connection, &mut input) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(input)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp::from_ptr(input, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_DSPConnection_GetInput", e))
         }
     }
 
-    pub fn get_output(&self) -> Result<dsp::Dsp, ::Status> {
+    pub fn get_output(&self) -> Result<dsp::Dsp, ::FmodError> {
         let mut output = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSPConnection_GetOutput(self.dsp_connection, &mut output) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(output)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp::from_ptr(output, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_DSPConnection_GetOutput", e))
         }
     }
 
-    pub fn set_mix(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_DSPConnection_SetMix(self.dsp_connection, volume) }
+    pub fn set_mix(&self, volume: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_DSPConnection_SetMix(self.dsp_connection, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_DSPConnection_SetMix", e)),
+        }
     }
 
-    pub fn get_mix(&self) -> Result<f32, ::Status> {
+    pub fn get_mix(&self) -> Result<f32, ::FmodError> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_DSPConnection_GetMix(self.dsp_connection, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_DSPConnection_GetMix", e))
         }
     }
 
-    pub fn set_levels(&self, speaker: ::Speaker, levels: &mut Vec<f32>) -> ::Status {
-        unsafe { ffi::FMOD_DSPConnection_SetLevels(self.dsp_connection, speaker,
-                                                   levels.as_mut_ptr(), levels.len() as c_int) }
+    pub fn set_levels(&self, speaker: ::Speaker, levels: &mut Vec<f32>) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_DSPConnection_SetLevels(self.dsp_connection, speaker,
+                                                         levels.as_mut_ptr(), levels.len() as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_DSPConnection_SetLevels", e)),
+        }
     }
 
-    pub fn get_levels(&self, speaker: ::Speaker, num_levels: usize) -> Result<Vec<f32>, ::Status> {
+    pub fn get_levels(&self, speaker: ::Speaker, num_levels: usize)
+                     -> Result<Vec<f32>, ::FmodError> {
         let mut levels : Vec<f32> = ::std::iter::repeat(0f32).take(num_levels).collect();
 
         match unsafe { ffi::FMOD_DSPConnection_GetLevels(self.dsp_connection, speaker,
                                                          levels.as_mut_ptr(),
                                                          levels.len() as c_int) } {
             ::Status::Ok => Ok(levels),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_DSPConnection_GetLevels", e)),
         }
     }
 
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::FmodError> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
@@ -114,25 +142,31 @@ impl DspConnection {
                                                              event_memory_bits, &mut memory_used,
                                                              &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_DSPConnection_GetMemoryInfo", e)),
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_DSPConnection_SetUserData(self.dsp_connection, transmute(user_data)) }
+    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_DSPConnection_SetUserData(self.dsp_connection, transmute(user_data)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_DSPConnection_SetUserData", e)),
+        }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
+    /// Returns None if no user data has been set.
+    pub fn get_user_data<'r, T>(&'r self) -> Result<Option<&'r mut T>, ::FmodError> {
         unsafe {
             let mut user_data : *mut c_void = ::std::ptr::null_mut();
 
             match ffi::FMOD_DSPConnection_GetUserData(self.dsp_connection, &mut user_data) {
                ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
+                    if user_data.is_null() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(transmute::<*mut c_void, &mut T>(user_data)))
+                    }
                 }
-                e => Err(e),
+                e => Err(::FmodError::new("FMOD_DSPConnection_GetUserData", e)),
             }
         }
     }
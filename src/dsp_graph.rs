@@ -0,0 +1,483 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use ffi;
+use dsp::{self, Dsp};
+use libc::{c_char, c_int, c_uint};
+use std::fmt::Write;
+
+/// Output speakers whose levels are read for every connection.
+const SPEAKERS : [::Speaker; 8] = [::Speaker::FrontLeft, ::Speaker::FrontRight,
+                                   ::Speaker::FrontCenter, ::Speaker::LowFrequency,
+                                   ::Speaker::BackLeft, ::Speaker::BackRight,
+                                   ::Speaker::SideLeft, ::Speaker::SideRight];
+
+/// Parameter of a [`DspNode`](struct.DspNode.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DspNodeParameter {
+    pub index: i32,
+    pub name: String,
+    /// Unit of the value, like "hz".
+    pub label: String,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// DSP unit of a [`DspGraph`](struct.DspGraph.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DspNode {
+    /// Identifies the unit in the graph, and between graphs of the same system.
+    pub id: usize,
+    pub name: String,
+    pub dsp_type: ::DspType,
+    pub active: bool,
+    pub bypass: bool,
+    pub parameters: Vec<DspNodeParameter>,
+}
+
+/// Connection of a [`DspGraph`](struct.DspGraph.html), `input` being mixed into `output`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DspEdge {
+    /// Id of the input node.
+    pub input: usize,
+    /// Id of the output node.
+    pub output: usize,
+    pub mix: f32,
+    /// Levels of the input channels for each output speaker, only for the speakers FMOD returned
+    /// them for.
+    pub levels: Vec<(::Speaker, Vec<f32>)>,
+}
+
+/// Snapshot of a DSP network, returned by
+/// [`Sys::dsp_graph`](struct.Sys.html#method.dsp_graph).
+///
+/// ```ignore
+/// let graph = fmod.dsp_graph().unwrap();
+///
+/// for node in graph.nodes.iter().filter(|n| n.bypass || !n.active) {
+///     println!("{} ({:?}) isn't processing", node.name, node.dsp_type);
+/// }
+/// ::std::fs::File::create("dsp.dot").unwrap().write_all(graph.to_dot().as_bytes()).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DspGraph {
+    /// Nodes in breadth first order from the head, which comes first.
+    pub nodes: Vec<DspNode>,
+    pub edges: Vec<DspEdge>,
+}
+
+impl DspGraph {
+    /// Walks the inputs of `head` to build the graph of the units mixed into it.
+    pub fn from_dsp(head: &Dsp) -> Result<DspGraph, ::FmodError> {
+        let mut graph = DspGraph { nodes: Vec::new(), edges: Vec::new() };
+        let mut queue = vec![ffi::FFI::unwrap(head)];
+        let mut i = 0;
+
+        while i < queue.len() {
+            // `head` keeps the system alive while the graph is built
+            let dsp = dsp::from_ptr(queue[i], None);
+            let id = queue[i] as usize;
+            let channels = match get_node(&dsp, id) {
+                Ok((node, channels)) => {
+                    graph.nodes.push(node);
+                    channels
+                }
+                Err(e) => return Err(e),
+            };
+            let num_inputs = match dsp.get_num_inputs() {
+                Ok(n) => n,
+                Err(e) => return Err(e),
+            };
+
+            for index in 0..num_inputs {
+                let (input, connection) = match dsp.get_input(index) {
+                    Ok(i) => i,
+                    Err(e) => return Err(e),
+                };
+                let raw = ffi::FFI::unwrap(&input);
+                let mix = match connection.get_mix() {
+                    Ok(m) => m,
+                    Err(e) => return Err(e),
+                };
+                // units processing "whatever is in the network" report 0 channels
+                let num_levels = if channels > 0 { channels as usize } else { 2 };
+                let levels = SPEAKERS.iter()
+                                     .filter_map(|&s| connection.get_levels(s, num_levels).ok()
+                                                                .map(|l| (s, l)))
+                                     .collect();
+
+                graph.edges.push(DspEdge { input: raw as usize, output: id, mix: mix,
+                                           levels: levels });
+                if !queue.contains(&raw) {
+                    queue.push(raw);
+                }
+            }
+            i += 1;
+        }
+        Ok(graph)
+    }
+
+    pub fn get_node(&self, id: usize) -> Option<&DspNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Returns the edges whose output is `id`.
+    pub fn get_inputs(&self, id: usize) -> Vec<&DspEdge> {
+        self.edges.iter().filter(|e| e.output == id).collect()
+    }
+
+    /// Returns the edges whose input is `id`.
+    pub fn get_outputs(&self, id: usize) -> Vec<&DspEdge> {
+        self.edges.iter().filter(|e| e.input == id).collect()
+    }
+
+    /// Returns the graph in the Graphviz DOT format. Inactive nodes are dashed, bypassed ones
+    /// grey, and edges are labeled with their mix.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dsp {\n    rankdir=RL;\n    node [shape=box];\n");
+
+        for node in self.nodes.iter() {
+            let mut label = format!("{}\\n{:?}", escape_dot(&node.name), node.dsp_type);
+            let mut style = Vec::new();
+
+            for p in node.parameters.iter() {
+                let name = if p.name.is_empty() {
+                    format!("#{}", p.index)
+                } else {
+                    escape_dot(&p.name)
+                };
+
+                let _ = write!(label, "\\n{} = {}{}", name, p.value, escape_dot(&p.label));
+            }
+            if !node.active {
+                label.push_str("\\n(inactive)");
+                style.push("dashed");
+            }
+            if node.bypass {
+                label.push_str("\\n(bypass)");
+                style.push("filled");
+            }
+            let _ = write!(dot, "    n{} [label=\"{}\"", node.id, label);
+            if !style.is_empty() {
+                let _ = write!(dot, ", style=\"{}\"", style.join(","));
+            }
+            if node.bypass {
+                dot.push_str(", fillcolor=grey");
+            }
+            dot.push_str("];\n");
+        }
+        for edge in self.edges.iter() {
+            let _ = write!(dot, "    n{} -> n{} [label=\"{}\"", edge.input, edge.output, edge.mix);
+            if edge.mix == 0f32 {
+                dot.push_str(", style=dashed");
+            }
+            dot.push_str("];\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Returns the graph as a JSON object with a `nodes` and an `edges` array, the fields being
+    /// the ones of `DspNode` and `DspEdge`. Levels are an object mapping speakers to arrays.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"nodes\":[");
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let _ = write!(json, "{{\"id\":{},\"name\":\"{}\",\"dsp_type\":\"{:?}\",\"active\":{},\
+                                  \"bypass\":{},\"parameters\":[",
+                           node.id, escape_json(&node.name), node.dsp_type, node.active,
+                           node.bypass);
+            for (j, p) in node.parameters.iter().enumerate() {
+                if j > 0 {
+                    json.push(',');
+                }
+                let _ = write!(json, "{{\"index\":{},\"name\":\"{}\",\"label\":\"{}\",\
+                                      \"value\":{},\"min\":{},\"max\":{}}}",
+                               p.index, escape_json(&p.name), escape_json(&p.label),
+                               json_number(p.value), json_number(p.min), json_number(p.max));
+            }
+            json.push_str("]}");
+        }
+        json.push_str("],\"edges\":[");
+        for (i, edge) in self.edges.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let _ = write!(json, "{{\"input\":{},\"output\":{},\"mix\":{},\"levels\":{{",
+                           edge.input, edge.output, json_number(edge.mix));
+            for (j, &(speaker, ref levels)) in edge.levels.iter().enumerate() {
+                let levels : Vec<String> = levels.iter().map(|&l| json_number(l)).collect();
+
+                if j > 0 {
+                    json.push(',');
+                }
+                let _ = write!(json, "\"{:?}\":[{}]", speaker, levels.join(","));
+            }
+            json.push_str("}}");
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+fn get_node(dsp: &Dsp, id: usize) -> Result<(DspNode, i32), ::FmodError> {
+    let mut name = [0 as c_char; 32];
+    let mut version = 0 as c_uint;
+    let mut channels = 0 as c_int;
+    let mut config_width = 0 as c_int;
+    let mut config_height = 0 as c_int;
+
+    match unsafe { ffi::FMOD_DSP_GetInfo(ffi::FFI::unwrap(dsp), name.as_mut_ptr(), &mut version,
+                                         &mut channels, &mut config_width,
+                                         &mut config_height) } {
+        ::Status::Ok => {}
+        e => return Err(::FmodError::new("FMOD_DSP_GetInfo", e)),
+    }
+    let dsp_type = match dsp.get_type() {
+        Ok(t) => t,
+        Err(e) => return Err(e),
+    };
+    let active = match dsp.get_active() {
+        Ok(a) => a,
+        Err(e) => return Err(e),
+    };
+    let bypass = match dsp.get_bypass() {
+        Ok(b) => b,
+        Err(e) => return Err(e),
+    };
+    let num_parameters = match dsp.get_num_parameters() {
+        Ok(n) => n,
+        Err(e) => return Err(e),
+    };
+    let mut parameters = Vec::with_capacity(num_parameters.max(0) as usize);
+
+    for index in 0..num_parameters {
+        match get_parameter(dsp, index) {
+            Ok(p) => parameters.push(p),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok((DspNode {
+        id: id,
+        name: to_string(&name),
+        dsp_type: dsp_type,
+        active: active,
+        bypass: bypass,
+        parameters: parameters,
+    }, channels))
+}
+
+/// Reads the description and the current value of the parameter at `index`.
+pub fn get_parameter(dsp: &Dsp, index: i32) -> Result<DspNodeParameter, ::FmodError> {
+    let mut name = [0 as c_char; 16];
+    let mut label = [0 as c_char; 16];
+    let mut min = 0f32;
+    let mut max = 0f32;
+
+    match unsafe { ffi::FMOD_DSP_GetParameterInfo(ffi::FFI::unwrap(dsp), index,
+                                                  name.as_mut_ptr(), label.as_mut_ptr(),
+                                                  ::std::ptr::null_mut(), 0, &mut min,
+                                                  &mut max) } {
+        ::Status::Ok => {}
+        e => return Err(::FmodError::new("FMOD_DSP_GetParameterInfo", e)),
+    }
+    match dsp.get_parameter(index, 16) {
+        Ok((value, _)) => Ok(DspNodeParameter {
+            index: index,
+            name: to_string(&name),
+            label: to_string(&label),
+            value: value,
+            min: min,
+            max: max,
+        }),
+        Err(e) => Err(e),
+    }
+}
+
+/// Converts a nul terminated buffer, which FMOD may have filled up to the end.
+fn to_string(buffer: &[c_char]) -> String {
+    let bytes : Vec<u8> = buffer.iter().take_while(|&&c| c != 0).map(|&c| c as u8).collect();
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// JSON has no infinity nor NaN: they become null.
+fn json_number(v: f32) -> String {
+    if v.is_finite() {
+        format!("{}", v)
+    } else {
+        "null".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameter(name: &str, value: f32) -> DspNodeParameter {
+        DspNodeParameter {
+            index: 0,
+            name: name.to_owned(),
+            label: "hz".to_owned(),
+            value: value,
+            min: -1f32,
+            max: 1f32,
+        }
+    }
+
+    fn graph() -> DspGraph {
+        DspGraph {
+            nodes: vec![DspNode {
+                id: 1,
+                name: "Head \"main\"".to_owned(),
+                dsp_type: ::DspType::Mixer,
+                active: true,
+                bypass: false,
+                parameters: Vec::new(),
+            }, DspNode {
+                id: 2,
+                name: "Low\\pass".to_owned(),
+                dsp_type: ::DspType::LowPass,
+                active: false,
+                bypass: true,
+                parameters: vec![parameter("Cutoff", 5000f32)],
+            }],
+            edges: vec![DspEdge {
+                input: 2,
+                output: 1,
+                mix: 0.5f32,
+                levels: vec![(::Speaker::FrontLeft, vec![1f32, 0f32]),
+                             (::Speaker::FrontRight, vec![0f32, 1f32])],
+            }],
+        }
+    }
+
+    #[test]
+    fn escape_json_special_characters() {
+        assert_eq!(escape_json("a\"b\\c"), "a\\\"b\\\\c");
+        assert_eq!(escape_json("1\n2\r3\t4"), "1\\n2\\r3\\t4");
+        assert_eq!(escape_json("\u{1}\u{1f}"), "\\u0001\\u001f");
+        assert_eq!(escape_json("écho"), "écho");
+    }
+
+    #[test]
+    fn escape_dot_quotes() {
+        assert_eq!(escape_dot("a\"b\\c"), "a\\\"b\\\\c");
+    }
+
+    #[test]
+    fn json_number_not_finite() {
+        assert_eq!(json_number(0.5f32), "0.5");
+        assert_eq!(json_number(-2f32), "-2");
+        assert_eq!(json_number(::std::f32::NAN), "null");
+        assert_eq!(json_number(::std::f32::INFINITY), "null");
+        assert_eq!(json_number(::std::f32::NEG_INFINITY), "null");
+    }
+
+    #[test]
+    fn to_json_nodes_and_edges() {
+        assert_eq!(graph().to_json(),
+                   "{\"nodes\":[\
+                    {\"id\":1,\"name\":\"Head \\\"main\\\"\",\"dsp_type\":\"Mixer\",\
+                     \"active\":true,\"bypass\":false,\"parameters\":[]},\
+                    {\"id\":2,\"name\":\"Low\\\\pass\",\"dsp_type\":\"LowPass\",\
+                     \"active\":false,\"bypass\":true,\"parameters\":[\
+                     {\"index\":0,\"name\":\"Cutoff\",\"label\":\"hz\",\"value\":5000,\
+                      \"min\":-1,\"max\":1}]}],\
+                    \"edges\":[{\"input\":2,\"output\":1,\"mix\":0.5,\
+                     \"levels\":{\"FrontLeft\":[1,0],\"FrontRight\":[0,1]}}]}");
+    }
+
+    #[test]
+    fn to_json_not_finite_values() {
+        let mut graph = graph();
+
+        graph.nodes[1].parameters[0].value = ::std::f32::NAN;
+        graph.edges[0].mix = ::std::f32::INFINITY;
+        graph.edges[0].levels = vec![(::Speaker::FrontLeft, vec![::std::f32::NAN])];
+
+        let json = graph.to_json();
+
+        assert!(json.contains("\"value\":null"));
+        assert!(json.contains("\"mix\":null"));
+        assert!(json.contains("\"levels\":{\"FrontLeft\":[null]}"));
+    }
+
+    #[test]
+    fn to_dot_nodes_and_edges() {
+        let dot = graph().to_dot();
+
+        assert!(dot.starts_with("digraph dsp {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("    n1 [label=\"Head \\\"main\\\"\\nMixer\"];\n"));
+        assert!(dot.contains("    n2 [label=\"Low\\\\pass\\nLowPass\\nCutoff = 5000hz\\n(inactive)\
+                              \\n(bypass)\", style=\"dashed,filled\", fillcolor=grey];\n"));
+        assert!(dot.contains("    n2 -> n1 [label=\"0.5\"];\n"));
+    }
+
+    #[test]
+    fn to_dot_muted_edge() {
+        let mut graph = graph();
+
+        graph.edges[0].mix = 0f32;
+        assert!(graph.to_dot().contains("    n2 -> n1 [label=\"0\", style=dashed];\n"));
+    }
+
+    #[test]
+    fn edges_of_node() {
+        let graph = graph();
+
+        assert_eq!(graph.get_inputs(1).len(), 1);
+        assert_eq!(graph.get_outputs(1).len(), 0);
+        assert_eq!(graph.get_outputs(2)[0].output, 1);
+        assert!(graph.get_node(3).is_none());
+    }
+}
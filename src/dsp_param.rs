@@ -0,0 +1,167 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use dsp::Dsp;
+use error::DspParamError;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Parameter of a builtin DSP effect, implemented by the per-effect enums like
+/// [`DspTypeEcho`](enum.DspTypeEcho.html) or [`DspCompressor`](enum.DspCompressor.html).
+pub trait DspParam: Copy + fmt::Debug {
+    /// Type of the DSP units having this parameter.
+    fn dsp_type() -> ::DspType;
+    /// Index of the parameter, as given to [`Dsp::set_parameter`](struct.Dsp.html#method.set_parameter).
+    fn index(&self) -> i32;
+}
+
+macro_rules! dsp_param {
+    ($($param:ident => $dsp_type:ident),*) => {
+        $(impl DspParam for ::$param {
+            fn dsp_type() -> ::DspType {
+                ::DspType::$dsp_type
+            }
+
+            fn index(&self) -> i32 {
+                *self as i32
+            }
+        })*
+    }
+}
+
+dsp_param!(DspOscillator => Oscillator,
+           DspLowPass => LowPass,
+           DspITLowPass => ITLowPass,
+           DspHighPass => HighPass,
+           DspTypeEcho => Echo,
+           DspDelay => Delay,
+           DspFlange => Flange,
+           DspTremolo => Tremolo,
+           DspDistortion => Distortion,
+           DspNormalize => Normalize,
+           DspTypeParameq => Parameq,
+           DspPitchShift => PitchShift,
+           DspChorus => Chorus,
+           DspITEcho => ITEcho,
+           DspCompressor => Compressor,
+           DspSfxReverb => SFXReverb,
+           DspLowPassSimple => LowPassSimple,
+           DspHighPassSimple => HighPassSimple);
+
+pub fn check_type<P: DspParam>(dsp: &Dsp) -> Result<(), DspParamError> {
+    match dsp.get_type() {
+        Ok(t) if t == P::dsp_type() => Ok(()),
+        Ok(t) => Err(DspParamError::WrongType { expected: P::dsp_type(), found: t }),
+        Err(e) => Err(DspParamError::Fmod(e)),
+    }
+}
+
+pub fn set_checked<P: DspParam>(dsp: &Dsp, param: P, value: f32) -> Result<(), DspParamError> {
+    set_in_range(dsp, param.index(), value)
+}
+
+/// Sets the parameter at `index` after checking that `value` is in its range.
+pub fn set_in_range(dsp: &Dsp, index: i32, value: f32) -> Result<(), DspParamError> {
+    let (min, max) = match dsp.get_parameter_range(index) {
+        Ok(r) => r,
+        Err(e) => return Err(DspParamError::Fmod(e)),
+    };
+
+    if value < min || value > max {
+        return Err(DspParamError::OutOfRange { value: value, min: min, max: max });
+    }
+    match dsp.set_parameter(index, value) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(DspParamError::Fmod(e)),
+    }
+}
+
+/// DSP unit whose type has been checked to match the parameters `P`, so they can be set without
+/// checking it again.
+///
+/// ```ignore
+/// let echo : rfmod::EchoDsp = fmod.create_typed_DSP().unwrap();
+///
+/// echo.set(rfmod::DspTypeEcho::Delay, 500f32).unwrap();
+/// echo.set(rfmod::DspTypeEcho::DecayRatio, 0.3f32).unwrap();
+/// fmod.add_DSP(echo.get_dsp()).unwrap();
+/// ```
+pub struct TypedDsp<P: DspParam> {
+    dsp: Dsp,
+    param: PhantomData<P>
+}
+
+impl<P: DspParam> TypedDsp<P> {
+    /// Wraps `dsp`, returning `DspParamError::WrongType` if its type isn't the one of `P`.
+    pub fn new(dsp: Dsp) -> Result<TypedDsp<P>, DspParamError> {
+        match check_type::<P>(&dsp) {
+            Ok(()) => Ok(TypedDsp { dsp: dsp, param: PhantomData }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Sets `param` after checking that `value` is in its range.
+    pub fn set(&self, param: P, value: f32) -> Result<(), DspParamError> {
+        set_checked(&self.dsp, param, value)
+    }
+
+    pub fn get(&self, param: P) -> Result<f32, ::FmodError> {
+        match self.dsp.get_parameter(param.index(), 16) {
+            Ok((value, _)) => Ok(value),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the (min, max) range of `param`.
+    pub fn get_range(&self, param: P) -> Result<(f32, f32), ::FmodError> {
+        self.dsp.get_parameter_range(param.index())
+    }
+
+    pub fn get_dsp(&self) -> &Dsp {
+        &self.dsp
+    }
+
+    pub fn into_dsp(self) -> Dsp {
+        self.dsp
+    }
+}
+
+pub type OscillatorDsp = TypedDsp<::DspOscillator>;
+pub type LowPassDsp = TypedDsp<::DspLowPass>;
+pub type ITLowPassDsp = TypedDsp<::DspITLowPass>;
+pub type HighPassDsp = TypedDsp<::DspHighPass>;
+pub type EchoDsp = TypedDsp<::DspTypeEcho>;
+pub type DelayDsp = TypedDsp<::DspDelay>;
+pub type FlangeDsp = TypedDsp<::DspFlange>;
+pub type TremoloDsp = TypedDsp<::DspTremolo>;
+pub type DistortionDsp = TypedDsp<::DspDistortion>;
+pub type NormalizeDsp = TypedDsp<::DspNormalize>;
+pub type ParameqDsp = TypedDsp<::DspTypeParameq>;
+pub type PitchShiftDsp = TypedDsp<::DspPitchShift>;
+pub type ChorusDsp = TypedDsp<::DspChorus>;
+pub type ITEchoDsp = TypedDsp<::DspITEcho>;
+pub type CompressorDsp = TypedDsp<::DspCompressor>;
+pub type SfxReverbDsp = TypedDsp<::DspSfxReverb>;
+pub type LowPassSimpleDsp = TypedDsp<::DspLowPassSimple>;
+pub type HighPassSimpleDsp = TypedDsp<::DspHighPassSimple>;
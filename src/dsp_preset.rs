@@ -0,0 +1,147 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use dsp::Dsp;
+use dsp_graph;
+use dsp_param;
+use fmod_sys::Sys;
+use error::DspParamError;
+
+/// Parameter value of a [`DspPreset`](struct.DspPreset.html).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DspPresetParameter {
+    pub index: i32,
+    /// Name FMOD gives to the parameter. Only there to make presets readable, it can be left out.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: String,
+    pub value: f32,
+}
+
+/// Type and parameter values of a DSP unit, which can be captured from a unit and applied to
+/// another one. With the `serde` feature, presets can be stored in any format serde supports:
+///
+/// ```ignore
+/// let preset : rfmod::DspPreset = toml::from_str(r#"
+///     dsp_type = "Echo"
+///
+///     [[parameters]]
+///     index = 0
+///     name = "Delay"
+///     value = 250.0
+/// "#).unwrap();
+/// let echo = preset.create(&fmod).unwrap();
+///
+/// fmod.add_DSP(&echo).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DspPreset {
+    pub dsp_type: ::DspType,
+    pub parameters: Vec<DspPresetParameter>,
+}
+
+impl DspPreset {
+    pub fn new(dsp_type: ::DspType) -> DspPreset {
+        DspPreset {
+            dsp_type: dsp_type,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Captures the type and the current value of every parameter of `dsp`.
+    pub fn from_dsp(dsp: &Dsp) -> Result<DspPreset, ::FmodError> {
+        let dsp_type = match dsp.get_type() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+        };
+        let num_parameters = match dsp.get_num_parameters() {
+            Ok(n) => n,
+            Err(e) => return Err(e),
+        };
+        let mut parameters = Vec::with_capacity(num_parameters.max(0) as usize);
+
+        for index in 0..num_parameters {
+            match dsp_graph::get_parameter(dsp, index) {
+                Ok(p) => parameters.push(DspPresetParameter {
+                    index: index,
+                    name: p.name,
+                    value: p.value,
+                }),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(DspPreset {
+            dsp_type: dsp_type,
+            parameters: parameters,
+        })
+    }
+
+    /// Sets the parameter at `index`, replacing its previous value if there was one.
+    pub fn set_parameter(&mut self, index: i32, value: f32) {
+        match self.parameters.iter_mut().find(|p| p.index == index) {
+            Some(p) => {
+                p.value = value;
+                return;
+            }
+            None => {}
+        }
+        self.parameters.push(DspPresetParameter { index: index, name: String::new(), value: value });
+    }
+
+    pub fn get_parameter(&self, index: i32) -> Option<f32> {
+        self.parameters.iter().find(|p| p.index == index).map(|p| p.value)
+    }
+
+    /// Sets the parameters of `dsp`, after checking that it has the type of the preset. Values
+    /// are checked against the parameter ranges, the first one out of its range stops the
+    /// parameters that follow it from being set.
+    pub fn apply(&self, dsp: &Dsp) -> Result<(), DspParamError> {
+        match dsp.get_type() {
+            Ok(t) if t == self.dsp_type => {}
+            Ok(t) => return Err(DspParamError::WrongType { expected: self.dsp_type, found: t }),
+            Err(e) => return Err(DspParamError::Fmod(e)),
+        }
+        for p in self.parameters.iter() {
+            match dsp_param::set_in_range(dsp, p.index, p.value) {
+                Ok(()) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates a DSP unit of the type of the preset and applies the preset to it.
+    pub fn create(&self, sys: &Sys) -> Result<Dsp, DspParamError> {
+        let dsp = match sys.create_DSP_by_type(self.dsp_type) {
+            Ok(dsp) => dsp,
+            Err(e) => return Err(DspParamError::Fmod(e)),
+        };
+
+        match self.apply(&dsp) {
+            Ok(()) => Ok(dsp),
+            Err(e) => Err(e),
+        }
+    }
+}
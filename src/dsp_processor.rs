@@ -0,0 +1,295 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use ffi;
+use dsp::DspParameterDesc;
+use libc::{c_char, c_int, c_uint, c_float, c_void};
+use std::ffi::CString;
+use std::sync::Mutex;
+
+/// Custom DSP unit written in Rust, created with
+/// [`Sys::create_DSP_from`](struct.Sys.html#method.create_DSP_from).
+///
+/// Every unit owns its processor: it is dropped when the unit is released. `process` runs on the
+/// FMOD mixer thread while the other methods run on the thread calling the matching
+/// [`Dsp`](struct.Dsp.html) method, so the processor is locked during each call.
+///
+/// ```ignore
+/// struct Gain {
+///     gain: f32,
+/// }
+///
+/// impl rfmod::DspProcessor for Gain {
+///     fn process(&mut self, input: &[f32], output: &mut [f32], _channels: usize) {
+///         for (o, i) in output.iter_mut().zip(input.iter()) {
+///             *o = *i * self.gain;
+///         }
+///     }
+///
+///     fn set_param(&mut self, _index: i32, value: f32) {
+///         self.gain = value;
+///     }
+///
+///     fn get_param(&self, _index: i32) -> f32 {
+///         self.gain
+///     }
+///
+///     fn get_parameters(&self) -> Vec<rfmod::DspParameterDesc> {
+///         vec![rfmod::DspParameterDesc { min: 0f32, max: 2f32, default_val: 1f32,
+///                                        name: "Gain".to_owned(), ..Default::default() }]
+///     }
+/// }
+///
+/// let dsp = fmod.create_DSP_from(Box::new(Gain { gain: 1f32 })).unwrap();
+/// ```
+pub trait DspProcessor: Send {
+    /// Writes into `output` the processed `input`. Both hold the same number of interleaved
+    /// samples of `channels` channels.
+    fn process(&mut self, input: &[f32], output: &mut [f32], channels: usize);
+
+    /// Called by [`Dsp::set_parameter`](struct.Dsp.html#method.set_parameter), with an index
+    /// in the range of `get_parameters`.
+    fn set_param(&mut self, _index: i32, _value: f32) {}
+
+    /// Called by [`Dsp::get_parameter`](struct.Dsp.html#method.get_parameter).
+    fn get_param(&self, _index: i32) -> f32 {
+        0f32
+    }
+
+    /// Called by [`Dsp::reset`](struct.Dsp.html#method.reset) to clear the history buffers.
+    fn reset(&mut self) {}
+
+    /// Name of the unit, truncated to 31 bytes.
+    fn get_name(&self) -> String {
+        String::new()
+    }
+
+    /// Parameters of the unit, read once when it is created.
+    fn get_parameters(&self) -> Vec<DspParameterDesc> {
+        Vec::new()
+    }
+}
+
+/// Data of a unit, stored in the `plugin_data` of its FMOD state.
+struct ProcessorData {
+    processor: Mutex<Box<dyn DspProcessor>>,
+    params: Vec<ffi::FMOD_DSP_PARAMETERDESC>,
+    /// Descriptions pointed by `params`.
+    #[allow(dead_code)]
+    descriptions: Vec<CString>,
+}
+
+fn copy_str(dst: &mut [c_char], s: &str) {
+    // the last byte is kept for the nul terminator
+    let len = dst.len() - 1;
+
+    for (d, b) in dst.iter_mut().zip(s.as_bytes().iter().take(len)) {
+        *d = *b as c_char;
+    }
+}
+
+/// Returns the data of a unit, set by the create callback.
+unsafe fn get_data<'a>(dsp_state: *mut ffi::FMOD_DSP_STATE) -> Option<&'a ProcessorData> {
+    if dsp_state.is_null() || (*dsp_state).plugin_data.is_null() {
+        None
+    } else {
+        Some(&*((*dsp_state).plugin_data as *const ProcessorData))
+    }
+}
+
+extern "C" fn create_callback(dsp_state: *mut ffi::FMOD_DSP_STATE) -> ::Status {
+    unsafe {
+        if dsp_state.is_null() || (*dsp_state).instance.is_null() {
+            return ::Status::InvalidParam;
+        }
+        let mut data = ::std::ptr::null_mut();
+
+        // the data is given as user data, it is moved into the plugin data so the user data
+        // stays free for Dsp::set_user_data
+        match ffi::FMOD_DSP_GetUserData((*dsp_state).instance, &mut data) {
+            ::Status::Ok => {
+                (*dsp_state).plugin_data = data;
+                ffi::FMOD_DSP_SetUserData((*dsp_state).instance, ::std::ptr::null_mut())
+            }
+            e => e,
+        }
+    }
+}
+
+extern "C" fn release_callback(dsp_state: *mut ffi::FMOD_DSP_STATE) -> ::Status {
+    unsafe {
+        if !dsp_state.is_null() && !(*dsp_state).plugin_data.is_null() {
+            drop(Box::from_raw((*dsp_state).plugin_data as *mut ProcessorData));
+            (*dsp_state).plugin_data = ::std::ptr::null_mut();
+        }
+        ::Status::Ok
+    }
+}
+
+extern "C" fn reset_callback(dsp_state: *mut ffi::FMOD_DSP_STATE) -> ::Status {
+    match unsafe { get_data(dsp_state) } {
+        Some(data) => match data.processor.lock() {
+            Ok(mut processor) => {
+                processor.reset();
+                ::Status::Ok
+            }
+            Err(_) => ::Status::Internal,
+        },
+        None => ::Status::Ok,
+    }
+}
+
+extern "C" fn read_callback(dsp_state: *mut ffi::FMOD_DSP_STATE, in_buffer: *mut c_float,
+                            out_buffer: *mut c_float, length: c_uint, in_channels: c_int,
+                            out_channels: c_int) -> ::Status {
+    let data = match unsafe { get_data(dsp_state) } {
+        Some(data) => data,
+        None => return ::Status::Ok,
+    };
+    if in_buffer.is_null() || out_buffer.is_null() || in_channels != out_channels ||
+       out_channels <= 0 {
+        return ::Status::InvalidParam;
+    }
+    let len = length as usize * out_channels as usize;
+    let input = unsafe { ::std::slice::from_raw_parts(in_buffer as *const f32, len) };
+    let output = unsafe { ::std::slice::from_raw_parts_mut(out_buffer, len) };
+
+    match data.processor.lock() {
+        Ok(mut processor) => {
+            processor.process(input, output, out_channels as usize);
+            ::Status::Ok
+        }
+        Err(_) => ::Status::Internal,
+    }
+}
+
+extern "C" fn set_parameter_callback(dsp_state: *mut ffi::FMOD_DSP_STATE, index: c_int,
+                                     value: c_float) -> ::Status {
+    match unsafe { get_data(dsp_state) } {
+        Some(data) => match data.processor.lock() {
+            Ok(mut processor) => {
+                processor.set_param(index, value);
+                ::Status::Ok
+            }
+            Err(_) => ::Status::Internal,
+        },
+        None => ::Status::Ok,
+    }
+}
+
+extern "C" fn get_parameter_callback(dsp_state: *mut ffi::FMOD_DSP_STATE, index: c_int,
+                                     value: *mut c_float, value_str: *mut c_char) -> ::Status {
+    let data = match unsafe { get_data(dsp_state) } {
+        Some(data) => data,
+        None => return ::Status::Ok,
+    };
+    let v = match data.processor.lock() {
+        Ok(processor) => processor.get_param(index),
+        Err(_) => return ::Status::Internal,
+    };
+
+    unsafe {
+        if !value.is_null() {
+            *value = v;
+        }
+        if !value_str.is_null() {
+            // FMOD gives a 16 bytes buffer
+            let s = ::std::slice::from_raw_parts_mut(value_str, 16);
+            let label = match data.params.get(index as usize) {
+                Some(p) => ::std::ffi::CStr::from_ptr(p.label.as_ptr()).to_string_lossy()
+                                                                       .into_owned(),
+                None => String::new(),
+            };
+
+            for c in s.iter_mut() {
+                *c = 0;
+            }
+            copy_str(s, &format!("{:.2}{}", v, label));
+        }
+    }
+    ::Status::Ok
+}
+
+/// Builds the description of a unit running `processor`. The data pointed by the `user_data`
+/// of the description must be freed with `free_data` if the unit isn't created.
+pub fn get_description(processor: Box<dyn DspProcessor>) -> ffi::FMOD_DSP_DESCRIPTION {
+    let parameters = processor.get_parameters();
+    let mut name = [0 as c_char; 32];
+    let mut descriptions = Vec::with_capacity(parameters.len());
+    let mut params = Vec::with_capacity(parameters.len());
+
+    copy_str(&mut name, &processor.get_name());
+    for parameter in parameters.iter() {
+        let description = CString::new(parameter.description.replace('\0', ""))
+                                  .unwrap_or_default();
+        let mut param = ffi::FMOD_DSP_PARAMETERDESC {
+            min: parameter.min,
+            max: parameter.max,
+            default_val: parameter.default_val,
+            name: [0; 16],
+            label: [0; 16],
+            description: description.as_ptr(),
+        };
+
+        copy_str(&mut param.name, &parameter.name);
+        copy_str(&mut param.label, &parameter.label);
+        params.push(param);
+        descriptions.push(description);
+    }
+    let mut data = Box::new(ProcessorData {
+        processor: Mutex::new(processor),
+        params: params,
+        descriptions: descriptions,
+    });
+
+    ffi::FMOD_DSP_DESCRIPTION {
+        name: name,
+        version: 0,
+        channels: 0,
+        create: Some(create_callback as extern "C" fn(*mut _) -> _),
+        release: Some(release_callback as extern "C" fn(*mut _) -> _),
+        reset: Some(reset_callback as extern "C" fn(*mut _) -> _),
+        read: Some(read_callback as extern "C" fn(*mut _, *mut _, *mut _, _, _, _) -> _),
+        set_position: None,
+        num_parameters: data.params.len() as c_int,
+        param_desc: if data.params.is_empty() {
+            ::std::ptr::null_mut()
+        } else {
+            data.params.as_mut_ptr()
+        },
+        set_parameter: Some(set_parameter_callback as extern "C" fn(*mut _, _, _) -> _),
+        get_parameter: Some(get_parameter_callback as extern "C" fn(*mut _, _, _, _) -> _),
+        config: None,
+        config_width: 0,
+        config_height: 0,
+        user_data: Box::into_raw(data) as *mut c_void,
+    }
+}
+
+/// Frees the data of a description whose unit hasn't been created.
+pub fn free_data(description: &ffi::FMOD_DSP_DESCRIPTION) {
+    if !description.user_data.is_null() {
+        unsafe { drop(Box::from_raw(description.user_data as *mut ProcessorData)) };
+    }
+}
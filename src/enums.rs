@@ -569,6 +569,7 @@ pub enum OutputType {
 }
 
 #[derive(Clone, PartialEq, PartialOrd, Debug, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(C)]
 // FIXME
 /// These are speaker types defined for use with the
@@ -713,6 +714,26 @@ pub enum OpenState {
 #[repr(C)]
 /// These callback types are used with
 /// [`Channel::set_callback`](../../struct.Channel.html#method.set_callback).
+pub enum ChannelCallbackType {
+    /// Called when a sound ends.
+    End,
+    /// Called when a voice is swapped out or swapped in.
+    VirtualVoice,
+    /// Called when a syncpoint is encountered. Can be from wav file markers.
+    SyncPoint,
+    /// Called when the channel has its geometry occlusion value calculated. Can be used to clamp
+    /// or change the value.
+    Occlusion,
+    /// Maximum number of callback types supported.
+    Max,
+    /// Makes sure this enum is signed 32bit.
+    ForceInt = 65536,
+}
+
+#[derive(Clone, PartialEq, PartialOrd, Debug, Copy)]
+#[repr(C)]
+/// These callback types are used with
+/// [`Sys::set_callback`](../../struct.Sys.html#method.set_callback).
 pub enum SystemCallbackType {
     /// Called from [`Sys::update`](../../struct.Sys.html#method.update) when the enumerated list of
     /// devices has changed.
@@ -768,6 +789,7 @@ pub enum SoundGroupBehavior {
 }
 
 #[derive(Clone, PartialEq, PartialOrd, Debug, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(C)]
 /// These definitions can be used for creating FMOD defined special effects or DSP units.
 /// Used with [`Dsp::set_parameter`](../struct.Dsp.html#method.set_parameter) and
@@ -22,6 +22,213 @@
 * 3. This notice may not be removed or altered from any source distribution.
 */
 
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Error returned by the FMOD wrappers: the status of the failing FMOD call and its name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FmodError {
+    function: &'static str,
+    status: ::Status,
+}
+
+impl FmodError {
+    pub fn new(function: &'static str, status: ::Status) -> FmodError {
+        FmodError {
+            function: function,
+            status: status,
+        }
+    }
+
+    /// Name of the failing call: the FMOD function, like `"FMOD_System_Init"`, or the wrapper
+    /// method when the error doesn't come from FMOD.
+    pub fn get_function(&self) -> &'static str {
+        self.function
+    }
+
+    pub fn get_status(&self) -> ::Status {
+        self.status
+    }
+}
+
+impl fmt::Display for FmodError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} failed: {}", self.function, error_string(self.status))
+    }
+}
+
+impl Error for FmodError {}
+
+/// Implements `Display`, `Error` and `From<FmodError>` for an error enum whose `Fmod` variant
+/// wraps an `FmodError`. The match arms given for the other variants display them and return
+/// their source; `from` replaces the default conversion into the `Fmod` variant.
+macro_rules! fmod_error {
+    ($name:ident, |$f:ident| { $($display:pat => $display_expr:expr),* $(,)* },
+     source { $($source:pat => $source_expr:expr),* $(,)* }) => {
+        fmod_error!($name, |$f| { $($display => $display_expr),* },
+                    source { $($source => $source_expr),* }, from |e| $name::Fmod(e));
+    };
+    ($name:ident, |$f:ident| { $($display:pat => $display_expr:expr),* $(,)* },
+     source { $($source:pat => $source_expr:expr),* $(,)* }, from |$e:ident| $from:expr) => {
+        impl fmt::Display for $name {
+            fn fmt(&self, $f: &mut fmt::Formatter) -> fmt::Result {
+                match *self {
+                    $name::Fmod(ref e) => write!($f, "{}", e),
+                    $($display => $display_expr,)*
+                }
+            }
+        }
+
+        impl Error for $name {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                match *self {
+                    $name::Fmod(ref e) => Some(e),
+                    $($source => $source_expr,)*
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+        }
+
+        impl From<FmodError> for $name {
+            fn from($e: FmodError) -> $name {
+                $from
+            }
+        }
+    };
+}
+
+/// Error returned by [`Sound::save_to_wav`](../struct.Sound.html#method.save_to_wav) and
+/// [`Sound::write_wav`](../struct.Sound.html#method.write_wav).
+#[derive(Debug)]
+pub enum WavError {
+    /// An FMOD call failed.
+    Fmod(FmodError),
+    /// Writing to the output failed.
+    Io(io::Error),
+    /// The sound data isn't PCM, so it can't be stored in a wav file as is.
+    UnsupportedFormat(::SoundFormat),
+}
+
+fmod_error!(WavError, |f| {
+    WavError::Io(ref e) => write!(f, "{}", e),
+    WavError::UnsupportedFormat(format) => write!(f, "unsupported sound format: {:?}", format),
+}, source {
+    WavError::Io(ref e) => Some(e),
+});
+
+impl From<io::Error> for WavError {
+    fn from(e: io::Error) -> WavError {
+        WavError::Io(e)
+    }
+}
+
+/// Error returned by [`GeometryImporter`](../struct.GeometryImporter.html).
+#[derive(Debug)]
+pub enum ImportError {
+    /// An FMOD call failed.
+    Fmod(FmodError),
+    /// Reading the mesh file failed.
+    Io(io::Error),
+    /// The OBJ data is invalid.
+    Parse {
+        /// Line of the error, starting at 1.
+        line: usize,
+        message: String
+    },
+}
+
+fmod_error!(ImportError, |f| {
+    ImportError::Io(ref e) => write!(f, "{}", e),
+    ImportError::Parse { line, ref message } => write!(f, "line {}: {}", line, message),
+}, source {
+    ImportError::Io(ref e) => Some(e),
+});
+
+impl From<io::Error> for ImportError {
+    fn from(e: io::Error) -> ImportError {
+        ImportError::Io(e)
+    }
+}
+
+/// Error returned by [`ChannelHandle`](../struct.ChannelHandle.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChannelError {
+    /// The channel has been reused to play another voice.
+    Stolen,
+    /// The voice has ended, or the handle is no longer valid.
+    InvalidHandle,
+    /// Another FMOD error.
+    Fmod(FmodError),
+}
+
+fmod_error!(ChannelError, |f| {
+    ChannelError::Stolen => write!(f, "{}", error_string(::Status::ChannelStolen)),
+    ChannelError::InvalidHandle => write!(f, "{}", error_string(::Status::InvalidHandle)),
+}, source {}, from |e| match e.get_status() {
+    ::Status::ChannelStolen => ChannelError::Stolen,
+    ::Status::InvalidHandle => ChannelError::InvalidHandle,
+    _ => ChannelError::Fmod(e),
+});
+
+/// Error returned by the commands of [`SharedSys`](../struct.SharedSys.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommandError {
+    /// An FMOD call failed.
+    Fmod(FmodError),
+    /// The channel has been reused to play another voice.
+    Stolen,
+    /// The sound or channel id doesn't exist, or the voice has ended.
+    UnknownId,
+    /// The `CommandQueue` has been dropped before running the command.
+    Disconnected,
+}
+
+fmod_error!(CommandError, |f| {
+    CommandError::Stolen => write!(f, "{}", error_string(::Status::ChannelStolen)),
+    CommandError::UnknownId => write!(f, "unknown sound or channel id"),
+    CommandError::Disconnected => write!(f, "the command queue has been dropped"),
+}, source {});
+
+impl From<ChannelError> for CommandError {
+    fn from(e: ChannelError) -> CommandError {
+        match e {
+            ChannelError::Stolen => CommandError::Stolen,
+            ChannelError::InvalidHandle => CommandError::UnknownId,
+            ChannelError::Fmod(e) => CommandError::Fmod(e),
+        }
+    }
+}
+
+/// Error returned when setting a parameter of a builtin DSP effect, see
+/// [`TypedDsp`](../struct.TypedDsp.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DspParamError {
+    /// The DSP unit isn't of the type the parameter belongs to.
+    WrongType {
+        expected: ::DspType,
+        found: ::DspType
+    },
+    /// The value is outside of the range returned by `Dsp::get_parameter_range`.
+    OutOfRange {
+        value: f32,
+        min: f32,
+        max: f32
+    },
+    /// An FMOD call failed.
+    Fmod(FmodError),
+}
+
+fmod_error!(DspParamError, |f| {
+    DspParamError::WrongType { expected, found } => {
+        write!(f, "expected a {:?} DSP, found a {:?} one", expected, found)
+    },
+    DspParamError::OutOfRange { value, min, max } => {
+        write!(f, "{} is out of the [{}, {}] range", value, min, max)
+    },
+}, source {});
+
 pub fn error_string(errcode: ::Status) -> &'static str {
     match errcode {
         ::Status::AlreadyLocked => "Tried to call lock a second time before unlock was called.",
@@ -65,6 +65,8 @@ pub struct FMOD_SYNCPOINT;
 
 pub type FMOD_SYSTEM_CALLBACK = Option<extern "C" fn(system: *mut FMOD_SYSTEM, _type: ::SystemCallbackType, command_data1: *mut c_void,
     command_data2: *mut c_void) -> ::Status>;
+pub type FMOD_CHANNEL_CALLBACK = Option<extern "C" fn(channel: *mut FMOD_CHANNEL, _type: ::ChannelCallbackType, command_data1: *mut c_void,
+    command_data2: *mut c_void) -> ::Status>;
 
 /* file callbacks */
 pub type FMOD_FILE_OPENCALLBACK = Option<extern "C" fn(name: *mut c_char, unicode: c_int, file_size: *mut c_uint, handle: *mut *mut c_void,
@@ -74,7 +76,7 @@ pub type FMOD_FILE_READCALLBACK = Option<extern "C" fn(handle: *mut c_void, buff
     user_data: *mut c_void) -> ::Status>;
 pub type FMOD_FILE_SEEKCALLBACK = Option<extern "C" fn(handle: *mut c_void, pos: c_uint, user_data: *mut c_void) -> ::Status>;
 pub type FMOD_FILE_ASYNCREADCALLBACK = Option<extern "C" fn(arg1: *mut FMOD_ASYNCREADINFO, arg2: *mut c_void) -> ::Status>;
-pub type FMOD_FILE_ASYNCCANCELCALLBACK = Option<extern "C" fn(arg1: *mut c_void, arg2: *mut c_void, arg3: c_uint) -> ::Status>;
+pub type FMOD_FILE_ASYNCCANCELCALLBACK = Option<extern "C" fn(handle: *mut c_void, user_data: *mut c_void) -> ::Status>;
 
 /* sound callbacks */
 pub type FMOD_SOUND_NONBLOCKCALLBACK = Option<extern "C" fn(sound: *mut FMOD_SOUND, result: ::Status) -> ::Status>;
@@ -93,7 +95,7 @@ pub type FMOD_CODEC_SOUNDCREATECALLBACK = Option<extern "C" fn(codec_state: *mut
 pub type FMOD_CODEC_METADATACALLBACK = Option<extern "C" fn(codec_state: *mut FMOD_CODEC_STATE, tag_type: ::TagType, name: *mut c_char, data: *mut c_void,
     data_len: c_uint, data_type: ::TagDataType, unique: c_int) -> ::Status>;
 pub type FMOD_CODEC_GETWAVEFORMAT = Option<extern "C" fn(codec_state: *mut FMOD_CODEC_STATE, index: c_int, wave_format: *mut FMOD_CODEC_WAVEFORMAT) -> ::Status>;
-pub type FMOD_3D_ROLLOFFCALLBACK = Option<extern "C" fn(channel: *mut FMOD_CHANNEL, distance: c_float) -> ::Status>;
+pub type FMOD_3D_ROLLOFFCALLBACK = Option<extern "C" fn(channel: *mut FMOD_CHANNEL, distance: c_float) -> c_float>;
 
 /*  DSP callbacks */
 pub type FMOD_DSP_CREATECALLBACK = Option<extern "C" fn(dsp_state: *mut FMOD_DSP_STATE) -> ::Status>;
@@ -106,6 +108,10 @@ pub type FMOD_DSP_SETPARAMCALLBACK = Option<extern "C" fn(dsp_state: *mut FMOD_D
 pub type FMOD_DSP_GETPARAMCALLBACK = Option<extern "C" fn(dsp_state: *mut FMOD_DSP_STATE, index: c_int, value: *mut c_float, value_str: *mut c_char) -> ::Status>;
 pub type FMOD_DSP_DIALOGCALLBACK = Option<extern "C" fn(dsp_state: *mut FMOD_DSP_STATE, hwnd: *mut c_void, show: c_int) -> ::Status>;
 
+#[cfg(feature = "mock")]
+pub use mock_ffi::*;
+
+#[cfg(not(feature = "mock"))]
 extern "C" {
     pub fn FMOD_System_Create(system: *mut *mut FMOD_SYSTEM) -> ::Status;
     pub fn FMOD_System_Release(system: *mut FMOD_SYSTEM) -> ::Status;
@@ -140,8 +146,9 @@ extern "C" {
     pub fn FMOD_System_GetAdvancedSettings(system: *mut FMOD_SYSTEM, settings: *mut FMOD_ADVANCEDSETTINGS) -> ::Status;
     pub fn FMOD_System_SetSpeakerMode(system: *mut FMOD_SYSTEM, speaker_mode: ::SpeakerMode) -> ::Status;
     pub fn FMOD_System_GetSpeakerMode(system: *mut FMOD_SYSTEM, speaker_mode: *mut ::SpeakerMode) -> ::Status;
-    // I'll bind it a little later
     pub fn FMOD_System_SetCallback(system: *mut FMOD_SYSTEM, call_back: FMOD_SYSTEM_CALLBACK) -> ::Status;
+    pub fn FMOD_System_SetUserData(system: *mut FMOD_SYSTEM, user_data: *mut c_void) -> ::Status;
+    pub fn FMOD_System_GetUserData(system: *mut FMOD_SYSTEM, user_data: *mut *mut c_void) -> ::Status;
     /* plug-in part functions */
     pub fn FMOD_System_SetPluginPath(system: *mut FMOD_SYSTEM, path: *const c_char) -> ::Status;
     pub fn FMOD_System_LoadPlugin(system: *mut FMOD_SYSTEM, filename: *const c_char, handle: *mut c_uint, priority: c_uint) -> ::Status;
@@ -174,7 +181,7 @@ extern "C" {
     pub fn FMOD_System_GetMemoryInfo(system: *mut FMOD_SYSTEM, memory_bits: c_uint, event_memory_bits: c_uint, memory_used: *mut c_uint,
         memoryused_details: *mut FMOD_MEMORY_USAGE_DETAILS) -> ::Status;
     /* I'll bind it later */
-    pub fn FMOD_System_SetFMOD_3D_ROLLOFFCALLBACK(system: *mut FMOD_SYSTEM, callback: FMOD_3D_ROLLOFFCALLBACK) -> ::Status;
+    pub fn FMOD_System_Set3DRolloffCallback(system: *mut FMOD_SYSTEM, callback: FMOD_3D_ROLLOFFCALLBACK) -> ::Status;
     pub fn FMOD_System_Set3DSpeakerPosition(system: *mut FMOD_SYSTEM, speaker: ::Speaker, x: c_float, y: c_float, active: FMOD_BOOL) -> ::Status;
     pub fn FMOD_System_Get3DSpeakerPosition(system: *mut FMOD_SYSTEM, speaker: ::Speaker, x: *mut c_float, y: *mut c_float, active: *mut FMOD_BOOL) -> ::Status;
     pub fn FMOD_System_Set3DSettings(system: *mut FMOD_SYSTEM, doppler_scale: c_float, distance_factor: c_float, roll_off_scale: c_float) -> ::Status;
@@ -331,8 +338,7 @@ extern "C" {
     pub fn FMOD_Channel_GetLowPassGain(channel: *mut FMOD_CHANNEL, gain: *mut c_float) -> ::Status;
     pub fn FMOD_Channel_SetChannelGroup(channel: *mut FMOD_CHANNEL, channelgroup: *mut FMOD_CHANNELGROUP) -> ::Status;
     pub fn FMOD_Channel_GetChannelGroup(channel: *mut FMOD_CHANNEL, channelgroup: *mut *mut FMOD_CHANNELGROUP) -> ::Status;
-    /* I'll bind it later */
-    //pub fn FMOD_Channel_SetCallback(channel: *mut FMOD_CHANNEL, callback: *mut FMOD_Channel_CALLBACK) -> ::Status;
+    pub fn FMOD_Channel_SetCallback(channel: *mut FMOD_CHANNEL, callback: FMOD_CHANNEL_CALLBACK) -> ::Status;
     /* 3D functionality */
     pub fn FMOD_Channel_Set3DAttributes(channel: *mut FMOD_CHANNEL, position: *mut FMOD_VECTOR, velociy: *mut FMOD_VECTOR) -> ::Status;
     pub fn FMOD_Channel_Get3DAttributes(channel: *mut FMOD_CHANNEL, position: *mut FMOD_VECTOR, velociy: *mut FMOD_VECTOR) -> ::Status;
@@ -386,6 +392,7 @@ extern "C" {
     
     /* channel_group functions */
     pub fn FMOD_ChannelGroup_Release(channel_group: *mut FMOD_CHANNELGROUP) -> ::Status;
+    pub fn FMOD_ChannelGroup_GetSystemObject(channel_group: *mut FMOD_CHANNELGROUP, system: *mut *mut FMOD_SYSTEM) -> ::Status;
     /* Channelgroup scale values. (changes attributes relative to the channels, doesn't overwrite them)*/
     pub fn FMOD_ChannelGroup_SetVolume(channel_group: *mut FMOD_CHANNELGROUP, volume: c_float) -> ::Status;
     pub fn FMOD_ChannelGroup_GetVolume(channel_group: *mut FMOD_CHANNELGROUP, volume: *mut c_float) -> ::Status;
@@ -430,6 +437,7 @@ extern "C" {
 
     /* sound_group functions */
     pub fn FMOD_SoundGroup_Release(sound_group: *mut FMOD_SOUNDGROUP) -> ::Status;
+    pub fn FMOD_SoundGroup_GetSystemObject(sound_group: *mut FMOD_SOUNDGROUP, system: *mut *mut FMOD_SYSTEM) -> ::Status;
     /* SoundGroup control functions.*/
     pub fn FMOD_SoundGroup_SetMaxAudible(sound_group: *mut FMOD_SOUNDGROUP, max_audible: c_int) -> ::Status;
     pub fn FMOD_SoundGroup_GetMaxAudible(sound_group: *mut FMOD_SOUNDGROUP, max_audible: *mut c_int) -> ::Status;
@@ -555,6 +563,7 @@ extern "C" {
         memory_used_details: *mut FMOD_MEMORY_USAGE_DETAILS) -> ::Status;
 }
 
+#[repr(C)]
 pub struct FMOD_ASYNCREADINFO {
     pub handle     : *mut c_void,   /* [r] The file handle that was filled out in the open callback. */
     pub offset     : c_uint,        /* [r] Seek position, make sure you read from this file offset. */
@@ -649,6 +658,7 @@ pub struct FMOD_ADVANCEDSETTINGS {
     pub stackSizeMixer             : c_uint              /* [r/w] Optional. Specify 0 to ignore. Specify the stack size for the FMOD mixer thread. Useful for custom dsps that use excess stack. Default 49,152 (48kb) */
 }
 
+#[repr(C)]
 pub struct FMOD_CODEC_DESCRIPTION {
     pub name           : *mut c_char,                   /* [in] Name of the codec. */
     pub version        : c_uint,                        /* [in] Plugin writer's version number. */
@@ -664,6 +674,7 @@ pub struct FMOD_CODEC_DESCRIPTION {
     pub getwaveformat  : FMOD_CODEC_GETWAVEFORMAT       /* [in] Callback to tell FMOD about the waveformat of a particular subsound. This is to save memory, rather than saving 1000 FMOD_CODEC_WAVEFORMAT structures in the codec, the codec might have a more optimal way of storing this information. */
 }
 
+#[repr(C)]
 pub struct FMOD_CODEC_WAVEFORMAT
 {
     pub name       : [c_char; 256],    /* [in] Name of sound.*/
@@ -679,10 +690,11 @@ pub struct FMOD_CODEC_WAVEFORMAT
     pub channelmask: c_uint            /* [in] Microsoft speaker channel mask, as defined for WAVEFORMATEXTENSIBLE and is found in ksmedia.h. Leave at 0 to play in natural speaker order. */
 }
 
+#[repr(C)]
 pub struct FMOD_CODEC_STATE
 {
     pub numsubsounds: c_int,                      /* [in] Number of 'subsounds' in this sound. Anything other than 0 makes it a 'container' format (ie CDDA/DLS/FSB etc which contain 1 or more su bsounds). For most normal, single sound codec such as WAV/AIFF/MP3, this should be 0 as they are not a container for subsounds, they are the sound by itself. */
-    pub waveformat  : FMOD_CODEC_WAVEFORMAT,      /* [in] Pointer to an array of format structures containing information about each sample. Can be 0 or NULL if FMOD_CODEC_GETWAVEFORMAT callback is preferred. The number of entries here must equal the number of subsounds defined in the subsound parameter. If numsubsounds = 0 then there should be 1 instance of this structure. */
+    pub waveformat  : *mut FMOD_CODEC_WAVEFORMAT, /* [in] Pointer to an array of format structures containing information about each sample. Can be 0 or NULL if FMOD_CODEC_GETWAVEFORMAT callback is preferred. The number of entries here must equal the number of subsounds defined in the subsound parameter. If numsubsounds = 0 then there should be 1 instance of this structure. */
     pub plugindata  : *mut c_void,                /* [in] Plugin writer created data the codec author wants to attach to this object. */
                                                
     pub filehandle  : *mut c_void,                /* [out] This will return an internal FMOD file handle to use with the callbacks provided. */
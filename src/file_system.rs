@@ -0,0 +1,135 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use std::io::{self, Read, Seek};
+use std::fs::File;
+use std::path::PathBuf;
+
+/// A stream FMOD reads a file from. It is implemented for every `Read + Seek + Send` type, like
+/// `std::fs::File` or `std::io::Cursor<Vec<u8>>`.
+pub trait FmodFileStream: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> FmodFileStream for T {}
+
+/// A virtual file system, to be given to
+/// [`Sys::set_virtual_file_system`](struct.Sys.html#method.set_virtual_file_system).
+///
+/// Every file FMOD opens goes through `open`. The returned stream is read and seeked from FMOD
+/// threads and dropped when FMOD closes the file. The file size is computed by seeking to the end
+/// of the stream.
+///
+/// ```ignore
+/// struct Pak { files: HashMap<String, Vec<u8>> }
+///
+/// impl rfmod::FmodFileSystem for Pak {
+///     fn open(&self, name: &str) -> io::Result<Box<dyn rfmod::FmodFileStream>> {
+///         match self.files.get(name) {
+///             Some(data) => Ok(Box::new(io::Cursor::new(data.clone()))),
+///             None => Err(io::Error::new(io::ErrorKind::NotFound, name.to_owned())),
+///         }
+///     }
+/// }
+/// ```
+pub trait FmodFileSystem: Send + Sync {
+    /// Opens the file `name`. Returning an error of kind `NotFound` makes FMOD report
+    /// [`Status::FileNotFound`](enum.Status.html), any other one
+    /// [`Status::FileBad`](enum.Status.html).
+    fn open(&self, name: &str) -> io::Result<Box<dyn FmodFileStream>>;
+}
+
+/// A file system opening files from the disk with `std::fs::File`, relative to a root directory.
+pub struct StdFileSystem {
+    root: PathBuf
+}
+
+impl StdFileSystem {
+    pub fn new<P: Into<PathBuf>>(root: P) -> StdFileSystem {
+        StdFileSystem {
+            root: root.into()
+        }
+    }
+}
+
+impl Default for StdFileSystem {
+    fn default() -> StdFileSystem {
+        StdFileSystem::new(PathBuf::new())
+    }
+}
+
+impl FmodFileSystem for StdFileSystem {
+    fn open(&self, name: &str) -> io::Result<Box<dyn FmodFileStream>> {
+        match File::open(self.root.join(name)) {
+            Ok(f) => Ok(Box::new(f)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// A file FMOD opened, as seen by a [`FileSystemObserver`](trait.FileSystemObserver.html).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObservedFile {
+    id: u64,
+    name: String,
+    size: u32
+}
+
+impl ObservedFile {
+    #[doc(hidden)]
+    pub fn new(id: u64, name: String, size: u32) -> ObservedFile {
+        ObservedFile {
+            id: id,
+            name: name,
+            size: size
+        }
+    }
+
+    /// Identifier unique to this opening of the file.
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Size of the file in bytes.
+    pub fn get_size(&self) -> u32 {
+        self.size
+    }
+}
+
+/// Monitors the file accesses FMOD makes with its own file system, once given to
+/// [`Sys::attach_file_system`](struct.Sys.html#method.attach_file_system).
+///
+/// Methods are called from the thread doing the access, which may be an FMOD one.
+pub trait FileSystemObserver: Send + Sync {
+    /// FMOD opened `file`.
+    fn open(&self, _file: &ObservedFile) {}
+    /// FMOD closed `file`.
+    fn close(&self, _file: &ObservedFile) {}
+    /// FMOD read `data` from `file`.
+    fn read(&self, _file: &ObservedFile, _data: &[u8]) {}
+    /// FMOD seeked `file` to `position`.
+    fn seek(&self, _file: &ObservedFile, _position: u32) {}
+}
@@ -31,8 +31,13 @@ use sound_group;
 use std::mem;
 use channel_group;
 use channel;
+use channel_handle;
 use dsp;
 use dsp::Dsp;
+use dsp_param::{DspParam, TypedDsp};
+use dsp_processor;
+use dsp_graph::DspGraph;
+use dsp_processor::DspProcessor;
 use vector;
 use reverb_properties;
 use geometry;
@@ -44,146 +49,493 @@ use std;
 use file;
 use libc::FILE;
 use c_vec::CVec;
-use std::ffi::CString;
-
-fn get_saved_sys_callback<'r>() -> &'r mut SysCallback {
-    static mut callback : SysCallback = SysCallback {
-            file_open: None,
-            file_close: None,
-            file_read: None,
-            file_seek: None
-        };
-
-    unsafe { &mut callback }
-}
-
-struct SysCallback {
+use std::ffi::{CString, CStr};
+use std::sync::{Arc, Mutex, Weak};
+use std::io::{self, SeekFrom};
+use std::slice;
+use file_system::{FmodFileSystem, FileSystemObserver, ObservedFile};
+use async_read_info;
+use codec;
+use codec::Codec;
+use async_read_info::{AsyncReadInfo, AsyncReadQueue, AsyncReader, VfsFile};
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+/// File callbacks set with [`Sys::set_file_system`](struct.Sys.html#method.set_file_system).
+struct FileCallbacks {
     file_open: FileOpenCallback,
     file_close: FileCloseCallback,
     file_read: FileReadCallback,
     file_seek: FileSeekCallback
 }
 
-impl SysCallback {
-    fn new() -> SysCallback {
-        SysCallback {
-            file_open: None,
-            file_close: None,
-            file_read: None,
-            file_seek: None
+/// Data given to FMOD as the user data of every file opened through the file callbacks. It keeps
+/// the callbacks alive until the file is closed, even if the file system is changed meanwhile.
+struct FileData {
+    callbacks: Arc<FileCallbacks>,
+    user_data: Option<UserData>
+}
+
+/// Maximum number of systems with a file system or a file system observer at the same time.
+pub const MAX_FILE_SYSTEMS : usize = 8;
+
+/// Address of the data of the system using each set of file callbacks, 0 if the set is free.
+static FILE_SYSTEM_SLOTS : Mutex<[usize; MAX_FILE_SYSTEMS]> = Mutex::new([0; MAX_FILE_SYSTEMS]);
+
+/// File callbacks of a slot, which find the system from the slot.
+struct SlotCallbacks {
+    file_open: ffi::FMOD_FILE_OPENCALLBACK,
+    file_close: ffi::FMOD_FILE_CLOSECALLBACK,
+    file_read: ffi::FMOD_FILE_READCALLBACK,
+    file_seek: ffi::FMOD_FILE_SEEKCALLBACK,
+    vfs_open: ffi::FMOD_FILE_OPENCALLBACK,
+    async_open: ffi::FMOD_FILE_OPENCALLBACK,
+    observer_open: ffi::FMOD_FILE_OPENCALLBACK
+}
+
+/// FMOD doesn't give the system to the file callbacks, so each system using its own file system
+/// gets a slot with its own callbacks. Files opened from any thread, like FMOD's non-blocking
+/// loading thread, so reach the system which opened them.
+macro_rules! file_system_callbacks {
+    ($($slot:expr => $name:ident),*) => (
+        $(mod $name {
+            use libc::{c_void, c_uint, c_int, c_char};
+
+            pub extern "C" fn file_open(name: *mut c_char, unicode: c_int, file_size: *mut c_uint,
+                                        handle: *mut *mut c_void,
+                                        user_data: *mut *mut c_void) -> ::Status {
+                super::file_open_callback($slot, name, unicode, file_size, handle, user_data)
+            }
+
+            pub extern "C" fn file_close(handle: *mut c_void, user_data: *mut c_void) -> ::Status {
+                super::file_close_callback($slot, handle, user_data)
+            }
+
+            pub extern "C" fn file_read(handle: *mut c_void, buffer: *mut c_void,
+                                        size_bytes: c_uint, bytes_read: *mut c_uint,
+                                        user_data: *mut c_void) -> ::Status {
+                super::file_read_callback($slot, handle, buffer, size_bytes, bytes_read, user_data)
+            }
+
+            pub extern "C" fn file_seek(handle: *mut c_void, pos: c_uint,
+                                        user_data: *mut c_void) -> ::Status {
+                super::file_seek_callback($slot, handle, pos, user_data)
+            }
+
+            pub extern "C" fn vfs_open(name: *mut c_char, unicode: c_int, file_size: *mut c_uint,
+                                       handle: *mut *mut c_void,
+                                       user_data: *mut *mut c_void) -> ::Status {
+                super::vfs_open_callback($slot, name, unicode, file_size, handle, user_data)
+            }
+
+            pub extern "C" fn async_open(name: *mut c_char, unicode: c_int, file_size: *mut c_uint,
+                                         handle: *mut *mut c_void,
+                                         user_data: *mut *mut c_void) -> ::Status {
+                super::async_open_callback($slot, name, unicode, file_size, handle, user_data)
+            }
+
+            pub extern "C" fn observer_open(name: *mut c_char, unicode: c_int,
+                                            file_size: *mut c_uint, handle: *mut *mut c_void,
+                                            user_data: *mut *mut c_void) -> ::Status {
+                super::observer_open_callback($slot, name, unicode, file_size, handle, user_data)
+            }
+        })*
+
+        static FILE_SYSTEM_CALLBACKS : [SlotCallbacks; MAX_FILE_SYSTEMS] = [$(SlotCallbacks {
+            file_open: Some($name::file_open),
+            file_close: Some($name::file_close),
+            file_read: Some($name::file_read),
+            file_seek: Some($name::file_seek),
+            vfs_open: Some($name::vfs_open),
+            async_open: Some($name::async_open),
+            observer_open: Some($name::observer_open)
+        }),*];
+    )
+}
+
+file_system_callbacks!(0 => file_system_slot0, 1 => file_system_slot1,
+                       2 => file_system_slot2, 3 => file_system_slot3,
+                       4 => file_system_slot4, 5 => file_system_slot5,
+                       6 => file_system_slot6, 7 => file_system_slot7);
+
+/// Returns the slot of the file callbacks of `data`, taking a free one the first time.
+fn get_file_system_slot(data: &SysData, function: &'static str) -> Result<usize, ::FmodError> {
+    let mut slot = match data.file_system_slot.lock() {
+        Ok(s) => s,
+        Err(_) => return Err(::FmodError::new(function, ::Status::Internal)),
+    };
+
+    if let Some(s) = *slot {
+        return Ok(s);
+    }
+    let mut slots = match FILE_SYSTEM_SLOTS.lock() {
+        Ok(s) => s,
+        Err(_) => return Err(::FmodError::new(function, ::Status::Internal)),
+    };
+
+    match slots.iter().position(|&d| d == 0) {
+        Some(s) => {
+            slots[s] = data as *const SysData as usize;
+            *slot = Some(s);
+            Ok(s)
         }
+        None => Err(::FmodError::new(function, ::Status::Memory)),
+    }
+}
+
+/// Returns the data of the system using the callbacks of `slot`.
+fn get_slot_data(slot: usize) -> *mut SysData {
+    match FILE_SYSTEM_SLOTS.lock() {
+        Ok(slots) => slots[slot] as *mut SysData,
+        Err(_) => ::std::ptr::null_mut(),
     }
 }
 
-extern "C" fn file_open_callback(name: *mut c_char, unicode: c_int, file_size: *mut c_uint,
-                                 handle: *mut *mut c_void,
-                                 user_data: *mut *mut c_void) -> ::Status {
-    let tmp = get_saved_sys_callback();
+fn get_file_callbacks(slot: usize) -> Option<Arc<FileCallbacks>> {
+    let data = get_slot_data(slot);
 
-    match tmp.file_open {
-        Some(s) => {
+    if data.is_null() {
+        None
+    } else {
+        match unsafe { (*data).file_system.lock() } {
+            Ok(f) => f.clone(),
+            Err(_) => None,
+        }
+    }
+}
+
+fn get_virtual_file_system(slot: usize) -> Option<Arc<dyn FmodFileSystem>> {
+    let data = get_slot_data(slot);
+
+    if data.is_null() {
+        None
+    } else {
+        match unsafe { (*data).virtual_file_system.lock() } {
+            Ok(f) => f.clone(),
+            Err(_) => None,
+        }
+    }
+}
+
+/// Calls `f` with the callbacks and the user data of the file. Files which weren't opened by
+/// our open callback have no FileData, the callbacks of the system of `slot` are used instead.
+fn with_file_data<T, F>(slot: usize, user_data: *mut c_void, f: F) -> Option<T>
+    where F: FnOnce(&FileCallbacks, Option<&mut UserData>) -> T {
+    if user_data.is_null() {
+        match get_file_callbacks(slot) {
+            Some(callbacks) => Some(f(&callbacks, None)),
+            None => None,
+        }
+    } else {
+        let data = unsafe { &mut *(user_data as *mut FileData) };
+
+        Some(f(&data.callbacks, data.user_data.as_mut()))
+    }
+}
+
+fn file_open_callback(slot: usize, name: *mut c_char, unicode: c_int, file_size: *mut c_uint,
+                      handle: *mut *mut c_void, user_data: *mut *mut c_void) -> ::Status {
+    unsafe {
+        *file_size = 0u32;
+        *handle = std::ptr::null_mut();
+        *user_data = std::ptr::null_mut();
+    }
+    let callbacks = match get_file_callbacks(slot) {
+        Some(c) => c,
+        None => return ::Status::FileNotFound,
+    };
+    let opened = match callbacks.file_open {
+        Some(ref s) => {
             let t_name = if name.is_null() {
                 String::new()
             } else {
-                let l = ffi::strlen(name);
-
-                unsafe { String::from_raw_parts(name as *mut u8, l, l) }
+                unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned()
             };
-            match s(t_name.as_ref(), unicode) {
-                Some((f, s)) => {
-                    unsafe {
-                        *file_size = f.get_file_size() as u32;
-                        *handle = file::get_ffi(&f) as *mut c_void;
-                        *user_data = match s {
-                            Some(mut d) => std::mem::transmute(&mut d),
-                            None => ::std::ptr::null_mut()
-                        };
-                    }
-                   ::Status::Ok
-                }
-                None => {
-                    unsafe {
-                        *file_size = 0u32;
-                        *handle = std::ptr::null_mut();
-                        *user_data = std::ptr::null_mut();
-                    }
-                    ::Status::FileNotFound
-                }
-            }
-        },
-        None => {
+
+            s(t_name.as_ref(), unicode)
+        }
+        None => return ::Status::FileNotFound,
+    };
+
+    match opened {
+        Some((f, s)) => {
             unsafe {
-                *file_size = 0u32;
-                *handle = std::ptr::null_mut();
-                *user_data = std::ptr::null_mut();
+                *file_size = f.get_file_size() as u32;
+                *handle = file::get_ffi(&f) as *mut c_void;
+                *user_data = Box::into_raw(Box::new(FileData {
+                    callbacks: callbacks.clone(),
+                    user_data: s
+                })) as *mut c_void;
             }
             ::Status::Ok
         }
+        None => ::Status::FileNotFound
     }
 }
 
-extern "C" fn file_close_callback(handle: *mut c_void, user_data: *mut c_void) -> ::Status {
-    let tmp = get_saved_sys_callback();
-
-    match tmp.file_close {
-        Some(s) => {
-            unsafe {
-                s(&mut file::from_ffi(handle as *mut FILE), if user_data.is_null() {
-                    None
-                } else {
-                    Some(std::mem::transmute(user_data))
-                });
-            }
-            ::Status::Ok
+fn file_close_callback(slot: usize, handle: *mut c_void, user_data: *mut c_void) -> ::Status {
+    with_file_data(slot, user_data, |callbacks, data| {
+        if let Some(ref s) = callbacks.file_close {
+            s(&mut file::from_ffi(handle as *mut FILE), data);
         }
-        None => ::Status::Ok
+    });
+    if !user_data.is_null() {
+        drop(unsafe { Box::from_raw(user_data as *mut FileData) });
     }
+    ::Status::Ok
 }
 
-extern "C" fn file_read_callback(handle: *mut c_void, buffer: *mut c_void, size_bytes: c_uint,
-                                 bytes_read: *mut c_uint, user_data: *mut c_void) -> ::Status {
-    let tmp = get_saved_sys_callback();
+fn file_read_callback(slot: usize, handle: *mut c_void, buffer: *mut c_void, size_bytes: c_uint,
+                      bytes_read: *mut c_uint, user_data: *mut c_void) -> ::Status {
+    let ret = with_file_data(slot, user_data, |callbacks, data| {
+        match callbacks.file_read {
+            Some(ref s) => {
+                let mut data_vec : CVec<u8> = unsafe { CVec::new(buffer as *mut u8,
+                                                                 size_bytes as usize) };
 
-    match tmp.file_read {
-        Some(s) => {
-            unsafe {
-                let mut data_vec : CVec<u8> = CVec::new(buffer as *mut u8, size_bytes as usize);
+                Some(s(&mut file::from_ffi(handle as *mut FILE), data_vec.as_mut(), size_bytes,
+                       data))
+            }
+            None => None
+        }
+    });
 
-                let read_bytes = s(&mut file::from_ffi(handle as *mut FILE), data_vec.as_mut(),
-                                   size_bytes, if user_data.is_null() {
-                    None
-                } else {
-                    Some(std::mem::transmute(user_data))
-                });
-                *bytes_read = read_bytes as u32;
-                if read_bytes < size_bytes as usize {
-                    ::Status::FileEOF
-                } else {
-                   ::Status::Ok
-                }
+    match ret {
+        Some(Some(read_bytes)) => {
+            unsafe { *bytes_read = read_bytes as u32 };
+            if read_bytes < size_bytes as usize {
+                ::Status::FileEOF
+            } else {
+                ::Status::Ok
             }
         }
-        None => ::Status::Ok
+        _ => ::Status::Ok
     }
 }
 
-extern "C" fn file_seek_callback(handle: *mut c_void, pos: c_uint,
-                                 user_data: *mut c_void) -> ::Status {
-    let tmp = get_saved_sys_callback();
+fn file_seek_callback(slot: usize, handle: *mut c_void, pos: c_uint,
+                      user_data: *mut c_void) -> ::Status {
+    with_file_data(slot, user_data, |callbacks, data| {
+        if let Some(ref s) = callbacks.file_seek {
+            s(&mut file::from_ffi(handle as *mut FILE), pos, data);
+        }
+    });
+    ::Status::Ok
+}
 
-    match tmp.file_seek {
-        Some(s) => {
-            unsafe {
-                s(&mut file::from_ffi(handle as *mut FILE), pos, if user_data.is_null() {
-                    None
-                } else {
-                    Some(std::mem::transmute(user_data))
-                });
-            }
+/// Opens `name` with the virtual file system of the system of `slot` and returns the handle to
+/// give to FMOD.
+fn vfs_open(slot: usize, name: *mut c_char, file_size: *mut c_uint,
+            handle: *mut *mut c_void) -> ::Status {
+    let file_system = match get_virtual_file_system(slot) {
+        Some(f) => f,
+        None => return ::Status::FileNotFound,
+    };
+    let t_name = if name.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned()
+    };
+    let mut stream = match file_system.open(&t_name) {
+        Ok(s) => s,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return ::Status::FileNotFound,
+        Err(_) => return ::Status::FileBad,
+    };
+    let size = match stream.seek(SeekFrom::End(0)).and_then(|size| {
+        stream.seek(SeekFrom::Start(0)).map(|_| size)
+    }) {
+        Ok(size) => size,
+        Err(_) => return ::Status::FileCouldNotSeek,
+    };
+    let file : Box<VfsFile> = Box::new(Mutex::new(stream));
+
+    unsafe {
+        *file_size = size as u32;
+        *handle = Box::into_raw(file) as *mut c_void;
+    }
+    ::Status::Ok
+}
+
+fn vfs_open_callback(slot: usize, name: *mut c_char, _unicode: c_int, file_size: *mut c_uint,
+                     handle: *mut *mut c_void, user_data: *mut *mut c_void) -> ::Status {
+    unsafe {
+        *file_size = 0u32;
+        *handle = std::ptr::null_mut();
+        *user_data = std::ptr::null_mut();
+    }
+    vfs_open(slot, name, file_size, handle)
+}
+
+extern "C" fn vfs_close_callback(handle: *mut c_void, _user_data: *mut c_void) -> ::Status {
+    if !handle.is_null() {
+        drop(unsafe { Box::from_raw(handle as *mut VfsFile) });
+    }
+    ::Status::Ok
+}
+
+extern "C" fn vfs_read_callback(handle: *mut c_void, buffer: *mut c_void, size_bytes: c_uint,
+                                bytes_read: *mut c_uint, _user_data: *mut c_void) -> ::Status {
+    unsafe { *bytes_read = 0 };
+    if handle.is_null() {
+        return ::Status::InvalidParam;
+    }
+    let file = unsafe { &*(handle as *const VfsFile) };
+    let buffer = unsafe { slice::from_raw_parts_mut(buffer as *mut u8, size_bytes as usize) };
+    let (read, status) = match file.lock() {
+        Ok(mut stream) => async_read_info::read_full(&mut **stream, buffer),
+        Err(_) => (0, ::Status::FileBad),
+    };
+
+    unsafe { *bytes_read = read as u32 };
+    status
+}
+
+extern "C" fn vfs_seek_callback(handle: *mut c_void, pos: c_uint,
+                                _user_data: *mut c_void) -> ::Status {
+    if handle.is_null() {
+        return ::Status::InvalidParam;
+    }
+    let file = unsafe { &*(handle as *const VfsFile) };
+
+    match file.lock() {
+        Ok(mut stream) => match stream.seek(SeekFrom::Start(pos as u64)) {
+            Ok(_) => ::Status::Ok,
+            Err(_) => ::Status::FileCouldNotSeek,
+        },
+        Err(_) => ::Status::FileBad,
+    }
+}
+
+/// Same as vfs_open_callback, but the file user data is the queue its reads go to.
+fn async_open_callback(slot: usize, name: *mut c_char, _unicode: c_int, file_size: *mut c_uint,
+                       handle: *mut *mut c_void, user_data: *mut *mut c_void) -> ::Status {
+    unsafe {
+        *file_size = 0u32;
+        *handle = std::ptr::null_mut();
+        *user_data = std::ptr::null_mut();
+    }
+    let data = get_slot_data(slot);
+    let queue = if data.is_null() {
+        None
+    } else {
+        match unsafe { (*data).async_reader.lock() } {
+            Ok(r) => r.as_ref().map(|r| r.get_queue().clone()),
+            Err(_) => None,
+        }
+    };
+    let queue = match queue {
+        Some(q) => q,
+        None => return ::Status::FileNotFound,
+    };
+
+    match vfs_open(slot, name, file_size, handle) {
+        ::Status::Ok => {
+            unsafe { *user_data = Arc::into_raw(queue) as *mut c_void };
             ::Status::Ok
         }
-        None => ::Status::Ok
+        e => e
+    }
+}
+
+extern "C" fn async_close_callback(handle: *mut c_void, user_data: *mut c_void) -> ::Status {
+    if !user_data.is_null() {
+        let queue = unsafe { Arc::from_raw(user_data as *const AsyncReadQueue) };
+
+        queue.cancel(handle);
+    }
+    vfs_close_callback(handle, ::std::ptr::null_mut())
+}
+
+extern "C" fn async_read_callback(info: *mut ffi::FMOD_ASYNCREADINFO,
+                                  user_data: *mut c_void) -> ::Status {
+    if user_data.is_null() || info.is_null() {
+        return ::Status::InvalidParam;
+    }
+    let queue = unsafe { &*(user_data as *const AsyncReadQueue) };
+
+    queue.push(AsyncReadInfo::new(info));
+    ::Status::Ok
+}
+
+extern "C" fn async_cancel_callback(handle: *mut c_void, user_data: *mut c_void) -> ::Status {
+    if !user_data.is_null() {
+        let queue = unsafe { &*(user_data as *const AsyncReadQueue) };
+
+        queue.cancel(handle);
     }
+    ::Status::Ok
+}
+
+static NEXT_OBSERVED_FILE_ID : AtomicUsize = AtomicUsize::new(0);
+
+/// File user data of the files FMOD opens while an observer is attached.
+struct ObserverData {
+    observer: Arc<dyn FileSystemObserver>,
+    file: ObservedFile
+}
+
+fn observer_open_callback(slot: usize, name: *mut c_char, _unicode: c_int, file_size: *mut c_uint,
+                          _handle: *mut *mut c_void, user_data: *mut *mut c_void) -> ::Status {
+    let data = get_slot_data(slot);
+
+    if data.is_null() {
+        return ::Status::Ok;
+    }
+    let observer = match unsafe { (*data).observer.lock() } {
+        Ok(o) => match *o {
+            Some(ref o) => o.clone(),
+            None => return ::Status::Ok,
+        },
+        Err(_) => return ::Status::Ok,
+    };
+    let t_name = if name.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned()
+    };
+    let size = if file_size.is_null() { 0 } else { unsafe { *file_size } };
+    let file = ObservedFile::new(NEXT_OBSERVED_FILE_ID.fetch_add(1, Ordering::SeqCst) as u64,
+                                 t_name, size);
+
+    observer.open(&file);
+    unsafe {
+        *user_data = Box::into_raw(Box::new(ObserverData {
+            observer: observer,
+            file: file
+        })) as *mut c_void;
+    }
+    ::Status::Ok
+}
+
+extern "C" fn observer_close_callback(_handle: *mut c_void, user_data: *mut c_void) -> ::Status {
+    if !user_data.is_null() {
+        let data = unsafe { Box::from_raw(user_data as *mut ObserverData) };
+
+        data.observer.close(&data.file);
+    }
+    ::Status::Ok
+}
+
+extern "C" fn observer_read_callback(_handle: *mut c_void, buffer: *mut c_void, size_bytes: c_uint,
+                                     bytes_read: *mut c_uint,
+                                     user_data: *mut c_void) -> ::Status {
+    if !user_data.is_null() && !buffer.is_null() {
+        let data = unsafe { &*(user_data as *const ObserverData) };
+        let read = if bytes_read.is_null() { size_bytes } else { unsafe { *bytes_read } };
+
+        data.observer.read(&data.file, unsafe { slice::from_raw_parts(buffer as *const u8,
+                                                                      read as usize) });
+    }
+    ::Status::Ok
+}
+
+extern "C" fn observer_seek_callback(_handle: *mut c_void, pos: c_uint,
+                                     user_data: *mut c_void) -> ::Status {
+    if !user_data.is_null() {
+        let data = unsafe { &*(user_data as *const ObserverData) };
+
+        data.observer.seek(&data.file, pos);
+    }
+    ::Status::Ok
 }
 
 extern "C" fn pcm_read_callback(sound: *mut ffi::FMOD_SOUND, data: *mut c_void,
@@ -201,7 +553,7 @@ extern "C" fn pcm_read_callback(sound: *mut ffi::FMOD_SOUND, data: *mut c_void,
                         let max = data_len as isize >> 2;
                         let mut data_vec = CVec::new(data as *mut c_short, max as usize * 2);
 
-                        let ret = p(&ffi::FFI::wrap(sound), data_vec.as_mut());
+                        let ret = p(&sound::from_callback_ptr(sound), data_vec.as_mut());
                         ret
                     },
                     None => ::Status::Ok
@@ -225,7 +577,7 @@ extern "C" fn non_block_callback(sound: *mut ffi::FMOD_SOUND, result: ::Status)
                 let callbacks : &mut ffi::SoundData = ::std::mem::transmute(tmp);
 
                 match callbacks.non_block {
-                    Some(p) => p(&ffi::FFI::wrap(sound), result),
+                    Some(p) => p(&sound::from_callback_ptr(sound), result),
                     None => ::Status::Ok
                 }
             } else {
@@ -248,7 +600,7 @@ extern "C" fn pcm_set_pos_callback(sound: *mut ffi::FMOD_SOUND, sub_sound: c_int
                 let callbacks : &mut ffi::SoundData = ::std::mem::transmute(tmp);
 
                 match callbacks.pcm_set_pos {
-                    Some(p) => p(&ffi::FFI::wrap(sound), sub_sound, position, TimeUnit(postype)),
+                    Some(p) => p(&sound::from_callback_ptr(sound), sub_sound, position, TimeUnit(postype)),
                     None => ::Status::Ok
                 }
             } else {
@@ -260,6 +612,280 @@ extern "C" fn pcm_set_pos_callback(sound: *mut ffi::FMOD_SOUND, sub_sound: c_int
     }
 }
 
+/// Event received by the closure given to
+/// [`Sys::set_callback`](struct.Sys.html#method.set_callback).
+#[derive(Clone, PartialEq, Debug)]
+pub enum SystemEvent {
+    /// The enumerated list of devices has changed. Sent from
+    /// [`Sys::update`](struct.Sys.html#method.update).
+    DeviceListChanged,
+    /// An output device has been lost and FMOD cannot automatically recover. Sent from
+    /// [`Sys::update`](struct.Sys.html#method.update).
+    DeviceLost,
+    /// A memory allocation failed somewhere in FMOD.
+    MemoryAllocationFailed {
+        /// File and line where the allocation happened.
+        location: String,
+        /// Size of the allocation which failed.
+        size: i32
+    },
+    /// FMOD created a thread.
+    ThreadCreated {
+        /// Name of the thread.
+        name: String
+    },
+    /// FMOD destroyed a thread.
+    ThreadDestroyed {
+        /// Name of the thread.
+        name: String
+    },
+    /// A bad connection was made with [`Dsp::add_input`](struct.Dsp.html#method.add_input).
+    /// Usually sent from the mixer thread.
+    BadDSPConnection {
+        /// Type of the unit the input was added to.
+        target: ::DspType,
+        /// Type of the unit added as input.
+        source: ::DspType
+    },
+    /// Too many effects were added, exceeding the maximum tree depth of 128. Usually sent from
+    /// the mixer thread.
+    BadDSPLevel,
+}
+
+/// Data attached to an FMOD system through its user data. It is created on demand and released
+/// with the [`Sys`](struct.Sys.html) which created the system.
+struct SysData {
+    callback: Mutex<Option<Arc<Mutex<Box<dyn FnMut(SystemEvent) + Send>>>>>,
+    file_system: Mutex<Option<Arc<FileCallbacks>>>,
+    virtual_file_system: Mutex<Option<Arc<dyn FmodFileSystem>>>,
+    async_reader: Mutex<Option<AsyncReader>>,
+    observer: Mutex<Option<Arc<dyn FileSystemObserver>>>,
+    codec_slots: Mutex<Vec<usize>>,
+    file_system_slot: Mutex<Option<usize>>,
+    rolloff: Mutex<Option<Arc<dyn Fn(&channel::Channel, f32) -> f32 + Send + Sync>>>
+}
+
+impl Drop for SysData {
+    fn drop(&mut self) {
+        if let Ok(slots) = self.codec_slots.lock() {
+            for slot in slots.iter() {
+                codec::unregister(*slot);
+            }
+        }
+        if let Ok(slot) = self.file_system_slot.lock() {
+            if let Some(slot) = *slot {
+                if let Ok(mut slots) = FILE_SYSTEM_SLOTS.lock() {
+                    slots[slot] = 0;
+                }
+            }
+        }
+    }
+}
+
+/// System which receives the events sent without system handle (memory allocation failures).
+static LAST_CALLBACK_DATA : AtomicPtr<SysData> = AtomicPtr::new(0 as *mut SysData);
+
+fn get_sys_data<'r>(system: *mut ffi::FMOD_SYSTEM) -> Result<&'r SysData, ::FmodError> {
+    let mut tmp = ::std::ptr::null_mut();
+
+    match unsafe { ffi::FMOD_System_GetUserData(system, &mut tmp) } {
+        ::Status::Ok => {}
+        e => return Err(::FmodError::new("FMOD_System_GetUserData", e)),
+    }
+    if tmp.is_null() {
+        tmp = Box::into_raw(Box::new(SysData {
+            callback: Mutex::new(None),
+            file_system: Mutex::new(None),
+            virtual_file_system: Mutex::new(None),
+            async_reader: Mutex::new(None),
+            observer: Mutex::new(None),
+            codec_slots: Mutex::new(Vec::new()),
+            file_system_slot: Mutex::new(None),
+            rolloff: Mutex::new(None)
+        })) as *mut c_void;
+        match unsafe { ffi::FMOD_System_SetUserData(system, tmp) } {
+            ::Status::Ok => {}
+            e => {
+                drop(unsafe { Box::from_raw(tmp as *mut SysData) });
+                return Err(::FmodError::new("FMOD_System_SetUserData", e));
+            }
+        }
+    }
+    Ok(unsafe { &*(tmp as *const SysData) })
+}
+
+/// Every `SystemRef` alive, keyed by the system it keeps alive.
+static SYSTEM_REFS : Mutex<Vec<(usize, Weak<SystemRef>)>> = Mutex::new(Vec::new());
+
+/// Shared ownership of a system created by `Sys::new`. The first `Sys` and every object coming
+/// from it hold one, the system is closed and released once the last of them is dropped.
+pub struct SystemRef {
+    system: AtomicPtr<ffi::FMOD_SYSTEM>
+}
+
+impl SystemRef {
+    fn new(system: *mut ffi::FMOD_SYSTEM) -> Arc<SystemRef> {
+        let system_ref = Arc::new(SystemRef {
+            system: AtomicPtr::new(system)
+        });
+
+        if let Ok(mut refs) = SYSTEM_REFS.lock() {
+            refs.push((system as usize, Arc::downgrade(&system_ref)));
+        }
+        system_ref
+    }
+
+    fn release(&self) -> Result<(), ::FmodError> {
+        let system = self.system.load(Ordering::SeqCst);
+
+        if system.is_null() {
+            return Ok(());
+        }
+        unsafe {
+            let mut data = ::std::ptr::null_mut();
+
+            ffi::FMOD_System_GetUserData(system, &mut data);
+            match ffi::FMOD_System_Close(system) {
+                ::Status::Ok => {}
+                e => return Err(::FmodError::new("FMOD_System_Close", e)),
+            }
+            match ffi::FMOD_System_Release(system) {
+                ::Status::Ok => {
+                    self.system.store(::std::ptr::null_mut(), Ordering::SeqCst);
+                    channel::remove_callbacks(system as usize, None);
+                    channel_handle::remove_generations(system as usize);
+                    if !data.is_null() {
+                        let data = data as *mut SysData;
+
+                        let _ = LAST_CALLBACK_DATA.compare_exchange(data,
+                                                                    ::std::ptr::null_mut(),
+                                                                    Ordering::SeqCst,
+                                                                    Ordering::SeqCst);
+                        drop(Box::from_raw(data));
+                    }
+                    Ok(())
+                }
+                e => Err(::FmodError::new("FMOD_System_Release", e))
+            }
+        }
+    }
+}
+
+impl Drop for SystemRef {
+    fn drop(&mut self) {
+        let _ = self.release();
+        // strong_count is used rather than upgrade so no SystemRef is dropped under the lock
+        if let Ok(mut refs) = SYSTEM_REFS.lock() {
+            refs.retain(|&(_, ref r)| r.strong_count() > 0);
+        }
+    }
+}
+
+/// Returns a reference keeping `system` alive, None if it hasn't been created by `Sys::new` or
+/// is already being released.
+pub fn get_system_ref(system: *mut ffi::FMOD_SYSTEM) -> Option<Arc<SystemRef>> {
+    if system.is_null() {
+        return None;
+    }
+    match SYSTEM_REFS.lock() {
+        Ok(refs) => refs.iter()
+                        .filter(|&&(s, _)| s == system as usize)
+                        .filter_map(|&(_, ref r)| r.upgrade())
+                        .next(),
+        Err(_) => None,
+    }
+}
+
+fn c_str_to_string(s: *mut c_void) -> String {
+    if s.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(s as *const c_char) }.to_string_lossy().into_owned()
+    }
+}
+
+fn dsp_type_from_raw(value: *mut c_void) -> ::DspType {
+    let value = value as usize;
+
+    if value <= ::DspType::HighPassSimple as usize || value == ::DspType::Hardware as usize {
+        unsafe { ::std::mem::transmute(value as i32) }
+    } else {
+        ::DspType::Unknown
+    }
+}
+
+extern "C" fn system_callback(system: *mut ffi::FMOD_SYSTEM, _type: ::SystemCallbackType,
+                              command_data1: *mut c_void,
+                              command_data2: *mut c_void) -> ::Status {
+    let data = if system.is_null() {
+        LAST_CALLBACK_DATA.load(Ordering::SeqCst)
+    } else {
+        let mut tmp = ::std::ptr::null_mut();
+
+        unsafe { ffi::FMOD_System_GetUserData(system, &mut tmp) };
+        tmp as *mut SysData
+    };
+    if data.is_null() {
+        return ::Status::Ok;
+    }
+    let event = match _type {
+        ::SystemCallbackType::DeviceListChanged => SystemEvent::DeviceListChanged,
+        ::SystemCallbackType::DeviceLost => SystemEvent::DeviceLost,
+        ::SystemCallbackType::MemoryAllocationFailed => SystemEvent::MemoryAllocationFailed {
+            location: c_str_to_string(command_data1),
+            size: command_data2 as usize as i32
+        },
+        ::SystemCallbackType::ThreadCreated => SystemEvent::ThreadCreated {
+            name: c_str_to_string(command_data2)
+        },
+        ::SystemCallbackType::ThreadDestroyed => SystemEvent::ThreadDestroyed {
+            name: c_str_to_string(command_data2)
+        },
+        ::SystemCallbackType::BadDSPConnection => SystemEvent::BadDSPConnection {
+            target: dsp_type_from_raw(command_data1),
+            source: dsp_type_from_raw(command_data2)
+        },
+        ::SystemCallbackType::BadDSPLevel => SystemEvent::BadDSPLevel,
+        _ => return ::Status::Ok,
+    };
+
+    // the closure is cloned out so it can replace or remove itself, the events being sent from
+    // Sys::update on the caller's thread
+    let callback = match unsafe { (*data).callback.lock() } {
+        Ok(c) => match *c {
+            Some(ref c) => c.clone(),
+            None => return ::Status::Ok,
+        },
+        Err(_) => return ::Status::Ok,
+    };
+
+    if let Ok(mut callback) = callback.lock() {
+        (&mut *callback)(event);
+    }
+    ::Status::Ok
+}
+
+extern "C" fn rolloff_callback(channel: *mut ffi::FMOD_CHANNEL, distance: f32) -> f32 {
+    let mut system = ::std::ptr::null_mut();
+    let mut data = ::std::ptr::null_mut();
+
+    if unsafe { ffi::FMOD_Channel_GetSystemObject(channel, &mut system) } != ::Status::Ok ||
+       unsafe { ffi::FMOD_System_GetUserData(system, &mut data) } != ::Status::Ok ||
+       data.is_null() {
+        return 1f32;
+    }
+    // the closure is cloned so the lock isn't held while it runs on the mixer thread
+    let rolloff = match unsafe { (*(data as *mut SysData)).rolloff.lock() } {
+        Ok(r) => match *r {
+            Some(ref r) => r.clone(),
+            None => return 1f32,
+        },
+        Err(_) => return 1f32,
+    };
+
+    rolloff(&channel::from_callback_ptr(channel), distance)
+}
+
 /// Structure describing a globally unique identifier.
 pub struct Guid
 {
@@ -340,6 +966,7 @@ impl Default for SoftwareFormat {
 
 /// Settings for advanced features like configuring memory and cpu usage for the
 /// FMOD_CREATECOMPRESSEDSAMPLE feature.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AdvancedSettings {
     /// [r/w] Optional. Specify 0 to ignore. For use with FMOD_CREATECOMPRESSEDSAMPLE only. Mpeg
     /// codecs consume 21,684 bytes per instance and this number will determine how many mpeg
@@ -711,8 +1338,9 @@ impl CreateSoundexInfo {
     }
 }
 
-/// When creating a codec, declare one of these and provide the relevant callbacks and name for FMOD
-/// to use when it opens and reads a file.
+/// Describes a [`Codec`](trait.Codec.html) to FMOD. Returned by
+/// [`Codec::get_description`](trait.Codec.html#method.get_description), the callbacks are the
+/// methods of the codec.
 pub struct FmodCodecDescription {
     /// [in] Name of the codec.
     pub name             : String,
@@ -733,31 +1361,6 @@ pub struct FmodCodecDescription {
     /// Use bitwise OR to accumulate different
     /// types.
     pub time_units       : TimeUnit,
-    /// [in] Open callback for the codec for when FMOD tries to open a sound using this codec.
-    open                 : ffi::FMOD_CODEC_OPENCALLBACK,
-    /// [in] Close callback for the codec for when FMOD tries to close a sound using this codec.
-    close                : ffi::FMOD_CODEC_CLOSECALLBACK,
-    /// [in] Read callback for the codec for when FMOD tries to read some data from the file to the
-    /// destination format (specified in the open callback).
-    read                 : ffi::FMOD_CODEC_READCALLBACK,
-    /// [in] Callback to return the length of the song in whatever format required when
-    /// [`Sound::get_length`](doc/rfmod/struct.Sound.html#method.get_length).
-    /// is called.
-    get_length           : ffi::FMOD_CODEC_GETLENGTHCALLBACK,
-    /// [in] Seek callback for the codec for when FMOD tries to seek within the file with
-    /// [`Channel::set_position`](doc/rfmod/struct.Channel.html#method.set_position).
-    set_position         : ffi::FMOD_CODEC_SETPOSITIONCALLBACK,
-    /// [in] Tell callback for the codec for when FMOD tries to get the current position within the
-    /// with [`Channel::get_position`](doc/rfmod/struct.Channel.html#method.get_position).
-    get_position         : ffi::FMOD_CODEC_GETPOSITIONCALLBACK,
-    /// [in] Sound creation callback for the codec when FMOD finishes creating the sound. (So the
-    /// codec can set more parameters for the related created sound, ie loop points/mode or 3D
-    /// attributes etc).
-    sound_create         : ffi::FMOD_CODEC_SOUNDCREATECALLBACK,
-    /// [in] Callback to tell FMOD about the waveformat of a particular subsound. This is to save
-    /// memory, rather than saving 1000 FMOD_CODEC_WAVEFORMAT structures in the codec, the codec
-    /// might have a more optimal way of storing this information.
-    get_wave_format      : ffi::FMOD_CODEC_GETWAVEFORMAT,
 }
 
 impl Default for FmodCodecDescription {
@@ -767,14 +1370,6 @@ impl Default for FmodCodecDescription {
             version: 0u32,
             default_as_stream: 0i32,
             time_units: TimeUnit(0u32),
-            open: None,
-            close: None,
-            read: None,
-            get_length: None,
-            set_position: None,
-            get_position: None,
-            sound_create: None,
-            get_wave_format: None,
         }
     }
 }
@@ -1050,12 +1645,13 @@ pub fn from_memory_usage_details_ptr(details: ffi::FMOD_MEMORY_USAGE_DETAILS) ->
 /// FMOD System Object
 pub struct Sys {
     system: *mut ffi::FMOD_SYSTEM,
-    is_first: bool
+    is_first: bool,
+    system_ref: Option<Arc<SystemRef>>
 }
 
 impl ffi::FFI<ffi::FMOD_SYSTEM> for Sys {
     fn wrap(system: *mut ffi::FMOD_SYSTEM) -> Sys {
-        Sys {system: system, is_first: false}
+        Sys {system: system, is_first: false, system_ref: get_system_ref(system)}
     }
 
     fn unwrap(s: &Sys) -> *mut ffi::FMOD_SYSTEM {
@@ -1063,59 +1659,138 @@ impl ffi::FFI<ffi::FMOD_SYSTEM> for Sys {
     }
 }
 
+/// Wraps a system returned by one of its objects, sharing their `system_ref`.
+pub fn from_ptr(system: *mut ffi::FMOD_SYSTEM, system_ref: Option<Arc<SystemRef>>) -> Sys {
+    Sys {system: system, is_first: false, system_ref: system_ref}
+}
+
+/// Returns another handle on the system of `sys`, which doesn't release it.
+pub fn share(sys: &Sys) -> Sys {
+    from_ptr(sys.system, sys.system_ref.clone())
+}
+
 impl Drop for Sys {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl Sys {
-    /* the first one created has to be the last one released */
-    pub fn new() -> Result<Sys, ::Status> {
+    /// Creates a new system. Objects created from it keep it alive, so it is only released once
+    /// they are all dropped.
+    pub fn new() -> Result<Sys, ::FmodError> {
         let mut tmp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_Create(&mut tmp) } {
-            ::Status::Ok => Ok(Sys{system: tmp, is_first: true}),
-            err => Err(err)
+            ::Status::Ok => Ok(Sys {
+                system: tmp,
+                is_first: true,
+                system_ref: Some(SystemRef::new(tmp))
+            }),
+            err => Err(::FmodError::new("FMOD_System_Create", err))
         }
     }
 
-    pub fn init(&self) -> ::Status {
-        unsafe { ffi::FMOD_System_Init(self.system, 1, ::INIT_NORMAL, ::std::ptr::null_mut()) }
+    pub fn init(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_Init(self.system, 1, ::INIT_NORMAL, ::std::ptr::null_mut()) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_Init", e)),
+        }
     }
 
-    pub fn init_with_parameters(&self, max_channels: i32, InitFlag(flag): InitFlag) -> ::Status {
-        unsafe { ffi::FMOD_System_Init(self.system, max_channels, flag, ::std::ptr::null_mut()) }
+    pub fn init_with_parameters(&self, max_channels: i32, InitFlag(flag): InitFlag)
+                               -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_Init(self.system, max_channels, flag, ::std::ptr::null_mut()) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_Init", e)),
+        }
     }
 
-    pub fn update(&self) -> ::Status {
-        unsafe { ffi::FMOD_System_Update(self.system) }
+    pub fn update(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_Update(self.system) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_Update", e)),
+        }
     }
 
-    pub fn release(&mut self) -> ::Status {
-        if self.is_first && !self.system.is_null() {
-            unsafe {
-                match match ffi::FMOD_System_Close(self.system) {
-                    ::Status::Ok => ffi::FMOD_System_Release(self.system),
-                    e => e
-                } {
-                    ::Status::Ok => {
-                        self.system = ::std::ptr::null_mut();
-                       ::Status::Ok
-                    }
-                    e => e
+    /// Releases the system. Fails with `Status::InvalidHandle` if objects created from it (sounds,
+    /// channels, DSPs...) are still alive: the system is left untouched, to be released once
+    /// they are dropped. Dropping the `Sys` instead lets the last of them release the system,
+    /// from whatever thread drops it.
+    pub fn release(&mut self) -> Result<(), ::FmodError> {
+        if !self.is_first || self.system.is_null() {
+            return Ok(());
+        }
+        let system_ref = match self.system_ref.take() {
+            Some(r) => r,
+            None => return Ok(()),
+        };
+        match Arc::try_unwrap(system_ref) {
+            Ok(system_ref) => match system_ref.release() {
+                Ok(()) => {
+                    self.system = ::std::ptr::null_mut();
+                    Ok(())
+                }
+                Err(e) => {
+                    // the system is still open, a new reference is registered to retry later
+                    system_ref.system.store(::std::ptr::null_mut(), Ordering::SeqCst);
+                    self.system_ref = Some(SystemRef::new(self.system));
+                    Err(e)
                 }
-                
+            },
+            Err(system_ref) => {
+                self.system_ref = Some(system_ref);
+                Err(::FmodError::new("Sys::release", ::Status::InvalidHandle))
             }
-        } else {
-            ::Status::Ok
+        }
+    }
+
+    /// Sets a closure receiving the system events (device changes, DSP graph errors...), in
+    /// place of the previous one.
+    ///
+    /// Events may be sent from FMOD threads, so the closure has to be `Send`. Memory allocation
+    /// failures are sent without system handle: they go to the last system which set a callback.
+    pub fn set_callback<F: FnMut(SystemEvent) + Send + 'static>(&self, callback: F)
+                                -> Result<(), ::FmodError> {
+        let data = match get_sys_data(self.system) {
+            Ok(d) => d,
+            Err(e) => return Err(e),
+        };
+
+        if let Ok(mut c) = data.callback.lock() {
+            *c = Some(Arc::new(Mutex::new(Box::new(callback))));
+        }
+        LAST_CALLBACK_DATA.store(data as *const SysData as *mut SysData, Ordering::SeqCst);
+        match unsafe { ffi::FMOD_System_SetCallback(self.system, Some(system_callback)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetCallback", e)),
+        }
+    }
+
+    /// Removes the closure set with [`set_callback`](#method.set_callback).
+    pub fn remove_callback(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetCallback(self.system, None) } {
+            ::Status::Ok => {}
+            e => return Err(::FmodError::new("FMOD_System_SetCallback", e)),
+        }
+        match get_sys_data(self.system) {
+            Ok(data) => {
+                let _ = LAST_CALLBACK_DATA.compare_exchange(data as *const SysData as *mut SysData,
+                                                            ::std::ptr::null_mut(),
+                                                            Ordering::SeqCst, Ordering::SeqCst);
+                if let Ok(mut c) = data.callback.lock() {
+                    *c = None;
+                }
+                Ok(())
+            }
+            Err(e) => Err(e),
         }
     }
 
     /// If music is empty, null is sent
     pub fn create_sound(&self, music: &str, options: Option<Mode>,
-                        exinfo: Option<&mut CreateSoundexInfo>) -> Result<Sound, ::Status> {
-        let mut sound = sound::from_ptr_first(::std::ptr::null_mut());
+                        exinfo: Option<&mut CreateSoundexInfo>) -> Result<Sound, ::FmodError> {
+        let mut sound = sound::from_ptr_first(::std::ptr::null_mut(), self.system_ref.clone());
         let op = match options {
             Some(Mode(t)) => t,
             None => ::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESTREAM
@@ -1148,13 +1823,13 @@ impl Sys {
             ::Status::Ok => {
                 Ok(sound)
             },
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_System_CreateSound", e))
         }
     }
 
     pub fn create_stream(&self, music: &str, options: Option<Mode>,
-                         exinfo: Option<&mut CreateSoundexInfo>) -> Result<Sound, ::Status> {
-        let mut sound = sound::from_ptr_first(::std::ptr::null_mut());
+                         exinfo: Option<&mut CreateSoundexInfo>) -> Result<Sound, ::FmodError> {
+        let mut sound = sound::from_ptr_first(::std::ptr::null_mut(), self.system_ref.clone());
         let op = match options {
             Some(Mode(t)) => t,
             None => ::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESTREAM
@@ -1185,99 +1860,130 @@ impl Sys {
                                                    sound::get_fffi(&mut sound)) }
         } {
             ::Status::Ok => Ok(sound),
-            err => Err(err)
+            err => Err(::FmodError::new("FMOD_System_CreateStream", err))
         }
     }
 
     pub fn create_channel_group(&self, group_name: &str)
-                                -> Result<channel_group::ChannelGroup, ::Status> {
+                                -> Result<channel_group::ChannelGroup, ::FmodError> {
         let mut channel_group = ::std::ptr::null_mut();
             let tmp_group_name = CString::new(group_name).unwrap();
 
         match unsafe { ffi::FMOD_System_CreateChannelGroup(self.system,
                                                           tmp_group_name.as_ptr() as *const c_char,
                                                           &mut channel_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel_group)),
-            e => Err(e)
+            ::Status::Ok => Ok(channel_group::from_ptr(channel_group, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_CreateChannelGroup", e))
         }
     }
 
     pub fn create_sound_group(&self, group_name: &str)
-                              -> Result<sound_group::SoundGroup, ::Status> {
+                              -> Result<sound_group::SoundGroup, ::FmodError> {
         let mut sound_group = ::std::ptr::null_mut();
             let tmp_group_name = CString::new(group_name).unwrap();
 
         match unsafe { ffi::FMOD_System_CreateSoundGroup(self.system,
                                                          tmp_group_name.as_ptr() as *const c_char,
                                                          &mut sound_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound_group)),
-            e => Err(e)
+            ::Status::Ok => Ok(sound_group::from_ptr(sound_group, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_CreateSoundGroup", e))
         }
     }
 
-    pub fn create_reverb(&self) -> Result<reverb::Reverb, ::Status>{
+    pub fn create_reverb(&self) -> Result<reverb::Reverb, ::FmodError>{
         let mut t_reverb = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_CreateReverb(self.system, &mut t_reverb) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(t_reverb)),
-            e => Err(e)
+            ::Status::Ok => Ok(reverb::from_ptr_first(t_reverb, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_CreateReverb", e))
         }
     }
 
-    pub fn create_DSP(&self) -> Result<dsp::Dsp, ::Status> {
+    pub fn create_DSP(&self) -> Result<dsp::Dsp, ::FmodError> {
         let mut t_dsp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_CreateDSP(self.system, ::std::ptr::null_mut(),
                                                   &mut t_dsp) } {
-            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_CreateDSP", e))
         }
     }
 
     pub fn create_DSP_with_description(&self, description: &mut dsp::DspDescription)
-                                       -> Result<dsp::Dsp, ::Status> {
+                                       -> Result<dsp::Dsp, ::FmodError> {
         let mut t_dsp = ::std::ptr::null_mut();
         let mut t_description = dsp::get_description_ffi(description);
 
         match unsafe { ffi::FMOD_System_CreateDSP(self.system, &mut t_description, &mut t_dsp) } {
-            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_CreateDSP", e))
+        }
+    }
+
+    /// Creates a custom DSP unit running `processor`, which is dropped when the unit is released.
+    /// See [`DspProcessor`](trait.DspProcessor.html).
+    pub fn create_DSP_from(&self, processor: Box<dyn DspProcessor>)
+                           -> Result<dsp::Dsp, ::FmodError> {
+        let mut t_dsp = ::std::ptr::null_mut();
+        let mut t_description = dsp_processor::get_description(processor);
+
+        match unsafe { ffi::FMOD_System_CreateDSP(self.system, &mut t_description, &mut t_dsp) } {
+            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp, self.system_ref.clone())),
+            e => {
+                dsp_processor::free_data(&t_description);
+                Err(::FmodError::new("FMOD_System_CreateDSP", e))
+            }
         }
     }
 
-    pub fn create_DSP_by_type(&self, _type: ::DspType) -> Result<dsp::Dsp, ::Status> {
+    pub fn create_DSP_by_type(&self, _type: ::DspType) -> Result<dsp::Dsp, ::FmodError> {
         let mut t_dsp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_CreateDSPByType(self.system, _type, &mut t_dsp) } {
-            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_CreateDSPByType", e))
+        }
+    }
+
+    /// Creates a builtin effect whose parameters are `P`:
+    ///
+    /// ```ignore
+    /// let compressor : rfmod::CompressorDsp = fmod.create_typed_DSP().unwrap();
+    /// ```
+    pub fn create_typed_DSP<P: DspParam>(&self) -> Result<TypedDsp<P>, ::error::DspParamError> {
+        match self.create_DSP_by_type(P::dsp_type()) {
+            Ok(dsp) => TypedDsp::new(dsp),
+            Err(e) => Err(::error::DspParamError::Fmod(e)),
         }
     }
 
-    pub fn set_output(&self, output_type: ::OutputType) -> ::Status {
-        unsafe { ffi::FMOD_System_SetOutput(self.system, output_type) }
+    pub fn set_output(&self, output_type: ::OutputType) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetOutput(self.system, output_type) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetOutput", e)),
+        }
     }
 
-    pub fn get_output(&self) -> Result<::OutputType, ::Status> {
+    pub fn get_output(&self) -> Result<::OutputType, ::FmodError> {
         let mut output_type = ::OutputType::AutoDetect;
         
         match unsafe { ffi::FMOD_System_GetOutput(self.system, &mut output_type) } {
             ::Status::Ok => Ok(output_type),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_System_GetOutput", e))
         }
     }
 
-    pub fn get_num_drivers(&self) -> Result<i32, ::Status> {
+    pub fn get_num_drivers(&self) -> Result<i32, ::FmodError> {
         let mut num_drivers = 0i32;
 
         match unsafe { ffi::FMOD_System_GetNumDrivers(self.system,
                                                       &mut num_drivers as *mut c_int) } {
             ::Status::Ok => Ok(num_drivers),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_System_GetNumDrivers", e))
         }
     }
 
-    pub fn get_driver_info(&self, id: i32, name_len: usize) -> Result<(Guid, String), ::Status> {
+    pub fn get_driver_info(&self, id: i32, name_len: usize) -> Result<(Guid, String), ::FmodError> {
         let mut c = Vec::with_capacity(name_len + 1);
         let mut guid = ffi::FMOD_GUID {
                            Data1: 0,
@@ -1299,11 +2005,11 @@ impl Sys {
                                     data3: guid.Data3,
                                     data4: guid.Data4,
                                 }, String::from_utf8(c).unwrap())),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetDriverInfo", e)),
         }
     }
 
-    pub fn get_driver_caps(&self, id: i32) -> Result<(FmodCaps, i32, ::SpeakerMode), ::Status> {
+    pub fn get_driver_caps(&self, id: i32) -> Result<(FmodCaps, i32, ::SpeakerMode), ::FmodError> {
         let mut fmod_caps = 0u32;
         let mut speaker_mode = ::SpeakerMode::Raw;
         let mut control_panel_output_rate = 0i32;
@@ -1312,28 +2018,34 @@ impl Sys {
                                                       &mut control_panel_output_rate as *mut c_int,
                                                       &mut speaker_mode) } {
             ::Status::Ok => Ok((FmodCaps(fmod_caps), control_panel_output_rate, speaker_mode)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetDriverCaps", e)),
         }
     }
 
-    pub fn set_driver(&self, driver: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetDriver(self.system, driver as c_int) }
+    pub fn set_driver(&self, driver: i32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetDriver(self.system, driver as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetDriver", e)),
+        }
     }
 
-    pub fn get_driver(&self) -> Result<i32, ::Status> {
+    pub fn get_driver(&self) -> Result<i32, ::FmodError> {
         let mut driver = 0i32;
 
         match unsafe { ffi::FMOD_System_GetDriver(self.system, &mut driver as *mut c_int) } {
             ::Status::Ok => Ok(driver),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetDriver", e)),
         }
     }
 
-    pub fn set_hardware_channels(&self, num_hardware_channels: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetHardwareChannels(self.system, num_hardware_channels as c_int) }
+    pub fn set_hardware_channels(&self, num_hardware_channels: i32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetHardwareChannels(self.system, num_hardware_channels as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetHardwareChannels", e)),
+        }
     }
 
-    pub fn get_hardware_channels(&self) -> Result<i32, ::Status> {
+    pub fn get_hardware_channels(&self) -> Result<i32, ::FmodError> {
         let mut num_hardware_channels = 0i32;
 
         match unsafe {
@@ -1341,15 +2053,18 @@ impl Sys {
                                                  &mut num_hardware_channels as *mut c_int)
         } {
             ::Status::Ok => Ok(num_hardware_channels),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetHardwareChannels", e)),
         }
     }
 
-    pub fn set_software_channels(&self, num_software_channels: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetSoftwareChannels(self.system, num_software_channels as c_int) }
+    pub fn set_software_channels(&self, num_software_channels: i32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetSoftwareChannels(self.system, num_software_channels as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetSoftwareChannels", e)),
+        }
     }
 
-    pub fn get_software_channels(&self) -> Result<i32, ::Status> {
+    pub fn get_software_channels(&self) -> Result<i32, ::FmodError> {
         let mut num_software_channels = 0i32;
 
         match unsafe {
@@ -1357,21 +2072,23 @@ impl Sys {
                                                  &mut num_software_channels as *mut c_int)
         } {
             ::Status::Ok => Ok(num_software_channels),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetSoftwareChannels", e)),
         }
     }
 
     pub fn set_software_format(&self, sample_rate: i32, format: ::SoundFormat,
                                num_output_channels: i32, max_input_channels: i32,
-                               resample_method: ::DspResampler) -> ::Status {
-        unsafe {
-            ffi::FMOD_System_SetSoftwareFormat(self.system, sample_rate as c_int, format,
-                                               num_output_channels as c_int,
-                                               max_input_channels as c_int, resample_method)
+                               resample_method: ::DspResampler) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetSoftwareFormat(self.system, sample_rate as c_int, format,
+                                                          num_output_channels as c_int,
+                                                          max_input_channels as c_int,
+                                                          resample_method) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetSoftwareFormat", e)),
         }
     }
 
-    pub fn get_software_format(&self) -> Result<SoftwareFormat, ::Status> {
+    pub fn get_software_format(&self) -> Result<SoftwareFormat, ::FmodError> {
         let mut t = SoftwareFormat {
             sample_rate: 0,
             format: ::SoundFormat::None,
@@ -1390,16 +2107,20 @@ impl Sys {
                                                           &mut t.bits as *mut c_int)
         } {
             ::Status::Ok => Ok(t),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetSoftwareFormat", e)),
         }
     }
 
-    pub fn set_DSP_buffer_size(&self, buffer_length: u32, num_buffers: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetDSPBufferSize(self.system, buffer_length as c_uint,
-                                                   num_buffers as c_int) }
+    pub fn set_DSP_buffer_size(&self, buffer_length: u32, num_buffers: i32)
+                              -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetDSPBufferSize(self.system, buffer_length as c_uint,
+                                                         num_buffers as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetDSPBufferSize", e)),
+        }
     }
 
-    pub fn get_DSP_buffer_size(&self) -> Result<(u32, i32), ::Status> {
+    pub fn get_DSP_buffer_size(&self) -> Result<(u32, i32), ::FmodError> {
         let mut buffer_length = 0u32;
         let mut num_buffers = 0i32;
 
@@ -1407,11 +2128,12 @@ impl Sys {
                                                          &mut buffer_length as *mut c_uint,
                                                          &mut num_buffers as *mut c_int) } {
             ::Status::Ok => Ok((buffer_length, num_buffers)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetDSPBufferSize", e)),
         }
     }
 
-    pub fn set_advanced_settings(&self, settings: &mut AdvancedSettings) -> ::Status {
+    pub fn set_advanced_settings(&self, settings: &mut AdvancedSettings)
+                                -> Result<(), ::FmodError> {
         let mut converted_c_char: Vec<*const c_char> =
             (0..settings.ASIO_channel_list.len()).map(|pos| {
             settings.ASIO_channel_list[pos].as_ptr() as *const c_char
@@ -1447,10 +2169,16 @@ impl Sys {
             stackSizeMixer: settings.stack_size_mixer,
         };
 
-        unsafe { ffi::FMOD_System_SetAdvancedSettings(self.system, &mut advanced_settings) }
+        match unsafe { ffi::FMOD_System_SetAdvancedSettings(self.system, &mut advanced_settings) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_System_SetAdvancedSettings", e)),
+
+        }
     }
 
-    pub fn get_advanced_settings(&self) -> Result<AdvancedSettings, ::Status> {
+    pub fn get_advanced_settings(&self) -> Result<AdvancedSettings, ::FmodError> {
         let mut advanced_settings = ffi::FMOD_ADVANCEDSETTINGS{
             cbsize: mem::size_of::<ffi::FMOD_ADVANCEDSETTINGS>() as i32,
             maxMPEGcodecs: 0,
@@ -1554,30 +2282,39 @@ impl Sys {
                     stack_size_mixer: advanced_settings.stackSizeMixer,
                 })
             }
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetAdvancedSettings", e)),
         }
     }
 
-    pub fn set_speaker_mode(&self, speaker_mode: ::SpeakerMode) -> ::Status {
-        unsafe { ffi::FMOD_System_SetSpeakerMode(self.system, speaker_mode) }
+    pub fn set_speaker_mode(&self, speaker_mode: ::SpeakerMode) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetSpeakerMode(self.system, speaker_mode) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetSpeakerMode", e)),
+        }
     }
 
-    pub fn get_speaker_mode(&self) -> Result<::SpeakerMode, ::Status> {
+    pub fn get_speaker_mode(&self) -> Result<::SpeakerMode, ::FmodError> {
         let mut speaker_mode = ::SpeakerMode::Raw;
 
         match unsafe { ffi::FMOD_System_GetSpeakerMode(self.system, &mut speaker_mode) } {
             ::Status::Ok => Ok(speaker_mode),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_System_GetSpeakerMode", e))
         }
     }
 
-    pub fn set_plugin_path(&self, path: &str) -> ::Status {
+    pub fn set_plugin_path(&self, path: &str) -> Result<(), ::FmodError> {
         let tmp_path = CString::new(path).unwrap();
 
-        unsafe { ffi::FMOD_System_SetPluginPath(self.system, tmp_path.as_ptr() as *const c_char) }
+        match unsafe { ffi::FMOD_System_SetPluginPath(self.system, tmp_path.as_ptr() as *const c_char) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_System_SetPluginPath", e)),
+
+        }
     }
 
-    pub fn load_plugin(&self, filename: &str, priority: u32) -> Result<PluginHandle, ::Status> {
+    pub fn load_plugin(&self, filename: &str, priority: u32) -> Result<PluginHandle, ::FmodError> {
         let mut handle = 0u32;
         let tmp_filename = filename.as_ptr();
 
@@ -1585,37 +2322,40 @@ impl Sys {
                                                    &mut handle as *mut c_uint,
                                                    priority as c_uint) } {
             ::Status::Ok => Ok(PluginHandle(handle)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_LoadPlugin", e)),
         }
     }
 
-    pub fn unload_plugin(&self, PluginHandle(handle): PluginHandle) -> ::Status {
-        unsafe { ffi::FMOD_System_UnloadPlugin(self.system, handle) }
+    pub fn unload_plugin(&self, PluginHandle(handle): PluginHandle) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_UnloadPlugin(self.system, handle) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_UnloadPlugin", e)),
+        }
     }
 
-    pub fn get_num_plugins(&self, plugin_type: ::PluginType) -> Result<i32, ::Status> {
+    pub fn get_num_plugins(&self, plugin_type: ::PluginType) -> Result<i32, ::FmodError> {
         let mut num_plugins = 0i32;
 
         match unsafe { ffi::FMOD_System_GetNumPlugins(self.system, plugin_type,
                                                       &mut num_plugins) } {
             ::Status::Ok => Ok(num_plugins),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetNumPlugins", e)),
         }
     }
 
     pub fn get_plugin_handle(&self, plugin_type: ::PluginType,
-                             index: i32) -> Result<PluginHandle, ::Status> {
+                             index: i32) -> Result<PluginHandle, ::FmodError> {
         let mut handle = 0u32;
 
         match unsafe { ffi::FMOD_System_GetPluginHandle(self.system, plugin_type, index as c_int,
                                                         &mut handle as *mut c_uint) } {
             ::Status::Ok => Ok(PluginHandle(handle)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetPluginHandle", e)),
         }
     }
 
     pub fn get_plugin_info(&self, PluginHandle(handle): PluginHandle,
-                           name_len: usize) -> Result<(String, ::PluginType, u32), ::Status> {
+                           name_len: usize) -> Result<(String, ::PluginType, u32), ::FmodError> {
         let mut plugin_type = ::PluginType::Output;
         let mut version = 0u32;
         let mut c = Vec::with_capacity(name_len + 1);
@@ -1629,57 +2369,70 @@ impl Sys {
                                                       name_len as c_int,
                                                       &mut version as *mut c_uint) } {
             ::Status::Ok => Ok((String::from_utf8(c).unwrap(), plugin_type, version)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetPluginInfo", e)),
         }
     }
 
-    pub fn set_output_by_plugin(&self, PluginHandle(handle): PluginHandle) -> ::Status {
-        unsafe { ffi::FMOD_System_SetOutputByPlugin(self.system, handle) }
+    pub fn set_output_by_plugin(&self, PluginHandle(handle): PluginHandle)
+                               -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetOutputByPlugin(self.system, handle) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetOutputByPlugin", e)),
+        }
     }
 
-    pub fn get_output_by_plugin(&self) -> Result<PluginHandle, ::Status> {
+    pub fn get_output_by_plugin(&self) -> Result<PluginHandle, ::FmodError> {
         let mut handle = 0u32;
 
         match unsafe { ffi::FMOD_System_GetOutputByPlugin(self.system, &mut handle) } {
             ::Status::Ok => Ok(PluginHandle(handle)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetOutputByPlugin", e)),
         }
     }
 
     pub fn create_DSP_by_plugin(&self,
-                                PluginHandle(handle): PluginHandle) -> Result<Dsp, ::Status> {
+                                PluginHandle(handle): PluginHandle) -> Result<Dsp, ::FmodError> {
         let mut dsp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_CreateDSPByPlugin(self.system, handle, &mut dsp) } {
-            ::Status::Ok => Ok(dsp::from_ptr_first(dsp)),
-            e => Err(e),
+            ::Status::Ok => Ok(dsp::from_ptr_first(dsp, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_CreateDSPByPlugin", e)),
         }
     }
 
-    pub fn set_3D_num_listeners(&self, num_listeners: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_Set3DNumListeners(self.system, num_listeners as c_int) }
+    pub fn set_3D_num_listeners(&self, num_listeners: i32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_Set3DNumListeners(self.system, num_listeners as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_Set3DNumListeners", e)),
+        }
     }
 
-    pub fn get_3D_num_listeners(&self) -> Result<i32, ::Status> {
+    pub fn get_3D_num_listeners(&self) -> Result<i32, ::FmodError> {
         let mut num_listeners = 0i32;
 
         match unsafe { ffi::FMOD_System_Get3DNumListeners(self.system,
                                                           &mut num_listeners as *mut c_int) } {
             ::Status::Ok => Ok(num_listeners),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_Get3DNumListeners", e)),
         }
     }
 
     pub fn set_3D_listener_attributes(&self, listener: i32, pos: &vector::Vector,
                                       vel: &vector::Vector, forward: &vector::Vector,
-                                      up: &vector::Vector) -> ::Status {
+                                      up: &vector::Vector) -> Result<(), ::FmodError> {
         let c_p = vector::get_ffi(pos);
         let c_v = vector::get_ffi(vel);
         let c_f = vector::get_ffi(forward);
         let c_u = vector::get_ffi(up);
 
-        unsafe { ffi::FMOD_System_Set3DListenerAttributes(self.system, listener as c_int, &c_p,
-                                                          &c_v, &c_f, &c_u) }
+        match unsafe { ffi::FMOD_System_Set3DListenerAttributes(self.system, listener as c_int, &c_p,
+                                                                &c_v, &c_f, &c_u) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_System_Set3DListenerAttributes", e)),
+
+        }
     }
 
     /// Returns:
@@ -1687,7 +2440,7 @@ impl Sys {
     /// Ok(position, velocity, forward, up)
     pub fn get_3D_listener_attributes(&self, listener: i32)
                                       -> Result<(vector::Vector, vector::Vector, vector::Vector,
-                                                 vector::Vector), ::Status> {
+                                                 vector::Vector), ::FmodError> {
         let mut pos = vector::get_ffi(&vector::Vector::new());
         let mut vel = vector::get_ffi(&vector::Vector::new());
         let mut forward = vector::get_ffi(&vector::Vector::new());
@@ -1698,24 +2451,27 @@ impl Sys {
                                                                 &mut up) } {
             ::Status::Ok => Ok((vector::from_ptr(pos), vector::from_ptr(vel),
                                 vector::from_ptr(forward), vector::from_ptr(up))),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_Get3DListenerAttributes", e)),
         }
     }
 
     pub fn set_3D_speaker_position(&self, speaker: ::Speaker, x: f32, y: f32,
-                                   active: bool) -> ::Status {
+                                   active: bool) -> Result<(), ::FmodError> {
         let t_active : c_int = match active {
             true => 1,
             false => 0,
         };
-        unsafe { ffi::FMOD_System_Set3DSpeakerPosition(self.system, speaker, x, y, t_active) }
+        match unsafe { ffi::FMOD_System_Set3DSpeakerPosition(self.system, speaker, x, y, t_active) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_Set3DSpeakerPosition", e)),
+        }
     }
 
     /// Returns:
     ///
     /// Ok(x, y, is_active)
     pub fn get_3D_speaker_position(&self,
-                                   speaker: ::Speaker) -> Result<(f32, f32, bool), ::Status> {
+                                   speaker: ::Speaker) -> Result<(f32, f32, bool), ::FmodError> {
         let mut x = 0f32;
         let mut y = 0f32;
         let mut active : c_int = 0;
@@ -1726,20 +2482,23 @@ impl Sys {
                 0 => false,
                 _ => true,
             })),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_Get3DSpeakerPosition", e)),
         }
     }
 
     pub fn set_3D_settings(&self, doppler_scale: f32, distance_factor: f32,
-                           roll_off_scale: f32) -> ::Status {
-        unsafe { ffi::FMOD_System_Set3DSettings(self.system, doppler_scale, distance_factor,
-                                                roll_off_scale) }
+                           roll_off_scale: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_Set3DSettings(self.system, doppler_scale, distance_factor,
+                                                      roll_off_scale) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_Set3DSettings", e)),
+        }
     }
 
     /// Returns:
     ///
     /// Ok(doppler_scale, distance_factor, roll_off_scale)
-    pub fn get_3D_settings(&self) -> Result<(f32, f32, f32), ::Status> {
+    pub fn get_3D_settings(&self) -> Result<(f32, f32, f32), ::FmodError> {
         let mut doppler_scale = 0f32;
         let mut distance_factor = 0f32;
         let mut roll_off_scale = 0f32;
@@ -1747,61 +2506,113 @@ impl Sys {
         match unsafe { ffi::FMOD_System_Get3DSettings(self.system, &mut doppler_scale,
                                                       &mut distance_factor, &mut roll_off_scale) } {
             ::Status::Ok => Ok((doppler_scale, distance_factor, roll_off_scale)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_Get3DSettings", e)),
+        }
+    }
+
+    /// Sets a closure computing the attenuation of 3D channels, overriding FMOD's rolloff
+    /// models. It receives the channel and its distance to the listener, and returns a volume
+    /// between 0 (silent) and 1 (full volume).
+    ///
+    /// The closure is called from the FMOD mixer thread, for every 3D channel, so it has to be
+    /// cheap.
+    ///
+    /// ```ignore
+    /// fmod.set_3D_rolloff_callback(|_, distance| {
+    ///     // inverse square with a bit of air absorption
+    ///     let attenuation = 1f32 / (1f32 + distance * distance);
+    ///
+    ///     attenuation * (-0.002f32 * distance).exp()
+    /// });
+    /// ```
+    pub fn set_3D_rolloff_callback<F>(&self, callback: F) -> Result<(), ::FmodError>
+        where F: Fn(&channel::Channel, f32) -> f32 + Send + Sync + 'static {
+        let data = match get_sys_data(self.system) {
+            Ok(d) => d,
+            Err(e) => return Err(e),
+        };
+
+        if let Ok(mut r) = data.rolloff.lock() {
+            *r = Some(Arc::new(callback));
+        }
+        match unsafe { ffi::FMOD_System_Set3DRolloffCallback(self.system, Some(rolloff_callback)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_Set3DRolloffCallback", e)),
+        }
+    }
+
+    /// Removes the closure set with [`set_3D_rolloff_callback`](#method.set_3D_rolloff_callback),
+    /// going back to the rolloff models of the channels.
+    pub fn remove_3D_rolloff_callback(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_Set3DRolloffCallback(self.system, None) } {
+            ::Status::Ok => {}
+            e => return Err(::FmodError::new("FMOD_System_Set3DRolloffCallback", e)),
+        }
+        match get_sys_data(self.system) {
+            Ok(data) => {
+                if let Ok(mut r) = data.rolloff.lock() {
+                    *r = None;
+                }
+                Ok(())
+            }
+            Err(e) => Err(e),
         }
     }
 
     pub fn set_stream_buffer_size(&self, file_buffer_size: u32,
-                                  TimeUnit(file_buffer_size_type): TimeUnit) -> ::Status {
-        unsafe { ffi::FMOD_System_SetStreamBufferSize(self.system, file_buffer_size as c_uint,
-                                                      file_buffer_size_type) }
+                                  TimeUnit(file_buffer_size_type): TimeUnit) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetStreamBufferSize(self.system, file_buffer_size as c_uint,
+                                                            file_buffer_size_type) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetStreamBufferSize", e)),
+        }
     }
 
     /// Returns:
     ///
     /// Ok(file_buffer_size, distance_factor, time)
-    pub fn get_stream_buffer_size(&self) -> Result<(u32, TimeUnit), ::Status> {
+    pub fn get_stream_buffer_size(&self) -> Result<(u32, TimeUnit), ::FmodError> {
         let mut file_buffer_size = 0u32;
         let mut file_buffer_size_type = 0u32;
 
         match unsafe { ffi::FMOD_System_GetStreamBufferSize(self.system, &mut file_buffer_size,
                                                             &mut file_buffer_size_type) } {
             ::Status::Ok => Ok((file_buffer_size, TimeUnit(file_buffer_size_type))),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetStreamBufferSize", e)),
         }
     }
 
-    pub fn get_version(&self) -> Result<u32, ::Status> {
+    pub fn get_version(&self) -> Result<u32, ::FmodError> {
         let mut version : c_uint = 0;
 
         match unsafe { ffi::FMOD_System_GetVersion(self.system, &mut version) } {
             ::Status::Ok => Ok(version as u32),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetVersion", e)),
         }
     }
 
-    pub fn get_output_handle(&self) -> Result<OutputHandle, ::Status> {
+    pub fn get_output_handle(&self) -> Result<OutputHandle, ::FmodError> {
         let mut output_h = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetOutputHandle(self.system, &mut output_h) } {
             ::Status::Ok => Ok(OutputHandle{handle: output_h}),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetOutputHandle", e)),
         }
     }
 
-    pub fn get_channels_playing(&self) -> Result<i32, ::Status> {
+    pub fn get_channels_playing(&self) -> Result<i32, ::FmodError> {
         let mut playing_chans : c_int = 0;
 
         match unsafe { ffi::FMOD_System_GetChannelsPlaying(self.system, &mut playing_chans) } {
             ::Status::Ok => Ok(playing_chans as i32),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetChannelsPlaying", e)),
         }
     }
 
     /// Returns:
     ///
     /// Ok(dsp, stream, geometry, update, total)
-    pub fn get_CPU_usage(&self) -> Result<(f32, f32, f32, f32, f32), ::Status> {
+    pub fn get_CPU_usage(&self) -> Result<(f32, f32, f32, f32, f32), ::FmodError> {
         let mut dsp = 0f32;
         let mut stream = 0f32;
         let mut geometry = 0f32;
@@ -1811,14 +2622,14 @@ impl Sys {
         match unsafe { ffi::FMOD_System_GetCPUUsage(self.system, &mut dsp, &mut stream,
                                                     &mut geometry, &mut update, &mut total) } {
             ::Status::Ok => Ok((dsp, stream, geometry, update, total)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetCPUUsage", e)),
         }
     }
 
     /// Returns:
     ///
     /// Ok(current_alloced, max_allocated, total)
-    pub fn get_sound_RAM(&self) -> Result<(i32, i32, i32), ::Status> {
+    pub fn get_sound_RAM(&self) -> Result<(i32, i32, i32), ::FmodError> {
         let mut current_alloced : c_int = 0;
         let mut max_allocated : c_int = 0;
         let mut total : c_int = 0;
@@ -1826,16 +2637,16 @@ impl Sys {
         match unsafe { ffi::FMOD_System_GetSoundRAM(self.system, &mut current_alloced,
                                                     &mut max_allocated, &mut total) } {
             ::Status::Ok => Ok((current_alloced as i32, max_allocated as i32, total as i32)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetSoundRAM", e)),
         }
     }
 
-    pub fn get_num_CDROM_drives(&self) -> Result<i32, ::Status> {
+    pub fn get_num_CDROM_drives(&self) -> Result<i32, ::FmodError> {
         let mut num_drives : c_int= 0;
 
         match unsafe { ffi::FMOD_System_GetNumCDROMDrives(self.system, &mut num_drives) } {
             ::Status::Ok => Ok(num_drives as i32),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_System_GetNumCDROMDrives", e))
         }
     }
 
@@ -1844,7 +2655,7 @@ impl Sys {
     /// Ok(drive_name, scsi_name, device_name)
     pub fn get_CDROM_drive_name(&self, drive: i32, drive_name_len: usize, scsi_name_len: usize,
                                 device_name_len: usize)
-                                -> Result<(String, String, String), ::Status> {
+                                -> Result<(String, String, String), ::FmodError> {
         let mut drive_name = Vec::with_capacity(drive_name_len + 1);
         let mut scsi_name = Vec::with_capacity(scsi_name_len + 1);
         let mut device_name = Vec::with_capacity(device_name_len + 1);
@@ -1869,12 +2680,12 @@ impl Sys {
             ::Status::Ok => Ok((String::from_utf8(drive_name).unwrap(),
                                 String::from_utf8(scsi_name).unwrap(),
                                 String::from_utf8(device_name).unwrap())),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetCDROMDriveName", e)),
         }
     }
 
     pub fn get_spectrum(&self, spectrum_size: usize, channel_offset: Option<i32>,
-                        window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::Status> {
+                        window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::FmodError> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(spectrum_size).collect();
         let c_window_type = match window_type {
             Some(wt) => wt,
@@ -1889,133 +2700,161 @@ impl Sys {
                                                     spectrum_size as c_int, c_channel_offset,
                                                     c_window_type) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetSpectrum", e)),
         }
     }
 
     pub fn get_wave_data(&self, wave_size: usize,
-                         channel_offset: i32) -> Result<Vec<f32>, ::Status> {
+                         channel_offset: i32) -> Result<Vec<f32>, ::FmodError> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(wave_size).collect();
 
         match unsafe { ffi::FMOD_System_GetWaveData(self.system, ptr.as_mut_ptr(),
                                                     wave_size as c_int, channel_offset as c_int) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetWaveData", e)),
         }
     }
     
-    pub fn get_channel(&self, channel_id: i32) -> Result<channel::Channel, ::Status> {
+    pub fn get_channel(&self, channel_id: i32) -> Result<channel::Channel, ::FmodError> {
         let mut channel = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetChannel(self.system, channel_id as c_int,
                                                    &mut channel) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e),
+            ::Status::Ok => Ok(channel::from_ptr(channel, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_GetChannel", e)),
         }
     }
 
-    pub fn get_master_channel_group(&self) -> Result<channel_group::ChannelGroup, ::Status> {
+    pub fn get_master_channel_group(&self) -> Result<channel_group::ChannelGroup, ::FmodError> {
         let mut channel_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetMasterChannelGroup(self.system, &mut channel_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel_group)),
-            e => Err(e),
+            ::Status::Ok => Ok(channel_group::from_ptr(channel_group, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_GetMasterChannelGroup", e)),
         }
     }
 
-    pub fn get_master_sound_group(&self) -> Result<sound_group::SoundGroup, ::Status> {
+    pub fn get_master_sound_group(&self) -> Result<sound_group::SoundGroup, ::FmodError> {
         let mut sound_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetMasterSoundGroup(self.system, &mut sound_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound_group)),
-            e => Err(e),
+            ::Status::Ok => Ok(sound_group::from_ptr(sound_group, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_GetMasterSoundGroup", e)),
         }
     }
 
     pub fn set_reverb_properties(&self,
-                                 properties: reverb_properties::ReverbProperties) -> ::Status {
+                                 properties: reverb_properties::ReverbProperties) -> Result<(), ::FmodError> {
         let t_properties = reverb_properties::get_ffi(properties);
 
-        unsafe { ffi::FMOD_System_SetReverbProperties(self.system, &t_properties) }
+        match unsafe { ffi::FMOD_System_SetReverbProperties(self.system, &t_properties) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_System_SetReverbProperties", e)),
+
+        }
     }
 
-    pub fn get_reverb_properties(&self) -> Result<reverb_properties::ReverbProperties, ::Status> {
+    pub fn get_reverb_properties(&self)
+                                -> Result<reverb_properties::ReverbProperties, ::FmodError> {
         let mut properties = reverb_properties::get_ffi(Default::default());
 
         match unsafe { ffi::FMOD_System_GetReverbProperties(self.system, &mut properties) } {
             ::Status::Ok => Ok(reverb_properties::from_ptr(properties)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetReverbProperties", e)),
         }
     }
 
     pub fn set_reverb_ambient_properties(&self, properties: reverb_properties::ReverbProperties)
-                                         -> ::Status {
+                                         -> Result<(), ::FmodError> {
         let mut t_properties = reverb_properties::get_ffi(properties);
 
-        unsafe { ffi::FMOD_System_SetReverbAmbientProperties(self.system, &mut t_properties) }
+        match unsafe { ffi::FMOD_System_SetReverbAmbientProperties(self.system, &mut t_properties) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_System_SetReverbAmbientProperties", e)),
+
+        }
     }
 
     pub fn get_reverb_ambient_properties(&self)
-                                         -> Result<reverb_properties::ReverbProperties, ::Status> {
+                                         -> Result<reverb_properties::ReverbProperties, ::FmodError> {
         let mut properties = reverb_properties::get_ffi(Default::default());
 
         match unsafe { ffi::FMOD_System_GetReverbAmbientProperties(self.system, &mut properties) } {
             ::Status::Ok => Ok(reverb_properties::from_ptr(properties)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetReverbAmbientProperties", e)),
         }
     }
 
-    pub fn get_DSP_head(&self) -> Result<Dsp, ::Status> {
+    pub fn get_DSP_head(&self) -> Result<Dsp, ::FmodError> {
         let mut head = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetDSPHead(self.system, &mut head) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(head)),
-            e => Err(e),
+            ::Status::Ok => Ok(dsp::from_ptr(head, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_GetDSPHead", e)),
+        }
+    }
+
+    /// Returns a snapshot of the DSP network, from the head returned by
+    /// [`get_DSP_head`](#method.get_DSP_head).
+    pub fn dsp_graph(&self) -> Result<DspGraph, ::FmodError> {
+        match self.get_DSP_head() {
+            Ok(head) => DspGraph::from_dsp(&head),
+            Err(e) => Err(e),
         }
     }
 
-    pub fn add_DSP(&self, dsp: &dsp::Dsp) -> Result<dsp_connection::DspConnection, ::Status> {
+    pub fn add_DSP(&self, dsp: &dsp::Dsp) -> Result<dsp_connection::DspConnection, ::FmodError> {
         let mut t_connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_AddDSP(self.system, ffi::FFI::unwrap(dsp),
                                                &mut t_connection) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(t_connection)),
-            e => Err(e),
+            ::Status::Ok => Ok(dsp_connection::from_ptr(t_connection, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_AddDSP", e)),
         }
     }
 
-    pub fn lock_DSP(&self) -> ::Status {
-        unsafe { ffi::FMOD_System_LockDSP(self.system) }
+    pub fn lock_DSP(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_LockDSP(self.system) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_LockDSP", e)),
+        }
     }
 
-    pub fn unlock_DSP(&self) -> ::Status {
-        unsafe { ffi::FMOD_System_UnlockDSP(self.system) }
+    pub fn unlock_DSP(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_UnlockDSP(self.system) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_UnlockDSP", e)),
+        }
     }
 
     /// Returns:
     ///
     /// Ok(hi, lo)
-    pub fn get_DSP_clock(&self) -> Result<(u32, u32), ::Status> {
+    pub fn get_DSP_clock(&self) -> Result<(u32, u32), ::FmodError> {
         let mut hi : c_uint = 0;
         let mut lo : c_uint = 0;
 
         match unsafe { ffi::FMOD_System_GetDSPClock(self.system, &mut hi, &mut lo) } {
             ::Status::Ok => Ok((hi as u32, lo as u32)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetDSPClock", e)),
         }
     }
 
-    pub fn get_record_num_drivers(&self) -> Result<i32, ::Status> {
+    pub fn get_record_num_drivers(&self) -> Result<i32, ::FmodError> {
         let mut num_drivers : c_int = 0;
 
         match unsafe { ffi::FMOD_System_GetRecordNumDrivers(self.system, &mut num_drivers) } {
             ::Status::Ok => Ok(num_drivers as i32),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetRecordNumDrivers", e)),
         }
     }
 
     pub fn get_record_driver_info(&self, id: i32,
-                                  name_len: usize) -> Result<(Guid, String), ::Status> {
+                                  name_len: usize) -> Result<(Guid, String), ::FmodError> {
         let mut guid = ffi::FMOD_GUID{
             Data1: 0,
             Data2: 0,
@@ -2037,14 +2876,14 @@ impl Sys {
                                     data3: guid.Data3,
                                     data4: guid.Data4
                                 }, String::from_utf8(c).unwrap())),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetRecordDriverInfo", e)),
         }
     }
 
     /// Returns:
     ///
     /// Ok(caps, min_frequency, max_frequency)
-    pub fn get_record_driver_caps(&self, id: i32) -> Result<(FmodCaps, i32, i32), ::Status> {
+    pub fn get_record_driver_caps(&self, id: i32) -> Result<(FmodCaps, i32, i32), ::FmodError> {
         let mut fmod_caps : c_uint = 0;
         let mut min_frequency : c_int = 0;
         let mut max_frequency : c_int = 0;
@@ -2053,83 +2892,131 @@ impl Sys {
                                                             &mut fmod_caps, &mut min_frequency,
                                                             &mut max_frequency) } {
             ::Status::Ok => Ok((FmodCaps(fmod_caps), min_frequency as i32, max_frequency as i32)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetRecordDriverCaps", e)),
         }
     }
 
-    pub fn get_record_position(&self, id: i32) -> Result<u32, ::Status> {
+    pub fn get_record_position(&self, id: i32) -> Result<u32, ::FmodError> {
         let mut position : c_uint = 0;
 
         match unsafe { ffi::FMOD_System_GetRecordPosition(self.system, id as c_int,
                                                           &mut position) } {
             ::Status::Ok => Ok(position as u32),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetRecordPosition", e)),
         }
     }
 
-    pub fn start_record(&self, id: i32, sound: &sound::Sound, _loop: bool) -> ::Status {
+    pub fn start_record(&self, id: i32, sound: &sound::Sound, _loop: bool)
+                       -> Result<(), ::FmodError> {
         let t_loop = match _loop {
             true => 1,
             _ => 0,
         };
 
-        unsafe { ffi::FMOD_System_RecordStart(self.system, id as c_int, ffi::FFI::unwrap(sound),
-                                              t_loop) }
+        match unsafe { ffi::FMOD_System_RecordStart(self.system, id as c_int, ffi::FFI::unwrap(sound),
+                                                    t_loop) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_System_RecordStart", e)),
+
+        }
     }
 
-    pub fn stop_record(&self, id: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_RecordStop(self.system, id as c_int) }
+    pub fn stop_record(&self, id: i32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_RecordStop(self.system, id as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_RecordStop", e)),
+        }
     }
 
-    pub fn is_recording(&self, id: i32) -> Result<bool, ::Status> {
+    pub fn is_recording(&self, id: i32) -> Result<bool, ::FmodError> {
         let mut is_recording : c_int = 0;
         
         match unsafe { ffi::FMOD_System_IsRecording(self.system, id as c_int, &mut is_recording) } {
             ::Status::Ok => Ok(is_recording == 1),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_IsRecording", e)),
         }
     }
 
     pub fn create_geometry(&self, max_polygons: i32,
-                           max_vertices: i32) -> Result<geometry::Geometry, ::Status> {
+                           max_vertices: i32) -> Result<geometry::Geometry, ::FmodError> {
         let mut geometry = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_CreateGeometry(self.system, max_polygons as c_int,
                                                        max_vertices as c_int, &mut geometry) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(geometry)),
-            e => Err(e),
+            ::Status::Ok => Ok(geometry::from_ptr_first(geometry, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_CreateGeometry", e)),
         }
     }
 
-    pub fn set_geometry_settings(&self, max_world_size: f32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetGeometrySettings(self.system, max_world_size) }
+    /// Creates a geometry from data returned by
+    /// [`Geometry::save`](struct.Geometry.html#method.save).
+    pub fn load_geometry(&self, data: &[u8]) -> Result<geometry::Geometry, ::FmodError> {
+        let mut geometry = ::std::ptr::null_mut();
+
+        match unsafe { ffi::FMOD_System_LoadGeometry(self.system, data.as_ptr() as *mut c_void,
+                                                     data.len() as c_int, &mut geometry) } {
+            ::Status::Ok => Ok(geometry::from_ptr_first(geometry, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_System_LoadGeometry", e)),
+        }
     }
 
-    pub fn get_geometry_settings(&self) -> Result<f32, ::Status> {
+    pub fn set_geometry_settings(&self, max_world_size: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_SetGeometrySettings(self.system, max_world_size) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetGeometrySettings", e)),
+        }
+    }
+
+    pub fn get_geometry_settings(&self) -> Result<f32, ::FmodError> {
         let mut max_world_size = 0f32;
 
         match unsafe { ffi::FMOD_System_GetGeometrySettings(self.system, &mut max_world_size) } {
             ::Status::Ok => Ok(max_world_size),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetGeometrySettings", e)),
         }
     }
 
     /// Returns:
     ///
-    /// Ok(listener, source, direct, reverb)
-    pub fn get_geometry_occlusion(&self)
-                                  -> Result<(vector::Vector, vector::Vector, f32, f32), ::Status> {
-        let listener = vector::get_ffi(&vector::Vector::new());
-        let source = vector::get_ffi(&vector::Vector::new());
+    /// Ok(direct, reverb), the occlusion factors between `listener` and `source`.
+    pub fn get_geometry_occlusion(&self, listener: &vector::Vector,
+                                  source: &vector::Vector) -> Result<(f32, f32), ::FmodError> {
+        let listener = vector::get_ffi(listener);
+        let source = vector::get_ffi(source);
         let mut direct = 0f32;
         let mut reverb = 0f32;
 
         match unsafe { ffi::FMOD_System_GetGeometryOcclusion(self.system, &listener, &source,
                                                              &mut direct, &mut reverb) } {
-            ::Status::Ok => Ok((vector::from_ptr(listener),
-                                vector::from_ptr(source), direct, reverb)),
-            e => Err(e),
+            ::Status::Ok => Ok((direct, reverb)),
+            e => Err(::FmodError::new("FMOD_System_GetGeometryOcclusion", e)),
+        }
+    }
+
+    /// Same as [`get_geometry_occlusion`](#method.get_geometry_occlusion) for every source.
+    ///
+    /// Returns:
+    ///
+    /// Ok(occlusions), the (direct, reverb) factors of each source, in the same order.
+    pub fn get_geometry_occlusions(&self, listener: &vector::Vector, sources: &[vector::Vector])
+                                   -> Result<Vec<(f32, f32)>, ::FmodError> {
+        let listener = vector::get_ffi(listener);
+        let mut occlusions = Vec::with_capacity(sources.len());
+
+        for source in sources.iter() {
+            let source = vector::get_ffi(source);
+            let mut direct = 0f32;
+            let mut reverb = 0f32;
+
+            match unsafe { ffi::FMOD_System_GetGeometryOcclusion(self.system, &listener, &source,
+                                                                 &mut direct, &mut reverb) } {
+                ::Status::Ok => occlusions.push((direct, reverb)),
+                e => return Err(::FmodError::new("FMOD_System_GetGeometryOcclusion", e)),
+            }
         }
+        Ok(occlusions)
     }
 
     /// Returns:
@@ -2137,50 +3024,233 @@ impl Sys {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::FmodError> {
         let mut details = get_memory_usage_details_ffi(Default::default());
         let mut memory_used : c_uint = 0;
 
         match unsafe { ffi::FMOD_System_GetMemoryInfo(self.system, memory_bits, event_memory_bits,
                                                       &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used as u32, from_memory_usage_details_ptr(details))),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_GetMemoryInfo", e)),
         }
     }
 
+    /// Sets the file callbacks used by this system to open, close, read and seek the files it
+    /// loads. Callbacks set to None are handled by FMOD.
+    ///
+    /// Callbacks belong to this system: several systems can each use their own, including for
+    /// the files opened from FMOD's non-blocking loading thread. At most
+    /// [`MAX_FILE_SYSTEMS`](constant.MAX_FILE_SYSTEMS.html) systems can set a file system or
+    /// attach an observer.
     pub fn set_file_system(&self, user_open: FileOpenCallback, user_close: FileCloseCallback,
                            user_read: FileReadCallback, user_seek: FileSeekCallback,/*
                            user_async_read: ffi::FMOD_FILE_ASYNCREADCALLBACK,
                            user_async_cancel: ffi::FMOD_FILE_ASYNCCANCELCALLBACK,*/
-                           block_align: i32) -> ::Status {
-        let tmp = get_saved_sys_callback();
-
-        tmp.file_open = user_open;
-        tmp.file_read = user_read;
-        tmp.file_close = user_close;
-        tmp.file_seek = user_seek;
-        unsafe { ffi::FMOD_System_SetFileSystem(self.system,
-            match user_open {
-                Some(_) => Some(file_open_callback as extern "C" fn(*mut _, _, *mut _, *mut *mut _,
-                                                                    *mut *mut _) -> _),
-                None => None
-            },
-            match user_close {
-                Some(_) => Some(file_close_callback as extern "C" fn(*mut _, *mut _) -> _),
-                None => None
-            },
-            match user_read {
-                Some(_) => Some(file_read_callback as extern "C" fn(*mut _, *mut _, _, *mut _,
-                                                                    *mut _) -> _),
-                None => None
-            },
-            match user_seek {
-                Some(_) => Some(file_seek_callback as extern "C" fn(*mut _, _, *mut _) -> _),
-                None => None
-            },
+                           block_align: i32) -> Result<(), ::FmodError> {
+        let data = match get_sys_data(self.system) {
+            Ok(d) => d,
+            Err(e) => return Err(e),
+        };
+        let slot = match get_file_system_slot(data, "Sys::set_file_system") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+        let callbacks = &FILE_SYSTEM_CALLBACKS[slot];
+        let has_open = user_open.is_some();
+        let has_close = user_close.is_some();
+        let has_read = user_read.is_some();
+        let has_seek = user_seek.is_some();
+        let status = unsafe { ffi::FMOD_System_SetFileSystem(self.system,
+            if has_open { callbacks.file_open } else { None },
+            if has_close { callbacks.file_close } else { None },
+            if has_read { callbacks.file_read } else { None },
+            if has_seek { callbacks.file_seek } else { None },
             None,
             None,
             block_align)
+        };
+
+        if status == ::Status::Ok {
+            if let Ok(mut f) = data.virtual_file_system.lock() {
+                *f = None;
+            }
+            if let Ok(mut r) = data.async_reader.lock() {
+                *r = None;
+            }
+            if let Ok(mut f) = data.file_system.lock() {
+                *f = if has_open || has_close || has_read || has_seek {
+                    Some(Arc::new(FileCallbacks {
+                        file_open: user_open,
+                        file_close: user_close,
+                        file_read: user_read,
+                        file_seek: user_seek
+                    }))
+                } else {
+                    None
+                };
+            }
+        }
+        match status {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetFileSystem", e)),
+        }
+    }
+
+    /// Makes this system load its files from `file_system` instead of the disk, in place of the
+    /// callbacks set with [`set_file_system`](#method.set_file_system).
+    ///
+    /// Like with [`set_file_system`](#method.set_file_system), several systems can each use
+    /// their own file system.
+    pub fn set_virtual_file_system<F: FmodFileSystem + 'static>(&self, file_system: F,
+                                                                block_align: i32) -> Result<(), ::FmodError> {
+        let data = match get_sys_data(self.system) {
+            Ok(d) => d,
+            Err(e) => return Err(e),
+        };
+        let slot = match get_file_system_slot(data, "Sys::set_virtual_file_system") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+        let status = unsafe { ffi::FMOD_System_SetFileSystem(self.system,
+                                                             FILE_SYSTEM_CALLBACKS[slot].vfs_open,
+                                                             Some(vfs_close_callback),
+                                                             Some(vfs_read_callback),
+                                                             Some(vfs_seek_callback),
+                                                             None,
+                                                             None,
+                                                             block_align) };
+
+        if status == ::Status::Ok {
+            if let Ok(mut f) = data.file_system.lock() {
+                *f = None;
+            }
+            if let Ok(mut r) = data.async_reader.lock() {
+                *r = None;
+            }
+            if let Ok(mut f) = data.virtual_file_system.lock() {
+                *f = Some(Arc::new(file_system));
+            }
+        }
+        match status {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetFileSystem", e)),
+        }
+    }
+
+    /// Same as [`set_virtual_file_system`](#method.set_virtual_file_system), but FMOD reads the
+    /// files asynchronously: its read requests are queued and serviced by `worker_count` threads,
+    /// so a slow storage doesn't stall the mixer. Requests on a file are cancelled when FMOD
+    /// closes it.
+    pub fn set_async_file_system<F: FmodFileSystem + 'static>(&self, file_system: F,
+                                                              worker_count: usize,
+                                                              block_align: i32) -> Result<(), ::FmodError> {
+        let data = match get_sys_data(self.system) {
+            Ok(d) => d,
+            Err(e) => return Err(e),
+        };
+        let slot = match get_file_system_slot(data, "Sys::set_async_file_system") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        // the reader has to be there before FMOD opens any file
+        if let Ok(mut f) = data.virtual_file_system.lock() {
+            *f = Some(Arc::new(file_system));
+        }
+        if let Ok(mut r) = data.async_reader.lock() {
+            *r = Some(AsyncReader::new(worker_count));
+        }
+        let status = unsafe { ffi::FMOD_System_SetFileSystem(self.system,
+                                                             FILE_SYSTEM_CALLBACKS[slot].async_open,
+                                                             Some(async_close_callback),
+                                                             Some(vfs_read_callback),
+                                                             Some(vfs_seek_callback),
+                                                             Some(async_read_callback),
+                                                             Some(async_cancel_callback),
+                                                             block_align) };
+
+        if status == ::Status::Ok {
+            if let Ok(mut f) = data.file_system.lock() {
+                *f = None;
+            }
+        } else {
+            if let Ok(mut r) = data.async_reader.lock() {
+                *r = None;
+            }
+            if let Ok(mut f) = data.virtual_file_system.lock() {
+                *f = None;
+            }
+        }
+        match status {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_SetFileSystem", e)),
+        }
+    }
+
+    /// Makes `observer` notified of every file access FMOD does, while FMOD keeps using its own
+    /// file system (or the one set with [`set_file_system`](#method.set_file_system)). Replaces
+    /// the previous observer.
+    pub fn attach_file_system<O: FileSystemObserver + 'static>(&self, observer: O)
+                                                              -> Result<(), ::FmodError> {
+        let data = match get_sys_data(self.system) {
+            Ok(d) => d,
+            Err(e) => return Err(e),
+        };
+        let slot = match get_file_system_slot(data, "Sys::attach_file_system") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        if let Ok(mut o) = data.observer.lock() {
+            *o = Some(Arc::new(observer));
+        }
+        match unsafe { ffi::FMOD_System_AttachFileSystem(self.system,
+                                                         FILE_SYSTEM_CALLBACKS[slot].observer_open,
+                                                         Some(observer_close_callback),
+                                                         Some(observer_read_callback),
+                                                         Some(observer_seek_callback)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_System_AttachFileSystem", e)),
+        }
+    }
+
+    /// Removes the observer set with [`attach_file_system`](#method.attach_file_system). Files
+    /// already open keep notifying it until they are closed.
+    pub fn detach_file_system(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_System_AttachFileSystem(self.system, None, None, None, None) } {
+            ::Status::Ok => {}
+            e => return Err(::FmodError::new("FMOD_System_AttachFileSystem", e)),
+        }
+        match get_sys_data(self.system) {
+            Ok(data) => {
+                if let Ok(mut o) = data.observer.lock() {
+                    *o = None;
+                }
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Registers a user defined codec, tried by FMOD on every file it opens. Codecs with a lower
+    /// `priority` are tried first, FMOD's own codecs have priorities between 100 and 1000.
+    ///
+    /// Returns the handle of the codec. At most [`MAX_CODECS`](constant.MAX_CODECS.html) codecs
+    /// can be registered at the same time, they are unregistered when the system is released.
+    pub fn register_codec(&self, codec: Box<dyn Codec>, priority: u32) -> Result<u32, ::FmodError> {
+        let data = match get_sys_data(self.system) {
+            Ok(d) => d,
+            Err(e) => return Err(e),
+        };
+
+        match codec::register(self.system, codec, priority) {
+            Ok((slot, handle)) => {
+                if let Ok(mut slots) = data.codec_slots.lock() {
+                    slots.push(slot);
+                }
+                Ok(handle)
+            }
+            Err(e) => Err(e)
         }
     }
 }
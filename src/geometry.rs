@@ -27,18 +27,20 @@ use types::*;
 use vector;
 use libc::{c_int, c_void};
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
+use fmod_sys::{MemoryUsageDetails, SystemRef};
+use std::sync::Arc;
 use std::mem::transmute;
 use std::default::Default;
 
 /// Geometry object
 pub struct Geometry {
-    geometry: *mut ffi::FMOD_GEOMETRY
+    geometry: *mut ffi::FMOD_GEOMETRY,
+    system_ref: Option<Arc<SystemRef>>
 }
 
 impl ffi::FFI<ffi::FMOD_GEOMETRY> for Geometry {
     fn wrap(g: *mut ffi::FMOD_GEOMETRY) -> Geometry {
-        Geometry {geometry: g}
+        Geometry {geometry: g, system_ref: None}
     }
 
     fn unwrap(g: &Geometry) -> *mut ffi::FMOD_GEOMETRY {
@@ -46,39 +48,44 @@ impl ffi::FFI<ffi::FMOD_GEOMETRY> for Geometry {
     }
 }
 
+/// FMOD can't tell the system of a geometry, so it is given when the geometry is created.
+pub fn from_ptr_first(geometry: *mut ffi::FMOD_GEOMETRY, system_ref: Option<Arc<SystemRef>>) -> Geometry {
+    Geometry {geometry: geometry, system_ref: system_ref}
+}
+
 impl Drop for Geometry {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl Geometry {
-    pub fn release(&mut self) -> ::Status {
+    pub fn release(&mut self) -> Result<(), ::FmodError> {
         if self.geometry !=::std::ptr::null_mut() {
             match unsafe { ffi::FMOD_Geometry_Release(self.geometry) } {
                 ::Status::Ok => {
                     self.geometry = ::std::ptr::null_mut();
-                   ::Status::Ok
+                   Ok(())
                 }
-                e => e,
+                e => Err(::FmodError::new("FMOD_Geometry_Release", e)),
             }
         } else {
-            ::Status::Ok
+            Ok(())
         }
     }
 
     pub fn add_polygon(&self, direct_occlusion: f32, reverb_occlusion: f32, double_sided: bool,
-                       vertices: Vec<vector::Vector>) -> Result<i32, ::Status> {
+                       vertices: Vec<vector::Vector>) -> Result<i32, ::FmodError> {
         let t_double_sided = if double_sided == true {
             1
         } else {
             0
         };
         let mut index = 0i32;
-        let t_vertices = Vec::with_capacity(vertices.len());
+        let mut t_vertices = Vec::with_capacity(vertices.len());
 
         for tmp in vertices.iter() {
-            vector::get_ffi(tmp);
+            t_vertices.push(vector::get_ffi(tmp));
         }
 
         match unsafe { ffi::FMOD_Geometry_AddPolygon(self.geometry, direct_occlusion,
@@ -86,74 +93,86 @@ impl Geometry {
                                                      vertices.len() as c_int, t_vertices.as_ptr(),
                                                      &mut index) } {
             ::Status::Ok => Ok(index),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Geometry_AddPolygon", e)),
         }
     }
 
-    pub fn get_num_polygons(&self) -> Result<i32, ::Status> {
+    pub fn get_num_polygons(&self) -> Result<i32, ::FmodError> {
         let mut num = 0i32;
 
         match unsafe { ffi::FMOD_Geometry_GetNumPolygons(self.geometry, &mut num) } {
             ::Status::Ok => Ok(num),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Geometry_GetNumPolygons", e))
         }
     }
 
-    pub fn get_max_polygons(&self) -> Result<(i32, i32), ::Status> {
+    pub fn get_max_polygons(&self) -> Result<(i32, i32), ::FmodError> {
         let mut max_polygons = 0i32;
         let mut max_vertices = 0i32;
 
         match unsafe { ffi::FMOD_Geometry_GetMaxPolygons(self.geometry, &mut max_polygons,
                                                          &mut max_vertices) } {
             ::Status::Ok => Ok((max_polygons, max_vertices)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Geometry_GetMaxPolygons", e)),
         }
     }
 
-    pub fn get_polygon_num_vertices(&self, index: i32) -> Result<i32, ::Status> {
+    pub fn get_polygon_num_vertices(&self, index: i32) -> Result<i32, ::FmodError> {
         let mut num = 0i32;
 
         match unsafe { ffi::FMOD_Geometry_GetPolygonNumVertices(self.geometry, index, &mut num) } {
             ::Status::Ok => Ok(num),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Geometry_GetPolygonNumVertices", e)),
         }
     }
 
     pub fn set_polygon_vertex(&self, index: i32, vertex_index: i32,
-                              vertex: vector::Vector) -> ::Status {
+                              vertex: vector::Vector) -> Result<(), ::FmodError> {
         let t_vertex = vector::get_ffi(&vertex);
 
-        unsafe { ffi::FMOD_Geometry_SetPolygonVertex(self.geometry, index, vertex_index,
-                                                     &t_vertex) }
+        match unsafe { ffi::FMOD_Geometry_SetPolygonVertex(self.geometry, index, vertex_index,
+                                                           &t_vertex) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Geometry_SetPolygonVertex", e)),
+
+        }
     }
 
     pub fn get_polygon_vertex(&self, index: i32,
-                              vertex_index: i32) -> Result<vector::Vector, ::Status> {
+                              vertex_index: i32) -> Result<vector::Vector, ::FmodError> {
         let mut vertex = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Geometry_GetPolygonVertex(self.geometry, index, vertex_index,
                                                            &mut vertex) } {
             ::Status::Ok => Ok(vector::from_ptr(vertex)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Geometry_GetPolygonVertex", e)),
         }
     }
 
     pub fn set_polygon_attributes(&self, index: i32, direct_occlusion: f32, reverb_occlusion: f32,
-                                  double_sided: bool) -> ::Status {
+                                  double_sided: bool) -> Result<(), ::FmodError> {
         let t_double_sided = if double_sided == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_Geometry_SetPolygonAttributes(self.geometry, index, direct_occlusion,
-                                                         reverb_occlusion, t_double_sided) }
+        match unsafe { ffi::FMOD_Geometry_SetPolygonAttributes(self.geometry, index, direct_occlusion,
+                                                               reverb_occlusion, t_double_sided) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Geometry_SetPolygonAttributes", e)),
+
+        }
     }
 
     /// Returns:
     ///
     /// Ok(direct_occlusion, reverb_occlusion, double_sided)
-    pub fn get_polygon_attributes(&self, index: i32) -> Result<(f32, f32, bool), ::Status> {
+    pub fn get_polygon_attributes(&self, index: i32) -> Result<(f32, f32, bool), ::FmodError> {
         let mut direct_occlusion = 0f32;
         let mut reverb_occlusion = 0f32;
         let mut double_sided = 0;
@@ -163,76 +182,101 @@ impl Geometry {
                                                                &mut reverb_occlusion,
                                                                &mut double_sided) } {
             ::Status::Ok => Ok((direct_occlusion, reverb_occlusion, double_sided == 1)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Geometry_GetPolygonAttributes", e)),
         }
     }
 
-    pub fn set_active(&self, active: bool) -> ::Status {
+    pub fn set_active(&self, active: bool) -> Result<(), ::FmodError> {
         let t_active = if active == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_Geometry_SetActive(self.geometry, t_active) }
+        match unsafe { ffi::FMOD_Geometry_SetActive(self.geometry, t_active) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Geometry_SetActive", e)),
+
+        }
     }
 
-    pub fn get_active(&self) -> Result<bool, ::Status> {
+    pub fn get_active(&self) -> Result<bool, ::FmodError> {
         let mut active = 0;
 
         match unsafe { ffi::FMOD_Geometry_GetActive(self.geometry, &mut active) } {
             ::Status::Ok => Ok(active == 1),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Geometry_GetActive", e))
         }
     }
 
-    pub fn set_rotation(&self, forward: vector::Vector, up: vector::Vector) -> ::Status {
+    pub fn set_rotation(&self, forward: vector::Vector, up: vector::Vector)
+                       -> Result<(), ::FmodError> {
         let t_forward = vector::get_ffi(&forward);
         let t_up = vector::get_ffi(&up);
 
-        unsafe { ffi::FMOD_Geometry_SetRotation(self.geometry, &t_forward, &t_up) }
+        match unsafe { ffi::FMOD_Geometry_SetRotation(self.geometry, &t_forward, &t_up) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Geometry_SetRotation", e)),
+
+        }
     }
 
     /// Returns:
     ///
     /// Ok(forward, up)
-    pub fn get_rotation(&self) -> Result<(vector::Vector, vector::Vector), ::Status> {
+    pub fn get_rotation(&self) -> Result<(vector::Vector, vector::Vector), ::FmodError> {
         let mut forward = vector::get_ffi(&vector::Vector::new());
         let mut up = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Geometry_GetRotation(self.geometry, &mut forward, &mut up) } {
             ::Status::Ok => Ok((vector::from_ptr(forward), vector::from_ptr(up))),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Geometry_GetRotation", e))
         }
     }
 
-    pub fn set_position(&self, position: vector::Vector) -> ::Status {
+    pub fn set_position(&self, position: vector::Vector) -> Result<(), ::FmodError> {
         let t_position = vector::get_ffi(&position);
 
-        unsafe { ffi::FMOD_Geometry_SetPosition(self.geometry, &t_position) }
+        match unsafe { ffi::FMOD_Geometry_SetPosition(self.geometry, &t_position) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Geometry_SetPosition", e)),
+
+        }
     }
 
-    pub fn get_position(&self) -> Result<vector::Vector, ::Status> {
+    pub fn get_position(&self) -> Result<vector::Vector, ::FmodError> {
         let mut position = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Geometry_GetPosition(self.geometry, &mut position) } {
             ::Status::Ok => Ok(vector::from_ptr(position)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Geometry_GetPosition", e))
         }
     }
 
-    pub fn set_scale(&self, scale: vector::Vector) -> ::Status {
+    pub fn set_scale(&self, scale: vector::Vector) -> Result<(), ::FmodError> {
         let t_scale = vector::get_ffi(&scale);
 
-        unsafe { ffi::FMOD_Geometry_SetScale(self.geometry, &t_scale) }
+        match unsafe { ffi::FMOD_Geometry_SetScale(self.geometry, &t_scale) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Geometry_SetScale", e)),
+
+        }
     }
 
-    pub fn get_scale(&self) -> Result<vector::Vector, ::Status> {
+    pub fn get_scale(&self) -> Result<vector::Vector, ::FmodError> {
         let mut scale = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Geometry_GetScale(self.geometry, &mut scale) } {
             ::Status::Ok => Ok(vector::from_ptr(scale)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Geometry_GetScale", e))
         }
     }
 
@@ -241,31 +285,60 @@ impl Geometry {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::FmodError> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_Geometry_GetMemoryInfo(self.geometry, memory_bits, event_memory_bits, &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Geometry_GetMemoryInfo", e))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_Geometry_SetUserData(self.geometry, transmute(user_data)) }
+    /// Serializes the geometry (polygons, position, rotation and scale) so it can be loaded back
+    /// with [`Sys::load_geometry`](struct.Sys.html#method.load_geometry).
+    pub fn save(&self) -> Result<Vec<u8>, ::FmodError> {
+        let mut data_size = 0i32;
+
+        match unsafe { ffi::FMOD_Geometry_Save(self.geometry, ::std::ptr::null_mut(),
+                                               &mut data_size) } {
+            ::Status::Ok => {}
+            e => return Err(::FmodError::new("FMOD_Geometry_Save", e)),
+        }
+
+        let mut data = vec![0u8; data_size as usize];
+
+        match unsafe { ffi::FMOD_Geometry_Save(self.geometry, data.as_mut_ptr() as *mut c_void,
+                                               &mut data_size) } {
+            ::Status::Ok => {
+                data.truncate(data_size as usize);
+                Ok(data)
+            }
+            e => Err(::FmodError::new("FMOD_Geometry_Save", e))
+        }
+    }
+
+    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Geometry_SetUserData(self.geometry, transmute(user_data)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Geometry_SetUserData", e)),
+        }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
+    /// Returns None if no user data has been set.
+    pub fn get_user_data<'r, T>(&'r self) -> Result<Option<&'r mut T>, ::FmodError> {
         unsafe {
             let mut user_data : *mut c_void = ::std::ptr::null_mut();
 
             match ffi::FMOD_Geometry_GetUserData(self.geometry, &mut user_data) {
                 ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
+                    if user_data.is_null() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(transmute::<*mut c_void, &mut T>(user_data)))
+                    }
                 },
-                e => Err(e)
+                e => Err(::FmodError::new("FMOD_Geometry_GetUserData", e))
             }
         }
     }
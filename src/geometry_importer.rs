@@ -0,0 +1,513 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use fmod_sys::Sys;
+use geometry::Geometry;
+use vector::Vector;
+use error::ImportError;
+use std::collections::HashMap;
+use std::default::Default;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+const DEFAULT_MAX_POLYGONS : i32 = 1024;
+const DEFAULT_MAX_VERTICES : i32 = 4096;
+
+/// Occlusion attributes given to the polygons of a material.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OcclusionMaterial {
+    /// Occlusion factor of the polygon for the direct path. 0 = no occlusion, 1 = full occlusion.
+    pub direct_occlusion: f32,
+    /// Occlusion factor of the polygon for the reverb mix.
+    pub reverb_occlusion: f32,
+    /// Whether the polygon occludes from both of its sides.
+    pub double_sided: bool,
+}
+
+impl OcclusionMaterial {
+    pub fn new(direct_occlusion: f32, reverb_occlusion: f32,
+               double_sided: bool) -> OcclusionMaterial {
+        OcclusionMaterial {
+            direct_occlusion: direct_occlusion,
+            reverb_occlusion: reverb_occlusion,
+            double_sided: double_sided,
+        }
+    }
+}
+
+impl Default for OcclusionMaterial {
+    fn default() -> OcclusionMaterial {
+        OcclusionMaterial::new(1f32, 1f32, true)
+    }
+}
+
+/// Maps material names (`usemtl` in OBJ files) to occlusion attributes.
+///
+/// Polygons without material, or with a material missing from the table, get the default one.
+pub struct MaterialTable {
+    materials: HashMap<String, OcclusionMaterial>,
+    default: OcclusionMaterial,
+}
+
+impl Default for MaterialTable {
+    fn default() -> MaterialTable {
+        MaterialTable::new()
+    }
+}
+
+impl MaterialTable {
+    /// Creates an empty table, whose default material fully occludes from both sides.
+    pub fn new() -> MaterialTable {
+        MaterialTable {
+            materials: HashMap::new(),
+            default: Default::default(),
+        }
+    }
+
+    pub fn insert(&mut self, name: &str, material: OcclusionMaterial) {
+        self.materials.insert(name.to_owned(), material);
+    }
+
+    pub fn set_default(&mut self, material: OcclusionMaterial) {
+        self.default = material;
+    }
+
+    pub fn get_default(&self) -> OcclusionMaterial {
+        self.default
+    }
+
+    /// Returns the attributes of `name`, or the default ones.
+    pub fn get(&self, name: Option<&str>) -> OcclusionMaterial {
+        match name {
+            Some(name) => match self.materials.get(name) {
+                Some(m) => *m,
+                None => self.default,
+            },
+            None => self.default,
+        }
+    }
+}
+
+struct MeshPolygon {
+    vertices: Vec<Vector>,
+    material: Option<String>,
+}
+
+/// Builds occlusion [`Geometry`](struct.Geometry.html) objects from meshes.
+///
+/// Polygons are added from Wavefront OBJ data or from triangle lists, then
+/// [`build`](#method.build) creates as many geometries as needed to stay under the polygon and
+/// vertex limits, and gives them the importer position, rotation and scale.
+///
+/// Polygons are given to FMOD as they are: OBJ faces have to be convex and planar. No handedness
+/// conversion is done either.
+///
+/// ```ignore
+/// let mut materials = rfmod::MaterialTable::new();
+///
+/// materials.insert("glass", rfmod::OcclusionMaterial::new(0.3f32, 0.5f32, true));
+/// materials.insert("curtain", rfmod::OcclusionMaterial::new(0.6f32, 0.2f32, true));
+///
+/// let mut importer = rfmod::GeometryImporter::new(materials);
+///
+/// importer.load_obj("level.obj").unwrap();
+/// let geometries = importer.build(&fmod).unwrap();
+/// ```
+pub struct GeometryImporter {
+    materials: MaterialTable,
+    polygons: Vec<MeshPolygon>,
+    position: Vector,
+    forward: Vector,
+    up: Vector,
+    scale: Vector,
+    max_polygons: i32,
+    max_vertices: i32,
+}
+
+impl GeometryImporter {
+    pub fn new(materials: MaterialTable) -> GeometryImporter {
+        GeometryImporter {
+            materials: materials,
+            polygons: Vec::new(),
+            position: Vector::new(),
+            forward: Vector { x: 0f32, y: 0f32, z: 1f32 },
+            up: Vector { x: 0f32, y: 1f32, z: 0f32 },
+            scale: Vector { x: 1f32, y: 1f32, z: 1f32 },
+            max_polygons: DEFAULT_MAX_POLYGONS,
+            max_vertices: DEFAULT_MAX_VERTICES,
+        }
+    }
+
+    pub fn get_materials(&self) -> &MaterialTable {
+        &self.materials
+    }
+
+    pub fn get_materials_mut(&mut self) -> &mut MaterialTable {
+        &mut self.materials
+    }
+
+    /// Sets the limits given to [`Sys::create_geometry`](struct.Sys.html#method.create_geometry)
+    /// for every built geometry.
+    pub fn set_limits(&mut self, max_polygons: i32, max_vertices: i32) {
+        self.max_polygons = max_polygons;
+        self.max_vertices = max_vertices;
+    }
+
+    /// Returns:
+    ///
+    /// (max_polygons, max_vertices)
+    pub fn get_limits(&self) -> (i32, i32) {
+        (self.max_polygons, self.max_vertices)
+    }
+
+    pub fn set_position(&mut self, position: Vector) {
+        self.position = position;
+    }
+
+    pub fn get_position(&self) -> Vector {
+        self.position
+    }
+
+    /// Same as [`Geometry::set_rotation`](struct.Geometry.html#method.set_rotation): `forward` and
+    /// `up` have to be unit length and perpendicular.
+    pub fn set_rotation(&mut self, forward: Vector, up: Vector) {
+        self.forward = forward;
+        self.up = up;
+    }
+
+    /// Returns:
+    ///
+    /// (forward, up)
+    pub fn get_rotation(&self) -> (Vector, Vector) {
+        (self.forward, self.up)
+    }
+
+    pub fn set_scale(&mut self, scale: Vector) {
+        self.scale = scale;
+    }
+
+    pub fn get_scale(&self) -> Vector {
+        self.scale
+    }
+
+    pub fn get_num_polygons(&self) -> usize {
+        self.polygons.len()
+    }
+
+    /// Adds a polygon, whose occlusion attributes come from `material`.
+    pub fn add_polygon(&mut self, vertices: &[Vector], material: Option<&str>) {
+        self.polygons.push(MeshPolygon {
+            vertices: vertices.to_vec(),
+            material: material.map(|m| m.to_owned()),
+        });
+    }
+
+    /// Adds a list of triangles sharing the same material.
+    pub fn add_triangles(&mut self, triangles: &[[Vector; 3]], material: Option<&str>) {
+        for triangle in triangles.iter() {
+            self.add_polygon(triangle, material);
+        }
+    }
+
+    /// Adds the faces of the OBJ file at `path`. Returns the number of added polygons.
+    pub fn load_obj<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, ImportError> {
+        match File::open(path) {
+            Ok(f) => self.read_obj(BufReader::new(f)),
+            Err(e) => Err(ImportError::Io(e)),
+        }
+    }
+
+    /// Adds the faces of OBJ data. Only vertices (`v`), faces (`f`) and materials (`usemtl`) are
+    /// used, everything else is ignored. Returns the number of added polygons.
+    pub fn read_obj<R: BufRead>(&mut self, reader: R) -> Result<usize, ImportError> {
+        let mut vertices = Vec::new();
+        let mut polygons = Vec::new();
+        let mut material = None;
+
+        for (it, line) in reader.lines().enumerate() {
+            let line = match line {
+                Ok(l) => l,
+                Err(e) => return Err(ImportError::Io(e)),
+            };
+            let line = match line.find('#') {
+                Some(pos) => &line[..pos],
+                None => &line[..],
+            };
+            let mut tokens = line.split_whitespace();
+
+            match tokens.next() {
+                Some("v") => {
+                    let mut coords = [0f32; 3];
+
+                    for coord in coords.iter_mut() {
+                        *coord = match tokens.next().map(|t| t.parse::<f32>()) {
+                            Some(Ok(c)) => c,
+                            _ => return Err(parse_error(it, "invalid vertex")),
+                        };
+                    }
+                    vertices.push(Vector { x: coords[0], y: coords[1], z: coords[2] });
+                }
+                Some("f") => {
+                    let mut face = Vec::new();
+
+                    for token in tokens {
+                        let index = match token.split('/').next().map(|t| t.parse::<i64>()) {
+                            Some(Ok(i)) => i,
+                            _ => return Err(parse_error(it, "invalid face index")),
+                        };
+                        // OBJ indices start at 1, negative ones are relative to the last vertex
+                        let index = if index > 0 {
+                            index - 1
+                        } else {
+                            vertices.len() as i64 + index
+                        };
+
+                        if index < 0 || index >= vertices.len() as i64 {
+                            return Err(parse_error(it, "face index out of range"));
+                        }
+                        face.push(vertices[index as usize]);
+                    }
+                    if face.len() < 3 {
+                        return Err(parse_error(it, "face with less than 3 vertices"));
+                    }
+                    polygons.push(MeshPolygon {
+                        vertices: face,
+                        material: material.clone(),
+                    });
+                }
+                Some("usemtl") => {
+                    material = tokens.next().map(|m| m.to_owned());
+                }
+                _ => {}
+            }
+        }
+
+        let added = polygons.len();
+
+        self.polygons.extend(polygons);
+        Ok(added)
+    }
+
+    /// Creates the geometries holding every added polygon. A new geometry is started each time
+    /// the current one reaches the limits returned by
+    /// [`Geometry::get_max_polygons`](struct.Geometry.html#method.get_max_polygons).
+    pub fn build(&self, fmod: &Sys) -> Result<Vec<Geometry>, ImportError> {
+        let mut geometries : Vec<Geometry> = Vec::new();
+        let mut num_polygons = 0i32;
+        let mut num_vertices = 0i32;
+        let mut max_polygons = 0i32;
+        let mut max_vertices = 0i32;
+
+        for polygon in self.polygons.iter() {
+            let polygon_vertices = polygon.vertices.len() as i32;
+
+            if geometries.is_empty() || num_polygons + 1 > max_polygons ||
+               num_vertices + polygon_vertices > max_vertices {
+                let geometry = match self.create_geometry(fmod) {
+                    Ok(g) => g,
+                    Err(e) => return Err(e),
+                };
+
+                match geometry.get_max_polygons() {
+                    Ok((p, v)) => {
+                        max_polygons = p;
+                        max_vertices = v;
+                    }
+                    Err(e) => return Err(ImportError::Fmod(e)),
+                }
+                if polygon_vertices > max_vertices {
+                    return Err(ImportError::Fmod(::FmodError::new("GeometryImporter::build",
+                                                                   ::Status::InvalidParam)));
+                }
+                num_polygons = 0;
+                num_vertices = 0;
+                geometries.push(geometry);
+            }
+
+            let material = self.materials.get(polygon.material.as_ref().map(|m| &m[..]));
+            let geometry = &geometries[geometries.len() - 1];
+
+            match geometry.add_polygon(material.direct_occlusion, material.reverb_occlusion,
+                                       material.double_sided, polygon.vertices.clone()) {
+                Ok(_) => {}
+                Err(e) => return Err(ImportError::Fmod(e)),
+            }
+            num_polygons += 1;
+            num_vertices += polygon_vertices;
+        }
+        Ok(geometries)
+    }
+
+    fn create_geometry(&self, fmod: &Sys) -> Result<Geometry, ImportError> {
+        let geometry = match fmod.create_geometry(self.max_polygons, self.max_vertices) {
+            Ok(g) => g,
+            Err(e) => return Err(ImportError::Fmod(e)),
+        };
+
+        match geometry.set_position(self.position) {
+            Ok(()) => {}
+            Err(e) => return Err(ImportError::Fmod(e)),
+        }
+        match geometry.set_rotation(self.forward, self.up) {
+            Ok(()) => {}
+            Err(e) => return Err(ImportError::Fmod(e)),
+        }
+        match geometry.set_scale(self.scale) {
+            Ok(()) => Ok(geometry),
+            Err(e) => Err(ImportError::Fmod(e)),
+        }
+    }
+}
+
+fn parse_error(line: usize, message: &str) -> ImportError {
+    ImportError::Parse {
+        line: line + 1,
+        message: message.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE : &'static str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n";
+
+    fn read(obj: &str) -> (GeometryImporter, Result<usize, ImportError>) {
+        let mut importer = GeometryImporter::new(MaterialTable::new());
+        let ret = importer.read_obj(obj.as_bytes());
+
+        (importer, ret)
+    }
+
+    fn parse_error_line(ret: Result<usize, ImportError>) -> usize {
+        match ret {
+            Err(ImportError::Parse { line, .. }) => line,
+            _ => panic!("invalid OBJ data was accepted"),
+        }
+    }
+
+    #[test]
+    fn faces_and_comments() {
+        let (importer, ret) = read(&format!("# square\n{}f 1 2 3 # first\nf -4/1 -2/2/3 -1//4\n\
+                                             vt 0 1\ns off\n", SQUARE));
+
+        assert_eq!(ret.unwrap(), 2);
+        assert_eq!(importer.get_num_polygons(), 2);
+        assert_eq!(importer.polygons[1].vertices[0].x, 0f32);
+        assert_eq!(importer.polygons[1].vertices[1].y, 1f32);
+    }
+
+    #[test]
+    fn malformed_lines() {
+        assert_eq!(parse_error_line(read(&format!("{}f 1 2 x\n", SQUARE)).1), 5);
+        assert_eq!(parse_error_line(read(&format!("{}f 1 2 5\n", SQUARE)).1), 5);
+        assert_eq!(parse_error_line(read(&format!("{}f 1 2 -5\n", SQUARE)).1), 5);
+        assert_eq!(parse_error_line(read(&format!("{}\nf 1 2\n", SQUARE)).1), 6);
+        assert_eq!(parse_error_line(read("v 0 0\n").1), 1);
+
+        // faces before the error aren't added either
+        let (importer, ret) = read(&format!("{}f 1 2 3\nf 1 0 3\n", SQUARE));
+
+        assert_eq!(parse_error_line(ret), 6);
+        assert_eq!(importer.get_num_polygons(), 0);
+    }
+
+    #[test]
+    fn unknown_material_gets_the_default() {
+        let glass = OcclusionMaterial::new(0.3f32, 0.5f32, true);
+        let default = OcclusionMaterial::new(0.8f32, 0.6f32, false);
+        let mut materials = MaterialTable::new();
+
+        materials.insert("glass", glass);
+        materials.set_default(default);
+        let mut importer = GeometryImporter::new(materials);
+        let obj = format!("{}f 1 2 3\nusemtl glass\nf 1 3 4\nusemtl brick\nf 1 2 4\n", SQUARE);
+
+        assert_eq!(importer.read_obj(obj.as_bytes()).unwrap(), 3);
+        let materials = importer.polygons.iter().map(|p| p.material.clone()).collect::<Vec<_>>();
+        assert_eq!(materials, vec![None, Some("glass".to_owned()), Some("brick".to_owned())]);
+        assert_eq!(importer.get_materials().get(None), default);
+        assert_eq!(importer.get_materials().get(Some("glass")), glass);
+        assert_eq!(importer.get_materials().get(Some("brick")), default);
+    }
+
+    #[cfg(feature = "mock")]
+    fn init() -> Sys {
+        let fmod = Sys::new().unwrap();
+
+        fmod.init_with_parameters(32, ::InitFlag(::INIT_NORMAL)).unwrap();
+        fmod
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn build_gives_materials_to_polygons() {
+        let fmod = init();
+        let mut materials = MaterialTable::new();
+
+        materials.insert("glass", OcclusionMaterial::new(0.3f32, 0.5f32, true));
+        materials.set_default(OcclusionMaterial::new(0.8f32, 0.6f32, false));
+        let mut importer = GeometryImporter::new(materials);
+        let obj = format!("{}usemtl glass\nf 1 2 3\nusemtl brick\nf 1 3 4\n", SQUARE);
+
+        importer.read_obj(obj.as_bytes()).unwrap();
+        importer.set_position(Vector { x: 1f32, y: 2f32, z: 3f32 });
+        let geometries = importer.build(&fmod).unwrap();
+
+        assert_eq!(geometries.len(), 1);
+        assert_eq!(geometries[0].get_num_polygons().unwrap(), 2);
+        assert_eq!(geometries[0].get_polygon_attributes(0).unwrap(), (0.3f32, 0.5f32, true));
+        assert_eq!(geometries[0].get_polygon_attributes(1).unwrap(), (0.8f32, 0.6f32, false));
+        assert_eq!(geometries[0].get_polygon_vertex(1, 2).unwrap().y, 1f32);
+        assert_eq!(geometries[0].get_position().unwrap().z, 3f32);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn build_splits_polygons() {
+        let fmod = init();
+        let (mut importer, ret) = read(&format!("{}f 1 2 3\nf 1 3 4\nf 1 2 4\nf 2 3 4\nf 1 2 3\n",
+                                                SQUARE));
+
+        assert_eq!(ret.unwrap(), 5);
+        importer.set_limits(2, 100);
+        let counts = importer.build(&fmod).unwrap().iter()
+                             .map(|g| g.get_num_polygons().unwrap()).collect::<Vec<_>>();
+        assert_eq!(counts, vec![2, 2, 1]);
+
+        // 3 triangles fit in 10 vertices
+        importer.set_limits(100, 10);
+        let counts = importer.build(&fmod).unwrap().iter()
+                             .map(|g| g.get_num_polygons().unwrap()).collect::<Vec<_>>();
+        assert_eq!(counts, vec![3, 2]);
+
+        // a polygon can't be split
+        importer.add_polygon(&[Vector::new(); 11], None);
+        match importer.build(&fmod) {
+            Err(ImportError::Fmod(e)) => assert_eq!(e.get_status(), ::Status::InvalidParam),
+            _ => panic!("a polygon larger than a geometry was accepted"),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+/*!
+Rust implementation of FMOD, used in place of the native library when the `mock` feature is
+enabled.
+
+It keeps the state of the systems, sounds, channels, groups and DSP units created through the
+wrappers and records every call, so code using rfmod can be tested on machines without FMOD:
+
+```
+let fmod = rfmod::Sys::new().unwrap();
+
+fmod.init().unwrap();
+let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+let channel = sound.play().unwrap();
+
+channel.set_volume(0.5f32).unwrap();
+assert_eq!(rfmod::mock::get_value(&channel, "volume"), Some(rfmod::mock::Value::Float(0.5f32)));
+assert!(rfmod::mock::get_calls_of(&channel).iter().any(|c| {
+    c.function == "FMOD_Channel_SetVolume" && c.args == [rfmod::mock::Value::Float(0.5f32)]
+}));
+```
+
+Nothing is played: channels only move forward when [`advance`](fn.advance.html) is called. The
+functions the mock doesn't handle are recorded and return `Status::Unsupported`.
+
+The state is shared by all threads. Handles are never reused, so tests running in parallel don't
+see each other's objects, but they do see each other's calls: filter them with
+[`get_calls_of`](fn.get_calls_of.html).
+!*/
+
+use ffi;
+use mock_ffi;
+
+/// Value of an object property, or argument of a recorded call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f32),
+    Bool(bool),
+    Str(String),
+    Vector(f32, f32, f32),
+    /// Handle of another FMOD object.
+    Handle(usize),
+}
+
+impl Value {
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            Value::Int(v) => Some(v),
+            Value::Bool(v) => Some(v as i64),
+            Value::Handle(v) => Some(v as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f32> {
+        match *self {
+            Value::Float(v) => Some(v),
+            Value::Int(v) => Some(v as f32),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(v) => Some(v),
+            Value::Int(v) => Some(v != 0),
+            _ => None,
+        }
+    }
+}
+
+/// Call made to an FMOD function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    /// Name of the FMOD function, like `"FMOD_Channel_SetVolume"`.
+    pub function: &'static str,
+    /// Handle of the object the function was called on.
+    pub object: usize,
+    /// Values given to the function. Getters have none.
+    pub args: Vec<Value>,
+}
+
+/// Object whose handle is known to the mock.
+pub trait Handle {
+    fn handle(&self) -> usize;
+}
+
+macro_rules! impl_handle {
+    ($($t:ty),*) => {
+        $(impl Handle for $t {
+            fn handle(&self) -> usize {
+                ffi::FFI::unwrap(self) as usize
+            }
+        })*
+    }
+}
+
+impl_handle!(::Sys, ::Sound, ::Channel, ::ChannelGroup, ::SoundGroup, ::Dsp, ::DspConnection,
+             ::Geometry, ::Reverb);
+
+/// Returns the calls made since the last `take_calls` or `reset`.
+pub fn get_calls() -> Vec<Call> {
+    mock_ffi::with_state(|s| s.calls.clone())
+}
+
+/// Returns the calls made since the last `take_calls` or `reset`, and forgets them.
+pub fn take_calls() -> Vec<Call> {
+    mock_ffi::with_state(|s| ::std::mem::replace(&mut s.calls, Vec::new()))
+}
+
+/// Returns the calls made on `object`.
+pub fn get_calls_of<T: Handle>(object: &T) -> Vec<Call> {
+    let handle = object.handle();
+
+    mock_ffi::with_state(|s| s.calls.iter().filter(|c| c.object == handle).cloned().collect())
+}
+
+/// Returns a property of `object`, like `"volume"` or `"paused"` for a channel. None if the
+/// object or the property doesn't exist.
+pub fn get_value<T: Handle>(object: &T, property: &str) -> Option<Value> {
+    let handle = object.handle();
+
+    mock_ffi::with_state(|s| match s.objects.get(&handle) {
+        Some(o) => o.values.get(property).cloned(),
+        None => None,
+    })
+}
+
+/// Sets a property of `object` without recording a call, e.g. the `"length"` in milliseconds
+/// of a sound. Returns false if the object doesn't exist.
+pub fn set_value<T: Handle>(object: &T, property: &str, value: Value) -> bool {
+    let handle = object.handle();
+
+    mock_ffi::with_state(|s| match s.objects.get_mut(&handle) {
+        Some(o) => {
+            o.values.insert(property.to_owned(), value);
+            true
+        }
+        None => false,
+    })
+}
+
+/// Sets the decoded data of a sound, in the format of its `"format"` and `"channels"` properties
+/// (PCM16 stereo by default). It is returned by `Sound::lock` and `Sound::read_data`, and gives
+/// the length of the sound. Returns false if the object doesn't exist.
+pub fn set_data<T: Handle>(object: &T, data: Vec<u8>) -> bool {
+    let handle = object.handle();
+
+    mock_ffi::with_state(|s| match s.objects.get_mut(&handle) {
+        Some(o) => {
+            mock_ffi::set_data(o, data);
+            true
+        }
+        None => false,
+    })
+}
+
+/// Returns the decoded data of a sound: the data given to `set_data`, or decoded by a codec.
+pub fn get_data<T: Handle>(object: &T) -> Option<Vec<u8>> {
+    let handle = object.handle();
+
+    mock_ffi::with_state(|s| s.objects.get(&handle).map(|o| mock_ffi::get_data(o).to_vec()))
+}
+
+/// Sets the content of the file `name`. Sounds created from it are opened by the codecs
+/// registered with `Sys::register_codec`, and fail with `Status::Format` if none of them
+/// recognizes it. Other files are opened without codec.
+pub fn set_file(name: &str, data: Vec<u8>) {
+    mock_ffi::with_state(|s| s.set_file(name, data))
+}
+
+/// Moves every playing channel forward by `ms` milliseconds. Channels reaching the end of a non
+/// looping sound end, and their `End` callback is called.
+pub fn advance(ms: u32) {
+    mock_ffi::advance(ms)
+}
+
+/// Forgets every object and call. Handles created before are invalid afterwards.
+pub fn reset() {
+    mock_ffi::with_state(|s| s.reset())
+}
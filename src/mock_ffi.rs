@@ -0,0 +1,3565 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+//! FMOD functions of the `mock` feature, see the [`mock`](../mock/index.html) module.
+
+#![allow(non_snake_case)]
+
+use ffi::*;
+use mock::{Call, Value};
+use libc::{c_char, c_int, c_uint, c_float, c_short, c_void};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Kind {
+    System,
+    Sound,
+    Channel,
+    ChannelGroup,
+    SoundGroup,
+    Dsp,
+    DspConnection,
+    Reverb,
+    Geometry,
+}
+
+/// Callbacks given to `FMOD_System_CreateDSP`.
+#[derive(Clone, Copy)]
+struct DspCallbacks {
+    release: FMOD_DSP_RELEASECALLBACK,
+    reset: FMOD_DSP_RESETCALLBACK,
+    set_parameter: FMOD_DSP_SETPARAMCALLBACK,
+    get_parameter: FMOD_DSP_GETPARAMCALLBACK,
+}
+
+pub struct Object {
+    pub kind: Kind,
+    pub system: usize,
+    pub values: HashMap<String, Value>,
+    user_data: usize,
+    /// Channel group of a channel or a group, sound group of a sound.
+    parent: usize,
+    /// Sound or DSP played by a channel.
+    source: usize,
+    /// Input and output DSPs of a connection.
+    connection: (usize, usize),
+    /// (dsp, connection) pairs of a DSP.
+    inputs: Vec<(usize, usize)>,
+    outputs: Vec<(usize, usize)>,
+    /// DSP unit of a system, channel or channel group, created on first use.
+    dsp_head: usize,
+    channel_callback: FMOD_CHANNEL_CALLBACK,
+    dsp_callbacks: Option<DspCallbacks>,
+    plugin_data: usize,
+    /// Decoded data of a sound, see `mock::set_data`.
+    data: Vec<u8>,
+    /// Polygons of a geometry.
+    polygons: Vec<Polygon>,
+}
+
+/// Polygon of a geometry, in local coordinates.
+struct Polygon {
+    direct_occlusion: f32,
+    reverb_occlusion: f32,
+    double_sided: bool,
+    vertices: Vec<::Vector>,
+}
+
+impl Object {
+    fn new(kind: Kind, system: usize) -> Object {
+        Object {
+            kind: kind,
+            system: system,
+            values: HashMap::new(),
+            user_data: 0,
+            parent: 0,
+            source: 0,
+            connection: (0, 0),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            dsp_head: 0,
+            channel_callback: None,
+            dsp_callbacks: None,
+            plugin_data: 0,
+            data: Vec::new(),
+            polygons: Vec::new(),
+        }
+    }
+
+    fn set(&mut self, name: &str, value: Value) {
+        self.values.insert(name.to_owned(), value);
+    }
+
+    fn int(&self, name: &str) -> i64 {
+        self.values.get(name).and_then(|v| v.as_int()).unwrap_or(0)
+    }
+
+    fn float(&self, name: &str) -> f32 {
+        self.values.get(name).and_then(|v| v.as_float()).unwrap_or(0f32)
+    }
+
+    fn bool(&self, name: &str) -> bool {
+        self.values.get(name).and_then(|v| v.as_bool()).unwrap_or(false)
+    }
+
+    fn str(&self, name: &str) -> String {
+        match self.values.get(name) {
+            Some(&Value::Str(ref s)) => s.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Callbacks of a description given to FMOD_System_RegisterCodec.
+#[derive(Clone, Copy)]
+struct CodecCallbacks {
+    open: FMOD_CODEC_OPENCALLBACK,
+    close: FMOD_CODEC_CLOSECALLBACK,
+    read: FMOD_CODEC_READCALLBACK,
+    set_position: FMOD_CODEC_SETPOSITIONCALLBACK,
+}
+
+/// In memory file read by a codec.
+struct CodecFile {
+    data: Vec<u8>,
+    position: usize,
+}
+
+/// Sound opened by a registered codec. Boxed, so that the pointers of the state stay valid.
+struct CodecSound {
+    state: FMOD_CODEC_STATE,
+    file: CodecFile,
+    callbacks: CodecCallbacks,
+}
+
+/// Open and close callbacks given to FMOD_System_SetFileSystem or FMOD_System_AttachFileSystem.
+#[derive(Clone, Copy)]
+struct FileCallbacks {
+    open: FMOD_FILE_OPENCALLBACK,
+    close: FMOD_FILE_CLOSECALLBACK,
+}
+
+pub struct State {
+    pub objects: HashMap<usize, Object>,
+    pub calls: Vec<Call>,
+    next_handle: usize,
+    file_systems: HashMap<usize, FileCallbacks>,
+    observers: HashMap<usize, FileCallbacks>,
+    /// (system, name) of the files of the sounds created with NONBLOCKING, opened by `advance`.
+    pending_opens: Vec<(usize, String)>,
+    /// Content of the files given to `mock::set_file`, read by the registered codecs.
+    files: HashMap<String, Vec<u8>>,
+    /// (system, priority, callbacks) of the registered codecs.
+    codecs: Vec<(usize, u32, CodecCallbacks)>,
+    /// Raw `Box<CodecSound>` of the streams decoded by a registered codec, by sound.
+    codec_sounds: HashMap<usize, usize>,
+}
+
+impl State {
+    fn new() -> State {
+        State {
+            objects: HashMap::new(),
+            calls: Vec::new(),
+            next_handle: 0,
+            file_systems: HashMap::new(),
+            observers: HashMap::new(),
+            pending_opens: Vec::new(),
+            files: HashMap::new(),
+            codecs: Vec::new(),
+            codec_sounds: HashMap::new(),
+        }
+    }
+
+    /// Forgets every object and call. Handles stay unique.
+    pub fn reset(&mut self) {
+        self.objects.clear();
+        self.calls.clear();
+        self.file_systems.clear();
+        self.observers.clear();
+        self.pending_opens.clear();
+        self.files.clear();
+        self.codecs.clear();
+        for (_, codec_sound) in self.codec_sounds.drain() {
+            drop(unsafe { Box::from_raw(codec_sound as *mut CodecSound) });
+        }
+    }
+
+    pub fn set_file(&mut self, name: &str, data: Vec<u8>) {
+        self.files.insert(name.to_owned(), data);
+    }
+
+    fn add(&mut self, object: Object) -> usize {
+        // never 0 and never reused, so stale handles stay invalid
+        self.next_handle += 0x10;
+        self.objects.insert(self.next_handle, object);
+        self.next_handle
+    }
+
+    fn get(&self, handle: usize, kind: Kind) -> Result<&Object, ::Status> {
+        match self.objects.get(&handle) {
+            Some(o) if o.kind == kind => Ok(o),
+            _ => Err(::Status::InvalidHandle),
+        }
+    }
+
+    fn get_mut(&mut self, handle: usize, kind: Kind) -> Result<&mut Object, ::Status> {
+        match self.objects.get_mut(&handle) {
+            Some(o) if o.kind == kind => Ok(o),
+            _ => Err(::Status::InvalidHandle),
+        }
+    }
+
+    /// Same as `get_mut`, but fails on channels which have ended or been stolen.
+    fn live_mut(&mut self, handle: usize, kind: Kind) -> Result<&mut Object, ::Status> {
+        match self.get_mut(handle, kind) {
+            Ok(o) => {
+                if kind == Kind::Channel && !o.bool("playing") {
+                    Err(if o.bool("stolen") { ::Status::ChannelStolen } else { ::Status::InvalidHandle })
+                } else {
+                    Ok(o)
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn live(&mut self, handle: usize, kind: Kind) -> Result<&Object, ::Status> {
+        match self.live_mut(handle, kind) {
+            Ok(o) => Ok(&*o),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn create(&mut self, kind: Kind, system: usize, values: Vec<(&str, Value)>) -> usize {
+        let mut object = Object::new(kind, system);
+
+        for (name, value) in values {
+            object.set(name, value);
+        }
+        self.add(object)
+    }
+
+    fn create_dsp(&mut self, system: usize, dsp_type: ::DspType, name: String) -> usize {
+        self.create(Kind::Dsp, system, vec![("type", Value::Int(dsp_type as i64)),
+                                            ("name", Value::Str(name)),
+                                            ("active", Value::Bool(false)),
+                                            ("bypass", Value::Bool(false)),
+                                            ("num_parameters", Value::Int(0))])
+    }
+
+    /// Returns the head of a channel or a group, creating it on first use. Like in FMOD, it is
+    /// an input of the head of the parent group, or of the system head for the master group.
+    fn get_dsp_head(&mut self, handle: usize, kind: Kind) -> Result<usize, ::Status> {
+        let (head, system, parent) = match self.live(handle, kind) {
+            Ok(o) => (o.dsp_head, o.system, o.parent),
+            Err(e) => return Err(e),
+        };
+
+        if head != 0 {
+            return Ok(head);
+        }
+        let head = self.create_dsp(system, ::DspType::Mixer, "FMOD Channel DSPHead".to_owned());
+        if let Ok(o) = self.get_mut(handle, kind) {
+            o.dsp_head = head;
+        }
+        let output = match kind {
+            Kind::Channel => self.get_dsp_head(parent, Kind::ChannelGroup).ok(),
+            Kind::ChannelGroup if parent != 0 => self.get_dsp_head(parent, Kind::ChannelGroup).ok(),
+            Kind::ChannelGroup => self.get_dsp_head(system, Kind::System).ok(),
+            _ => None,
+        };
+        if let Some(output) = output {
+            let _ = self.connect(output, head);
+        }
+        Ok(head)
+    }
+
+    /// Moves a channel or a group below the group `parent`, moving the connection going out of
+    /// its DSP head, or out of the last unit inserted after it, to the head of `parent`.
+    fn set_parent(&mut self, handle: usize, parent: usize) {
+        let (old_parent, head) = match self.objects.get_mut(&handle) {
+            Some(o) => (::std::mem::replace(&mut o.parent, parent), o.dsp_head),
+            None => return,
+        };
+        let old_head = self.objects.get(&old_parent).map(|o| o.dsp_head).unwrap_or(0);
+
+        if head == 0 || old_head == 0 || old_parent == parent {
+            return;
+        }
+        let mut queue = vec![head];
+        let mut i = 0;
+        let mut tail = None;
+
+        while i < queue.len() && tail.is_none() {
+            for &(output, connection) in self.objects[&queue[i]].outputs.iter() {
+                if output == old_head {
+                    tail = Some((queue[i], connection));
+                } else if !queue.contains(&output) {
+                    queue.push(output);
+                }
+            }
+            i += 1;
+        }
+        if let Some((tail, connection)) = tail {
+            let mix = self.objects[&connection].float("mix");
+
+            self.disconnect(connection);
+            if let Ok(new_head) = self.get_dsp_head(parent, Kind::ChannelGroup) {
+                if let Ok(c) = self.connect(new_head, tail) {
+                    self.objects.get_mut(&c).unwrap().set("mix", Value::Float(mix));
+                }
+            }
+        }
+    }
+
+    fn connect(&mut self, dsp: usize, input: usize) -> Result<usize, ::Status> {
+        let system = match (self.get(dsp, Kind::Dsp), self.get(input, Kind::Dsp)) {
+            (Ok(o), Ok(_)) => o.system,
+            _ => return Err(::Status::InvalidHandle),
+        };
+        let mut connection = Object::new(Kind::DspConnection, system);
+
+        connection.connection = (input, dsp);
+        connection.set("mix", Value::Float(1f32));
+        let connection = self.add(connection);
+        if let Ok(o) = self.get_mut(dsp, Kind::Dsp) {
+            o.inputs.push((input, connection));
+        }
+        if let Ok(o) = self.get_mut(input, Kind::Dsp) {
+            o.outputs.push((dsp, connection));
+        }
+        Ok(connection)
+    }
+
+    fn disconnect(&mut self, connection: usize) {
+        let (input, output) = match self.objects.remove(&connection) {
+            Some(c) => c.connection,
+            None => return,
+        };
+
+        if let Some(o) = self.objects.get_mut(&output) {
+            o.inputs.retain(|&(_, c)| c != connection);
+        }
+        if let Some(o) = self.objects.get_mut(&input) {
+            o.outputs.retain(|&(_, c)| c != connection);
+        }
+    }
+
+    /// Returns the channel groups in the tree starting at `group`.
+    fn group_tree(&self, group: usize) -> Vec<usize> {
+        let mut groups = vec![group];
+        let mut i = 0;
+
+        while i < groups.len() {
+            let parent = groups[i];
+
+            groups.extend(self.objects.iter()
+                              .filter(|&(_, o)| o.kind == Kind::ChannelGroup && o.parent == parent)
+                              .map(|(&h, _)| h));
+            i += 1;
+        }
+        groups
+    }
+
+    fn playing_channels<F: Fn(&Object) -> bool>(&self, filter: F) -> Vec<usize> {
+        let mut channels: Vec<usize> = self.objects.iter()
+                                           .filter(|&(_, o)| {
+                                               o.kind == Kind::Channel && o.bool("playing") &&
+                                               filter(o)
+                                           })
+                                           .map(|(&h, _)| h)
+                                           .collect();
+
+        channels.sort();
+        channels
+    }
+
+    /// Marks the channel as ended and returns its callback, to be called once the state is
+    /// unlocked.
+    fn end_channel(&mut self, channel: usize, stolen: bool) -> Option<(usize, FMOD_CHANNEL_CALLBACK)> {
+        match self.objects.get_mut(&channel) {
+            Some(o) => {
+                if !o.bool("playing") {
+                    return None;
+                }
+                o.set("playing", Value::Bool(false));
+                o.set("stolen", Value::Bool(stolen));
+                Some((channel, o.channel_callback))
+            }
+            None => None,
+        }
+    }
+
+    fn end_channels(&mut self, channels: Vec<usize>) -> Vec<(usize, FMOD_CHANNEL_CALLBACK)> {
+        channels.into_iter().filter_map(|c| self.end_channel(c, false)).collect()
+    }
+
+    fn is_paused(&self, channel: &Object) -> bool {
+        let mut group = channel.parent;
+
+        if channel.bool("paused") {
+            return true;
+        }
+        while let Some(o) = self.objects.get(&group) {
+            if o.bool("paused") {
+                return true;
+            }
+            group = o.parent;
+        }
+        false
+    }
+}
+
+static STATE : Mutex<Option<State>> = Mutex::new(None);
+
+pub fn with_state<R, F: FnOnce(&mut State) -> R>(f: F) -> R {
+    let mut state = match STATE.lock() {
+        Ok(s) => s,
+        Err(e) => e.into_inner(),
+    };
+
+    f(state.get_or_insert_with(State::new))
+}
+
+fn record(function: &'static str, object: usize, args: Vec<Value>) {
+    with_state(|s| s.calls.push(Call { function: function, object: object, args: args }));
+}
+
+/// Calls the `End` callback of ended channels. The state mustn't be locked: the callbacks may
+/// call FMOD functions.
+fn call_end_callbacks(channels: Vec<(usize, FMOD_CHANNEL_CALLBACK)>) {
+    for (channel, callback) in channels {
+        if let Some(callback) = callback {
+            callback(channel as *mut FMOD_CHANNEL, ::ChannelCallbackType::End,
+                     ::std::ptr::null_mut(), ::std::ptr::null_mut());
+        }
+    }
+}
+
+pub fn advance(ms: u32) {
+    let pending_opens = with_state(|s| ::std::mem::replace(&mut s.pending_opens, Vec::new()));
+
+    // like FMOD's loading thread, outside of any call to the system
+    for (system, name) in pending_opens {
+        unsafe { open_file(system, &name) };
+    }
+    let ended = with_state(|s| {
+        let channels = s.playing_channels(|_| true);
+        let mut ended = Vec::new();
+
+        for channel in channels {
+            let (position, length, looping, loop_count) = {
+                let o = &s.objects[&channel];
+
+                if s.is_paused(o) {
+                    continue;
+                }
+                let length = match s.objects.get(&o.source) {
+                    Some(source) if source.kind == Kind::Sound => source.int("length"),
+                    _ => 0,
+                };
+                (o.int("position") + ms as i64, length,
+                 o.int("mode") & (::LOOP_NORMAL | ::LOOP_BIDI) as i64 != 0, o.int("loop_count"))
+            };
+            if length <= 0 || position < length {
+                s.objects.get_mut(&channel).unwrap().set("position", Value::Int(position));
+            } else if looping && loop_count != 0 {
+                let o = s.objects.get_mut(&channel).unwrap();
+                let loops = position / length;
+
+                o.set("position", Value::Int(position % length));
+                if loop_count > 0 {
+                    o.set("loop_count", Value::Int((loop_count - loops).max(0)));
+                }
+            } else {
+                s.objects.get_mut(&channel).unwrap().set("position", Value::Int(length));
+                if let Some(c) = s.end_channel(channel, false) {
+                    ended.push(c);
+                }
+            }
+        }
+        ended
+    });
+
+    call_end_callbacks(ended);
+}
+
+unsafe fn write<T>(ptr: *mut T, value: T) {
+    if !ptr.is_null() {
+        *ptr = value;
+    }
+}
+
+unsafe fn read_str(s: *const c_char) -> String {
+    if s.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+}
+
+unsafe fn write_str(buffer: *mut c_char, len: c_int, s: &str) {
+    if buffer.is_null() || len <= 0 {
+        return;
+    }
+    let bytes = s.as_bytes();
+    let n = ::std::cmp::min(bytes.len(), len as usize - 1);
+
+    for (i, b) in bytes[..n].iter().enumerate() {
+        *buffer.offset(i as isize) = *b as c_char;
+    }
+    *buffer.offset(n as isize) = 0;
+}
+
+unsafe fn read_vector(v: *const FMOD_VECTOR) -> Option<Value> {
+    if v.is_null() {
+        None
+    } else {
+        Some(Value::Vector((*v).x, (*v).y, (*v).z))
+    }
+}
+
+unsafe fn write_vector(ptr: *mut FMOD_VECTOR, value: Option<&Value>) {
+    if let Some(&Value::Vector(x, y, z)) = value {
+        write(ptr, FMOD_VECTOR { x: x, y: y, z: z });
+    } else {
+        write(ptr, FMOD_VECTOR { x: 0f32, y: 0f32, z: 0f32 });
+    }
+}
+
+fn to_ms(value: u32, unit: FMOD_TIMEUNIT, frequency: f32) -> Result<i64, ::Status> {
+    match unit {
+        1 => Ok(value as i64),
+        2 => Ok((value as f64 * 1000f64 / frequency as f64) as i64),
+        4 => Ok((value as f64 / 4f64 * 1000f64 / frequency as f64) as i64),
+        _ => Err(::Status::Format),
+    }
+}
+
+fn from_ms(ms: i64, unit: FMOD_TIMEUNIT, frequency: f32) -> Result<u32, ::Status> {
+    match unit {
+        1 => Ok(ms as u32),
+        2 => Ok((ms as f64 * frequency as f64 / 1000f64) as u32),
+        4 => Ok((ms as f64 * frequency as f64 / 1000f64) as u32 * 4),
+        _ => Err(::Status::Format),
+    }
+}
+
+fn status<T>(result: Result<T, ::Status>) -> ::Status {
+    match result {
+        Ok(_) => ::Status::Ok,
+        Err(e) => e,
+    }
+}
+
+/// Records the call and stores `value` as the `name` property of the object.
+fn set_value(function: &'static str, object: usize, kind: Kind, name: &str,
+             value: Value) -> ::Status {
+    record(function, object, vec![value.clone()]);
+    with_state(|s| status(s.live_mut(object, kind).map(|o| o.set(name, value))))
+}
+
+/// Records the call and returns the `name` property of the object.
+fn get_value(function: &'static str, object: usize, kind: Kind,
+             name: &str) -> Result<Value, ::Status> {
+    record(function, object, Vec::new());
+    with_state(|s| s.live(object, kind).map(|o| o.values.get(name).cloned()
+                                                   .unwrap_or(Value::Int(0))))
+}
+
+unsafe fn get_float(function: &'static str, object: usize, kind: Kind, name: &str,
+                    out: *mut c_float) -> ::Status {
+    match get_value(function, object, kind, name) {
+        Ok(v) => {
+            write(out, v.as_float().unwrap_or(0f32));
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+unsafe fn get_int(function: &'static str, object: usize, kind: Kind, name: &str,
+                  out: *mut c_int) -> ::Status {
+    match get_value(function, object, kind, name) {
+        Ok(v) => {
+            write(out, v.as_int().unwrap_or(0) as c_int);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+unsafe fn get_bool(function: &'static str, object: usize, kind: Kind, name: &str,
+                   out: *mut FMOD_BOOL) -> ::Status {
+    match get_value(function, object, kind, name) {
+        Ok(v) => {
+            write(out, v.as_bool().unwrap_or(false) as FMOD_BOOL);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+unsafe fn get_handle<T>(function: &'static str, object: usize, kind: Kind,
+                        f: fn(&Object) -> usize, out: *mut *mut T) -> ::Status {
+    record(function, object, Vec::new());
+    match with_state(|s| s.live(object, kind).map(f)) {
+        Ok(h) => {
+            write(out, h as *mut T);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+unsafe fn get_system<T>(function: &'static str, object: usize, kind: Kind,
+                        out: *mut *mut FMOD_SYSTEM) -> ::Status {
+    get_handle(function, object, kind, |o| o.system, out)
+}
+
+unsafe fn get_user_data(function: &'static str, object: usize, kind: Kind,
+                        out: *mut *mut c_void) -> ::Status {
+    get_handle(function, object, kind, |o| o.user_data, out)
+}
+
+fn set_user_data(function: &'static str, object: usize, kind: Kind,
+                 user_data: *mut c_void) -> ::Status {
+    record(function, object, vec![Value::Handle(user_data as usize)]);
+    with_state(|s| status(s.live_mut(object, kind).map(|o| o.user_data = user_data as usize)))
+}
+
+unsafe fn get_name(function: &'static str, object: usize, kind: Kind, name: *mut c_char,
+                   name_len: c_int) -> ::Status {
+    record(function, object, Vec::new());
+    match with_state(|s| s.live(object, kind).map(|o| o.str("name"))) {
+        Ok(n) => {
+            write_str(name, name_len, &n);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+/* System */
+
+pub unsafe fn FMOD_System_Create(system: *mut *mut FMOD_SYSTEM) -> ::Status {
+    let handle = with_state(|s| {
+        let handle = s.create(Kind::System, 0, vec![("initialized", Value::Bool(false)),
+                                                    ("max_channels", Value::Int(0))]);
+        let master_group = s.create(Kind::ChannelGroup, handle,
+                                    vec![("name", Value::Str("FMOD master group".to_owned())),
+                                         ("volume", Value::Float(1f32)),
+                                         ("pitch", Value::Float(1f32)),
+                                         ("paused", Value::Bool(false)),
+                                         ("mute", Value::Bool(false))]);
+        let master_sound_group = s.create(Kind::SoundGroup, handle,
+                                          vec![("name", Value::Str("Master".to_owned())),
+                                               ("max_audible", Value::Int(-1)),
+                                               ("volume", Value::Float(1f32)),
+                                               ("mute_fade_speed", Value::Float(0f32))]);
+        let head = s.create_dsp(handle, ::DspType::Mixer,
+                                "FMOD SoundCard Unit".to_owned());
+        s.objects.get_mut(&head).unwrap().set("active", Value::Bool(true));
+        let o = s.objects.get_mut(&handle).unwrap();
+
+        o.system = handle;
+        o.dsp_head = head;
+        o.set("master_channel_group", Value::Handle(master_group));
+        o.set("master_sound_group", Value::Handle(master_sound_group));
+        for &h in &[master_group, master_sound_group, head] {
+            s.objects.get_mut(&h).unwrap().system = handle;
+        }
+        handle
+    });
+
+    record("FMOD_System_Create", handle, Vec::new());
+    write(system, handle as *mut FMOD_SYSTEM);
+    ::Status::Ok
+}
+
+pub unsafe fn FMOD_System_Release(system: *mut FMOD_SYSTEM) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_Release", system, Vec::new());
+    with_state(|s| match s.get(system, Kind::System) {
+        Ok(_) => {
+            s.objects.retain(|&h, o| h != system && o.system != system);
+            s.file_systems.remove(&system);
+            s.observers.remove(&system);
+            s.pending_opens.retain(|&(sys, _)| sys != system);
+            s.codecs.retain(|&(sys, _, _)| sys != system);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_System_Init(system: *mut FMOD_SYSTEM, max_channels: c_int,
+                               flags: FMOD_INITFLAGS, _extra_driver_data: *mut c_void) -> ::Status {
+    record("FMOD_System_Init", system as usize,
+           vec![Value::Int(max_channels as i64), Value::Int(flags as i64)]);
+    with_state(|s| match s.get_mut(system as usize, Kind::System) {
+        Ok(o) => {
+            if o.bool("initialized") {
+                ::Status::Initialized
+            } else {
+                o.set("initialized", Value::Bool(true));
+                o.set("max_channels", Value::Int(max_channels as i64));
+                o.set("flags", Value::Int(flags as i64));
+                ::Status::Ok
+            }
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_System_Close(system: *mut FMOD_SYSTEM) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_Close", system, Vec::new());
+    let ended = with_state(|s| match s.get_mut(system, Kind::System) {
+        Ok(o) => {
+            o.set("initialized", Value::Bool(false));
+            let channels = s.playing_channels(|c| c.system == system);
+            Ok(s.end_channels(channels))
+        }
+        Err(e) => Err(e),
+    });
+    match ended {
+        Ok(ended) => {
+            call_end_callbacks(ended);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_System_Update(system: *mut FMOD_SYSTEM) -> ::Status {
+    record("FMOD_System_Update", system as usize, Vec::new());
+    with_state(|s| status(s.get(system as usize, Kind::System)))
+}
+
+pub unsafe fn FMOD_System_SetUserData(system: *mut FMOD_SYSTEM,
+                                      user_data: *mut c_void) -> ::Status {
+    set_user_data("FMOD_System_SetUserData", system as usize, Kind::System, user_data)
+}
+
+pub unsafe fn FMOD_System_GetUserData(system: *mut FMOD_SYSTEM,
+                                      user_data: *mut *mut c_void) -> ::Status {
+    get_user_data("FMOD_System_GetUserData", system as usize, Kind::System, user_data)
+}
+
+pub unsafe fn FMOD_System_SetCallback(system: *mut FMOD_SYSTEM,
+                                      _call_back: FMOD_SYSTEM_CALLBACK) -> ::Status {
+    // the mock never sends system events
+    record("FMOD_System_SetCallback", system as usize, Vec::new());
+    with_state(|s| status(s.get(system as usize, Kind::System)))
+}
+
+pub unsafe fn FMOD_System_Set3DListenerAttributes(system: *mut FMOD_SYSTEM, listener: c_int,
+                                                  pos: *const FMOD_VECTOR,
+                                                  vel: *const FMOD_VECTOR,
+                                                  forward: *const FMOD_VECTOR,
+                                                  up: *const FMOD_VECTOR) -> ::Status {
+    let values = [("position", read_vector(pos)), ("velocity", read_vector(vel)),
+                  ("forward", read_vector(forward)), ("up", read_vector(up))];
+
+    record("FMOD_System_Set3DListenerAttributes", system as usize,
+           values.iter().map(|&(_, ref v)| v.clone().unwrap_or(Value::Int(0))).collect());
+    with_state(|s| match s.get_mut(system as usize, Kind::System) {
+        Ok(o) => {
+            for &(name, ref value) in values.iter() {
+                if let Some(ref value) = *value {
+                    o.set(&format!("listener{}.{}", listener, name), value.clone());
+                }
+            }
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_System_Get3DListenerAttributes(system: *mut FMOD_SYSTEM, listener: c_int,
+                                                  pos: *mut FMOD_VECTOR, vel: *mut FMOD_VECTOR,
+                                                  forward: *mut FMOD_VECTOR,
+                                                  up: *mut FMOD_VECTOR) -> ::Status {
+    record("FMOD_System_Get3DListenerAttributes", system as usize,
+           vec![Value::Int(listener as i64)]);
+    with_state(|s| match s.get(system as usize, Kind::System) {
+        Ok(o) => {
+            for &(name, ptr) in [("position", pos), ("velocity", vel), ("forward", forward),
+                                 ("up", up)].iter() {
+                write_vector(ptr, o.values.get(&format!("listener{}.{}", listener, name)));
+            }
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+/// The mixer doesn't run, the lock only counts how many times the network is locked, in the
+/// `dsp_lock` value.
+pub unsafe fn FMOD_System_LockDSP(system: *mut FMOD_SYSTEM) -> ::Status {
+    record("FMOD_System_LockDSP", system as usize, Vec::new());
+    with_state(|s| status(s.get_mut(system as usize, Kind::System).map(|o| {
+        let locks = o.int("dsp_lock");
+
+        o.set("dsp_lock", Value::Int(locks + 1));
+    })))
+}
+
+pub unsafe fn FMOD_System_UnlockDSP(system: *mut FMOD_SYSTEM) -> ::Status {
+    record("FMOD_System_UnlockDSP", system as usize, Vec::new());
+    with_state(|s| match s.get_mut(system as usize, Kind::System) {
+        Ok(o) => {
+            let locks = o.int("dsp_lock");
+
+            if locks > 0 {
+                o.set("dsp_lock", Value::Int(locks - 1));
+                ::Status::Ok
+            } else {
+                ::Status::InvalidParam
+            }
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_System_GetChannelsPlaying(system: *mut FMOD_SYSTEM,
+                                             channels: *mut c_int) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_GetChannelsPlaying", system, Vec::new());
+    with_state(|s| match s.get(system, Kind::System) {
+        Ok(_) => {
+            write(channels, s.playing_channels(|c| c.system == system).len() as c_int);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_System_GetChannel(system: *mut FMOD_SYSTEM, channel_id: c_int,
+                                     channel: *mut *mut FMOD_CHANNEL) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_GetChannel", system, vec![Value::Int(channel_id as i64)]);
+    with_state(|s| {
+        match s.playing_channels(|c| c.system == system && c.int("index") == channel_id as i64)
+               .first() {
+            Some(&c) => {
+                write(channel, c as *mut FMOD_CHANNEL);
+                ::Status::Ok
+            }
+            None => ::Status::InvalidParam,
+        }
+    })
+}
+
+pub unsafe fn FMOD_System_GetMasterChannelGroup(system: *mut FMOD_SYSTEM,
+                                                channel_group: *mut *mut FMOD_CHANNELGROUP)
+                                                -> ::Status {
+    get_handle("FMOD_System_GetMasterChannelGroup", system as usize, Kind::System,
+               |o| o.int("master_channel_group") as usize, channel_group)
+}
+
+pub unsafe fn FMOD_System_GetMasterSoundGroup(system: *mut FMOD_SYSTEM,
+                                              sound_group: *mut *mut FMOD_SOUNDGROUP)
+                                              -> ::Status {
+    get_handle("FMOD_System_GetMasterSoundGroup", system as usize, Kind::System,
+               |o| o.int("master_sound_group") as usize, sound_group)
+}
+
+/// Opens and closes `name` with the file system and the observer of `system`, as FMOD does when
+/// it loads a sound. Nothing is read.
+unsafe fn open_file(system: usize, name: &str) -> ::Status {
+    let (file_system, observer) = with_state(|s| (s.file_systems.get(&system).cloned(),
+                                                  s.observers.get(&system).cloned()));
+    let name = CString::new(name).unwrap_or_else(|_| CString::new("").unwrap());
+    let mut file_size = 0;
+    let mut handle = ::std::ptr::null_mut();
+    let mut user_data = ::std::ptr::null_mut();
+    let mut observer_data = ::std::ptr::null_mut();
+
+    if let Some(FileCallbacks { open: Some(open), .. }) = file_system {
+        match open(name.as_ptr() as *mut c_char, 0, &mut file_size, &mut handle, &mut user_data) {
+            ::Status::Ok => {}
+            e => return e,
+        }
+    }
+    if let Some(FileCallbacks { open: Some(open), close }) = observer {
+        open(name.as_ptr() as *mut c_char, 0, &mut file_size, &mut handle, &mut observer_data);
+        if let Some(close) = close {
+            close(handle, observer_data);
+        }
+    }
+    if let Some(FileCallbacks { close: Some(close), .. }) = file_system {
+        close(handle, user_data);
+    }
+    ::Status::Ok
+}
+
+pub fn set_data(sound: &mut Object, data: Vec<u8>) {
+    sound.data = data;
+    sound.set("read_position", Value::Int(0));
+}
+
+pub fn get_data(sound: &Object) -> &[u8] {
+    &sound.data
+}
+
+extern "C" fn codec_file_read(handle: *mut c_void, buffer: *mut c_void, size_bytes: c_uint,
+                              bytes_read: *mut c_uint, _: *mut c_void) -> ::Status {
+    let file = unsafe { &mut *(handle as *mut CodecFile) };
+    let read = ::std::cmp::min(size_bytes as usize, file.data.len() - file.position);
+
+    unsafe {
+        ::std::ptr::copy_nonoverlapping(file.data[file.position..].as_ptr(), buffer as *mut u8,
+                                        read);
+        write(bytes_read, read as c_uint);
+    }
+    file.position += read;
+    if read < size_bytes as usize {
+        ::Status::FileEOF
+    } else {
+        ::Status::Ok
+    }
+}
+
+extern "C" fn codec_file_seek(handle: *mut c_void, pos: c_uint, _: *mut c_void) -> ::Status {
+    let file = unsafe { &mut *(handle as *mut CodecFile) };
+
+    if pos as usize > file.data.len() {
+        ::Status::FileCouldNotSeek
+    } else {
+        file.position = pos as usize;
+        ::Status::Ok
+    }
+}
+
+/// Tries the codecs of `system` by priority on the file `name`, if it has been given to
+/// `mock::set_file`. Returns None if it hasn't or if there is no codec, like FMOD falling back
+/// to its own codecs.
+unsafe fn open_with_codecs(system: usize, name: &str,
+                           mode: FMOD_MODE) -> Option<Result<Box<CodecSound>, ::Status>> {
+    let (data, mut codecs) = with_state(|s| {
+        (s.files.get(name).cloned(),
+         s.codecs.iter().filter(|c| c.0 == system).map(|&(_, p, c)| (p, c)).collect::<Vec<_>>())
+    });
+    let data = match data {
+        Some(d) => d,
+        None => return None,
+    };
+
+    if codecs.is_empty() {
+        return None;
+    }
+    codecs.sort_by_key(|&(priority, _)| priority);
+    for (_, callbacks) in codecs {
+        let size = data.len() as c_uint;
+        let mut codec_sound = Box::new(CodecSound {
+            state: FMOD_CODEC_STATE {
+                numsubsounds: 0,
+                waveformat: ::std::ptr::null_mut(),
+                plugindata: ::std::ptr::null_mut(),
+                filehandle: ::std::ptr::null_mut(),
+                filesize: size,
+                fileread: Some(codec_file_read),
+                fileseek: Some(codec_file_seek),
+                metadata: None,
+            },
+            file: CodecFile { data: data.clone(), position: 0 },
+            callbacks: callbacks,
+        });
+
+        codec_sound.state.filehandle = &mut codec_sound.file as *mut CodecFile as *mut c_void;
+        let status = match callbacks.open {
+            Some(open) => open(&mut codec_sound.state, mode, ::std::ptr::null_mut()),
+            None => ::Status::Format,
+        };
+        match status {
+            ::Status::Ok if !codec_sound.state.waveformat.is_null() => {
+                return Some(Ok(codec_sound));
+            }
+            ::Status::Ok => {
+                close_codec(codec_sound);
+                return Some(Err(::Status::Format));
+            }
+            ::Status::Format => {}
+            e => return Some(Err(e)),
+        }
+    }
+    Some(Err(::Status::Format))
+}
+
+unsafe fn close_codec(mut codec_sound: Box<CodecSound>) {
+    if let Some(close) = codec_sound.callbacks.close {
+        close(&mut codec_sound.state);
+    }
+}
+
+/// Decodes a whole sound with its codec, as FMOD does for samples.
+unsafe fn decode(codec_sound: &mut CodecSound) -> Result<Vec<u8>, ::Status> {
+    let read_callback = match codec_sound.callbacks.read {
+        Some(r) => r,
+        None => return Err(::Status::Unsupported),
+    };
+    let mut data = Vec::new();
+    let mut buffer = vec![0u8; 4096];
+
+    loop {
+        let mut read = 0 as c_uint;
+
+        match read_callback(&mut codec_sound.state, buffer.as_mut_ptr() as *mut c_void,
+                            buffer.len() as c_uint, &mut read) {
+            ::Status::Ok if read > 0 => data.extend_from_slice(&buffer[..read as usize]),
+            // FMOD keeps reading until the codec reports the end of the file.
+            ::Status::Ok => return Err(::Status::Internal),
+            ::Status::FileEOF => {
+                data.extend_from_slice(&buffer[..read as usize]);
+                return Ok(data);
+            }
+            e => return Err(e),
+        }
+    }
+}
+
+fn sound_format(format: i64) -> ::SoundFormat {
+    match format {
+        1 => ::SoundFormat::PCM8,
+        2 => ::SoundFormat::PCM16,
+        3 => ::SoundFormat::PCM24,
+        4 => ::SoundFormat::PCM32,
+        5 => ::SoundFormat::PCMFloat,
+        _ => ::SoundFormat::None,
+    }
+}
+
+fn format_bits(format: ::SoundFormat) -> i64 {
+    match format {
+        ::SoundFormat::PCM8 => 8,
+        ::SoundFormat::PCM16 => 16,
+        ::SoundFormat::PCM24 => 24,
+        ::SoundFormat::PCM32 | ::SoundFormat::PCMFloat => 32,
+        _ => 0,
+    }
+}
+
+/// Size in bytes of a PCM sample of all the channels of a sound.
+fn block_align(sound: &Object) -> i64 {
+    sound.int("channels") * format_bits(sound_format(sound.int("format"))) / 8
+}
+
+unsafe fn create_sound(function: &'static str, system: *mut FMOD_SYSTEM,
+                       name_or_data: *const c_char, mode: FMOD_MODE,
+                       sound: *mut *mut FMOD_SOUND) -> ::Status {
+    let system = system as usize;
+    let name = if mode & (::OPENMEMORY | ::OPENMEMORY_POINT | ::OPENUSER) != 0 {
+        String::new()
+    } else {
+        read_str(name_or_data)
+    };
+
+    record(function, system, vec![Value::Str(name.clone()), Value::Int(mode as i64)]);
+    if !name.is_empty() {
+        if mode & ::NONBLOCKING != 0 {
+            with_state(|s| s.pending_opens.push((system, name.clone())));
+        } else {
+            match open_file(system, &name) {
+                ::Status::Ok => {}
+                e => return e,
+            }
+        }
+    }
+    let stream = function == "FMOD_System_CreateStream" ||
+                 mode & (::CREATESTREAM | ::OPENONLY) != 0;
+    let mut codec_sound = match open_with_codecs(system as usize, &name, mode) {
+        Some(Ok(c)) => Some(c),
+        Some(Err(e)) => return e,
+        None => None,
+    };
+    let decoded = match codec_sound {
+        Some(ref mut c) if !stream => match decode(c) {
+            Ok(data) => data,
+            Err(e) => {
+                close_codec(codec_sound.take().unwrap());
+                return e;
+            }
+        },
+        _ => Vec::new(),
+    };
+    let handle = with_state(|s| {
+        let sound_group = match s.get(system, Kind::System) {
+            Ok(o) if o.bool("initialized") => o.int("master_sound_group") as usize,
+            Ok(_) => return Err(::Status::Uninitialized),
+            Err(e) => return Err(e),
+        };
+        let handle = s.create(Kind::Sound, system,
+                              vec![("name", Value::Str(name)),
+                                   ("mode", Value::Int(mode as i64)),
+                                   ("stream", Value::Bool(function == "FMOD_System_CreateStream")),
+                                   ("length", Value::Int(1000)),
+                                   ("frequency", Value::Float(44100f32)),
+                                   ("volume", Value::Float(1f32)),
+                                   ("pan", Value::Float(0f32)),
+                                   ("priority", Value::Int(128)),
+                                   ("loop_count", Value::Int(-1)),
+                                   ("min_distance", Value::Float(1f32)),
+                                   ("max_distance", Value::Float(10000f32)),
+                                   ("format", Value::Int(::SoundFormat::PCM16 as i64)),
+                                   ("channels", Value::Int(2))]);
+        let object = s.objects.get_mut(&handle).unwrap();
+
+        object.parent = sound_group;
+        if let Some(ref codec_sound) = codec_sound {
+            let wave_format = &*codec_sound.state.waveformat;
+            let length_ms = wave_format.lengthpcm as i64 * 1000 /
+                            wave_format.frequency.max(1) as i64;
+
+            object.set("format", Value::Int(wave_format.format as i64));
+            object.set("channels", Value::Int(wave_format.channels as i64));
+            object.set("frequency", Value::Float(wave_format.frequency as f32));
+            object.set("length", Value::Int(length_ms));
+            object.set("length_pcm", Value::Int(wave_format.lengthpcm as i64));
+            object.data = decoded;
+        }
+        Ok(handle)
+    });
+    // samples are decoded at once, streams keep their codec until they are released
+    match (codec_sound, handle) {
+        (Some(c), Ok(h)) if stream => {
+            with_state(|s| s.codec_sounds.insert(h, Box::into_raw(c) as usize));
+        }
+        (Some(c), _) => close_codec(c),
+        (None, _) => {}
+    }
+    match handle {
+        Ok(h) => {
+            write(sound, h as *mut FMOD_SOUND);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_System_CreateSound(system: *mut FMOD_SYSTEM, name_or_data: *const c_char,
+                                      mode: FMOD_MODE, _exinfo: *mut FMOD_CREATESOUNDEXINFO,
+                                      sound: *mut *mut FMOD_SOUND) -> ::Status {
+    create_sound("FMOD_System_CreateSound", system, name_or_data, mode, sound)
+}
+
+pub unsafe fn FMOD_System_CreateStream(system: *mut FMOD_SYSTEM, name_or_data: *const c_char,
+                                       mode: FMOD_MODE, _exinfo: *mut FMOD_CREATESOUNDEXINFO,
+                                       sound: *mut *mut FMOD_SOUND) -> ::Status {
+    create_sound("FMOD_System_CreateStream", system, name_or_data, mode, sound)
+}
+
+/// Only the open and close callbacks are used: sounds are opened and closed when they are
+/// created, or by the next `advance` for NONBLOCKING ones.
+pub unsafe fn FMOD_System_SetFileSystem(system: *mut FMOD_SYSTEM,
+                                        user_open: FMOD_FILE_OPENCALLBACK,
+                                        user_close: FMOD_FILE_CLOSECALLBACK,
+                                        _user_read: FMOD_FILE_READCALLBACK,
+                                        _user_seek: FMOD_FILE_SEEKCALLBACK,
+                                        _user_async_read: FMOD_FILE_ASYNCREADCALLBACK,
+                                        _user_async_cancel: FMOD_FILE_ASYNCCANCELCALLBACK,
+                                        block_align: c_int) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_SetFileSystem", system, vec![Value::Int(block_align as i64)]);
+    with_state(|s| match s.get(system, Kind::System) {
+        Ok(_) => {
+            s.file_systems.insert(system, FileCallbacks { open: user_open, close: user_close });
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_System_AttachFileSystem(system: *mut FMOD_SYSTEM,
+                                           user_open: FMOD_FILE_OPENCALLBACK,
+                                           user_close: FMOD_FILE_CLOSECALLBACK,
+                                           _user_read: FMOD_FILE_READCALLBACK,
+                                           _user_seek: FMOD_FILE_SEEKCALLBACK) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_AttachFileSystem", system, Vec::new());
+    with_state(|s| match s.get(system, Kind::System) {
+        Ok(_) => {
+            s.observers.insert(system, FileCallbacks { open: user_open, close: user_close });
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+/// The codec is tried on the files given to `mock::set_file`, see `open_with_codecs`.
+pub unsafe fn FMOD_System_RegisterCodec(system: *mut FMOD_SYSTEM,
+                                        description: *mut FMOD_CODEC_DESCRIPTION,
+                                        handle: *mut c_uint, priority: c_uint) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_RegisterCodec", system, vec![Value::Int(priority as i64)]);
+    if description.is_null() {
+        return ::Status::InvalidParam;
+    }
+    let callbacks = CodecCallbacks {
+        open: (*description).open,
+        close: (*description).close,
+        read: (*description).read,
+        set_position: (*description).setposition,
+    };
+
+    with_state(|s| match s.get(system, Kind::System) {
+        Ok(_) => {
+            s.codecs.push((system, priority, callbacks));
+            write(handle, s.codecs.len() as c_uint);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_System_CreateChannelGroup(system: *mut FMOD_SYSTEM, name: *const c_char,
+                                             channel_group: *mut *mut FMOD_CHANNELGROUP)
+                                             -> ::Status {
+    let system = system as usize;
+    let name = read_str(name);
+
+    record("FMOD_System_CreateChannelGroup", system, vec![Value::Str(name.clone())]);
+    let handle = with_state(|s| {
+        let master = match s.get(system, Kind::System) {
+            Ok(o) => o.int("master_channel_group") as usize,
+            Err(e) => return Err(e),
+        };
+        let handle = s.create(Kind::ChannelGroup, system,
+                              vec![("name", Value::Str(name)),
+                                   ("volume", Value::Float(1f32)),
+                                   ("pitch", Value::Float(1f32)),
+                                   ("paused", Value::Bool(false)),
+                                   ("mute", Value::Bool(false))]);
+
+        s.objects.get_mut(&handle).unwrap().parent = master;
+        Ok(handle)
+    });
+    match handle {
+        Ok(h) => {
+            write(channel_group, h as *mut FMOD_CHANNELGROUP);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_System_CreateSoundGroup(system: *mut FMOD_SYSTEM, name: *const c_char,
+                                           sound_group: *mut *mut FMOD_SOUNDGROUP) -> ::Status {
+    let system = system as usize;
+    let name = read_str(name);
+
+    record("FMOD_System_CreateSoundGroup", system, vec![Value::Str(name.clone())]);
+    let handle = with_state(|s| match s.get(system, Kind::System) {
+        Ok(_) => Ok(s.create(Kind::SoundGroup, system,
+                             vec![("name", Value::Str(name)),
+                                  ("max_audible", Value::Int(-1)),
+                                  ("volume", Value::Float(1f32)),
+                                  ("mute_fade_speed", Value::Float(0f32))])),
+        Err(e) => Err(e),
+    });
+    match handle {
+        Ok(h) => {
+            write(sound_group, h as *mut FMOD_SOUNDGROUP);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_System_CreateDSPByType(system: *mut FMOD_SYSTEM, _type: ::DspType,
+                                          dsp: *mut *mut FMOD_DSP) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_CreateDSPByType", system, vec![Value::Int(_type as i64)]);
+    let handle = with_state(|s| match s.get(system, Kind::System) {
+        Ok(_) => Ok(s.create_dsp(system, _type, format!("FMOD {:?}", _type))),
+        Err(e) => Err(e),
+    });
+    match handle {
+        Ok(h) => {
+            write(dsp, h as *mut FMOD_DSP);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_System_CreateDSP(system: *mut FMOD_SYSTEM,
+                                    description: *mut FMOD_DSP_DESCRIPTION,
+                                    dsp: *mut *mut FMOD_DSP) -> ::Status {
+    let system = system as usize;
+
+    if description.is_null() {
+        record("FMOD_System_CreateDSP", system, Vec::new());
+        return ::Status::InvalidParam;
+    }
+    let description = &*description;
+    let name = read_str(description.name.as_ptr());
+
+    record("FMOD_System_CreateDSP", system, vec![Value::Str(name.clone())]);
+    let handle = with_state(|s| match s.get(system, Kind::System) {
+        Ok(_) => {
+            let handle = s.create_dsp(system, ::DspType::Unknown, name);
+            let o = s.objects.get_mut(&handle).unwrap();
+
+            o.set("num_parameters", Value::Int(description.num_parameters as i64));
+            o.set("version", Value::Int(description.version as i64));
+            o.set("channels", Value::Int(description.channels as i64));
+            o.user_data = description.user_data as usize;
+            for i in 0..description.num_parameters.max(0) {
+                if description.param_desc.is_null() {
+                    break;
+                }
+                let desc = &*description.param_desc.offset(i as isize);
+
+                o.set(&format!("parameter{}.min", i), Value::Float(desc.min));
+                o.set(&format!("parameter{}.max", i), Value::Float(desc.max));
+                o.set(&format!("parameter{}.name", i), Value::Str(read_str(desc.name.as_ptr())));
+                o.set(&format!("parameter{}.label", i),
+                      Value::Str(read_str(desc.label.as_ptr())));
+            }
+            o.dsp_callbacks = Some(DspCallbacks {
+                release: description.release,
+                reset: description.reset,
+                set_parameter: description.set_parameter,
+                get_parameter: description.get_parameter,
+            });
+            Ok(handle)
+        }
+        Err(e) => Err(e),
+    });
+    match handle {
+        Ok(h) => {
+            if let Some(create) = description.create {
+                let status = call_dsp_callback(h, |state| create(state));
+
+                if status != ::Status::Ok {
+                    with_state(|s| s.objects.remove(&h));
+                    return status;
+                }
+            }
+            write(dsp, h as *mut FMOD_DSP);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+/// Calls a DSP callback with the state of `dsp`, keeping the plugin data it sets.
+fn call_dsp_callback<F: FnOnce(*mut FMOD_DSP_STATE) -> ::Status>(dsp: usize, f: F) -> ::Status {
+    let plugin_data = with_state(|s| s.objects.get(&dsp).map(|o| o.plugin_data).unwrap_or(0));
+    let mut state = FMOD_DSP_STATE {
+        instance: dsp as *mut FMOD_DSP,
+        plugin_data: plugin_data as *mut c_void,
+        speaker_mask: 0,
+    };
+    let status = f(&mut state);
+
+    with_state(|s| if let Some(o) = s.objects.get_mut(&dsp) {
+        o.plugin_data = state.plugin_data as usize;
+    });
+    status
+}
+
+pub unsafe fn FMOD_System_CreateReverb(system: *mut FMOD_SYSTEM,
+                                       reverb: *mut *mut FMOD_REVERB) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_CreateReverb", system, Vec::new());
+    match with_state(|s| s.get(system, Kind::System).map(|_| ()).map(|_| {
+        s.create(Kind::Reverb, system, vec![("active", Value::Bool(true))])
+    })) {
+        Ok(h) => {
+            write(reverb, h as *mut FMOD_REVERB);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_System_CreateGeometry(system: *mut FMOD_SYSTEM, max_polygons: c_int,
+                                         max_vertices: c_int,
+                                         geometry: *mut *mut FMOD_GEOMETRY) -> ::Status {
+    let system = system as usize;
+
+    record("FMOD_System_CreateGeometry", system,
+           vec![Value::Int(max_polygons as i64), Value::Int(max_vertices as i64)]);
+    match with_state(|s| s.get(system, Kind::System).map(|_| ()).map(|_| {
+        s.create(Kind::Geometry, system, vec![("max_polygons", Value::Int(max_polygons as i64)),
+                                              ("max_vertices", Value::Int(max_vertices as i64)),
+                                              ("active", Value::Bool(true)),
+                                              ("position", Value::Vector(0f32, 0f32, 0f32)),
+                                              ("forward", Value::Vector(0f32, 0f32, 1f32)),
+                                              ("up", Value::Vector(0f32, 1f32, 0f32)),
+                                              ("scale", Value::Vector(1f32, 1f32, 1f32))])
+    })) {
+        Ok(h) => {
+            write(geometry, h as *mut FMOD_GEOMETRY);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+/// Starts `source` (a sound or a DSP) on a channel: the given one with `ChannelIndex::ReUse`,
+/// which keeps its handle as FMOD does, otherwise a free one, stealing the least important
+/// voice if all are busy.
+unsafe fn play(function: &'static str, system: usize, channel_id: ::ChannelIndex, source: usize,
+               source_kind: Kind, paused: FMOD_BOOL, channel: *mut *mut FMOD_CHANNEL) -> ::Status {
+    let reuse = match channel_id {
+        ::ChannelIndex::ReUse if !channel.is_null() => *channel as usize,
+        _ => 0,
+    };
+
+    record(function, system, vec![Value::Handle(source), Value::Bool(paused != 0)]);
+    let result = with_state(|s| {
+        let (max_channels, master) = match s.get(system, Kind::System) {
+            Ok(o) if o.bool("initialized") => (o.int("max_channels"),
+                                               o.int("master_channel_group") as usize),
+            Ok(_) => return Err(::Status::Uninitialized),
+            Err(e) => return Err(e),
+        };
+        let defaults = match s.get(source, source_kind) {
+            Ok(o) if o.system == system => {
+                if source_kind == Kind::Sound {
+                    (o.float("volume"), o.float("frequency"), o.float("pan"), o.int("priority"),
+                     o.int("mode"), o.int("loop_count"))
+                } else {
+                    (1f32, 44100f32, 0f32, 128, 0, -1)
+                }
+            }
+            _ => return Err(::Status::InvalidHandle),
+        };
+        let mut ended = Vec::new();
+        let (index, reused) = match s.objects.get(&reuse) {
+            Some(o) if o.kind == Kind::Channel && o.system == system && o.bool("playing") => {
+                let index = o.int("index");
+
+                ended.extend(s.end_channel(reuse, false));
+                (index, true)
+            }
+            _ => {
+                let playing = s.playing_channels(|c| c.system == system);
+                let used: Vec<i64> = playing.iter().map(|c| s.objects[c].int("index")).collect();
+
+                (match (0..max_channels).find(|i| !used.contains(i)) {
+                    Some(i) => i,
+                    None => {
+                        // the highest priority value is the least important, then the oldest
+                        let stolen = match playing.iter()
+                                                  .max_by_key(|&&c| (s.objects[&c].int("priority"),
+                                                                     ::std::cmp::Reverse(c))) {
+                            Some(&c) => c,
+                            None => return Err(::Status::ChannelAlloc),
+                        };
+                        let index = s.objects[&stolen].int("index");
+
+                        ended.extend(s.end_channel(stolen, true));
+                        index
+                    }
+                }, false)
+            }
+        };
+        let mut object = Object::new(Kind::Channel, system);
+
+        object.parent = master;
+        object.source = source;
+        for (name, value) in vec![("index", Value::Int(index)),
+                                  ("playing", Value::Bool(true)),
+                                  ("stolen", Value::Bool(false)),
+                                  ("paused", Value::Bool(paused != 0)),
+                                  ("mute", Value::Bool(false)),
+                                  ("volume", Value::Float(defaults.0)),
+                                  ("frequency", Value::Float(defaults.1)),
+                                  ("pan", Value::Float(defaults.2)),
+                                  ("priority", Value::Int(defaults.3)),
+                                  ("mode", Value::Int(defaults.4)),
+                                  ("loop_count", Value::Int(defaults.5)),
+                                  ("position", Value::Int(0)),
+                                  ("min_distance", Value::Float(1f32)),
+                                  ("max_distance", Value::Float(10000f32)),
+                                  ("direct_occlusion", Value::Float(0f32)),
+                                  ("reverb_occlusion", Value::Float(0f32)),
+                                  ("low_pass_gain", Value::Float(1f32)),
+                                  ("pan_level", Value::Float(1f32)),
+                                  ("doppler_level", Value::Float(1f32)),
+                                  ("spread", Value::Float(0f32))] {
+            object.set(name, value);
+        }
+        if reused {
+            s.objects.insert(reuse, object);
+            Ok((reuse, ended))
+        } else {
+            Ok((s.add(object), ended))
+        }
+    });
+    match result {
+        Ok((handle, ended)) => {
+            call_end_callbacks(ended);
+            write(channel, handle as *mut FMOD_CHANNEL);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_System_PlaySound(system: *mut FMOD_SYSTEM, channel_id: ::ChannelIndex,
+                                    sound: *mut FMOD_SOUND, paused: FMOD_BOOL,
+                                    channel: *mut *mut FMOD_CHANNEL) -> ::Status {
+    play("FMOD_System_PlaySound", system as usize, channel_id, sound as usize, Kind::Sound,
+         paused, channel)
+}
+
+pub unsafe fn FMOD_System_PlayDSP(system: *mut FMOD_SYSTEM, channel_id: ::ChannelIndex,
+                                  dsp: *mut FMOD_DSP, paused: FMOD_BOOL,
+                                  channel: *mut *mut FMOD_CHANNEL) -> ::Status {
+    play("FMOD_System_PlayDSP", system as usize, channel_id, dsp as usize, Kind::Dsp, paused,
+         channel)
+}
+
+unsafe fn get_dsp_head(function: &'static str, object: usize, kind: Kind,
+                       dsp: *mut *mut FMOD_DSP) -> ::Status {
+    record(function, object, Vec::new());
+    match with_state(|s| s.get_dsp_head(object, kind)) {
+        Ok(h) => {
+            write(dsp, h as *mut FMOD_DSP);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+unsafe fn add_dsp(function: &'static str, object: usize, kind: Kind, dsp: *mut FMOD_DSP,
+                  connection: *mut *mut FMOD_DSPCONNECTION) -> ::Status {
+    record(function, object, vec![Value::Handle(dsp as usize)]);
+    match with_state(|s| match (s.get_dsp_head(object, kind), s.get(dsp as usize, Kind::Dsp)) {
+        (Ok(head), Ok(_)) if kind == Kind::System => {
+            // the system head has no output: the unit is inserted between it and its inputs
+            let inputs = s.objects[&head].inputs.clone();
+
+            for (input, connection) in inputs {
+                let mix = s.objects[&connection].float("mix");
+
+                s.disconnect(connection);
+                if let Ok(c) = s.connect(dsp as usize, input) {
+                    s.objects.get_mut(&c).unwrap().set("mix", Value::Float(mix));
+                }
+            }
+            s.connect(head, dsp as usize)
+        }
+        (Ok(head), Ok(_)) => {
+            // the unit is inserted between the head and its outputs, so the inputs connected to
+            // the head later go through it
+            let outputs = s.objects[&head].outputs.clone();
+
+            for (output, connection) in outputs {
+                let mix = s.objects[&connection].float("mix");
+
+                s.disconnect(connection);
+                if let Ok(c) = s.connect(output, dsp as usize) {
+                    s.objects.get_mut(&c).unwrap().set("mix", Value::Float(mix));
+                }
+            }
+            s.connect(dsp as usize, head)
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    }) {
+        Ok(c) => {
+            write(connection, c as *mut FMOD_DSPCONNECTION);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_System_GetDSPHead(system: *mut FMOD_SYSTEM, dsp: *mut *mut FMOD_DSP)
+                                     -> ::Status {
+    get_dsp_head("FMOD_System_GetDSPHead", system as usize, Kind::System, dsp)
+}
+
+pub unsafe fn FMOD_System_AddDSP(system: *mut FMOD_SYSTEM, dsp: *mut FMOD_DSP,
+                                 connection: *mut *mut FMOD_DSPCONNECTION) -> ::Status {
+    add_dsp("FMOD_System_AddDSP", system as usize, Kind::System, dsp, connection)
+}
+
+/* Sound */
+
+pub unsafe fn FMOD_Sound_Release(sound: *mut FMOD_SOUND) -> ::Status {
+    let sound = sound as usize;
+
+    record("FMOD_Sound_Release", sound, Vec::new());
+    let ended = with_state(|s| match s.get(sound, Kind::Sound) {
+        Ok(_) => {
+            let channels = s.playing_channels(|c| c.source == sound);
+            let ended = s.end_channels(channels);
+
+            s.objects.remove(&sound);
+            Ok((ended, s.codec_sounds.remove(&sound)))
+        }
+        Err(e) => Err(e),
+    });
+    match ended {
+        Ok((ended, codec_sound)) => {
+            if let Some(codec_sound) = codec_sound {
+                close_codec(Box::from_raw(codec_sound as *mut CodecSound));
+            }
+            call_end_callbacks(ended);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_Sound_GetSystemObject(sound: *mut FMOD_SOUND,
+                                         system: *mut *mut FMOD_SYSTEM) -> ::Status {
+    get_system::<FMOD_SOUND>("FMOD_Sound_GetSystemObject", sound as usize, Kind::Sound, system)
+}
+
+pub unsafe fn FMOD_Sound_SetDefaults(sound: *mut FMOD_SOUND, frequency: c_float, volume: c_float,
+                                     pan: c_float, priority: c_int) -> ::Status {
+    record("FMOD_Sound_SetDefaults", sound as usize,
+           vec![Value::Float(frequency), Value::Float(volume), Value::Float(pan),
+                Value::Int(priority as i64)]);
+    with_state(|s| status(s.get_mut(sound as usize, Kind::Sound).map(|o| {
+        o.set("frequency", Value::Float(frequency));
+        o.set("volume", Value::Float(volume));
+        o.set("pan", Value::Float(pan));
+        o.set("priority", Value::Int(priority as i64));
+    })))
+}
+
+pub unsafe fn FMOD_Sound_GetDefaults(sound: *mut FMOD_SOUND, frequency: *mut c_float,
+                                     volume: *mut c_float, pan: *mut c_float,
+                                     priority: *mut c_int) -> ::Status {
+    record("FMOD_Sound_GetDefaults", sound as usize, Vec::new());
+    with_state(|s| match s.get(sound as usize, Kind::Sound) {
+        Ok(o) => {
+            write(frequency, o.float("frequency"));
+            write(volume, o.float("volume"));
+            write(pan, o.float("pan"));
+            write(priority, o.int("priority") as c_int);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Sound_Set3DMinMaxDistance(sound: *mut FMOD_SOUND, min: c_float,
+                                             max: c_float) -> ::Status {
+    record("FMOD_Sound_Set3DMinMaxDistance", sound as usize,
+           vec![Value::Float(min), Value::Float(max)]);
+    with_state(|s| status(s.get_mut(sound as usize, Kind::Sound).map(|o| {
+        o.set("min_distance", Value::Float(min));
+        o.set("max_distance", Value::Float(max));
+    })))
+}
+
+pub unsafe fn FMOD_Sound_Get3DMinMaxDistance(sound: *mut FMOD_SOUND, min: *mut c_float,
+                                             max: *mut c_float) -> ::Status {
+    record("FMOD_Sound_Get3DMinMaxDistance", sound as usize, Vec::new());
+    with_state(|s| match s.get(sound as usize, Kind::Sound) {
+        Ok(o) => {
+            write(min, o.float("min_distance"));
+            write(max, o.float("max_distance"));
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Sound_GetName(sound: *mut FMOD_SOUND, name: *mut c_char,
+                                 name_len: c_int) -> ::Status {
+    get_name("FMOD_Sound_GetName", sound as usize, Kind::Sound, name, name_len)
+}
+
+pub unsafe fn FMOD_Sound_GetLength(sound: *mut FMOD_SOUND, length: *mut c_uint,
+                                   length_type: FMOD_TIMEUNIT) -> ::Status {
+    record("FMOD_Sound_GetLength", sound as usize, vec![Value::Int(length_type as i64)]);
+    let lengths = with_state(|s| s.get(sound as usize, Kind::Sound).map(|o| {
+        // sounds with data or decoded by a codec have an exact length in samples
+        let pcm = if o.values.contains_key("length_pcm") {
+            Some(o.int("length_pcm"))
+        } else if !o.data.is_empty() && block_align(o) > 0 {
+            Some(o.data.len() as i64 / block_align(o))
+        } else {
+            None
+        };
+
+        (o.int("length"), o.float("frequency"), pcm, block_align(o))
+    }));
+    match lengths {
+        Ok((_, frequency, Some(pcm), block_align)) => {
+            match length_type {
+                1 => write(length, (pcm * 1000 / frequency.max(1f32) as i64) as c_uint),
+                2 => write(length, pcm as c_uint),
+                4 => write(length, (pcm * block_align) as c_uint),
+                _ => return ::Status::Format,
+            }
+            ::Status::Ok
+        }
+        Ok((ms, frequency, None, _)) => match from_ms(ms, length_type, frequency) {
+            Ok(l) => {
+                write(length, l);
+                ::Status::Ok
+            }
+            Err(e) => e,
+        },
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_Sound_GetFormat(sound: *mut FMOD_SOUND, _type: *mut ::SoundType,
+                                   format: *mut ::SoundFormat, channels: *mut c_int,
+                                   bits: *mut c_int) -> ::Status {
+    record("FMOD_Sound_GetFormat", sound as usize, Vec::new());
+    match with_state(|s| s.get(sound as usize, Kind::Sound).map(|o| (o.int("format"),
+                                                                      o.int("channels")))) {
+        Ok((f, c)) => {
+            write(_type, ::SoundType::Unknown);
+            write(format, sound_format(f));
+            write(channels, c as c_int);
+            write(bits, format_bits(sound_format(f)) as c_int);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+/// Reads the data given to `mock::set_data`, or decodes a stream with its codec.
+pub unsafe fn FMOD_Sound_ReadData(sound: *mut FMOD_SOUND, buffer: *mut c_void, len_bytes: c_uint,
+                                  read: *mut c_uint) -> ::Status {
+    let sound = sound as usize;
+
+    record("FMOD_Sound_ReadData", sound, vec![Value::Int(len_bytes as i64)]);
+    write(read, 0);
+    let codec_sound = with_state(|s| {
+        let codec_sound = s.codec_sounds.get(&sound).cloned();
+
+        match s.get_mut(sound, Kind::Sound) {
+            Ok(_) if codec_sound.is_some() => Ok(Ok(codec_sound.unwrap())),
+            Ok(o) => {
+                let position = o.int("read_position") as usize;
+                let count = ::std::cmp::min(len_bytes as usize,
+                                            o.data.len().saturating_sub(position));
+
+                if count > 0 {
+                    ::std::ptr::copy_nonoverlapping(o.data[position..].as_ptr(),
+                                                    buffer as *mut u8, count);
+                }
+                write(read, count as c_uint);
+                o.set("read_position", Value::Int((position + count) as i64));
+                Ok(Err(count))
+            }
+            Err(e) => Err(e),
+        }
+    });
+    match codec_sound {
+        // the state isn't locked while the codec runs
+        Ok(Ok(codec_sound)) => {
+            let codec_sound = &mut *(codec_sound as *mut CodecSound);
+
+            match codec_sound.callbacks.read {
+                Some(f) => f(&mut codec_sound.state, buffer, len_bytes, read),
+                None => ::Status::Unsupported,
+            }
+        }
+        Ok(Err(count)) if count < len_bytes as usize => ::Status::FileEOF,
+        Ok(Err(_)) => ::Status::Ok,
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_Sound_SeekData(sound: *mut FMOD_SOUND, pcm: c_uint) -> ::Status {
+    let sound = sound as usize;
+
+    record("FMOD_Sound_SeekData", sound, vec![Value::Int(pcm as i64)]);
+    let codec_sound = with_state(|s| {
+        let codec_sound = s.codec_sounds.get(&sound).cloned();
+
+        match s.get_mut(sound, Kind::Sound) {
+            Ok(_) if codec_sound.is_some() => Ok(codec_sound),
+            Ok(o) => {
+                let position = pcm as i64 * block_align(o);
+
+                if position as usize > o.data.len() {
+                    return Err(::Status::InvalidPosition);
+                }
+                o.set("read_position", Value::Int(position));
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    });
+    match codec_sound {
+        Ok(Some(codec_sound)) => {
+            let codec_sound = &mut *(codec_sound as *mut CodecSound);
+
+            match codec_sound.callbacks.set_position {
+                Some(f) => f(&mut codec_sound.state, 0, pcm, 2),
+                None => ::Status::Unsupported,
+            }
+        }
+        Ok(None) => ::Status::Ok,
+        Err(e) => e,
+    }
+}
+
+/// Locks the data given to `mock::set_data`, which is never split in two parts. Streams can't be
+/// locked.
+pub unsafe fn FMOD_Sound_Lock(sound: *mut FMOD_SOUND, offset: c_uint, length: c_uint,
+                              ptr1: *mut *mut c_void, ptr2: *mut *mut c_void, len1: *mut c_uint,
+                              len2: *mut c_uint) -> ::Status {
+    let sound = sound as usize;
+
+    record("FMOD_Sound_Lock", sound, vec![Value::Int(offset as i64), Value::Int(length as i64)]);
+    write(ptr1, ::std::ptr::null_mut());
+    write(ptr2, ::std::ptr::null_mut());
+    write(len1, 0);
+    write(len2, 0);
+    with_state(|s| {
+        let stream = s.codec_sounds.contains_key(&sound);
+
+        match s.get_mut(sound, Kind::Sound) {
+            Ok(o) if stream || o.int("mode") & ::CREATESTREAM as i64 != 0 => ::Status::BadCommand,
+            Ok(o) if offset as usize > o.data.len() => ::Status::InvalidParam,
+            Ok(o) => {
+                let count = ::std::cmp::min(length as usize, o.data.len() - offset as usize);
+
+                write(ptr1, o.data.as_mut_ptr().offset(offset as isize) as *mut c_void);
+                write(len1, count as c_uint);
+                ::Status::Ok
+            }
+            Err(e) => e,
+        }
+    })
+}
+
+pub unsafe fn FMOD_Sound_Unlock(sound: *mut FMOD_SOUND, _ptr1: *mut c_void, _ptr2: *mut c_void,
+                                len1: c_uint, len2: c_uint) -> ::Status {
+    record("FMOD_Sound_Unlock", sound as usize,
+           vec![Value::Int(len1 as i64), Value::Int(len2 as i64)]);
+    with_state(|s| status(s.get(sound as usize, Kind::Sound).map(|_| ())))
+}
+
+pub unsafe fn FMOD_Sound_SetMode(sound: *mut FMOD_SOUND, mode: FMOD_MODE) -> ::Status {
+    set_value("FMOD_Sound_SetMode", sound as usize, Kind::Sound, "mode", Value::Int(mode as i64))
+}
+
+pub unsafe fn FMOD_Sound_GetMode(sound: *mut FMOD_SOUND, mode: *mut FMOD_MODE) -> ::Status {
+    match get_value("FMOD_Sound_GetMode", sound as usize, Kind::Sound, "mode") {
+        Ok(v) => {
+            write(mode, v.as_int().unwrap_or(0) as FMOD_MODE);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_Sound_SetLoopCount(sound: *mut FMOD_SOUND, loop_count: c_int) -> ::Status {
+    set_value("FMOD_Sound_SetLoopCount", sound as usize, Kind::Sound, "loop_count",
+              Value::Int(loop_count as i64))
+}
+
+pub unsafe fn FMOD_Sound_GetLoopCount(sound: *mut FMOD_SOUND, loop_count: *mut c_int) -> ::Status {
+    get_int("FMOD_Sound_GetLoopCount", sound as usize, Kind::Sound, "loop_count", loop_count)
+}
+
+pub unsafe fn FMOD_Sound_SetSoundGroup(sound: *mut FMOD_SOUND,
+                                       sound_group: *mut FMOD_SOUNDGROUP) -> ::Status {
+    record("FMOD_Sound_SetSoundGroup", sound as usize,
+           vec![Value::Handle(sound_group as usize)]);
+    with_state(|s| {
+        if let Err(e) = s.get(sound_group as usize, Kind::SoundGroup) {
+            return e;
+        }
+        status(s.get_mut(sound as usize, Kind::Sound).map(|o| o.parent = sound_group as usize))
+    })
+}
+
+pub unsafe fn FMOD_Sound_GetSoundGroup(sound: *mut FMOD_SOUND,
+                                       sound_group: *mut *mut FMOD_SOUNDGROUP) -> ::Status {
+    get_handle("FMOD_Sound_GetSoundGroup", sound as usize, Kind::Sound, |o| o.parent,
+               sound_group)
+}
+
+pub unsafe fn FMOD_Sound_SetUserData(sound: *mut FMOD_SOUND, user_data: *mut c_void) -> ::Status {
+    set_user_data("FMOD_Sound_SetUserData", sound as usize, Kind::Sound, user_data)
+}
+
+pub unsafe fn FMOD_Sound_GetUserData(sound: *mut FMOD_SOUND,
+                                     user_data: *mut *mut c_void) -> ::Status {
+    get_user_data("FMOD_Sound_GetUserData", sound as usize, Kind::Sound, user_data)
+}
+
+/* Channel */
+
+pub unsafe fn FMOD_Channel_GetSystemObject(channel: *mut FMOD_CHANNEL,
+                                           system: *mut *mut FMOD_SYSTEM) -> ::Status {
+    get_system::<FMOD_CHANNEL>("FMOD_Channel_GetSystemObject", channel as usize, Kind::Channel,
+                               system)
+}
+
+pub unsafe fn FMOD_Channel_Stop(channel: *mut FMOD_CHANNEL) -> ::Status {
+    let channel = channel as usize;
+
+    record("FMOD_Channel_Stop", channel, Vec::new());
+    match with_state(|s| s.live(channel, Kind::Channel).map(|_| ()).map(|_| {
+        s.end_channel(channel, false)
+    })) {
+        Ok(ended) => {
+            call_end_callbacks(ended.into_iter().collect());
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+macro_rules! float_property {
+    ($kind:ident, $object:ident: $t:ty, $set:ident, $get:ident, $name:expr) => {
+        pub unsafe fn $set($object: *mut $t, value: c_float) -> ::Status {
+            set_value(stringify!($set), $object as usize, Kind::$kind, $name, Value::Float(value))
+        }
+
+        pub unsafe fn $get($object: *mut $t, value: *mut c_float) -> ::Status {
+            get_float(stringify!($get), $object as usize, Kind::$kind, $name, value)
+        }
+    }
+}
+
+macro_rules! bool_property {
+    ($kind:ident, $object:ident: $t:ty, $set:ident, $get:ident, $name:expr) => {
+        pub unsafe fn $set($object: *mut $t, value: FMOD_BOOL) -> ::Status {
+            set_value(stringify!($set), $object as usize, Kind::$kind, $name,
+                      Value::Bool(value != 0))
+        }
+
+        pub unsafe fn $get($object: *mut $t, value: *mut FMOD_BOOL) -> ::Status {
+            get_bool(stringify!($get), $object as usize, Kind::$kind, $name, value)
+        }
+    }
+}
+
+macro_rules! int_property {
+    ($kind:ident, $object:ident: $t:ty, $set:ident, $get:ident, $name:expr) => {
+        pub unsafe fn $set($object: *mut $t, value: c_int) -> ::Status {
+            set_value(stringify!($set), $object as usize, Kind::$kind, $name,
+                      Value::Int(value as i64))
+        }
+
+        pub unsafe fn $get($object: *mut $t, value: *mut c_int) -> ::Status {
+            get_int(stringify!($get), $object as usize, Kind::$kind, $name, value)
+        }
+    }
+}
+
+bool_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_SetPaused, FMOD_Channel_GetPaused,
+               "paused");
+float_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_SetVolume, FMOD_Channel_GetVolume,
+                "volume");
+float_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_SetFrequency,
+                FMOD_Channel_GetFrequency, "frequency");
+float_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_SetPan, FMOD_Channel_GetPan, "pan");
+bool_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_SetMute, FMOD_Channel_GetMute, "mute");
+int_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_SetPriority, FMOD_Channel_GetPriority,
+              "priority");
+float_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_SetLowPassGain,
+                FMOD_Channel_GetLowPassGain, "low_pass_gain");
+float_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_Set3DSpread,
+                FMOD_Channel_Get3DSpread, "spread");
+float_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_Set3DPanLevel,
+                FMOD_Channel_Get3DPanLevel, "pan_level");
+float_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_Set3DDopplerLevel,
+                FMOD_Channel_Get3DDopplerLevel, "doppler_level");
+int_property!(Channel, channel: FMOD_CHANNEL, FMOD_Channel_SetLoopCount,
+              FMOD_Channel_GetLoopCount, "loop_count");
+
+pub unsafe fn FMOD_Channel_SetPosition(channel: *mut FMOD_CHANNEL, position: c_uint,
+                                       postype: FMOD_TIMEUNIT) -> ::Status {
+    record("FMOD_Channel_SetPosition", channel as usize,
+           vec![Value::Int(position as i64), Value::Int(postype as i64)]);
+    with_state(|s| match s.live_mut(channel as usize, Kind::Channel) {
+        Ok(o) => match to_ms(position, postype, o.float("frequency")) {
+            Ok(ms) => {
+                o.set("position", Value::Int(ms));
+                ::Status::Ok
+            }
+            Err(e) => e,
+        },
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Channel_GetPosition(channel: *mut FMOD_CHANNEL, position: *mut c_uint,
+                                       postype: FMOD_TIMEUNIT) -> ::Status {
+    record("FMOD_Channel_GetPosition", channel as usize, vec![Value::Int(postype as i64)]);
+    match with_state(|s| s.live(channel as usize, Kind::Channel)
+                          .map(|o| (o.int("position"), o.float("frequency")))) {
+        Ok((ms, frequency)) => match from_ms(ms, postype, frequency) {
+            Ok(p) => {
+                write(position, p);
+                ::Status::Ok
+            }
+            Err(e) => e,
+        },
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_Channel_SetMode(channel: *mut FMOD_CHANNEL, mode: FMOD_MODE) -> ::Status {
+    set_value("FMOD_Channel_SetMode", channel as usize, Kind::Channel, "mode",
+              Value::Int(mode as i64))
+}
+
+pub unsafe fn FMOD_Channel_GetMode(channel: *mut FMOD_CHANNEL, mode: *mut FMOD_MODE) -> ::Status {
+    match get_value("FMOD_Channel_GetMode", channel as usize, Kind::Channel, "mode") {
+        Ok(v) => {
+            write(mode, v.as_int().unwrap_or(0) as FMOD_MODE);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_Channel_SetChannelGroup(channel: *mut FMOD_CHANNEL,
+                                           channelgroup: *mut FMOD_CHANNELGROUP) -> ::Status {
+    record("FMOD_Channel_SetChannelGroup", channel as usize,
+           vec![Value::Handle(channelgroup as usize)]);
+    with_state(|s| {
+        if let Err(e) = s.get(channelgroup as usize, Kind::ChannelGroup) {
+            return e;
+        }
+        match s.live(channel as usize, Kind::Channel) {
+            Ok(_) => {
+                s.set_parent(channel as usize, channelgroup as usize);
+                ::Status::Ok
+            }
+            Err(e) => e,
+        }
+    })
+}
+
+pub unsafe fn FMOD_Channel_GetChannelGroup(channel: *mut FMOD_CHANNEL,
+                                           channelgroup: *mut *mut FMOD_CHANNELGROUP)
+                                           -> ::Status {
+    get_handle("FMOD_Channel_GetChannelGroup", channel as usize, Kind::Channel, |o| o.parent,
+               channelgroup)
+}
+
+pub unsafe fn FMOD_Channel_SetCallback(channel: *mut FMOD_CHANNEL,
+                                       callback: FMOD_CHANNEL_CALLBACK) -> ::Status {
+    record("FMOD_Channel_SetCallback", channel as usize, Vec::new());
+    with_state(|s| status(s.live_mut(channel as usize, Kind::Channel)
+                           .map(|o| o.channel_callback = callback)))
+}
+
+pub unsafe fn FMOD_Channel_Set3DAttributes(channel: *mut FMOD_CHANNEL,
+                                           position: *mut FMOD_VECTOR,
+                                           velociy: *mut FMOD_VECTOR) -> ::Status {
+    let position = read_vector(position);
+    let velocity = read_vector(velociy);
+
+    record("FMOD_Channel_Set3DAttributes", channel as usize,
+           vec![position.clone().unwrap_or(Value::Int(0)),
+                velocity.clone().unwrap_or(Value::Int(0))]);
+    with_state(|s| status(s.live_mut(channel as usize, Kind::Channel).map(|o| {
+        if let Some(position) = position {
+            o.set("position3d", position);
+        }
+        if let Some(velocity) = velocity {
+            o.set("velocity3d", velocity);
+        }
+    })))
+}
+
+pub unsafe fn FMOD_Channel_Get3DAttributes(channel: *mut FMOD_CHANNEL,
+                                           position: *mut FMOD_VECTOR,
+                                           velociy: *mut FMOD_VECTOR) -> ::Status {
+    record("FMOD_Channel_Get3DAttributes", channel as usize, Vec::new());
+    with_state(|s| match s.live(channel as usize, Kind::Channel) {
+        Ok(o) => {
+            write_vector(position, o.values.get("position3d"));
+            write_vector(velociy, o.values.get("velocity3d"));
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Channel_Set3DMinMaxDistance(channel: *mut FMOD_CHANNEL, min_distance: c_float,
+                                               max_distance: c_float) -> ::Status {
+    record("FMOD_Channel_Set3DMinMaxDistance", channel as usize,
+           vec![Value::Float(min_distance), Value::Float(max_distance)]);
+    with_state(|s| status(s.live_mut(channel as usize, Kind::Channel).map(|o| {
+        o.set("min_distance", Value::Float(min_distance));
+        o.set("max_distance", Value::Float(max_distance));
+    })))
+}
+
+pub unsafe fn FMOD_Channel_Get3DMinMaxDistance(channel: *mut FMOD_CHANNEL,
+                                               min_distance: *mut c_float,
+                                               max_distance: *mut c_float) -> ::Status {
+    record("FMOD_Channel_Get3DMinMaxDistance", channel as usize, Vec::new());
+    with_state(|s| match s.live(channel as usize, Kind::Channel) {
+        Ok(o) => {
+            write(min_distance, o.float("min_distance"));
+            write(max_distance, o.float("max_distance"));
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Channel_Set3DOcclusion(channel: *mut FMOD_CHANNEL, direct_occlusion: c_float,
+                                          reverb_occlusion: c_float) -> ::Status {
+    record("FMOD_Channel_Set3DOcclusion", channel as usize,
+           vec![Value::Float(direct_occlusion), Value::Float(reverb_occlusion)]);
+    with_state(|s| status(s.live_mut(channel as usize, Kind::Channel).map(|o| {
+        o.set("direct_occlusion", Value::Float(direct_occlusion));
+        o.set("reverb_occlusion", Value::Float(reverb_occlusion));
+    })))
+}
+
+pub unsafe fn FMOD_Channel_Get3DOcclusion(channel: *mut FMOD_CHANNEL,
+                                          direct_occlusion: *mut c_float,
+                                          reverb_occlusion: *mut c_float) -> ::Status {
+    record("FMOD_Channel_Get3DOcclusion", channel as usize, Vec::new());
+    with_state(|s| match s.live(channel as usize, Kind::Channel) {
+        Ok(o) => {
+            write(direct_occlusion, o.float("direct_occlusion"));
+            write(reverb_occlusion, o.float("reverb_occlusion"));
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Channel_IsPlaying(channel: *mut FMOD_CHANNEL,
+                                     is_playing: *mut FMOD_BOOL) -> ::Status {
+    // ended channels still answer, so playback can be polled until the end
+    record("FMOD_Channel_IsPlaying", channel as usize, Vec::new());
+    with_state(|s| match s.get(channel as usize, Kind::Channel) {
+        Ok(o) if o.bool("stolen") => ::Status::ChannelStolen,
+        Ok(o) => {
+            write(is_playing, o.bool("playing") as FMOD_BOOL);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Channel_IsVirtual(channel: *mut FMOD_CHANNEL,
+                                     is_virtual: *mut FMOD_BOOL) -> ::Status {
+    record("FMOD_Channel_IsVirtual", channel as usize, Vec::new());
+    with_state(|s| status(s.live(channel as usize, Kind::Channel).map(|_| write(is_virtual, 0))))
+}
+
+pub unsafe fn FMOD_Channel_GetCurrentSound(channel: *mut FMOD_CHANNEL,
+                                           sound: *mut *mut FMOD_SOUND) -> ::Status {
+    get_handle("FMOD_Channel_GetCurrentSound", channel as usize, Kind::Channel, |o| o.source,
+               sound)
+}
+
+pub unsafe fn FMOD_Channel_GetIndex(channel: *mut FMOD_CHANNEL, index: *mut c_int) -> ::Status {
+    get_int("FMOD_Channel_GetIndex", channel as usize, Kind::Channel, "index", index)
+}
+
+pub unsafe fn FMOD_Channel_GetDSPHead(channel: *mut FMOD_CHANNEL,
+                                      dsp: *mut *mut FMOD_DSP) -> ::Status {
+    get_dsp_head("FMOD_Channel_GetDSPHead", channel as usize, Kind::Channel, dsp)
+}
+
+pub unsafe fn FMOD_Channel_AddDSP(channel: *mut FMOD_CHANNEL, dsp: *mut FMOD_DSP,
+                                  connection: *mut *mut FMOD_DSPCONNECTION) -> ::Status {
+    add_dsp("FMOD_Channel_AddDSP", channel as usize, Kind::Channel, dsp, connection)
+}
+
+pub unsafe fn FMOD_Channel_SetUserData(channel: *mut FMOD_CHANNEL,
+                                       user_data: *mut c_void) -> ::Status {
+    set_user_data("FMOD_Channel_SetUserData", channel as usize, Kind::Channel, user_data)
+}
+
+pub unsafe fn FMOD_Channel_GetUserData(channel: *mut FMOD_CHANNEL,
+                                       user_data: *mut *mut c_void) -> ::Status {
+    get_user_data("FMOD_Channel_GetUserData", channel as usize, Kind::Channel, user_data)
+}
+
+/* ChannelGroup */
+
+pub unsafe fn FMOD_ChannelGroup_Release(channel_group: *mut FMOD_CHANNELGROUP) -> ::Status {
+    let group = channel_group as usize;
+
+    record("FMOD_ChannelGroup_Release", group, Vec::new());
+    with_state(|s| {
+        let (system, parent) = match s.get(group, Kind::ChannelGroup) {
+            Ok(o) => (o.system, o.parent),
+            Err(e) => return e,
+        };
+        let master = s.objects.get(&system).map(|o| o.int("master_channel_group") as usize);
+
+        if master == Some(group) {
+            return ::Status::InvalidHandle;
+        }
+        // channels and subgroups go to the parent group
+        let children : Vec<usize> = s.objects.iter().filter(|&(_, o)| {
+            o.parent == group && (o.kind == Kind::Channel || o.kind == Kind::ChannelGroup)
+        }).map(|(&h, _)| h).collect();
+
+        for child in children {
+            s.set_parent(child, parent);
+        }
+        if let Some(head) = s.objects.remove(&group).map(|o| o.dsp_head) {
+            let links : Vec<usize> = s.objects.get(&head).map(|o| {
+                o.inputs.iter().chain(o.outputs.iter()).map(|&(_, c)| c).collect()
+            }).unwrap_or(Vec::new());
+
+            for c in links {
+                s.disconnect(c);
+            }
+        }
+        ::Status::Ok
+    })
+}
+
+pub unsafe fn FMOD_ChannelGroup_GetSystemObject(channel_group: *mut FMOD_CHANNELGROUP,
+                                                system: *mut *mut FMOD_SYSTEM) -> ::Status {
+    get_system::<FMOD_CHANNELGROUP>("FMOD_ChannelGroup_GetSystemObject", channel_group as usize,
+                                    Kind::ChannelGroup, system)
+}
+
+float_property!(ChannelGroup, channel_group: FMOD_CHANNELGROUP, FMOD_ChannelGroup_SetVolume,
+                FMOD_ChannelGroup_GetVolume, "volume");
+float_property!(ChannelGroup, channel_group: FMOD_CHANNELGROUP, FMOD_ChannelGroup_SetPitch,
+                FMOD_ChannelGroup_GetPitch, "pitch");
+bool_property!(ChannelGroup, channel_group: FMOD_CHANNELGROUP, FMOD_ChannelGroup_SetPaused,
+               FMOD_ChannelGroup_GetPaused, "paused");
+bool_property!(ChannelGroup, channel_group: FMOD_CHANNELGROUP, FMOD_ChannelGroup_SetMute,
+               FMOD_ChannelGroup_GetMute, "mute");
+
+pub unsafe fn FMOD_ChannelGroup_Stop(channel_group: *mut FMOD_CHANNELGROUP) -> ::Status {
+    let group = channel_group as usize;
+
+    record("FMOD_ChannelGroup_Stop", group, Vec::new());
+    match with_state(|s| match s.get(group, Kind::ChannelGroup) {
+        Ok(_) => {
+            let groups = s.group_tree(group);
+            let channels = s.playing_channels(|c| groups.contains(&c.parent));
+
+            Ok(s.end_channels(channels))
+        }
+        Err(e) => Err(e),
+    }) {
+        Ok(ended) => {
+            call_end_callbacks(ended);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_ChannelGroup_AddGroup(channel_group: *mut FMOD_CHANNELGROUP,
+                                         group: *mut FMOD_CHANNELGROUP) -> ::Status {
+    let parent = channel_group as usize;
+    let group = group as usize;
+
+    record("FMOD_ChannelGroup_AddGroup", parent, vec![Value::Handle(group)]);
+    with_state(|s| {
+        if let Err(e) = s.get(parent, Kind::ChannelGroup) {
+            return e;
+        }
+        // a group can't be added below itself
+        if s.group_tree(group).contains(&parent) {
+            return ::Status::InvalidParam;
+        }
+        match s.get(group, Kind::ChannelGroup) {
+            Ok(_) => {
+                s.set_parent(group, parent);
+                ::Status::Ok
+            }
+            Err(e) => e,
+        }
+    })
+}
+
+fn child_groups(s: &State, group: usize) -> Vec<usize> {
+    let mut groups: Vec<usize> = s.objects.iter()
+                                  .filter(|&(_, o)| o.kind == Kind::ChannelGroup &&
+                                                    o.parent == group)
+                                  .map(|(&h, _)| h)
+                                  .collect();
+
+    groups.sort();
+    groups
+}
+
+pub unsafe fn FMOD_ChannelGroup_GetNumGroups(channel_group: *mut FMOD_CHANNELGROUP,
+                                             num_groups: *mut c_int) -> ::Status {
+    let group = channel_group as usize;
+
+    record("FMOD_ChannelGroup_GetNumGroups", group, Vec::new());
+    with_state(|s| match s.get(group, Kind::ChannelGroup) {
+        Ok(_) => {
+            write(num_groups, child_groups(s, group).len() as c_int);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_ChannelGroup_GetGroup(channel_group: *mut FMOD_CHANNELGROUP, index: c_int,
+                                         group: *mut *mut FMOD_CHANNELGROUP) -> ::Status {
+    let parent = channel_group as usize;
+
+    record("FMOD_ChannelGroup_GetGroup", parent, vec![Value::Int(index as i64)]);
+    with_state(|s| match s.get(parent, Kind::ChannelGroup) {
+        Ok(_) => match child_groups(s, parent).get(index as usize) {
+            Some(&g) if index >= 0 => {
+                write(group, g as *mut FMOD_CHANNELGROUP);
+                ::Status::Ok
+            }
+            _ => ::Status::InvalidParam,
+        },
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_ChannelGroup_GetParentGroup(channel_group: *mut FMOD_CHANNELGROUP,
+                                               group: *mut *mut FMOD_CHANNELGROUP) -> ::Status {
+    get_handle("FMOD_ChannelGroup_GetParentGroup", channel_group as usize, Kind::ChannelGroup,
+               |o| o.parent, group)
+}
+
+pub unsafe fn FMOD_ChannelGroup_GetDSPHead(channel_group: *mut FMOD_CHANNELGROUP,
+                                           dsp: *mut *mut FMOD_DSP) -> ::Status {
+    get_dsp_head("FMOD_ChannelGroup_GetDSPHead", channel_group as usize, Kind::ChannelGroup, dsp)
+}
+
+pub unsafe fn FMOD_ChannelGroup_AddDSP(channel_group: *mut FMOD_CHANNELGROUP, dsp: *mut FMOD_DSP,
+                                       disp_connection: *mut *mut FMOD_DSPCONNECTION)
+                                       -> ::Status {
+    add_dsp("FMOD_ChannelGroup_AddDSP", channel_group as usize, Kind::ChannelGroup, dsp,
+            disp_connection)
+}
+
+pub unsafe fn FMOD_ChannelGroup_GetName(channel_group: *mut FMOD_CHANNELGROUP,
+                                        name: *mut c_char, name_len: c_int) -> ::Status {
+    get_name("FMOD_ChannelGroup_GetName", channel_group as usize, Kind::ChannelGroup, name,
+             name_len)
+}
+
+pub unsafe fn FMOD_ChannelGroup_GetNumChannels(channel_group: *mut FMOD_CHANNELGROUP,
+                                               num_channels: *mut c_int) -> ::Status {
+    let group = channel_group as usize;
+
+    record("FMOD_ChannelGroup_GetNumChannels", group, Vec::new());
+    with_state(|s| match s.get(group, Kind::ChannelGroup) {
+        Ok(_) => {
+            write(num_channels, s.playing_channels(|c| c.parent == group).len() as c_int);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_ChannelGroup_GetChannel(channel_group: *mut FMOD_CHANNELGROUP, index: c_int,
+                                           channel: *mut *mut FMOD_CHANNEL) -> ::Status {
+    let group = channel_group as usize;
+
+    record("FMOD_ChannelGroup_GetChannel", group, vec![Value::Int(index as i64)]);
+    with_state(|s| match s.get(group, Kind::ChannelGroup) {
+        Ok(_) => match s.playing_channels(|c| c.parent == group).get(index as usize) {
+            Some(&c) if index >= 0 => {
+                write(channel, c as *mut FMOD_CHANNEL);
+                ::Status::Ok
+            }
+            _ => ::Status::InvalidParam,
+        },
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_ChannelGroup_SetUserData(channel_group: *mut FMOD_CHANNELGROUP,
+                                            user_data: *mut c_void) -> ::Status {
+    set_user_data("FMOD_ChannelGroup_SetUserData", channel_group as usize, Kind::ChannelGroup,
+                  user_data)
+}
+
+pub unsafe fn FMOD_ChannelGroup_GetUserData(channel_group: *mut FMOD_CHANNELGROUP,
+                                            user_data: *mut *mut c_void) -> ::Status {
+    get_user_data("FMOD_ChannelGroup_GetUserData", channel_group as usize, Kind::ChannelGroup,
+                  user_data)
+}
+
+/* SoundGroup */
+
+pub unsafe fn FMOD_SoundGroup_Release(sound_group: *mut FMOD_SOUNDGROUP) -> ::Status {
+    let group = sound_group as usize;
+
+    record("FMOD_SoundGroup_Release", group, Vec::new());
+    with_state(|s| {
+        let system = match s.get(group, Kind::SoundGroup) {
+            Ok(o) => o.system,
+            Err(e) => return e,
+        };
+        let master = s.objects.get(&system).map(|o| o.int("master_sound_group") as usize)
+                              .unwrap_or(0);
+
+        if master == group {
+            return ::Status::InvalidHandle;
+        }
+        for o in s.objects.values_mut() {
+            if o.kind == Kind::Sound && o.parent == group {
+                o.parent = master;
+            }
+        }
+        s.objects.remove(&group);
+        ::Status::Ok
+    })
+}
+
+pub unsafe fn FMOD_SoundGroup_GetSystemObject(sound_group: *mut FMOD_SOUNDGROUP,
+                                              system: *mut *mut FMOD_SYSTEM) -> ::Status {
+    get_system::<FMOD_SOUNDGROUP>("FMOD_SoundGroup_GetSystemObject", sound_group as usize,
+                                  Kind::SoundGroup, system)
+}
+
+int_property!(SoundGroup, sound_group: FMOD_SOUNDGROUP, FMOD_SoundGroup_SetMaxAudible,
+              FMOD_SoundGroup_GetMaxAudible, "max_audible");
+float_property!(SoundGroup, sound_group: FMOD_SOUNDGROUP, FMOD_SoundGroup_SetMuteFadeSpeed,
+                FMOD_SoundGroup_GetMuteFadeSpeed, "mute_fade_speed");
+float_property!(SoundGroup, sound_group: FMOD_SOUNDGROUP, FMOD_SoundGroup_SetVolume,
+                FMOD_SoundGroup_GetVolume, "volume");
+
+fn group_sounds(s: &State, group: usize) -> Vec<usize> {
+    let mut sounds: Vec<usize> = s.objects.iter()
+                                  .filter(|&(_, o)| o.kind == Kind::Sound && o.parent == group)
+                                  .map(|(&h, _)| h)
+                                  .collect();
+
+    sounds.sort();
+    sounds
+}
+
+pub unsafe fn FMOD_SoundGroup_Stop(sound_group: *mut FMOD_SOUNDGROUP) -> ::Status {
+    let group = sound_group as usize;
+
+    record("FMOD_SoundGroup_Stop", group, Vec::new());
+    match with_state(|s| match s.get(group, Kind::SoundGroup) {
+        Ok(_) => {
+            let sounds = group_sounds(s, group);
+            let channels = s.playing_channels(|c| sounds.contains(&c.source));
+
+            Ok(s.end_channels(channels))
+        }
+        Err(e) => Err(e),
+    }) {
+        Ok(ended) => {
+            call_end_callbacks(ended);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_SoundGroup_GetName(sound_group: *mut FMOD_SOUNDGROUP, name: *mut c_char,
+                                      name_len: c_int) -> ::Status {
+    get_name("FMOD_SoundGroup_GetName", sound_group as usize, Kind::SoundGroup, name, name_len)
+}
+
+pub unsafe fn FMOD_SoundGroup_GetNumSounds(sound_group: *mut FMOD_SOUNDGROUP,
+                                           num_sounds: *mut c_int) -> ::Status {
+    let group = sound_group as usize;
+
+    record("FMOD_SoundGroup_GetNumSounds", group, Vec::new());
+    with_state(|s| match s.get(group, Kind::SoundGroup) {
+        Ok(_) => {
+            write(num_sounds, group_sounds(s, group).len() as c_int);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_SoundGroup_GetSound(sound_group: *mut FMOD_SOUNDGROUP, index: c_int,
+                                       sound: *mut *mut FMOD_SOUND) -> ::Status {
+    let group = sound_group as usize;
+
+    record("FMOD_SoundGroup_GetSound", group, vec![Value::Int(index as i64)]);
+    with_state(|s| match s.get(group, Kind::SoundGroup) {
+        Ok(_) => match group_sounds(s, group).get(index as usize) {
+            Some(&h) if index >= 0 => {
+                write(sound, h as *mut FMOD_SOUND);
+                ::Status::Ok
+            }
+            _ => ::Status::InvalidParam,
+        },
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_SoundGroup_GetNumPlaying(sound_group: *mut FMOD_SOUNDGROUP,
+                                            num_playing: *mut c_int) -> ::Status {
+    let group = sound_group as usize;
+
+    record("FMOD_SoundGroup_GetNumPlaying", group, Vec::new());
+    with_state(|s| match s.get(group, Kind::SoundGroup) {
+        Ok(_) => {
+            let sounds = group_sounds(s, group);
+
+            write(num_playing, s.playing_channels(|c| sounds.contains(&c.source)).len() as c_int);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_SoundGroup_SetUserData(sound_group: *mut FMOD_SOUNDGROUP,
+                                          user_data: *mut c_void) -> ::Status {
+    set_user_data("FMOD_SoundGroup_SetUserData", sound_group as usize, Kind::SoundGroup,
+                  user_data)
+}
+
+pub unsafe fn FMOD_SoundGroup_GetUserData(sound_group: *mut FMOD_SOUNDGROUP,
+                                          user_data: *mut *mut c_void) -> ::Status {
+    get_user_data("FMOD_SoundGroup_GetUserData", sound_group as usize, Kind::SoundGroup,
+                  user_data)
+}
+
+/* DSP */
+
+pub unsafe fn FMOD_DSP_Release(dsp: *mut FMOD_DSP) -> ::Status {
+    let dsp = dsp as usize;
+
+    record("FMOD_DSP_Release", dsp, Vec::new());
+    let callbacks = match with_state(|s| s.get(dsp, Kind::Dsp).map(|o| o.dsp_callbacks)) {
+        Ok(c) => c,
+        Err(e) => return e,
+    };
+    if let Some(release) = callbacks.and_then(|c| c.release) {
+        call_dsp_callback(dsp, |state| release(state));
+    }
+    with_state(|s| {
+        let connections: Vec<usize> = match s.objects.get(&dsp) {
+            Some(o) => o.inputs.iter().chain(o.outputs.iter()).map(|&(_, c)| c).collect(),
+            None => Vec::new(),
+        };
+
+        for connection in connections {
+            s.disconnect(connection);
+        }
+        s.objects.remove(&dsp);
+    });
+    ::Status::Ok
+}
+
+pub unsafe fn FMOD_DSP_GetSystemObject(dsp: *mut FMOD_DSP,
+                                       system: *mut *mut FMOD_SYSTEM) -> ::Status {
+    get_system::<FMOD_DSP>("FMOD_DSP_GetSystemObject", dsp as usize, Kind::Dsp, system)
+}
+
+pub unsafe fn FMOD_DSP_AddInput(dsp: *mut FMOD_DSP, target: *mut FMOD_DSP,
+                                connection: *mut *mut FMOD_DSPCONNECTION) -> ::Status {
+    record("FMOD_DSP_AddInput", dsp as usize, vec![Value::Handle(target as usize)]);
+    match with_state(|s| s.connect(dsp as usize, target as usize)) {
+        Ok(c) => {
+            write(connection, c as *mut FMOD_DSPCONNECTION);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_DSP_DisconnectFrom(dsp: *mut FMOD_DSP, target: *mut FMOD_DSP) -> ::Status {
+    let dsp = dsp as usize;
+    let target = target as usize;
+
+    record("FMOD_DSP_DisconnectFrom", dsp, vec![Value::Handle(target)]);
+    with_state(|s| {
+        let connections: Vec<usize> = match s.get(dsp, Kind::Dsp) {
+            Ok(o) => o.inputs.iter().chain(o.outputs.iter())
+                      .filter(|&&(d, _)| d == target).map(|&(_, c)| c).collect(),
+            Err(e) => return e,
+        };
+
+        for connection in connections {
+            s.disconnect(connection);
+        }
+        ::Status::Ok
+    })
+}
+
+pub unsafe fn FMOD_DSP_DisconnectAll(dsp: *mut FMOD_DSP, inputs: FMOD_BOOL,
+                                     outputs: FMOD_BOOL) -> ::Status {
+    let dsp = dsp as usize;
+
+    record("FMOD_DSP_DisconnectAll", dsp, vec![Value::Bool(inputs != 0), Value::Bool(outputs != 0)]);
+    with_state(|s| {
+        let connections: Vec<usize> = match s.get(dsp, Kind::Dsp) {
+            Ok(o) => {
+                let mut c = Vec::new();
+
+                if inputs != 0 {
+                    c.extend(o.inputs.iter().map(|&(_, c)| c));
+                }
+                if outputs != 0 {
+                    c.extend(o.outputs.iter().map(|&(_, c)| c));
+                }
+                c
+            }
+            Err(e) => return e,
+        };
+
+        for connection in connections {
+            s.disconnect(connection);
+        }
+        ::Status::Ok
+    })
+}
+
+pub unsafe fn FMOD_DSP_Remove(dsp: *mut FMOD_DSP) -> ::Status {
+    let dsp = dsp as usize;
+
+    record("FMOD_DSP_Remove", dsp, Vec::new());
+    with_state(|s| {
+        let (inputs, outputs) = match s.get(dsp, Kind::Dsp) {
+            Ok(o) => (o.inputs.clone(), o.outputs.clone()),
+            Err(e) => return e,
+        };
+
+        // the inputs are reconnected to the outputs, as FMOD does
+        for &(_, c) in inputs.iter().chain(outputs.iter()) {
+            s.disconnect(c);
+        }
+        for &(output, _) in outputs.iter() {
+            for &(input, _) in inputs.iter() {
+                let _ = s.connect(output, input);
+            }
+        }
+        ::Status::Ok
+    })
+}
+
+pub unsafe fn FMOD_DSP_GetNumInputs(dsp: *mut FMOD_DSP, num_inputs: *mut c_int) -> ::Status {
+    record("FMOD_DSP_GetNumInputs", dsp as usize, Vec::new());
+    with_state(|s| status(s.get(dsp as usize, Kind::Dsp)
+                           .map(|o| write(num_inputs, o.inputs.len() as c_int))))
+}
+
+pub unsafe fn FMOD_DSP_GetNumOutputs(dsp: *mut FMOD_DSP, num_outputs: *mut c_int) -> ::Status {
+    record("FMOD_DSP_GetNumOutputs", dsp as usize, Vec::new());
+    with_state(|s| status(s.get(dsp as usize, Kind::Dsp)
+                           .map(|o| write(num_outputs, o.outputs.len() as c_int))))
+}
+
+unsafe fn write_link(links: &[(usize, usize)], index: c_int, dsp: *mut *mut FMOD_DSP,
+                     connection: *mut *mut FMOD_DSPCONNECTION) -> ::Status {
+    match links.get(index as usize) {
+        Some(&(d, c)) if index >= 0 => {
+            write(dsp, d as *mut FMOD_DSP);
+            write(connection, c as *mut FMOD_DSPCONNECTION);
+            ::Status::Ok
+        }
+        _ => ::Status::InvalidParam,
+    }
+}
+
+pub unsafe fn FMOD_DSP_GetInput(dsp: *mut FMOD_DSP, index: c_int, input: *mut *mut FMOD_DSP,
+                                input_connection: *mut *mut FMOD_DSPCONNECTION) -> ::Status {
+    record("FMOD_DSP_GetInput", dsp as usize, vec![Value::Int(index as i64)]);
+    with_state(|s| match s.get(dsp as usize, Kind::Dsp) {
+        Ok(o) => write_link(&o.inputs, index, input, input_connection),
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_DSP_GetOutput(dsp: *mut FMOD_DSP, index: c_int, output: *mut *mut FMOD_DSP,
+                                 output_connection: *mut *mut FMOD_DSPCONNECTION) -> ::Status {
+    record("FMOD_DSP_GetOutput", dsp as usize, vec![Value::Int(index as i64)]);
+    with_state(|s| match s.get(dsp as usize, Kind::Dsp) {
+        Ok(o) => write_link(&o.outputs, index, output, output_connection),
+        Err(e) => e,
+    })
+}
+
+bool_property!(Dsp, dsp: FMOD_DSP, FMOD_DSP_SetActive, FMOD_DSP_GetActive, "active");
+bool_property!(Dsp, dsp: FMOD_DSP, FMOD_DSP_SetBypass, FMOD_DSP_GetBypass, "bypass");
+
+pub unsafe fn FMOD_DSP_Reset(dsp: *mut FMOD_DSP) -> ::Status {
+    let dsp = dsp as usize;
+
+    record("FMOD_DSP_Reset", dsp, Vec::new());
+    match with_state(|s| s.get(dsp, Kind::Dsp).map(|o| o.dsp_callbacks)) {
+        Ok(callbacks) => match callbacks.and_then(|c| c.reset) {
+            Some(reset) => call_dsp_callback(dsp, |state| reset(state)),
+            None => ::Status::Ok,
+        },
+        Err(e) => e,
+    }
+}
+
+/// Returns the callbacks of a user DSP after checking `index`, builtin DSPs accept any index.
+fn get_parameter_callbacks(dsp: usize, index: c_int) -> Result<Option<DspCallbacks>, ::Status> {
+    with_state(|s| match s.get(dsp, Kind::Dsp) {
+        Ok(o) => {
+            if index < 0 || (o.dsp_callbacks.is_some() && index as i64 >= o.int("num_parameters")) {
+                Err(::Status::InvalidParam)
+            } else {
+                Ok(o.dsp_callbacks)
+            }
+        }
+        Err(e) => Err(e),
+    })
+}
+
+pub unsafe fn FMOD_DSP_SetParameter(dsp: *mut FMOD_DSP, index: c_int, value: c_float) -> ::Status {
+    let dsp = dsp as usize;
+
+    record("FMOD_DSP_SetParameter", dsp, vec![Value::Int(index as i64), Value::Float(value)]);
+    let callbacks = match get_parameter_callbacks(dsp, index) {
+        Ok(c) => c,
+        Err(e) => return e,
+    };
+    if let Some(set_parameter) = callbacks.and_then(|c| c.set_parameter) {
+        let status = call_dsp_callback(dsp, |state| set_parameter(state, index, value));
+
+        if status != ::Status::Ok {
+            return status;
+        }
+    }
+    with_state(|s| {
+        if let Some(o) = s.objects.get_mut(&dsp) {
+            o.set(&format!("parameter{}", index), Value::Float(value));
+            if o.dsp_callbacks.is_none() && index as i64 >= o.int("num_parameters") {
+                o.set("num_parameters", Value::Int(index as i64 + 1));
+            }
+        }
+    });
+    ::Status::Ok
+}
+
+pub unsafe fn FMOD_DSP_GetParameter(dsp: *mut FMOD_DSP, index: c_int, value: *mut c_float,
+                                    value_str: *mut c_char, value_str_len: c_int) -> ::Status {
+    let dsp = dsp as usize;
+
+    record("FMOD_DSP_GetParameter", dsp, vec![Value::Int(index as i64)]);
+    let callbacks = match get_parameter_callbacks(dsp, index) {
+        Ok(c) => c,
+        Err(e) => return e,
+    };
+    if let Some(get_parameter) = callbacks.and_then(|c| c.get_parameter) {
+        return call_dsp_callback(dsp, |state| get_parameter(state, index, value, value_str));
+    }
+    let v = with_state(|s| s.objects.get(&dsp).map(|o| o.float(&format!("parameter{}", index)))
+                             .unwrap_or(0f32));
+    write(value, v);
+    write_str(value_str, value_str_len, &format!("{:.2}", v));
+    ::Status::Ok
+}
+
+/// Ranges of builtin DSPs are unbounded, unless set with
+/// `mock::set_value(&dsp, "parameter{index}.min", ..)`.
+pub unsafe fn FMOD_DSP_GetParameterInfo(dsp: *mut FMOD_DSP, index: c_int, name: *mut c_char,
+                                        label: *mut c_char, description: *mut c_char,
+                                        description_len: c_int, min: *mut c_float,
+                                        max: *mut c_float) -> ::Status {
+    let dsp = dsp as usize;
+
+    record("FMOD_DSP_GetParameterInfo", dsp, vec![Value::Int(index as i64)]);
+    if let Err(e) = get_parameter_callbacks(dsp, index) {
+        return e;
+    }
+    with_state(|s| {
+        let o = &s.objects[&dsp];
+        let range = |name: &str, default: f32| {
+            o.values.get(&format!("parameter{}.{}", index, name)).and_then(|v| v.as_float())
+             .unwrap_or(default)
+        };
+
+        // name and label buffers are 16 bytes long
+        write_str(name, 16, &o.str(&format!("parameter{}.name", index)));
+        write_str(label, 16, &o.str(&format!("parameter{}.label", index)));
+        write_str(description, description_len, "");
+        write(min, range("min", -::std::f32::MAX));
+        write(max, range("max", ::std::f32::MAX));
+    });
+    ::Status::Ok
+}
+
+pub unsafe fn FMOD_DSP_GetNumParameters(dsp: *mut FMOD_DSP, num_params: *mut c_int) -> ::Status {
+    get_int("FMOD_DSP_GetNumParameters", dsp as usize, Kind::Dsp, "num_parameters", num_params)
+}
+
+pub unsafe fn FMOD_DSP_GetInfo(dsp: *mut FMOD_DSP, name: *mut c_char, version: *mut c_uint,
+                               channels: *mut c_int, config_width: *mut c_int,
+                               config_height: *mut c_int) -> ::Status {
+    record("FMOD_DSP_GetInfo", dsp as usize, Vec::new());
+    with_state(|s| match s.get(dsp as usize, Kind::Dsp) {
+        Ok(o) => {
+            // the name buffer is 32 bytes long
+            write_str(name, 32, &o.str("name"));
+            write(version, o.int("version") as c_uint);
+            write(channels, o.int("channels") as c_int);
+            write(config_width, 0);
+            write(config_height, 0);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_DSP_GetType(dsp: *mut FMOD_DSP, _type: *mut ::DspType) -> ::Status {
+    match get_value("FMOD_DSP_GetType", dsp as usize, Kind::Dsp, "type") {
+        Ok(v) => {
+            write(_type, ::std::mem::transmute(v.as_int().unwrap_or(0) as i32));
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn FMOD_DSP_SetDefaults(dsp: *mut FMOD_DSP, frequency: c_float, volume: c_float,
+                                   pan: c_float, priority: c_int) -> ::Status {
+    record("FMOD_DSP_SetDefaults", dsp as usize,
+           vec![Value::Float(frequency), Value::Float(volume), Value::Float(pan),
+                Value::Int(priority as i64)]);
+    with_state(|s| status(s.get_mut(dsp as usize, Kind::Dsp).map(|o| {
+        o.set("frequency", Value::Float(frequency));
+        o.set("volume", Value::Float(volume));
+        o.set("pan", Value::Float(pan));
+        o.set("priority", Value::Int(priority as i64));
+    })))
+}
+
+pub unsafe fn FMOD_DSP_GetDefaults(dsp: *mut FMOD_DSP, frequency: *mut c_float,
+                                   volume: *mut c_float, pan: *mut c_float,
+                                   priority: *mut c_int) -> ::Status {
+    record("FMOD_DSP_GetDefaults", dsp as usize, Vec::new());
+    with_state(|s| match s.get(dsp as usize, Kind::Dsp) {
+        Ok(o) => {
+            let defaults = o.values.contains_key("frequency");
+
+            write(frequency, if defaults { o.float("frequency") } else { 44100f32 });
+            write(volume, if defaults { o.float("volume") } else { 1f32 });
+            write(pan, o.float("pan"));
+            write(priority, if defaults { o.int("priority") as c_int } else { 128 });
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_DSP_SetUserData(dsp: *mut FMOD_DSP, user_data: *mut c_void) -> ::Status {
+    set_user_data("FMOD_DSP_SetUserData", dsp as usize, Kind::Dsp, user_data)
+}
+
+pub unsafe fn FMOD_DSP_GetUserData(dsp: *mut FMOD_DSP, user_data: *mut *mut c_void) -> ::Status {
+    get_user_data("FMOD_DSP_GetUserData", dsp as usize, Kind::Dsp, user_data)
+}
+
+/* DSPConnection */
+
+pub unsafe fn FMOD_DSPConnection_GetInput(dsp_connection: *mut FMOD_DSPCONNECTION,
+                                          input: *mut *mut FMOD_DSP) -> ::Status {
+    get_handle("FMOD_DSPConnection_GetInput", dsp_connection as usize, Kind::DspConnection,
+               |o| o.connection.0, input)
+}
+
+pub unsafe fn FMOD_DSPConnection_GetOutput(dsp_connection: *mut FMOD_DSPCONNECTION,
+                                           output: *mut *mut FMOD_DSP) -> ::Status {
+    get_handle("FMOD_DSPConnection_GetOutput", dsp_connection as usize, Kind::DspConnection,
+               |o| o.connection.1, output)
+}
+
+float_property!(DspConnection, dsp_connection: FMOD_DSPCONNECTION, FMOD_DSPConnection_SetMix,
+                FMOD_DSPConnection_GetMix, "mix");
+
+pub unsafe fn FMOD_DSPConnection_SetUserData(dsp_connection: *mut FMOD_DSPCONNECTION,
+                                             user_data: *mut c_void) -> ::Status {
+    set_user_data("FMOD_DSPConnection_SetUserData", dsp_connection as usize, Kind::DspConnection,
+                  user_data)
+}
+
+pub unsafe fn FMOD_DSPConnection_GetUserData(dsp_connection: *mut FMOD_DSPCONNECTION,
+                                             user_data: *mut *mut c_void) -> ::Status {
+    get_user_data("FMOD_DSPConnection_GetUserData", dsp_connection as usize, Kind::DspConnection,
+                  user_data)
+}
+
+/* Reverb and Geometry */
+
+pub unsafe fn FMOD_Reverb_Release(reverb: *mut FMOD_REVERB) -> ::Status {
+    record("FMOD_Reverb_Release", reverb as usize, Vec::new());
+    with_state(|s| match s.get(reverb as usize, Kind::Reverb) {
+        Ok(_) => {
+            s.objects.remove(&(reverb as usize));
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Reverb_Set3DAttributes(reverb: *mut FMOD_REVERB, position: *const FMOD_VECTOR,
+                                          min_distance: c_float,
+                                          max_distance: c_float) -> ::Status {
+    let position = read_vector(position);
+
+    record("FMOD_Reverb_Set3DAttributes", reverb as usize,
+           vec![position.clone().unwrap_or(Value::Int(0)), Value::Float(min_distance),
+                Value::Float(max_distance)]);
+    with_state(|s| status(s.get_mut(reverb as usize, Kind::Reverb).map(|o| {
+        if let Some(position) = position {
+            o.set("position3d", position);
+        }
+        o.set("min_distance", Value::Float(min_distance));
+        o.set("max_distance", Value::Float(max_distance));
+    })))
+}
+
+pub unsafe fn FMOD_Reverb_Get3DAttributes(reverb: *mut FMOD_REVERB, position: *mut FMOD_VECTOR,
+                                          min_distance: *mut c_float,
+                                          max_distance: *mut c_float) -> ::Status {
+    record("FMOD_Reverb_Get3DAttributes", reverb as usize, Vec::new());
+    with_state(|s| match s.get(reverb as usize, Kind::Reverb) {
+        Ok(o) => {
+            write_vector(position, o.values.get("position3d"));
+            write(min_distance, o.float("min_distance"));
+            write(max_distance, o.float("max_distance"));
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+/// Only the environment and the room level of reverb properties are kept.
+unsafe fn set_reverb_properties(function: &'static str, object: usize, kind: Kind,
+                                properties: *const FMOD_REVERB_PROPERTIES) -> ::Status {
+    let environment = Value::Int((*properties).Environment as i64);
+    let room = Value::Int((*properties).Room as i64);
+
+    record(function, object, vec![environment.clone(), room.clone()]);
+    with_state(|s| status(s.get_mut(object, kind).map(|o| {
+        o.set("reverb.environment", environment);
+        o.set("reverb.room", room);
+    })))
+}
+
+pub unsafe fn FMOD_Reverb_SetProperties(reverb: *mut FMOD_REVERB,
+                                        properties: *const FMOD_REVERB_PROPERTIES) -> ::Status {
+    set_reverb_properties("FMOD_Reverb_SetProperties", reverb as usize, Kind::Reverb, properties)
+}
+
+pub unsafe fn FMOD_System_SetReverbAmbientProperties(system: *mut FMOD_SYSTEM,
+                                                     prop: *mut FMOD_REVERB_PROPERTIES)
+                                                     -> ::Status {
+    set_reverb_properties("FMOD_System_SetReverbAmbientProperties", system as usize,
+                          Kind::System, prop)
+}
+
+bool_property!(Reverb, reverb: FMOD_REVERB, FMOD_Reverb_SetActive, FMOD_Reverb_GetActive,
+               "active");
+
+pub unsafe fn FMOD_Geometry_Release(geometry: *mut FMOD_GEOMETRY) -> ::Status {
+    record("FMOD_Geometry_Release", geometry as usize, Vec::new());
+    with_state(|s| match s.get(geometry as usize, Kind::Geometry) {
+        Ok(_) => {
+            s.objects.remove(&(geometry as usize));
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+/// Adds a polygon, if it fits in the limits given to `FMOD_System_CreateGeometry`.
+pub unsafe fn FMOD_Geometry_AddPolygon(geometry: *mut FMOD_GEOMETRY, direct_occlusion: c_float,
+                                       reverb_occlusion: c_float, double_sided: FMOD_BOOL,
+                                       num_vertices: c_int, vertices: *const FMOD_VECTOR,
+                                       polygon_index: *mut c_int) -> ::Status {
+    record("FMOD_Geometry_AddPolygon", geometry as usize,
+           vec![Value::Float(direct_occlusion), Value::Float(reverb_occlusion),
+                Value::Bool(double_sided != 0), Value::Int(num_vertices as i64)]);
+    if num_vertices < 3 || vertices.is_null() {
+        return ::Status::InvalidParam;
+    }
+    let vertices = (0..num_vertices as isize).map(|i| ::Vector {
+        x: (*vertices.offset(i)).x,
+        y: (*vertices.offset(i)).y,
+        z: (*vertices.offset(i)).z,
+    }).collect::<Vec<_>>();
+
+    with_state(|s| match s.get_mut(geometry as usize, Kind::Geometry) {
+        Ok(o) => {
+            let used_vertices = o.polygons.iter().map(|p| p.vertices.len()).sum::<usize>();
+
+            if o.polygons.len() as i64 >= o.int("max_polygons") ||
+               (used_vertices + vertices.len()) as i64 > o.int("max_vertices") {
+                return ::Status::InvalidParam;
+            }
+            write(polygon_index, o.polygons.len() as c_int);
+            o.polygons.push(Polygon {
+                direct_occlusion: direct_occlusion,
+                reverb_occlusion: reverb_occlusion,
+                double_sided: double_sided != 0,
+                vertices: vertices,
+            });
+            ::Status::Ok
+        }
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Geometry_GetNumPolygons(geometry: *mut FMOD_GEOMETRY,
+                                           num_polygons: *mut c_int) -> ::Status {
+    record("FMOD_Geometry_GetNumPolygons", geometry as usize, Vec::new());
+    with_state(|s| status(s.get(geometry as usize, Kind::Geometry).map(|o| {
+        write(num_polygons, o.polygons.len() as c_int);
+    })))
+}
+
+pub unsafe fn FMOD_Geometry_GetMaxPolygons(geometry: *mut FMOD_GEOMETRY,
+                                           max_polygons: *mut c_int,
+                                           max_vertices: *mut c_int) -> ::Status {
+    record("FMOD_Geometry_GetMaxPolygons", geometry as usize, Vec::new());
+    with_state(|s| status(s.get(geometry as usize, Kind::Geometry).map(|o| {
+        write(max_polygons, o.int("max_polygons") as c_int);
+        write(max_vertices, o.int("max_vertices") as c_int);
+    })))
+}
+
+/// Runs `f` on the polygon `index` of `geometry`.
+fn with_polygon<F: FnOnce(&mut Polygon) -> ::Status>(geometry: usize, index: c_int,
+                                                     f: F) -> ::Status {
+    with_state(|s| match s.get_mut(geometry, Kind::Geometry) {
+        Ok(o) if index < 0 || index as usize >= o.polygons.len() => ::Status::InvalidParam,
+        Ok(o) => f(&mut o.polygons[index as usize]),
+        Err(e) => e,
+    })
+}
+
+pub unsafe fn FMOD_Geometry_GetPolygonNumVertices(geometry: *mut FMOD_GEOMETRY, index: c_int,
+                                                  num_vertices: *mut c_int) -> ::Status {
+    record("FMOD_Geometry_GetPolygonNumVertices", geometry as usize,
+           vec![Value::Int(index as i64)]);
+    with_polygon(geometry as usize, index, |p| {
+        write(num_vertices, p.vertices.len() as c_int);
+        ::Status::Ok
+    })
+}
+
+pub unsafe fn FMOD_Geometry_SetPolygonVertex(geometry: *mut FMOD_GEOMETRY, index: c_int,
+                                             vertex_index: c_int,
+                                             vertex: *const FMOD_VECTOR) -> ::Status {
+    record("FMOD_Geometry_SetPolygonVertex", geometry as usize,
+           vec![Value::Int(index as i64), Value::Int(vertex_index as i64),
+                read_vector(vertex).unwrap_or(Value::Int(0))]);
+    with_polygon(geometry as usize, index, |p| {
+        if vertex.is_null() || vertex_index < 0 || vertex_index as usize >= p.vertices.len() {
+            return ::Status::InvalidParam;
+        }
+        p.vertices[vertex_index as usize] = ::Vector { x: (*vertex).x, y: (*vertex).y,
+                                                       z: (*vertex).z };
+        ::Status::Ok
+    })
+}
+
+pub unsafe fn FMOD_Geometry_GetPolygonVertex(geometry: *mut FMOD_GEOMETRY, index: c_int,
+                                             vertex_index: c_int,
+                                             vertex: *mut FMOD_VECTOR) -> ::Status {
+    record("FMOD_Geometry_GetPolygonVertex", geometry as usize,
+           vec![Value::Int(index as i64), Value::Int(vertex_index as i64)]);
+    with_polygon(geometry as usize, index, |p| {
+        if vertex_index < 0 || vertex_index as usize >= p.vertices.len() {
+            return ::Status::InvalidParam;
+        }
+        let v = p.vertices[vertex_index as usize];
+
+        write(vertex, FMOD_VECTOR { x: v.x, y: v.y, z: v.z });
+        ::Status::Ok
+    })
+}
+
+pub unsafe fn FMOD_Geometry_SetPolygonAttributes(geometry: *mut FMOD_GEOMETRY, index: c_int,
+                                                 direct_occlusion: c_float,
+                                                 reverb_occlusion: c_float,
+                                                 double_sided: FMOD_BOOL) -> ::Status {
+    record("FMOD_Geometry_SetPolygonAttributes", geometry as usize,
+           vec![Value::Int(index as i64), Value::Float(direct_occlusion),
+                Value::Float(reverb_occlusion), Value::Bool(double_sided != 0)]);
+    with_polygon(geometry as usize, index, |p| {
+        p.direct_occlusion = direct_occlusion;
+        p.reverb_occlusion = reverb_occlusion;
+        p.double_sided = double_sided != 0;
+        ::Status::Ok
+    })
+}
+
+pub unsafe fn FMOD_Geometry_GetPolygonAttributes(geometry: *mut FMOD_GEOMETRY, index: c_int,
+                                                 direct_occlusion: *mut c_float,
+                                                 reverb_occlusion: *mut c_float,
+                                                 double_sided: *mut FMOD_BOOL) -> ::Status {
+    record("FMOD_Geometry_GetPolygonAttributes", geometry as usize,
+           vec![Value::Int(index as i64)]);
+    with_polygon(geometry as usize, index, |p| {
+        write(direct_occlusion, p.direct_occlusion);
+        write(reverb_occlusion, p.reverb_occlusion);
+        write(double_sided, p.double_sided as FMOD_BOOL);
+        ::Status::Ok
+    })
+}
+
+bool_property!(Geometry, geometry: FMOD_GEOMETRY, FMOD_Geometry_SetActive,
+               FMOD_Geometry_GetActive, "active");
+
+/// Records the call and stores the vector as the `name` property of the geometry.
+unsafe fn set_geometry_vector(function: &'static str, geometry: *mut FMOD_GEOMETRY, name: &str,
+                              vector: *const FMOD_VECTOR) -> ::Status {
+    match read_vector(vector) {
+        Some(v) => set_value(function, geometry as usize, Kind::Geometry, name, v),
+        None => ::Status::InvalidParam,
+    }
+}
+
+pub unsafe fn FMOD_Geometry_SetRotation(geometry: *mut FMOD_GEOMETRY, forward: *const FMOD_VECTOR,
+                                        up: *const FMOD_VECTOR) -> ::Status {
+    match set_geometry_vector("FMOD_Geometry_SetRotation", geometry, "forward", forward) {
+        ::Status::Ok => {}
+        e => return e,
+    }
+    with_state(|s| status(s.get_mut(geometry as usize, Kind::Geometry).map(|o| {
+        if let Some(up) = read_vector(up) {
+            o.set("up", up);
+        }
+    })))
+}
+
+pub unsafe fn FMOD_Geometry_GetRotation(geometry: *mut FMOD_GEOMETRY, forward: *mut FMOD_VECTOR,
+                                        up: *mut FMOD_VECTOR) -> ::Status {
+    record("FMOD_Geometry_GetRotation", geometry as usize, Vec::new());
+    with_state(|s| status(s.get(geometry as usize, Kind::Geometry).map(|o| {
+        write_vector(forward, o.values.get("forward"));
+        write_vector(up, o.values.get("up"));
+    })))
+}
+
+pub unsafe fn FMOD_Geometry_SetPosition(geometry: *mut FMOD_GEOMETRY,
+                                        position: *const FMOD_VECTOR) -> ::Status {
+    set_geometry_vector("FMOD_Geometry_SetPosition", geometry, "position", position)
+}
+
+pub unsafe fn FMOD_Geometry_GetPosition(geometry: *mut FMOD_GEOMETRY,
+                                        position: *mut FMOD_VECTOR) -> ::Status {
+    record("FMOD_Geometry_GetPosition", geometry as usize, Vec::new());
+    with_state(|s| status(s.get(geometry as usize, Kind::Geometry).map(|o| {
+        write_vector(position, o.values.get("position"));
+    })))
+}
+
+pub unsafe fn FMOD_Geometry_SetScale(geometry: *mut FMOD_GEOMETRY,
+                                     scale: *const FMOD_VECTOR) -> ::Status {
+    set_geometry_vector("FMOD_Geometry_SetScale", geometry, "scale", scale)
+}
+
+pub unsafe fn FMOD_Geometry_GetScale(geometry: *mut FMOD_GEOMETRY,
+                                     scale: *mut FMOD_VECTOR) -> ::Status {
+    record("FMOD_Geometry_GetScale", geometry as usize, Vec::new());
+    with_state(|s| status(s.get(geometry as usize, Kind::Geometry).map(|o| {
+        write_vector(scale, o.values.get("scale"));
+    })))
+}
+
+fn vector_value(o: &Object, name: &str) -> ::Vector {
+    match o.values.get(name) {
+        Some(&Value::Vector(x, y, z)) => ::Vector { x: x, y: y, z: z },
+        _ => ::Vector::new(),
+    }
+}
+
+fn sub(a: ::Vector, b: ::Vector) -> ::Vector {
+    ::Vector { x: a.x - b.x, y: a.y - b.y, z: a.z - b.z }
+}
+
+fn dot(a: ::Vector, b: ::Vector) -> f32 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+fn cross(a: ::Vector, b: ::Vector) -> ::Vector {
+    ::Vector { x: a.y * b.z - a.z * b.y, y: a.z * b.x - a.x * b.z, z: a.x * b.y - a.y * b.x }
+}
+
+/// Whether the segment from `from` to `to` goes through the convex polygon `vertices`. Single
+/// sided polygons are only crossed from their front side, the one their normal points to.
+fn crosses(from: ::Vector, to: ::Vector, vertices: &[::Vector], double_sided: bool) -> bool {
+    let normal = cross(sub(vertices[1], vertices[0]), sub(vertices[2], vertices[0]));
+    let direction = sub(to, from);
+    let denominator = dot(normal, direction);
+
+    if denominator == 0f32 || (!double_sided && denominator > 0f32) {
+        return false;
+    }
+    let t = dot(normal, sub(vertices[0], from)) / denominator;
+
+    if t < 0f32 || t > 1f32 {
+        return false;
+    }
+    let point = ::Vector {
+        x: from.x + direction.x * t,
+        y: from.y + direction.y * t,
+        z: from.z + direction.z * t,
+    };
+
+    (0..vertices.len()).all(|i| {
+        let edge = sub(vertices[(i + 1) % vertices.len()], vertices[i]);
+
+        dot(cross(edge, sub(point, vertices[i])), normal) >= 0f32
+    })
+}
+
+/// Combines the occlusion of the polygons of the active geometries of the system crossed by the
+/// segment from the listener to the source. Geometries are moved and scaled, but not rotated.
+pub unsafe fn FMOD_System_GetGeometryOcclusion(system: *mut FMOD_SYSTEM,
+                                               listener: *const FMOD_VECTOR,
+                                               source: *const FMOD_VECTOR, direct: *mut c_float,
+                                               reverb: *mut c_float) -> ::Status {
+    let system = system as usize;
+    let (listener, source) = match (read_vector(listener), read_vector(source)) {
+        (Some(Value::Vector(lx, ly, lz)), Some(Value::Vector(sx, sy, sz))) => {
+            (::Vector { x: lx, y: ly, z: lz }, ::Vector { x: sx, y: sy, z: sz })
+        }
+        _ => return ::Status::InvalidParam,
+    };
+
+    record("FMOD_System_GetGeometryOcclusion", system,
+           vec![Value::Vector(listener.x, listener.y, listener.z),
+                Value::Vector(source.x, source.y, source.z)]);
+    let occlusion = with_state(|s| s.get(system, Kind::System).map(|_| {
+        let mut direct_left = 1f32;
+        let mut reverb_left = 1f32;
+
+        for o in s.objects.values() {
+            if o.kind != Kind::Geometry || o.system != system || !o.bool("active") {
+                continue;
+            }
+            let position = vector_value(o, "position");
+            let scale = vector_value(o, "scale");
+
+            for polygon in o.polygons.iter() {
+                let vertices = polygon.vertices.iter().map(|v| ::Vector {
+                    x: position.x + v.x * scale.x,
+                    y: position.y + v.y * scale.y,
+                    z: position.z + v.z * scale.z,
+                }).collect::<Vec<_>>();
+
+                if crosses(listener, source, &vertices, polygon.double_sided) {
+                    direct_left *= 1f32 - polygon.direct_occlusion;
+                    reverb_left *= 1f32 - polygon.reverb_occlusion;
+                }
+            }
+        }
+        (1f32 - direct_left, 1f32 - reverb_left)
+    }));
+    match occlusion {
+        Ok((d, r)) => {
+            write(direct, d);
+            write(reverb, r);
+            ::Status::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+/// Functions the mock doesn't handle: the call is recorded, and `Status::Unsupported` returned.
+macro_rules! unsupported {
+    ($($name:ident($object:ident: $t:ty $(, $arg:ident: $arg_t:ty)*);)*) => {
+        $(pub unsafe fn $name($object: $t $(, $arg: $arg_t)*) -> ::Status {
+            $(let _ = $arg;)*
+            record(stringify!($name), $object as usize, Vec::new());
+            ::Status::Unsupported
+        })*
+    }
+}
+
+unsupported! {
+    FMOD_System_SetOutput(system: *mut FMOD_SYSTEM, output_type: ::OutputType);
+    FMOD_System_GetOutput(system: *mut FMOD_SYSTEM, output_type: *mut ::OutputType);
+    FMOD_System_GetNumDrivers(system: *mut FMOD_SYSTEM, num_drivers: *mut c_int);
+    FMOD_System_GetDriverInfo(system: *mut FMOD_SYSTEM, id: c_int, name: *mut c_char,
+                              name_len: c_int, guid: *mut FMOD_GUID);
+    FMOD_System_GetDriverInfoW(system: *mut FMOD_SYSTEM, id: c_int, name: *mut c_short,
+                               name_len: c_int, guid: *mut FMOD_GUID);
+    FMOD_System_GetDriverCaps(system: *mut FMOD_SYSTEM, id: c_int, caps: *mut FMOD_CAPS,
+                              control_panel_output_rate: *mut c_int,
+                              controlpanelspeakermode: *mut ::SpeakerMode);
+    FMOD_System_SetDriver(system: *mut FMOD_SYSTEM, driver: c_int);
+    FMOD_System_GetDriver(system: *mut FMOD_SYSTEM, driver: *mut c_int);
+    FMOD_System_SetHardwareChannels(system: *mut FMOD_SYSTEM, num_hardware_channels: c_int);
+    FMOD_System_GetHardwareChannels(system: *mut FMOD_SYSTEM, num_hardware_channels: *mut c_int);
+    FMOD_System_SetSoftwareChannels(system: *mut FMOD_SYSTEM, num_software_channels: c_int);
+    FMOD_System_GetSoftwareChannels(system: *mut FMOD_SYSTEM, num_software_channels: *mut c_int);
+    FMOD_System_SetSoftwareFormat(system: *mut FMOD_SYSTEM, sample_rate: c_int,
+                                  format: ::SoundFormat, num_output_channels: c_int,
+                                  max_input_channels: c_int, resample_method: ::DspResampler);
+    FMOD_System_GetSoftwareFormat(system: *mut FMOD_SYSTEM, sample_rate: *mut c_int,
+                                  format: *mut ::SoundFormat, num_output_channels: *mut c_int,
+                                  max_input_channels: *mut c_int,
+                                  resample_method: *mut ::DspResampler, bits: *mut c_int);
+    FMOD_System_SetDSPBufferSize(system: *mut FMOD_SYSTEM, buffer_length: c_uint,
+                                 num_buffers: c_int);
+    FMOD_System_GetDSPBufferSize(system: *mut FMOD_SYSTEM, buffer_length: *mut c_uint,
+                                 num_buffers: *mut c_int);
+    FMOD_System_SetAdvancedSettings(system: *mut FMOD_SYSTEM, settings: *mut FMOD_ADVANCEDSETTINGS);
+    FMOD_System_GetAdvancedSettings(system: *mut FMOD_SYSTEM, settings: *mut FMOD_ADVANCEDSETTINGS);
+    FMOD_System_SetSpeakerMode(system: *mut FMOD_SYSTEM, speaker_mode: ::SpeakerMode);
+    FMOD_System_GetSpeakerMode(system: *mut FMOD_SYSTEM, speaker_mode: *mut ::SpeakerMode);
+    FMOD_System_SetPluginPath(system: *mut FMOD_SYSTEM, path: *const c_char);
+    FMOD_System_LoadPlugin(system: *mut FMOD_SYSTEM, filename: *const c_char, handle: *mut c_uint,
+                           priority: c_uint);
+    FMOD_System_UnloadPlugin(system: *mut FMOD_SYSTEM, handle: c_uint);
+    FMOD_System_GetNumPlugins(system: *mut FMOD_SYSTEM, plugin_type: ::PluginType,
+                              num_plugins: *mut c_int);
+    FMOD_System_GetPluginHandle(system: *mut FMOD_SYSTEM, plugin_type: ::PluginType, index: c_int,
+                                handle: *mut c_uint);
+    FMOD_System_GetPluginInfo(system: *mut FMOD_SYSTEM, handle: c_uint,
+                              plugin_type: *mut ::PluginType, name: *mut c_char, name_len: c_int,
+                              version: *mut c_uint);
+    FMOD_System_SetOutputByPlugin(system: *mut FMOD_SYSTEM, handle: c_uint);
+    FMOD_System_GetOutputByPlugin(system: *mut FMOD_SYSTEM, handle: *mut c_uint);
+    FMOD_System_CreateDSPByPlugin(system: *mut FMOD_SYSTEM, handle: c_uint,
+                                  dsp: *mut *mut FMOD_DSP);
+    FMOD_System_GetSpectrum(system: *mut FMOD_SYSTEM, spectrum_array: *mut c_float,
+                            num_values: c_int, channel_offset: c_int, window_type: ::DspFftWindow);
+    FMOD_System_GetWaveData(system: *mut FMOD_SYSTEM, wave_array: *mut c_float, num_values: c_int,
+                            channel_offset: c_int);
+    FMOD_System_SetStreamBufferSize(system: *mut FMOD_SYSTEM, file_buffer_size: c_uint,
+                                    file_buffer_size_type: FMOD_TIMEUNIT);
+    FMOD_System_GetStreamBufferSize(system: *mut FMOD_SYSTEM, file_buffer_size: *mut c_uint,
+                                    file_buffer_size_type: *mut FMOD_TIMEUNIT);
+    FMOD_System_Set3DNumListeners(system: *mut FMOD_SYSTEM, num_listeners: c_int);
+    FMOD_System_Get3DNumListeners(system: *mut FMOD_SYSTEM, num_listeners: *mut c_int);
+    FMOD_System_GetMemoryInfo(system: *mut FMOD_SYSTEM, memory_bits: c_uint,
+                              event_memory_bits: c_uint, memory_used: *mut c_uint,
+                              memoryused_details: *mut FMOD_MEMORY_USAGE_DETAILS);
+    FMOD_System_Set3DRolloffCallback(system: *mut FMOD_SYSTEM, callback: FMOD_3D_ROLLOFFCALLBACK);
+    FMOD_System_Set3DSpeakerPosition(system: *mut FMOD_SYSTEM, speaker: ::Speaker, x: c_float,
+                                     y: c_float, active: FMOD_BOOL);
+    FMOD_System_Get3DSpeakerPosition(system: *mut FMOD_SYSTEM, speaker: ::Speaker, x: *mut c_float,
+                                     y: *mut c_float, active: *mut FMOD_BOOL);
+    FMOD_System_Set3DSettings(system: *mut FMOD_SYSTEM, doppler_scale: c_float,
+                              distance_factor: c_float, roll_off_scale: c_float);
+    FMOD_System_Get3DSettings(system: *mut FMOD_SYSTEM, doppler_scale: *mut c_float,
+                              distance_factor: *mut c_float, roll_off_scale: *mut c_float);
+    FMOD_System_GetVersion(system: *mut FMOD_SYSTEM, version: *mut c_uint);
+    FMOD_System_GetOutputHandle(system: *mut FMOD_SYSTEM, handle: *mut *mut c_void);
+    FMOD_System_GetCPUUsage(system: *mut FMOD_SYSTEM, dsp: *mut c_float, stream: *mut c_float,
+                            geometry: *mut c_float, update: *mut c_float, total: *mut c_float);
+    FMOD_System_GetSoundRAM(system: *mut FMOD_SYSTEM, current_alloced: *mut c_int,
+                            max_alloced: *mut c_int, total: *mut c_int);
+    FMOD_System_GetNumCDROMDrives(system: *mut FMOD_SYSTEM, num_drives: *mut c_int);
+    FMOD_System_GetCDROMDriveName(system: *mut FMOD_SYSTEM, drive: c_int, drive_name: *mut c_char,
+                                  drive_name_len: c_int, scsi_name: *mut c_char,
+                                  scsi_name_len: c_int, device_name: *mut c_char,
+                                  device_name_len: c_int);
+    FMOD_System_SetReverbProperties(system: *mut FMOD_SYSTEM, prop: *const FMOD_REVERB_PROPERTIES);
+    FMOD_System_GetReverbProperties(system: *mut FMOD_SYSTEM, prop: *mut FMOD_REVERB_PROPERTIES);
+    FMOD_System_GetReverbAmbientProperties(system: *mut FMOD_SYSTEM,
+                                           prop: *mut FMOD_REVERB_PROPERTIES);
+    FMOD_System_GetDSPClock(system: *mut FMOD_SYSTEM, hi: *mut c_uint, lo: *mut c_uint);
+    FMOD_System_GetRecordNumDrivers(system: *mut FMOD_SYSTEM, num_drivers: *mut c_int);
+    FMOD_System_GetRecordDriverInfo(system: *mut FMOD_SYSTEM, id: c_int, name: *mut c_char,
+                                    name_len: c_int, guid: *mut FMOD_GUID);
+    FMOD_System_GetRecordDriverInfoW(system: *mut FMOD_SYSTEM, id: c_int, name: *mut c_short,
+                                     name_len: c_int, guid: *mut FMOD_GUID);
+    FMOD_System_GetRecordDriverCaps(system: *mut FMOD_SYSTEM, id: c_int, caps: *mut FMOD_CAPS,
+                                    min_frequency: *mut c_int, max_frequency: *mut c_int);
+    FMOD_System_GetRecordPosition(system: *mut FMOD_SYSTEM, id: c_int, position: *mut c_uint);
+    FMOD_System_RecordStart(system: *mut FMOD_SYSTEM, id: c_int, sound: *mut FMOD_SOUND,
+                            _loop: FMOD_BOOL);
+    FMOD_System_RecordStop(system: *mut FMOD_SYSTEM, id: c_int);
+    FMOD_System_IsRecording(system: *mut FMOD_SYSTEM, id: c_int, recording: *mut FMOD_BOOL);
+    FMOD_System_SetGeometrySettings(system: *mut FMOD_SYSTEM, max_world_size: c_float);
+    FMOD_System_GetGeometrySettings(system: *mut FMOD_SYSTEM, max_world_size: *mut c_float);
+    FMOD_System_LoadGeometry(system: *mut FMOD_SYSTEM, data: *mut c_void, data_size: c_int,
+                             geometry: *mut *mut FMOD_GEOMETRY);
+    FMOD_Sound_SetVariations(sound: *mut FMOD_SOUND, frequency_var: c_float, volume_var: c_float,
+                             pan_var: c_float);
+    FMOD_Sound_GetVariations(sound: *mut FMOD_SOUND, frequency_var: *mut c_float,
+                             volume_var: *mut c_float, pan_var: *mut c_float);
+    FMOD_Sound_Set3DConeSettings(sound: *mut FMOD_SOUND, inside_cone_angle: c_float,
+                                 outside_cone_angle: c_float, outside_volume: c_float);
+    FMOD_Sound_Get3DConeSettings(sound: *mut FMOD_SOUND, inside_cone_angle: *mut c_float,
+                                 outside_cone_angle: *mut c_float, outside_volume: *mut c_float);
+    FMOD_Sound_Set3DCustomRolloff(sound: *mut FMOD_SOUND, points: *mut FMOD_VECTOR,
+                                  num_points: c_int);
+    FMOD_Sound_Get3DCustomRolloff(sound: *mut FMOD_SOUND, points: *mut *mut FMOD_VECTOR,
+                                  num_points: c_int);
+    FMOD_Sound_SetSubSound(sound: *mut FMOD_SOUND, index: c_int, sub_sound: *mut FMOD_SOUND);
+    FMOD_Sound_GetSubSound(sound: *mut FMOD_SOUND, index: c_int, sub_sound: *mut *mut FMOD_SOUND);
+    FMOD_Sound_SetSubSoundSentence(sound: *mut FMOD_SOUND, sub_sound_list: *mut c_int,
+                                   num_sub_sound: c_int);
+    FMOD_Sound_GetNumSubSounds(sound: *mut FMOD_SOUND, num_sub_sound: *mut c_int);
+    FMOD_Sound_GetNumTags(sound: *mut FMOD_SOUND, num_tags: *mut c_int,
+                          num_tags_updated: *mut c_int);
+    FMOD_Sound_GetTag(sound: *mut FMOD_SOUND, name: *const c_char, index: c_int,
+                      tag: *mut FMOD_TAG);
+    FMOD_Sound_GetOpenState(sound: *mut FMOD_SOUND, open_state: *mut ::OpenState,
+                            percent_buffered: *mut c_uint, starving: *mut FMOD_BOOL,
+                            disk_busy: *mut FMOD_BOOL);
+    FMOD_Sound_GetNumSyncPoints(sound: *mut FMOD_SOUND, num_sync_points: *mut c_int);
+    FMOD_Sound_GetSyncPoint(sound: *mut FMOD_SOUND, index: c_int, point: *mut *mut FMOD_SYNCPOINT);
+    FMOD_Sound_GetSyncPointInfo(sound: *mut FMOD_SOUND, point: *mut FMOD_SYNCPOINT,
+                                name: *mut c_char, name_len: c_int, offset: *mut c_uint,
+                                offset_type: FMOD_TIMEUNIT);
+    FMOD_Sound_AddSyncPoint(sound: *mut FMOD_SOUND, offset: c_uint, offset_type: FMOD_TIMEUNIT,
+                            name: *const c_char, point: *mut *mut FMOD_SYNCPOINT);
+    FMOD_Sound_DeleteSyncPoint(sound: *mut FMOD_SOUND, point: *mut FMOD_SYNCPOINT);
+    FMOD_Sound_SetLoopPoints(sound: *mut FMOD_SOUND, loop_start: c_uint,
+                             loop_start_type: FMOD_TIMEUNIT, loop_end: c_uint,
+                             loop_end_type: FMOD_TIMEUNIT);
+    FMOD_Sound_GetLoopPoints(sound: *mut FMOD_SOUND, loop_start: *mut c_uint,
+                             loop_start_type: FMOD_TIMEUNIT, loop_end: *mut c_uint,
+                             loop_end_type: FMOD_TIMEUNIT);
+    FMOD_Sound_GetMusicNumChannels(sound: *mut FMOD_SOUND, num_channels: *mut c_int);
+    FMOD_Sound_SetMusicChannelVolume(sound: *mut FMOD_SOUND, channel: c_int, volume: c_float);
+    FMOD_Sound_GetMusicChannelVolume(sound: *mut FMOD_SOUND, channel: c_int, volume: *mut c_float);
+    FMOD_Sound_SetMusicSpeed(sound: *mut FMOD_SOUND, speed: c_float);
+    FMOD_Sound_GetMusicSpeed(sound: *mut FMOD_SOUND, speed: *mut c_float);
+    FMOD_Sound_GetMemoryInfo(sound: *mut FMOD_SOUND, memory_bits: c_uint, event_memory_bits: c_uint,
+                             memory_used: *mut c_uint,
+                             memory_used_details: *mut FMOD_MEMORY_USAGE_DETAILS);
+    FMOD_Channel_SetDelay(channel: *mut FMOD_CHANNEL, delay_type: ::DelayType, delayhi: c_uint,
+                          delaylo: c_uint);
+    FMOD_Channel_GetDelay(channel: *mut FMOD_CHANNEL, delay_type: ::DelayType, delayhi: *mut c_uint,
+                          delaylo: *mut c_uint);
+    FMOD_Channel_SetSpeakerMix(channel: *mut FMOD_CHANNEL, front_left: c_float,
+                               front_right: c_float, center: c_float, lfe: c_float,
+                               back_left: c_float, back_right: c_float, side_left: c_float,
+                               side_right: c_float);
+    FMOD_Channel_GetSpeakerMix(channel: *mut FMOD_CHANNEL, front_left: *mut c_float,
+                               front_right: *mut c_float, center: *mut c_float, lfe: *mut c_float,
+                               back_left: *mut c_float, back_right: *mut c_float,
+                               side_left: *mut c_float, side_right: *mut c_float);
+    FMOD_Channel_SetSpeakerLevels(channel: *mut FMOD_CHANNEL, speaker: ::Speaker,
+                                  levels: *mut c_float, num_levels: c_int);
+    FMOD_Channel_GetSpeakerLevels(channel: *mut FMOD_CHANNEL, speaker: ::Speaker,
+                                  levels: *mut c_float, num_levels: c_int);
+    FMOD_Channel_SetInputChannelMix(channel: *mut FMOD_CHANNEL, levels: *mut c_float,
+                                    num_levels: c_int);
+    FMOD_Channel_GetInputChannelMix(channel: *mut FMOD_CHANNEL, levels: *mut c_float,
+                                    num_levels: c_int);
+    FMOD_Channel_SetReverbProperties(channel: *mut FMOD_CHANNEL,
+                                     prop: *const FMOD_REVERB_CHANNELPROPERTIES);
+    FMOD_Channel_GetReverbProperties(channel: *mut FMOD_CHANNEL,
+                                     prop: *mut FMOD_REVERB_CHANNELPROPERTIES);
+    FMOD_Channel_Set3DConeSettings(channel: *mut FMOD_CHANNEL, inside_cone_angle: c_float,
+                                   outside_cone_angle: c_float, outside_volume: c_float);
+    FMOD_Channel_Get3DConeSettings(channel: *mut FMOD_CHANNEL, inside_cone_angle: *mut c_float,
+                                   outside_cone_angle: *mut c_float, outside_volume: *mut c_float);
+    FMOD_Channel_Set3DConeOrientation(channel: *mut FMOD_CHANNEL, orientation: *mut FMOD_VECTOR);
+    FMOD_Channel_Get3DConeOrientation(channel: *mut FMOD_CHANNEL, orientation: *mut FMOD_VECTOR);
+    FMOD_Channel_Set3DCustomRolloff(channel: *mut FMOD_CHANNEL, points: *mut FMOD_VECTOR,
+                                    num_points: c_int);
+    FMOD_Channel_Get3DCustomRolloff(channel: *mut FMOD_CHANNEL, points: *mut *mut FMOD_VECTOR,
+                                    num_points: *mut c_int);
+    FMOD_Channel_Set3DDistanceFilter(channel: *mut FMOD_CHANNEL, custom: FMOD_BOOL,
+                                     custom_level: c_float, center_freq: c_float);
+    FMOD_Channel_Get3DDistanceFilter(channel: *mut FMOD_CHANNEL, custom: *mut FMOD_BOOL,
+                                     custom_level: *mut c_float, center_freq: *mut c_float);
+    FMOD_Channel_GetAudibility(channel: *mut FMOD_CHANNEL, audibility: *mut c_float);
+    FMOD_Channel_GetSpectrum(channel: *mut FMOD_CHANNEL, spectrum_array: *mut c_float,
+                             num_values: c_int, channel_offset: c_int, window_type: ::DspFftWindow);
+    FMOD_Channel_GetWaveData(channel: *mut FMOD_CHANNEL, wave_array: *mut c_float,
+                             num_values: c_int, channel_offset: c_int);
+    FMOD_Channel_SetLoopPoints(channel: *mut FMOD_CHANNEL, loop_start: c_uint,
+                               loop_start_type: FMOD_TIMEUNIT, loop_end: c_uint,
+                               loop_end_type: FMOD_TIMEUNIT);
+    FMOD_Channel_GetLoopPoints(channel: *mut FMOD_CHANNEL, loop_start: *mut c_uint,
+                               loop_start_type: FMOD_TIMEUNIT, loop_end: *mut c_uint,
+                               loop_end_type: FMOD_TIMEUNIT);
+    FMOD_Channel_GetMemoryInfo(channel: *mut FMOD_CHANNEL, memory_bits: c_uint,
+                               event_memory_bits: c_uint, memory_used: *mut c_uint,
+                               memoryused_details: *mut FMOD_MEMORY_USAGE_DETAILS);
+    FMOD_ChannelGroup_Set3DOcclusion(channel_group: *mut FMOD_CHANNELGROUP,
+                                     direct_occlusion: c_float, reverb_occlusion: c_float);
+    FMOD_ChannelGroup_Get3DOcclusion(channel_group: *mut FMOD_CHANNELGROUP,
+                                     direct_occlusion: *mut c_float,
+                                     reverb_occlusion: *mut c_float);
+    FMOD_ChannelGroup_OverrideVolume(channel_group: *mut FMOD_CHANNELGROUP, volume: c_float);
+    FMOD_ChannelGroup_OverrideFrequency(channel_group: *mut FMOD_CHANNELGROUP, frequency: c_float);
+    FMOD_ChannelGroup_OverridePan(channel_group: *mut FMOD_CHANNELGROUP, pan: c_float);
+    FMOD_ChannelGroup_OverrideReverbProperties(channel_group: *mut FMOD_CHANNELGROUP,
+                                               prop: *const FMOD_REVERB_CHANNELPROPERTIES);
+    FMOD_ChannelGroup_Override3DAttributes(channel_group: *mut FMOD_CHANNELGROUP,
+                                           pos: *mut FMOD_VECTOR, vel: *mut FMOD_VECTOR);
+    FMOD_ChannelGroup_OverrideSpeakerMix(channel_group: *mut FMOD_CHANNELGROUP, front_left: c_float,
+                                         front_right: c_float, center: c_float, lfe: c_float,
+                                         back_left: c_float, back_right: c_float,
+                                         side_left: c_float, side_right: c_float);
+    FMOD_ChannelGroup_GetSpectrum(channel_group: *mut FMOD_CHANNELGROUP,
+                                  spectrum_array: *mut c_float, num_values: c_int,
+                                  channel_offset: c_int, window_type: ::DspFftWindow);
+    FMOD_ChannelGroup_GetWaveData(channel_group: *mut FMOD_CHANNELGROUP, wave_array: *mut c_float,
+                                  num_values: c_int, channel_offset: c_int);
+    FMOD_ChannelGroup_GetMemoryInfo(channel_group: *mut FMOD_CHANNELGROUP, memory_bits: c_uint,
+                                    event_memory_bits: c_uint, memory_used: *mut c_uint,
+                                    memoryused_details: *mut FMOD_MEMORY_USAGE_DETAILS);
+    FMOD_SoundGroup_SetMaxAudibleBehavior(sound_group: *mut FMOD_SOUNDGROUP,
+                                          behavior: ::SoundGroupBehavior);
+    FMOD_SoundGroup_GetMaxAudibleBehavior(sound_group: *mut FMOD_SOUNDGROUP,
+                                          behavior: *mut ::SoundGroupBehavior);
+    FMOD_SoundGroup_GetMemoryInfo(sound_group: *mut FMOD_SOUNDGROUP, memory_bits: c_uint,
+                                  event_memory_bits: c_uint, memory_used: *mut c_uint,
+                                  memoryused_details: *mut FMOD_MEMORY_USAGE_DETAILS);
+    FMOD_DSP_SetSpeakerActive(dsp: *mut FMOD_DSP, speaker: ::Speaker, active: FMOD_BOOL);
+    FMOD_DSP_GetSpeakerActive(dsp: *mut FMOD_DSP, speaker: ::Speaker, active: *mut FMOD_BOOL);
+    FMOD_DSP_ShowConfigDialog(dsp: *mut FMOD_DSP, hwnd: *mut c_void, show: FMOD_BOOL);
+    FMOD_DSP_GetMemoryInfo(dsp: *mut FMOD_DSP, memory_bits: c_uint, event_memory_bits: c_uint,
+                           memory_used: *mut c_uint,
+                           memory_used_details: *mut FMOD_MEMORY_USAGE_DETAILS);
+    FMOD_DSPConnection_SetLevels(dsp_connection: *mut FMOD_DSPCONNECTION, speaker: ::Speaker,
+                                 levels: *mut c_float, num_levels: c_int);
+    FMOD_DSPConnection_GetLevels(dsp_connection: *mut FMOD_DSPCONNECTION, speaker: ::Speaker,
+                                 levels: *mut c_float, num_levels: c_int);
+    FMOD_DSPConnection_GetMemoryInfo(dsp_connection: *mut FMOD_DSPCONNECTION, memory_bits: c_uint,
+                                     event_memory_bits: c_uint, memory_used: *mut c_uint,
+                                     memory_used_details: *mut FMOD_MEMORY_USAGE_DETAILS);
+    FMOD_Geometry_Save(geometry: *mut FMOD_GEOMETRY, data: *mut c_void, data_size: *mut c_int);
+    FMOD_Geometry_SetUserData(geometry: *mut FMOD_GEOMETRY, user_data: *mut c_void);
+    FMOD_Geometry_GetUserData(geometry: *mut FMOD_GEOMETRY, user_data: *mut *mut c_void);
+    FMOD_Geometry_GetMemoryInfo(geometry: *mut FMOD_GEOMETRY, memory_bits: c_uint,
+                                event_memory_bits: c_uint, memory_used: *mut c_uint,
+                                memory_used_details: *mut FMOD_MEMORY_USAGE_DETAILS);
+    FMOD_Reverb_GetProperties(reverb: *mut FMOD_REVERB, properties: *mut FMOD_REVERB_PROPERTIES);
+    FMOD_Reverb_SetUserData(reverb: *mut FMOD_REVERB, user_data: *mut c_void);
+    FMOD_Reverb_GetUserData(reverb: *mut FMOD_REVERB, user_data: *mut *mut c_void);
+    FMOD_Reverb_GetMemoryInfo(reverb: *mut FMOD_REVERB, memory_bits: c_uint,
+                              event_memory_bits: c_uint, memory_used: *mut c_uint,
+                              memory_used_details: *mut FMOD_MEMORY_USAGE_DETAILS);
+}
@@ -0,0 +1,221 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use fmod_sys::Sys;
+use vector::Vector;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Occlusion factor exported by [`OcclusionMap::write_pgm`](struct.OcclusionMap.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OcclusionKind {
+    /// Occlusion of the direct path.
+    Direct,
+    /// Occlusion of the reverb mix.
+    Reverb,
+}
+
+/// Geometry occlusion sampled on a regular grid of source positions, for a given listener.
+///
+/// Cell (x, y, z) is at `origin + (x * step.x, y * step.y, z * step.z)`. A 2D map is a grid with
+/// a depth of 1.
+///
+/// ```ignore
+/// let listener = rfmod::Vector { x: 0f32, y: 1.8f32, z: 0f32 };
+/// let origin = rfmod::Vector { x: -50f32, y: 0f32, z: 10f32 };
+/// let step = rfmod::Vector { x: 1f32, y: 0.1f32, z: 0f32 };
+/// // 100 x 100 cells on the x/y plane 10 units in front of the listener, saved as a 100x100 image
+/// let map = rfmod::OcclusionMap::sample(&fmod, &listener, origin, step, (100, 100, 1)).unwrap();
+///
+/// map.save_pgm("occlusion.pgm", 0, rfmod::OcclusionKind::Direct).unwrap();
+/// ```
+pub struct OcclusionMap {
+    listener: Vector,
+    origin: Vector,
+    step: Vector,
+    size: (usize, usize, usize),
+    values: Vec<(f32, f32)>,
+}
+
+impl OcclusionMap {
+    /// Queries the occlusion of every cell of a `size` = (width, height, depth) grid.
+    pub fn sample(fmod: &Sys, listener: &Vector, origin: Vector, step: Vector,
+                  size: (usize, usize, usize)) -> Result<OcclusionMap, ::FmodError> {
+        let (width, height, depth) = size;
+        let mut sources = Vec::with_capacity(width * height * depth);
+
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    sources.push(cell_position(&origin, &step, x, y, z));
+                }
+            }
+        }
+        match fmod.get_geometry_occlusions(listener, &sources) {
+            Ok(values) => Ok(OcclusionMap {
+                listener: *listener,
+                origin: origin,
+                step: step,
+                size: size,
+                values: values,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn get_listener(&self) -> Vector {
+        self.listener
+    }
+
+    pub fn get_origin(&self) -> Vector {
+        self.origin
+    }
+
+    pub fn get_step(&self) -> Vector {
+        self.step
+    }
+
+    /// Returns:
+    ///
+    /// (width, height, depth)
+    pub fn get_size(&self) -> (usize, usize, usize) {
+        self.size
+    }
+
+    /// Returns the position of a cell.
+    pub fn get_position(&self, x: usize, y: usize, z: usize) -> Vector {
+        cell_position(&self.origin, &self.step, x, y, z)
+    }
+
+    /// Returns:
+    ///
+    /// Some(direct, reverb), or None if the cell is out of the map.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<(f32, f32)> {
+        let (width, height, depth) = self.size;
+
+        if x >= width || y >= height || z >= depth {
+            None
+        } else {
+            Some(self.values[(z * height + y) * width + x])
+        }
+    }
+
+    /// Writes a binary PGM image of one layer (z value) of the map: one row per y value, white
+    /// where nothing occludes and black where the occlusion is full.
+    pub fn write_pgm<W: Write>(&self, writer: &mut W, layer: usize,
+                               kind: OcclusionKind) -> io::Result<()> {
+        let (width, height, depth) = self.size;
+
+        if layer >= depth {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "layer out of the map"));
+        }
+
+        let layer_size = width * height;
+        let mut pixels = Vec::with_capacity(layer_size);
+
+        for &(direct, reverb) in self.values[layer * layer_size..(layer + 1) * layer_size].iter() {
+            let occlusion = match kind {
+                OcclusionKind::Direct => direct,
+                OcclusionKind::Reverb => reverb,
+            };
+            let occlusion = if occlusion < 0f32 {
+                0f32
+            } else if occlusion > 1f32 {
+                1f32
+            } else {
+                occlusion
+            };
+
+            pixels.push(((1f32 - occlusion) * 255f32).round() as u8);
+        }
+        match write!(writer, "P5\n{} {}\n255\n", width, height) {
+            Ok(()) => writer.write_all(&pixels),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes every cell as a `x,y,z,direct,reverb` line, after a header line. Positions are in
+    /// world units.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let (width, height, depth) = self.size;
+
+        match writeln!(writer, "x,y,z,direct,reverb") {
+            Ok(()) => {}
+            Err(e) => return Err(e),
+        }
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    let position = self.get_position(x, y, z);
+                    let (direct, reverb) = self.values[(z * height + y) * width + x];
+
+                    match writeln!(writer, "{},{},{},{},{}", position.x, position.y, position.z,
+                                   direct, reverb) {
+                        Ok(()) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as [`write_pgm`](#method.write_pgm), in the file `file_name`.
+    pub fn save_pgm(&self, file_name: &str, layer: usize, kind: OcclusionKind) -> io::Result<()> {
+        match File::create(file_name) {
+            Ok(f) => {
+                let mut writer = BufWriter::new(f);
+
+                match self.write_pgm(&mut writer, layer, kind) {
+                    Ok(()) => writer.flush(),
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Same as [`write_csv`](#method.write_csv), in the file `file_name`.
+    pub fn save_csv(&self, file_name: &str) -> io::Result<()> {
+        match File::create(file_name) {
+            Ok(f) => {
+                let mut writer = BufWriter::new(f);
+
+                match self.write_csv(&mut writer) {
+                    Ok(()) => writer.flush(),
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn cell_position(origin: &Vector, step: &Vector, x: usize, y: usize, z: usize) -> Vector {
+    Vector {
+        x: origin.x + x as f32 * step.x,
+        y: origin.y + y as f32 * step.y,
+        z: origin.z + z as f32 * step.z,
+    }
+}
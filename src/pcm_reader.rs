@@ -0,0 +1,305 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use sound::Sound;
+use byteorder::{ByteOrder, LittleEndian};
+use std::marker::PhantomData;
+
+const READ_CHUNK_SIZE : usize = 16384;
+
+/// Sample type a [`PcmReader`](struct.PcmReader.html) can convert decoded data to.
+///
+/// Integer formats are scaled to the full range of the target type, so a 24 bits sample read as
+/// i16 keeps its 16 most significant bits, and a 16 bits sample read as f32 lies in [-1.0, 1.0].
+pub trait PcmSample: Copy {
+    fn from_pcm8(sample: i8) -> Self;
+    fn from_pcm16(sample: i16) -> Self;
+    fn from_pcm24(sample: i32) -> Self;
+    fn from_pcm32(sample: i32) -> Self;
+    fn from_pcm_float(sample: f32) -> Self;
+}
+
+impl PcmSample for i16 {
+    fn from_pcm8(sample: i8) -> i16 {
+        (sample as i16) << 8
+    }
+
+    fn from_pcm16(sample: i16) -> i16 {
+        sample
+    }
+
+    fn from_pcm24(sample: i32) -> i16 {
+        (sample >> 8) as i16
+    }
+
+    fn from_pcm32(sample: i32) -> i16 {
+        (sample >> 16) as i16
+    }
+
+    fn from_pcm_float(sample: f32) -> i16 {
+        let sample = if sample > 1f32 {
+            1f32
+        } else if sample < -1f32 {
+            -1f32
+        } else {
+            sample
+        };
+
+        (sample * 32767f32) as i16
+    }
+}
+
+impl PcmSample for f32 {
+    fn from_pcm8(sample: i8) -> f32 {
+        sample as f32 / 128f32
+    }
+
+    fn from_pcm16(sample: i16) -> f32 {
+        sample as f32 / 32768f32
+    }
+
+    fn from_pcm24(sample: i32) -> f32 {
+        sample as f32 / 8388608f32
+    }
+
+    fn from_pcm32(sample: i32) -> f32 {
+        sample as f32 / 2147483648f32
+    }
+
+    fn from_pcm_float(sample: f32) -> f32 {
+        sample
+    }
+}
+
+/// Returns the size in bytes of one sample of the given format, or None if the format isn't a
+/// PCM one.
+pub fn bytes_per_sample(format: ::SoundFormat) -> Option<usize> {
+    match format {
+        ::SoundFormat::PCM8 => Some(1),
+        ::SoundFormat::PCM16 => Some(2),
+        ::SoundFormat::PCM24 => Some(3),
+        ::SoundFormat::PCM32 | ::SoundFormat::PCMFloat => Some(4),
+        _ => None,
+    }
+}
+
+/// Decodes the PCM data of a [`Sound`](struct.Sound.html) without playing it, converting every
+/// sample to `T`.
+///
+/// The sound has to be created with [`OPENONLY`](constant.OPENONLY.html) (and usually
+/// [`CREATESTREAM`](constant.CREATESTREAM.html)) so FMOD lets
+/// [`Sound::read_data`](struct.Sound.html#method.read_data) decode it. Samples are interleaved, so
+/// a stereo sound yields left, right, left, right...
+///
+/// ```ignore
+/// let mode = rfmod::Mode(rfmod::OPENONLY | rfmod::CREATESTREAM);
+/// let sound = fmod.create_sound("music.ogg", Some(mode), None).unwrap();
+/// let reader : rfmod::PcmReader<f32> = rfmod::PcmReader::new(&sound).unwrap();
+/// let peak = reader.fold(0f32, |peak, sample| peak.max(sample.abs()));
+/// ```
+pub struct PcmReader<'a, T> {
+    sound: &'a Sound,
+    format: ::SoundFormat,
+    channels: i32,
+    sample_size: usize,
+    remaining: Option<usize>,
+    buffer: Vec<u8>,
+    start: usize,
+    end: usize,
+    error: Option<::FmodError>,
+    sample_type: PhantomData<T>,
+}
+
+impl<'a, T: PcmSample> PcmReader<'a, T> {
+    /// Creates a reader decoding the whole sound, from its beginning.
+    pub fn new(sound: &'a Sound) -> Result<PcmReader<'a, T>, ::FmodError> {
+        let reader = match PcmReader::create(sound) {
+            Ok(r) => r,
+            Err(e) => return Err(e),
+        };
+
+        match sound.seek_data(0) {
+            Ok(()) => Ok(reader),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Creates a reader decoding `length` PCM samples (per channel) starting at PCM sample
+    /// `start`.
+    pub fn with_range(sound: &'a Sound, start: u32, length: u32)
+                      -> Result<PcmReader<'a, T>, ::FmodError> {
+        let mut reader = match PcmReader::create(sound) {
+            Ok(r) => r,
+            Err(e) => return Err(e),
+        };
+
+        reader.remaining = Some(length as usize * reader.channels as usize * reader.sample_size);
+        match sound.seek_data(start) {
+            Ok(()) => Ok(reader),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn create(sound: &'a Sound) -> Result<PcmReader<'a, T>, ::FmodError> {
+        let (format, channels) = match sound.get_format() {
+            Ok((_, format, channels, _)) => (format, channels),
+            Err(e) => return Err(e),
+        };
+        let sample_size = match bytes_per_sample(format) {
+            Some(s) => s,
+            None => return Err(::FmodError::new("PcmReader::new", ::Status::Format)),
+        };
+
+        Ok(PcmReader {
+            sound: sound,
+            format: format,
+            channels: channels,
+            sample_size: sample_size,
+            remaining: None,
+            buffer: vec![0u8; READ_CHUNK_SIZE - READ_CHUNK_SIZE % sample_size],
+            start: 0,
+            end: 0,
+            error: None,
+            sample_type: PhantomData,
+        })
+    }
+
+    /// Format of the decoded data, before conversion to `T`.
+    pub fn get_format(&self) -> ::SoundFormat {
+        self.format
+    }
+
+    /// Number of interleaved channels.
+    pub fn get_channels(&self) -> i32 {
+        self.channels
+    }
+
+    /// Returns the error which stopped the iteration, if any.
+    pub fn get_error(&self) -> Option<::FmodError> {
+        self.error
+    }
+
+    /// Fills `out` with converted samples and returns how many were written. Ok(0) means the end
+    /// of the sound (or of the requested range) has been reached.
+    pub fn read(&mut self, out: &mut [T]) -> Result<usize, ::FmodError> {
+        let mut written = 0usize;
+
+        while written < out.len() {
+            if self.end - self.start < self.sample_size {
+                match self.fill() {
+                    Ok(0) => break,
+                    Ok(_) => {}
+                    Err(e) => {
+                        self.error = Some(e);
+                        return Err(e);
+                    }
+                }
+            }
+            while written < out.len() && self.end - self.start >= self.sample_size {
+                out[written] = self.convert(self.start);
+                self.start += self.sample_size;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+
+    /// Reads and converts every remaining sample.
+    pub fn read_to_end(&mut self) -> Result<Vec<T>, ::FmodError> {
+        let mut out = Vec::new();
+
+        while let Some(sample) = self.next() {
+            out.push(sample);
+        }
+        match self.error {
+            None => Ok(out),
+            Some(e) => Err(e),
+        }
+    }
+
+    fn fill(&mut self) -> Result<usize, ::FmodError> {
+        let left = self.end - self.start;
+
+        for it in 0..left {
+            self.buffer[it] = self.buffer[self.start + it];
+        }
+        self.start = 0;
+        self.end = left;
+
+        let mut to_read = self.buffer.len() - left;
+        if let Some(remaining) = self.remaining {
+            if remaining < to_read {
+                to_read = remaining;
+            }
+        }
+        if to_read == 0 {
+            return Ok(0);
+        }
+
+        let read = match self.sound.read_data(&mut self.buffer[left..left + to_read]) {
+            Ok(r) => r as usize,
+            Err(e) => return Err(e),
+        };
+
+        self.end += read;
+        if let Some(ref mut remaining) = self.remaining {
+            *remaining -= read;
+        }
+        Ok(read)
+    }
+
+    fn convert(&self, pos: usize) -> T {
+        let data = &self.buffer[pos..pos + self.sample_size];
+
+        match self.format {
+            ::SoundFormat::PCM8 => T::from_pcm8(data[0] as i8),
+            ::SoundFormat::PCM16 => T::from_pcm16(LittleEndian::read_i16(data)),
+            ::SoundFormat::PCM24 => T::from_pcm24(LittleEndian::read_int(data, 3) as i32),
+            ::SoundFormat::PCM32 => T::from_pcm32(LittleEndian::read_i32(data)),
+            _ => T::from_pcm_float(LittleEndian::read_f32(data)),
+        }
+    }
+}
+
+impl<'a, T: PcmSample> Iterator for PcmReader<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.end - self.start < self.sample_size {
+            match self.fill() {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => {
+                    self.error = Some(e);
+                    return None;
+                }
+            }
+        }
+
+        let sample = self.convert(self.start);
+
+        self.start += self.sample_size;
+        Some(sample)
+    }
+}
@@ -27,7 +27,8 @@ use types::*;
 use vector;
 use reverb_properties;
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
+use fmod_sys::{MemoryUsageDetails, SystemRef};
+use std::sync::Arc;
 use std::mem::transmute;
 use libc::{c_void};
 use std::default::Default;
@@ -35,17 +36,23 @@ use std::default::Default;
 /// Reverb object
 pub struct Reverb {
     reverb: *mut ffi::FMOD_REVERB,
+    system_ref: Option<Arc<SystemRef>>
+}
+
+/// FMOD can't tell the system of a reverb, so it is given when the reverb is created.
+pub fn from_ptr_first(reverb: *mut ffi::FMOD_REVERB, system_ref: Option<Arc<SystemRef>>) -> Reverb {
+    Reverb {reverb: reverb, system_ref: system_ref}
 }
 
 impl Drop for Reverb {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl ffi::FFI<ffi::FMOD_REVERB> for Reverb {
     fn wrap(r: *mut ffi::FMOD_REVERB) -> Reverb {
-        Reverb {reverb: r}
+        Reverb {reverb: r, system_ref: None}
     }
 
     fn unwrap(r: &Reverb) -> *mut ffi::FMOD_REVERB {
@@ -54,29 +61,35 @@ impl ffi::FFI<ffi::FMOD_REVERB> for Reverb {
 }
 
 impl Reverb {
-    pub fn release(&mut self) -> ::Status {
+    pub fn release(&mut self) -> Result<(), ::FmodError> {
         if self.reverb !=::std::ptr::null_mut() {
             match unsafe { ffi::FMOD_Reverb_Release(self.reverb) } {
                 ::Status::Ok => {
                     self.reverb = ::std::ptr::null_mut();
-                    ::Status::Ok
+                    Ok(())
                 }
-                e => e,
+                e => Err(::FmodError::new("FMOD_Reverb_Release", e)),
             }
         } else {
-            ::Status::Ok
+            Ok(())
         }
     }
 
     pub fn set_3D_attributes(&self, position: vector::Vector, min_distance: f32,
-                             max_distance: f32) -> ::Status {
+                             max_distance: f32) -> Result<(), ::FmodError> {
         let t_position = vector::get_ffi(&position);
 
-        unsafe { ffi::FMOD_Reverb_Set3DAttributes(self.reverb, &t_position, min_distance,
-                                                  max_distance) }
+        match unsafe { ffi::FMOD_Reverb_Set3DAttributes(self.reverb, &t_position, min_distance,
+                                                        max_distance) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Reverb_Set3DAttributes", e)),
+
+        }
     }
 
-    pub fn get_3D_attributes(&self) -> Result<(vector::Vector, f32, f32), ::Status> {
+    pub fn get_3D_attributes(&self) -> Result<(vector::Vector, f32, f32), ::FmodError> {
         let mut position = vector::get_ffi(&vector::Vector::new());
         let mut min_distance = 0f32;
         let mut max_distance = 0f32;
@@ -84,43 +97,55 @@ impl Reverb {
         match unsafe { ffi::FMOD_Reverb_Get3DAttributes(self.reverb, &mut position,
                                                         &mut min_distance, &mut max_distance) } {
             ::Status::Ok => Ok((vector::from_ptr(position), min_distance, max_distance)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Reverb_Get3DAttributes", e)),
         }
     }
 
     pub fn set_properties(&self,
-                          reverb_properties: reverb_properties::ReverbProperties) -> ::Status {
+                          reverb_properties: reverb_properties::ReverbProperties) -> Result<(), ::FmodError> {
         let t_reverb_properties = reverb_properties::get_ffi(reverb_properties);
 
-        unsafe { ffi::FMOD_Reverb_SetProperties(self.reverb, &t_reverb_properties) }
+        match unsafe { ffi::FMOD_Reverb_SetProperties(self.reverb, &t_reverb_properties) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Reverb_SetProperties", e)),
+
+        }
     }
 
     pub fn get_properties(&self, reverb_properties: reverb_properties::ReverbProperties)
-                          -> Result<reverb_properties::ReverbProperties, ::Status> {
+                          -> Result<reverb_properties::ReverbProperties, ::FmodError> {
         let mut t_reverb_properties = reverb_properties::get_ffi(reverb_properties);
 
         match unsafe { ffi::FMOD_Reverb_GetProperties(self.reverb, &mut t_reverb_properties) } {
             ::Status::Ok => Ok(reverb_properties::from_ptr(t_reverb_properties)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Reverb_GetProperties", e)),
         }
     }
 
-    pub fn set_active(&self, active: bool) -> ::Status {
+    pub fn set_active(&self, active: bool) -> Result<(), ::FmodError> {
         let t_active = if active == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_Reverb_SetActive(self.reverb, t_active) }
+        match unsafe { ffi::FMOD_Reverb_SetActive(self.reverb, t_active) } {
+
+            ::Status::Ok => Ok(()),
+
+            e => Err(::FmodError::new("FMOD_Reverb_SetActive", e)),
+
+        }
     }
 
-    pub fn get_active(&self) -> Result<bool, ::Status> {
+    pub fn get_active(&self) -> Result<bool, ::FmodError> {
         let mut active = 0i32;
 
         match unsafe { ffi::FMOD_Reverb_GetActive(self.reverb, &mut active) } {
             ::Status::Ok => Ok(active == 1),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Reverb_GetActive", e))
         }
     }
 
@@ -129,32 +154,38 @@ impl Reverb {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::FmodError> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_Reverb_GetMemoryInfo(self.reverb, memory_bits, event_memory_bits,
                                                       &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Reverb_GetMemoryInfo", e))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_Reverb_SetUserData(self.reverb, transmute(user_data)) }
+    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Reverb_SetUserData(self.reverb, transmute(user_data)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Reverb_SetUserData", e)),
+        }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
+    /// Returns None if no user data has been set.
+    pub fn get_user_data<'r, T>(&'r self) -> Result<Option<&'r mut T>, ::FmodError> {
         unsafe {
             let mut user_data : *mut c_void = ::std::ptr::null_mut();
 
             match ffi::FMOD_Reverb_GetUserData(self.reverb, &mut user_data) {
                 ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
+                    if user_data.is_null() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(transmute::<*mut c_void, &mut T>(user_data)))
+                    }
                 },
-                e => Err(e)
+                e => Err(::FmodError::new("FMOD_Reverb_GetUserData", e))
             }
         }
     }
@@ -76,7 +76,8 @@ pub fn get_ffi(reverb: ReverbProperties) -> ffi::FMOD_REVERB_PROPERTIES {
 }
 
 /// Structure defining a reverb environment.
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ReverbProperties {
     /// [w]   Min: 0 - Max: 3 - Default: 0 - Environment Instance. (SUPPORTED:SFX(4 instances) and Wii (3 instances))
     pub instance         : i32,
@@ -146,3 +147,208 @@ impl Default for ReverbProperties {
         }
     }
 }
+
+macro_rules! reverb_preset(
+    ($(#[$attr:meta])* fn $name:ident($instance:expr, $environment:expr, $env_diffusion:expr,
+                                      $room:expr, $room_HF:expr, $room_LF:expr,
+                                      $decay_time:expr, $decay_HF_ratio:expr,
+                                      $decay_LF_ratio:expr, $reflections:expr,
+                                      $reflections_delay:expr, $reverb:expr, $reverb_delay:expr,
+                                      $modulation_time:expr, $modulation_depth:expr,
+                                      $HF_reference:expr, $LF_reference:expr, $diffusion:expr,
+                                      $density:expr, $flags:expr)) => (
+        $(#[$attr])*
+        pub fn $name() -> ReverbProperties {
+            ReverbProperties {
+                instance: $instance,
+                environment: $environment,
+                env_diffusion: $env_diffusion,
+                room: $room,
+                room_HF: $room_HF,
+                room_LF: $room_LF,
+                decay_time: $decay_time,
+                decay_HF_ratio: $decay_HF_ratio,
+                decay_LF_ratio: $decay_LF_ratio,
+                reflections: $reflections,
+                reflections_delay: $reflections_delay,
+                reverb: $reverb,
+                reverb_delay: $reverb_delay,
+                modulation_time: $modulation_time,
+                modulation_depth: $modulation_depth,
+                HF_reference: $HF_reference,
+                LF_reference: $LF_reference,
+                diffusion: $diffusion,
+                density: $density,
+                flags: $flags,
+            }
+        }
+    )
+);
+
+/// Presets of the FMOD_PRESET_* table, except the PSP only ones.
+impl ReverbProperties {
+    reverb_preset!(fn off(0, -1, 1.00, -10000, -10000, 0, 1.00, 1.00, 1.0, -2602, 0.007, 200, 0.011,
+                          0.25, 0.000, 5000.0, 250.0, 0.0, 0.0, 0x33f));
+    reverb_preset!(fn generic(0, 0, 1.00, -1000, -100, 0, 1.49, 0.83, 1.0, -2602, 0.007, 200, 0.011,
+                              0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn padded_cell(0, 1, 1.00, -1000, -6000, 0, 0.17, 0.10, 1.0, -1204, 0.001, 207,
+                                  0.002, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn room(0, 2, 1.00, -1000, -454, 0, 0.40, 0.83, 1.0, -1646, 0.002, 53, 0.003,
+                           0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn bathroom(0, 3, 1.00, -1000, -1200, 0, 1.49, 0.54, 1.0, -370, 0.007, 1030,
+                               0.011, 0.25, 0.000, 5000.0, 250.0, 100.0, 60.0, 0x3f));
+    reverb_preset!(fn living_room(0, 4, 1.00, -1000, -6000, 0, 0.50, 0.10, 1.0, -1376, 0.003, -1104,
+                                  0.004, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn stone_room(0, 5, 1.00, -1000, -300, 0, 2.31, 0.64, 1.0, -711, 0.012, 83,
+                                 0.017, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn auditorium(0, 6, 1.00, -1000, -476, 0, 4.32, 0.59, 1.0, -789, 0.020, -289,
+                                 0.030, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn concert_hall(0, 7, 1.00, -1000, -500, 0, 3.92, 0.70, 1.0, -1230, 0.020, -2,
+                                   0.029, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn cave(0, 8, 1.00, -1000, 0, 0, 2.91, 1.30, 1.0, -602, 0.015, -302, 0.022, 0.25,
+                           0.000, 5000.0, 250.0, 100.0, 100.0, 0x1f));
+    reverb_preset!(fn arena(0, 9, 1.00, -1000, -698, 0, 7.24, 0.33, 1.0, -1166, 0.020, 16, 0.030,
+                            0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn hangar(0, 10, 1.00, -1000, -1000, 0, 10.05, 0.23, 1.0, -602, 0.020, 198,
+                             0.030, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn carpetted_hallway(0, 11, 1.00, -1000, -4000, 0, 0.30, 0.10, 1.0, -1831, 0.002,
+                                        -1630, 0.030, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0,
+                                        0x3f));
+    reverb_preset!(fn hallway(0, 12, 1.00, -1000, -300, 0, 1.49, 0.59, 1.0, -1219, 0.007, 441,
+                              0.011, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn stone_corridor(0, 13, 1.00, -1000, -237, 0, 2.70, 0.79, 1.0, -1214, 0.013,
+                                     395, 0.020, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn alley(0, 14, 0.30, -1000, -270, 0, 1.49, 0.86, 1.0, -1204, 0.007, -4, 0.011,
+                            0.125, 0.950, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn forest(0, 15, 0.30, -1000, -3300, 0, 1.49, 0.54, 1.0, -2560, 0.162, -229,
+                             0.088, 0.125, 1.000, 5000.0, 250.0, 79.0, 100.0, 0x3f));
+    reverb_preset!(fn city(0, 16, 0.50, -1000, -800, 0, 1.49, 0.67, 1.0, -2273, 0.007, -1691, 0.011,
+                           0.25, 0.000, 5000.0, 250.0, 50.0, 100.0, 0x3f));
+    reverb_preset!(fn mountains(0, 17, 0.27, -1000, -2500, 0, 1.49, 0.21, 1.0, -2780, 0.300, -1434,
+                                0.100, 0.25, 0.000, 5000.0, 250.0, 27.0, 100.0, 0x1f));
+    reverb_preset!(fn quarry(0, 18, 1.00, -1000, -1000, 0, 1.49, 0.83, 1.0, -10000, 0.061, 500,
+                             0.025, 0.125, 0.700, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+    reverb_preset!(fn plain(0, 19, 0.21, -1000, -2000, 0, 1.49, 0.50, 1.0, -2466, 0.179, -1926,
+                            0.100, 0.25, 0.000, 5000.0, 250.0, 21.0, 100.0, 0x3f));
+    reverb_preset!(fn parking_lot(0, 20, 1.00, -1000, 0, 0, 1.65, 1.50, 1.0, -1363, 0.008, -1153,
+                                  0.012, 0.25, 0.000, 5000.0, 250.0, 100.0, 100.0, 0x1f));
+    reverb_preset!(fn sewer_pipe(0, 21, 0.80, -1000, -1000, 0, 2.81, 0.14, 1.0, 429, 0.014, 1023,
+                                 0.021, 0.25, 0.000, 5000.0, 250.0, 80.0, 60.0, 0x3f));
+    reverb_preset!(fn underwater(0, 22, 1.00, -1000, -4000, 0, 1.49, 0.10, 1.0, -449, 0.007, 1700,
+                                 0.011, 1.18, 0.348, 5000.0, 250.0, 100.0, 100.0, 0x3f));
+
+    /// Interpolates between `a` and `b`, `t` being clamped between 0 (`a`) and 1 (`b`). Levels
+    /// are interpolated in millibels, reference frequencies on a logarithmic scale and the other
+    /// values linearly. `instance`, `environment` and `flags` can't be mixed: they switch from
+    /// `a` to `b` when `t` reaches 0.5.
+    ///
+    /// ```ignore
+    /// let t = (distance_in_cave / 10f32).min(1f32);
+    ///
+    /// fmod.set_reverb_properties(rfmod::ReverbProperties::lerp(
+    ///     &rfmod::ReverbProperties::hallway(), &rfmod::ReverbProperties::cave(), t)).unwrap();
+    /// ```
+    pub fn lerp(a: &ReverbProperties, b: &ReverbProperties, t: f32) -> ReverbProperties {
+        let t = if t < 0f32 { 0f32 } else if t > 1f32 { 1f32 } else { t };
+        let linear = |a: f32, b: f32| a + (b - a) * t;
+        let level = |a: i32, b: i32| linear(a as f32, b as f32).round() as i32;
+        let frequency = |a: f32, b: f32| {
+            if a > 0f32 && b > 0f32 {
+                a * (b / a).powf(t)
+            } else {
+                linear(a, b)
+            }
+        };
+        let switched = if t < 0.5f32 { a } else { b };
+
+        ReverbProperties {
+            instance: switched.instance,
+            environment: switched.environment,
+            env_diffusion: linear(a.env_diffusion, b.env_diffusion),
+            room: level(a.room, b.room),
+            room_HF: level(a.room_HF, b.room_HF),
+            room_LF: level(a.room_LF, b.room_LF),
+            decay_time: linear(a.decay_time, b.decay_time),
+            decay_HF_ratio: linear(a.decay_HF_ratio, b.decay_HF_ratio),
+            decay_LF_ratio: linear(a.decay_LF_ratio, b.decay_LF_ratio),
+            reflections: level(a.reflections, b.reflections),
+            reflections_delay: linear(a.reflections_delay, b.reflections_delay),
+            reverb: level(a.reverb, b.reverb),
+            reverb_delay: linear(a.reverb_delay, b.reverb_delay),
+            modulation_time: linear(a.modulation_time, b.modulation_time),
+            modulation_depth: linear(a.modulation_depth, b.modulation_depth),
+            HF_reference: frequency(a.HF_reference, b.HF_reference),
+            LF_reference: frequency(a.LF_reference, b.LF_reference),
+            diffusion: linear(a.diffusion, b.diffusion),
+            density: linear(a.density, b.density),
+            flags: switched.flags,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReverbProperties;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+
+    fn properties(environment: i32, HF_reference: f32, LF_reference: f32, flags: u32)
+                  -> ReverbProperties {
+        ReverbProperties {
+            instance: environment,
+            environment: environment,
+            room: -1000 * environment,
+            decay_time: environment as f32,
+            HF_reference: HF_reference,
+            LF_reference: LF_reference,
+            flags: flags,
+            ..ReverbProperties::generic()
+        }
+    }
+
+    #[test]
+    fn lerp_clamps_t() {
+        let a = properties(1, 1000f32, 100f32, 0x1f);
+        let b = properties(3, 4000f32, 400f32, 0x3f);
+
+        let below = ReverbProperties::lerp(&a, &b, -1f32);
+        let above = ReverbProperties::lerp(&a, &b, 2f32);
+
+        assert_eq!((below.environment, below.room, below.flags), (1, -1000, 0x1f));
+        assert_close(below.decay_time, 1f32);
+        assert_close(below.HF_reference, 1000f32);
+        assert_close(below.LF_reference, 100f32);
+        assert_eq!((above.environment, above.room, above.flags), (3, -3000, 0x3f));
+        assert_close(above.decay_time, 3f32);
+        assert_close(above.HF_reference, 4000f32);
+        assert_close(above.LF_reference, 400f32);
+    }
+
+    #[test]
+    fn lerp_switches_at_the_midpoint() {
+        let a = properties(1, 1000f32, 100f32, 0x1f);
+        let b = properties(3, 4000f32, 400f32, 0x3f);
+        let before = ReverbProperties::lerp(&a, &b, 0.49f32);
+        let after = ReverbProperties::lerp(&a, &b, 0.5f32);
+
+        assert_eq!((before.instance, before.environment, before.flags), (1, 1, 0x1f));
+        assert_eq!((after.instance, after.environment, after.flags), (3, 3, 0x3f));
+        assert_eq!(after.room, -2000);
+        assert_close(after.decay_time, 2f32);
+    }
+
+    #[test]
+    fn lerp_interpolates_reference_frequencies_on_a_log_scale() {
+        let a = properties(1, 1000f32, 100f32, 0x1f);
+        let b = properties(3, 4000f32, 400f32, 0x3f);
+        let middle = ReverbProperties::lerp(&a, &b, 0.5f32);
+        let quarter = ReverbProperties::lerp(&a, &b, 0.25f32);
+
+        assert_close(middle.HF_reference, 2000f32);
+        assert_close(middle.LF_reference, 200f32);
+        assert_close(quarter.HF_reference, 1000f32 * 2f32.sqrt());
+        assert_close(quarter.LF_reference, 100f32 * 2f32.sqrt());
+    }
+}
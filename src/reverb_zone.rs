@@ -0,0 +1,287 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use fmod_sys::{self, Sys};
+use reverb::Reverb;
+use reverb_properties::ReverbProperties;
+use vector::Vector;
+use std::cmp::Ordering;
+
+/// Spherical reverb zone of a [`ReverbZoneManager`](struct.ReverbZoneManager.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReverbZone {
+    pub position: Vector,
+    /// Distance under which the zone is fully applied.
+    pub min_distance: f32,
+    /// Distance beyond which the zone has no effect.
+    pub max_distance: f32,
+    pub properties: ReverbProperties,
+    /// Zones with a higher priority are activated first, zones of the same priority are activated
+    /// from the nearest one.
+    pub priority: i32,
+}
+
+impl ReverbZone {
+    pub fn new(position: Vector, min_distance: f32, max_distance: f32,
+               properties: ReverbProperties) -> ReverbZone {
+        ReverbZone {
+            position: position,
+            min_distance: min_distance,
+            max_distance: max_distance,
+            properties: properties,
+            priority: 0,
+        }
+    }
+
+    /// Distance between `position` and the center of the zone.
+    pub fn get_distance(&self, position: &Vector) -> f32 {
+        let x = position.x - self.position.x;
+        let y = position.y - self.position.y;
+        let z = position.z - self.position.z;
+
+        (x * x + y * y + z * z).sqrt()
+    }
+}
+
+/// Identifies a zone of a [`ReverbZoneManager`](struct.ReverbZoneManager.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReverbZoneId(usize);
+
+struct ZoneEntry {
+    id: ReverbZoneId,
+    zone: ReverbZone,
+    /// Only the active zones have a reverb.
+    reverb: Option<Reverb>,
+    /// The zone has been changed since its reverb was set up.
+    changed: bool,
+}
+
+/// Owns reverb zones and only gives a reverb to the ones in range of the listener, up to
+/// `max_active` of them. FMOD mixes the reverbs of the active zones, and uses the ambient
+/// properties where none of them applies.
+///
+/// ```ignore
+/// let mut zones = rfmod::ReverbZoneManager::new(&fmod, 4).unwrap();
+///
+/// zones.set_ambient_properties(rfmod::ReverbProperties::plain()).unwrap();
+/// zones.add_zone(rfmod::ReverbZone::new(cave_entrance, 5f32, 20f32,
+///                                       rfmod::ReverbProperties::cave()));
+/// loop {
+///     let (position, _, _, _) = fmod.get_3D_listener_attributes(0).unwrap();
+///
+///     zones.update(&position).unwrap();
+///     fmod.update();
+/// }
+/// ```
+pub struct ReverbZoneManager {
+    system: Sys,
+    zones: Vec<ZoneEntry>,
+    next_id: usize,
+    max_active: usize,
+    active: Vec<ReverbZoneId>,
+    ambient: ReverbProperties,
+}
+
+impl ReverbZoneManager {
+    /// Sets the ambient properties of `system` to `ReverbProperties::off()`, the value returned
+    /// by [`get_ambient_properties`](#method.get_ambient_properties) until it is changed.
+    pub fn new(system: &Sys, max_active: usize) -> Result<ReverbZoneManager, ::FmodError> {
+        let ambient = ReverbProperties::off();
+
+        match system.set_reverb_ambient_properties(ambient) {
+            Ok(()) => Ok(ReverbZoneManager {
+                system: fmod_sys::share(system),
+                zones: Vec::new(),
+                next_id: 0,
+                max_active: max_active,
+                active: Vec::new(),
+                ambient: ambient,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The zone is only activated by the next [`update`](#method.update).
+    pub fn add_zone(&mut self, zone: ReverbZone) -> ReverbZoneId {
+        let id = ReverbZoneId(self.next_id);
+
+        self.next_id += 1;
+        self.zones.push(ZoneEntry { id: id, zone: zone, reverb: None, changed: true });
+        id
+    }
+
+    /// Removes the zone and releases its reverb. Returns None if there is no such zone.
+    pub fn remove_zone(&mut self, id: ReverbZoneId) -> Option<ReverbZone> {
+        match self.zones.iter().position(|e| e.id == id) {
+            Some(pos) => {
+                self.active.retain(|&a| a != id);
+                Some(self.zones.remove(pos).zone)
+            }
+            None => None,
+        }
+    }
+
+    pub fn get_zone(&self, id: ReverbZoneId) -> Option<&ReverbZone> {
+        self.zones.iter().find(|e| e.id == id).map(|e| &e.zone)
+    }
+
+    /// Replaces the zone, its reverb is changed by the next [`update`](#method.update). Returns
+    /// false if there is no such zone.
+    pub fn set_zone(&mut self, id: ReverbZoneId, zone: ReverbZone) -> bool {
+        match self.zones.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.zone = zone;
+                entry.changed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_zones(&self) -> Vec<(ReverbZoneId, &ReverbZone)> {
+        self.zones.iter().map(|e| (e.id, &e.zone)).collect()
+    }
+
+    /// Takes effect on the next [`update`](#method.update).
+    pub fn set_max_active(&mut self, max_active: usize) {
+        self.max_active = max_active;
+    }
+
+    pub fn get_max_active(&self) -> usize {
+        self.max_active
+    }
+
+    /// Sets the properties used where no zone applies, see
+    /// [`Sys::set_reverb_ambient_properties`](struct.Sys.html#method.set_reverb_ambient_properties).
+    pub fn set_ambient_properties(&mut self,
+                                  properties: ReverbProperties) -> Result<(), ::FmodError> {
+        match self.system.set_reverb_ambient_properties(properties) {
+            Ok(()) => {
+                self.ambient = properties;
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn get_ambient_properties(&self) -> ReverbProperties {
+        self.ambient
+    }
+
+    /// Zones contributing to the reverb since the last [`update`](#method.update), by priority
+    /// then distance.
+    pub fn get_active_zones(&self) -> &[ReverbZoneId] {
+        &self.active
+    }
+
+    pub fn is_active(&self, id: ReverbZoneId) -> bool {
+        self.active.contains(&id)
+    }
+
+    /// Activates the zones in range of `listener`, releasing the reverbs of the zones which
+    /// aren't anymore. If there are more than `max_active` zones in range, the ones with the
+    /// highest priority, then the nearest ones, are activated.
+    ///
+    /// On error, the zones out of range have been released and
+    /// [`get_active_zones`](#method.get_active_zones) lists the zones in range which have a
+    /// reverb: the ones active before, and the ones activated before the failing call. The next
+    /// `update` activates the others.
+    pub fn update(&mut self, listener: &Vector) -> Result<(), ::FmodError> {
+        let mut in_range : Vec<(usize, f32)> = self.zones.iter().enumerate().filter_map(|(i, e)| {
+            let distance = e.zone.get_distance(listener);
+
+            if distance < e.zone.max_distance {
+                Some((i, distance))
+            } else {
+                None
+            }
+        }).collect();
+
+        {
+            let zones = &self.zones;
+
+            in_range.sort_by(|&(a, da), &(b, db)| {
+                match zones[b].zone.priority.cmp(&zones[a].zone.priority) {
+                    Ordering::Equal => da.partial_cmp(&db).unwrap_or(Ordering::Equal),
+                    o => o,
+                }
+            });
+        }
+        in_range.truncate(self.max_active);
+
+        for (i, entry) in self.zones.iter_mut().enumerate() {
+            if entry.reverb.is_some() && !in_range.iter().any(|&(j, _)| j == i) {
+                // dropping the reverb releases it
+                entry.reverb = None;
+            }
+        }
+        let mut result = Ok(());
+
+        for &(i, _) in in_range.iter() {
+            let entry = &mut self.zones[i];
+
+            if entry.reverb.is_none() {
+                match self.system.create_reverb() {
+                    Ok(reverb) => {
+                        entry.reverb = Some(reverb);
+                        entry.changed = true;
+                    }
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }
+            if entry.changed {
+                match set_up(entry.reverb.as_ref().unwrap(), &entry.zone) {
+                    Ok(()) => entry.changed = false,
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }
+        }
+        // built from the reverbs rather than while creating them, so that it is still right
+        // after an error
+        self.active = in_range.iter()
+                              .filter(|&&(i, _)| self.zones[i].reverb.is_some())
+                              .map(|&(i, _)| self.zones[i].id)
+                              .collect();
+        result
+    }
+}
+
+fn set_up(reverb: &Reverb, zone: &ReverbZone) -> Result<(), ::FmodError> {
+    match reverb.set_3D_attributes(zone.position, zone.min_distance, zone.max_distance) {
+        Ok(()) => {}
+        Err(e) => return Err(e),
+    }
+    match reverb.set_properties(zone.properties) {
+        Ok(()) => {}
+        Err(e) => return Err(e),
+    }
+    reverb.set_active(true)
+}
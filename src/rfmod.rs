@@ -70,8 +70,8 @@ fn main() {
     };
 
     match fmod.init() {
-        rfmod::Status::Ok => {}
-        e => {
+        Ok(()) => {}
+        Err(e) => {
             panic!("Sys.init failed : {}", e);
         }
     };
@@ -84,10 +84,10 @@ fn main() {
     };
 
     match sound.play_to_the_end() {
-        rfmod::Status::Ok => {
+        Ok(()) => {
             println!("Ok !");
         }
-        err => {
+        Err(err) => {
             panic!("Error code : {}", err);
         }
     };
@@ -98,15 +98,18 @@ For a more complete example : https://github.com/GuillaumeGomez/rust-music-playe
 
 ##License
 
-    Copyright (c) 2014 Guillaume Gomez
+```text
+Copyright (c) 2014 Guillaume Gomez
 
-    The license of this project is available in the LICENSE.TXT file. Please refer to it.
-    If you want more information, here is the website for FMOD : http://www.fmod.org/
+The license of this project is available in the LICENSE.TXT file. Please refer to it.
+If you want more information, here is the website for FMOD : http://www.fmod.org/
+```
 
 #Notes
 
  * Members marked with [r] mean the variable is modified by FMOD and is for reading purposes only. Do not change this value.
  * Members marked with [w] mean the variable can be written to. The user can set the value.
+ * Objects created from a `Sys` (sounds, channels, DSPs, groups...) keep its system alive: `Sys::release` fails while some of them still exist, and dropping the `Sys` releases the system once the last one is dropped.
 
 
 Here is the list of all modules :
@@ -124,21 +127,37 @@ Here is the list of all modules :
 extern crate libc;
 extern crate c_vec;
 extern crate byteorder;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
 
 pub use channel::{
     Channel,
+    ChannelEvent,
     SpeakerMixOptions,
     ReverbChannelProperties
 };
+pub use channel_handle::ChannelHandle;
+pub use shared_sys::{
+    SharedSys,
+    CommandQueue,
+    SharedObjects,
+    Response,
+    SoundId,
+    ChannelId
+};
 pub use fmod_sys::{
     Sys,
+    MAX_FILE_SYSTEMS,
     Guid,
     SoftwareFormat,
     AdvancedSettings,
     OutputHandle,
     CreateSoundexInfo,
     MemoryUsageDetails,
-    UserData
+    UserData,
+    SystemEvent,
+    FmodCodecDescription
 };
 pub use sound::{
     Sound,
@@ -155,15 +174,86 @@ pub use dsp::{
     DspDescription,
     DspState
 };
+pub use dsp_param::{
+    DspParam,
+    TypedDsp,
+    OscillatorDsp,
+    LowPassDsp,
+    ITLowPassDsp,
+    HighPassDsp,
+    EchoDsp,
+    DelayDsp,
+    FlangeDsp,
+    TremoloDsp,
+    DistortionDsp,
+    NormalizeDsp,
+    ParameqDsp,
+    PitchShiftDsp,
+    ChorusDsp,
+    ITEchoDsp,
+    CompressorDsp,
+    SfxReverbDsp,
+    LowPassSimpleDsp,
+    HighPassSimpleDsp
+};
+pub use dsp_processor::DspProcessor;
+pub use dsp_graph::{
+    DspGraph,
+    DspNode,
+    DspNodeParameter,
+    DspEdge
+};
+pub use dsp_chain::{
+    DspChain,
+    DspEffect,
+    AppliedDspChain
+};
+pub use dsp_preset::{
+    DspPreset,
+    DspPresetParameter
+};
 pub use dsp_connection::DspConnection;
 pub use reverb::Reverb;
 pub use reverb_properties::ReverbProperties;
+pub use reverb_zone::{
+    ReverbZone,
+    ReverbZoneId,
+    ReverbZoneManager
+};
 pub use vector::Vector;
 pub use geometry::Geometry;
+pub use error::FmodError;
+pub use geometry_importer::{
+    GeometryImporter,
+    MaterialTable,
+    OcclusionMaterial
+};
+pub use occlusion_map::{
+    OcclusionMap,
+    OcclusionKind
+};
+pub use codec::{
+    Codec,
+    CodecStream,
+    CodecFile,
+    CodecWaveFormat,
+    MAX_CODECS
+};
+pub use pcm_reader::{
+    PcmReader,
+    PcmSample
+};
 pub use file::{
     FmodFile,
     SeekStyle
 };
+pub use file_system::{
+    FmodFileSystem,
+    FmodFileStream,
+    StdFileSystem,
+    FileSystemObserver,
+    ObservedFile
+};
 pub use self::enums::{
     Status,
     SpeakerMapType,
@@ -181,6 +271,7 @@ pub use self::enums::{
     PluginType,
     OpenState,
     SystemCallbackType,
+    ChannelCallbackType,
     SoundGroupBehavior,
     DspType,
     DspOscillator,
@@ -215,20 +306,38 @@ pub use self::types::{
 mod ffi;
 mod sound;
 mod channel;
+mod channel_handle;
+mod shared_sys;
 mod channel_group;
 mod sound_group;
 mod fmod_sys;
 mod dsp;
+mod dsp_param;
+mod dsp_processor;
+mod dsp_graph;
+mod dsp_chain;
+mod dsp_preset;
 mod dsp_connection;
 mod geometry;
+mod geometry_importer;
+mod occlusion_map;
 mod vector;
 mod reverb;
 mod reverb_properties;
+mod reverb_zone;
 mod file;
+mod file_system;
+mod async_read_info;
+mod codec;
 mod enums;
+mod pcm_reader;
 pub mod types;
 pub mod callbacks;
 pub mod error;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "mock")]
+mod mock_ffi;
 
 /// Default for all modes listed below. LOOP_OFF, 2D, HARDWARE
 pub const DEFAULT                : u32 = 0x00000000;
@@ -474,7 +583,7 @@ pub const EVENT_MEMBITS_EVENTINSTANCE_GROUP  : u32 = EVENT_MEMBITS_EVENTINSTANCE
 /// All sound definition memory
 pub const EVENT_MEMBITS_SOUNDDEF_GROUP       : u32 = EVENT_MEMBITS_SOUNDDEFCLASS | EVENT_MEMBITS_SOUNDDEFDEFCLASS | EVENT_MEMBITS_SOUNDDEFPOOL;
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "mock")))]
 mod platform {
     #[cfg(target_arch="x86")]
     #[link(name = "fmodex")] extern{}
@@ -482,7 +591,7 @@ mod platform {
     #[link(name = "fmodex64")] extern{}
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(all(target_os = "macos", not(feature = "mock")))]
 mod platform {
     #[link(name = "fmodex")] extern{}
 }
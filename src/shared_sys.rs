@@ -0,0 +1,412 @@
+/*
+* Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
+*
+* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use fmod_sys::Sys;
+use sound::Sound;
+use channel_handle::ChannelHandle;
+use vector::Vector;
+use error::CommandError;
+use types::Mode;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::task::{Context, Poll, Waker};
+
+type Command = Box<dyn FnOnce(&Sys, &mut SharedObjects) + Send>;
+
+/// Id of a sound owned by a [`CommandQueue`](struct.CommandQueue.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SoundId(usize);
+
+/// Id of a channel owned by a [`CommandQueue`](struct.CommandQueue.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelId(usize);
+
+struct Slot<T> {
+    value: Option<Result<T, CommandError>>,
+    waker: Option<Waker>,
+}
+
+type SharedSlot<T> = Arc<(Mutex<Slot<T>>, Condvar)>;
+
+/// Result of a command sent through [`SharedSys`](struct.SharedSys.html), available once the
+/// thread owning the system has run it.
+///
+/// It can be awaited, polled with `try_get` or waited for with `wait`. Never wait for it on the
+/// thread owning the system: the command would never run.
+pub struct Response<T> {
+    slot: SharedSlot<T>,
+}
+
+impl<T> Response<T> {
+    /// Returns true if the command has been run.
+    pub fn is_ready(&self) -> bool {
+        match (self.slot.0).lock() {
+            Ok(slot) => slot.value.is_some(),
+            Err(_) => true,
+        }
+    }
+
+    /// Returns the result if the command has been run, None otherwise.
+    pub fn try_get(&mut self) -> Option<Result<T, CommandError>> {
+        match (self.slot.0).lock() {
+            Ok(mut slot) => slot.value.take(),
+            Err(_) => Some(Err(CommandError::Disconnected)),
+        }
+    }
+
+    /// Blocks until the command has been run and returns its result.
+    pub fn wait(self) -> Result<T, CommandError> {
+        let (ref lock, ref condvar) = *self.slot;
+        let mut slot = match lock.lock() {
+            Ok(s) => s,
+            Err(_) => return Err(CommandError::Disconnected),
+        };
+
+        loop {
+            if let Some(value) = slot.value.take() {
+                return value;
+            }
+            slot = match condvar.wait(slot) {
+                Ok(s) => s,
+                Err(_) => return Err(CommandError::Disconnected),
+            };
+        }
+    }
+}
+
+impl<T> Future for Response<T> {
+    type Output = Result<T, CommandError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<T, CommandError>> {
+        match (self.slot.0).lock() {
+            Ok(mut slot) => match slot.value.take() {
+                Some(value) => Poll::Ready(value),
+                None => {
+                    slot.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            },
+            Err(_) => Poll::Ready(Err(CommandError::Disconnected)),
+        }
+    }
+}
+
+/// Sending side of a `Response`. If it is dropped without value (the command was dropped with
+/// its queue), the response gets `CommandError::Disconnected`.
+struct Promise<T> {
+    slot: Option<SharedSlot<T>>,
+}
+
+impl<T> Promise<T> {
+    fn set(&mut self, value: Result<T, CommandError>) {
+        if let Some(slot) = self.slot.take() {
+            let (ref lock, ref condvar) = *slot;
+
+            if let Ok(mut slot) = lock.lock() {
+                slot.value = Some(value);
+                if let Some(waker) = slot.waker.take() {
+                    waker.wake();
+                }
+            }
+            condvar.notify_all();
+        }
+    }
+}
+
+impl<T> Drop for Promise<T> {
+    fn drop(&mut self) {
+        self.set(Err(CommandError::Disconnected));
+    }
+}
+
+/// Sounds and channels owned by a [`CommandQueue`](struct.CommandQueue.html), referenced by
+/// id from the other threads.
+pub struct SharedObjects {
+    sounds: HashMap<usize, Sound>,
+    channels: HashMap<usize, ChannelHandle>,
+    next_id: usize,
+}
+
+impl SharedObjects {
+    fn new() -> SharedObjects {
+        SharedObjects {
+            sounds: HashMap::new(),
+            channels: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn add_sound(&mut self, sound: Sound) -> SoundId {
+        self.next_id += 1;
+        self.sounds.insert(self.next_id, sound);
+        SoundId(self.next_id)
+    }
+
+    pub fn get_sound(&self, SoundId(id): SoundId) -> Result<&Sound, CommandError> {
+        match self.sounds.get(&id) {
+            Some(s) => Ok(s),
+            None => Err(CommandError::UnknownId),
+        }
+    }
+
+    /// Removes the sound, its channels stop when it is dropped.
+    pub fn remove_sound(&mut self, SoundId(id): SoundId) -> Result<Sound, CommandError> {
+        match self.sounds.remove(&id) {
+            Some(s) => Ok(s),
+            None => Err(CommandError::UnknownId),
+        }
+    }
+
+    pub fn add_channel(&mut self, channel: ChannelHandle) -> ChannelId {
+        self.next_id += 1;
+        self.channels.insert(self.next_id, channel);
+        ChannelId(self.next_id)
+    }
+
+    pub fn get_channel(&self, ChannelId(id): ChannelId) -> Result<&ChannelHandle, CommandError> {
+        match self.channels.get(&id) {
+            Some(c) => Ok(c),
+            None => Err(CommandError::UnknownId),
+        }
+    }
+
+    pub fn remove_channel(&mut self, ChannelId(id): ChannelId)
+                          -> Result<ChannelHandle, CommandError> {
+        match self.channels.remove(&id) {
+            Some(c) => Ok(c),
+            None => Err(CommandError::UnknownId),
+        }
+    }
+}
+
+/// Receiving side of [`SharedSys`](struct.SharedSys.html), owned by the thread owning the
+/// system.
+///
+/// ```ignore
+/// let fmod = rfmod::Sys::new().unwrap();
+/// let mut queue = rfmod::CommandQueue::new();
+/// let shared = queue.get_shared();
+///
+/// fmod.init().unwrap();
+/// ::std::thread::spawn(move || {
+///     let sound = shared.create_sound("music.mp3", None).wait().unwrap();
+///     let channel = shared.play_sound(sound).wait().unwrap();
+///
+///     shared.set_volume(channel, 0.5f32);
+/// });
+/// loop {
+///     queue.update(&fmod).unwrap();
+///     ::std::thread::sleep(::std::time::Duration::from_millis(16));
+/// }
+/// ```
+pub struct CommandQueue {
+    sender: Sender<Command>,
+    receiver: Receiver<Command>,
+    objects: SharedObjects,
+}
+
+impl CommandQueue {
+    pub fn new() -> CommandQueue {
+        let (sender, receiver) = mpsc::channel();
+
+        CommandQueue {
+            sender: sender,
+            receiver: receiver,
+            objects: SharedObjects::new(),
+        }
+    }
+
+    /// Returns a handle sending commands to this queue.
+    pub fn get_shared(&self) -> SharedSys {
+        SharedSys {
+            sender: self.sender.clone(),
+        }
+    }
+
+    pub fn get_objects(&self) -> &SharedObjects {
+        &self.objects
+    }
+
+    pub fn get_objects_mut(&mut self) -> &mut SharedObjects {
+        &mut self.objects
+    }
+
+    /// Runs the pending commands on `fmod` and returns how many were run.
+    pub fn process(&mut self, fmod: &Sys) -> usize {
+        let mut count = 0;
+
+        while let Ok(command) = self.receiver.try_recv() {
+            command(fmod, &mut self.objects);
+            count += 1;
+        }
+        count
+    }
+
+    /// Runs the pending commands, forgets the channels whose voice has ended and updates `fmod`.
+    pub fn update(&mut self, fmod: &Sys) -> Result<(), ::FmodError> {
+        self.process(fmod);
+        self.objects.channels.retain(|_, c| c.is_valid());
+        fmod.update()
+    }
+}
+
+/// Cloneable handle on a system owned by another thread: calls are sent to its
+/// [`CommandQueue`](struct.CommandQueue.html) and run on the next `process` or `update`.
+///
+/// Every call returns a [`Response`](struct.Response.html), which can be dropped if the result
+/// isn't needed.
+#[derive(Clone)]
+pub struct SharedSys {
+    sender: Sender<Command>,
+}
+
+impl SharedSys {
+    /// Runs `f` on the thread owning the system.
+    pub fn run<T, F>(&self, f: F) -> Response<T>
+        where T: Send + 'static,
+              F: FnOnce(&Sys, &mut SharedObjects) -> Result<T, CommandError> + Send + 'static {
+        let slot = Arc::new((Mutex::new(Slot { value: None, waker: None }), Condvar::new()));
+        let mut promise = Promise { slot: Some(slot.clone()) };
+
+        // if the queue is gone, the command and its promise are dropped right away
+        let _ = self.sender.send(Box::new(move |fmod: &Sys, objects: &mut SharedObjects| {
+            promise.set(f(fmod, objects));
+        }));
+        Response { slot: slot }
+    }
+
+    pub fn create_sound(&self, music: &str, options: Option<Mode>) -> Response<SoundId> {
+        let music = music.to_owned();
+
+        self.run(move |fmod, objects| {
+            match fmod.create_sound(&music, options, None) {
+                Ok(s) => Ok(objects.add_sound(s)),
+                Err(e) => Err(CommandError::from(e)),
+            }
+        })
+    }
+
+    pub fn release_sound(&self, sound: SoundId) -> Response<()> {
+        self.run(move |_, objects| objects.remove_sound(sound).map(|_| ()))
+    }
+
+    pub fn play_sound(&self, sound: SoundId) -> Response<ChannelId> {
+        self.run(move |_, objects| {
+            let channel = match objects.get_sound(sound) {
+                Ok(s) => match s.play() {
+                    Ok(c) => c,
+                    Err(e) => return Err(CommandError::from(e)),
+                },
+                Err(e) => return Err(e),
+            };
+            match ChannelHandle::new(channel) {
+                Ok(c) => Ok(objects.add_channel(c)),
+                Err(e) => Err(CommandError::from(e)),
+            }
+        })
+    }
+
+    pub fn stop(&self, channel: ChannelId) -> Response<()> {
+        self.run(move |_, objects| {
+            match objects.remove_channel(channel) {
+                Ok(c) => c.stop().map_err(CommandError::from),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    pub fn is_playing(&self, channel: ChannelId) -> Response<bool> {
+        self.run(move |_, objects| {
+            match objects.get_channel(channel) {
+                Ok(c) => c.is_playing().map_err(CommandError::from),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    pub fn set_paused(&self, channel: ChannelId, paused: bool) -> Response<()> {
+        self.run(move |_, objects| {
+            match objects.get_channel(channel) {
+                Ok(c) => c.set_paused(paused).map_err(CommandError::from),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    pub fn get_paused(&self, channel: ChannelId) -> Response<bool> {
+        self.run(move |_, objects| {
+            match objects.get_channel(channel) {
+                Ok(c) => c.get_paused().map_err(CommandError::from),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    pub fn set_volume(&self, channel: ChannelId, volume: f32) -> Response<()> {
+        self.run(move |_, objects| {
+            match objects.get_channel(channel) {
+                Ok(c) => c.set_volume(volume).map_err(CommandError::from),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    pub fn get_volume(&self, channel: ChannelId) -> Response<f32> {
+        self.run(move |_, objects| {
+            match objects.get_channel(channel) {
+                Ok(c) => c.get_volume().map_err(CommandError::from),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    pub fn set_3D_attributes(&self, channel: ChannelId, position: Vector, velocity: Vector)
+                             -> Response<()> {
+        self.run(move |_, objects| {
+            match objects.get_channel(channel) {
+                Ok(c) => c.call(|c| c.set_3D_attributes(&position, &velocity))
+                          .map_err(CommandError::from),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    pub fn set_3D_listener_attributes(&self, listener: i32, pos: Vector, vel: Vector,
+                                      forward: Vector, up: Vector) -> Response<()> {
+        self.run(move |fmod, _| {
+            fmod.set_3D_listener_attributes(listener, &pos, &vel, &forward, &up)
+                .map_err(CommandError::from)
+        })
+    }
+
+    pub fn get_3D_listener_attributes(&self, listener: i32)
+                                      -> Response<(Vector, Vector, Vector, Vector)> {
+        self.run(move |fmod, _| {
+            fmod.get_3D_listener_attributes(listener).map_err(CommandError::from)
+        })
+    }
+}
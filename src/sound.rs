@@ -23,45 +23,85 @@
 */
 
 use types::*;
-use libc::{c_int, c_uint, c_char, c_ushort, c_void};
+use libc::{c_int, c_uint, c_char, c_void};
 use ffi;
 use channel;
+use channel_handle;
 use sound_group;
 use vector;
 use fmod_sys;
-use fmod_sys::{MemoryUsageDetails, Sys};
+use fmod_sys::{MemoryUsageDetails, Sys, SystemRef};
 use std::mem::transmute;
 use std::fs::File;
-use std::mem;
 use std::slice;
 use std::default::Default;
 use byteorder::{WriteBytesExt, LittleEndian};
-use std::io::Write;
+use std::io::{Write, Seek, SeekFrom};
+use error::WavError;
 use std::ffi::CString;
 use std::time::Duration;
+use std::sync::Arc;
+
+const WAVE_FORMAT_PCM        : u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT : u16 = 3;
+
+/// Builds the RIFF, fmt (and fact for float data) and data chunk headers of a wav file holding
+/// `data_len` bytes of sample data.
+fn wav_header(format_tag: u16, channels: u16, rate: u32, bits: u16, data_len: u32) -> Vec<u8> {
+    let block_align = channels * bits / 8;
+    // non-PCM formats need the cbSize field and a fact chunk
+    let extended = format_tag != WAVE_FORMAT_PCM;
+    let fmt_size = if extended { 18u32 } else { 16u32 };
+    let fact_size = if extended { 12u32 } else { 0u32 };
+    let riff_size = 4 + (8 + fmt_size) + fact_size + 8 + data_len + (data_len & 1);
+    let mut wtr = vec![];
+
+    /* wav header */
+    wtr.write_all(b"RIFF").unwrap();
+    wtr.write_u32::<LittleEndian>(riff_size).unwrap();
+    wtr.write_all(b"WAVE").unwrap();
+
+    /* fmt chunk */
+    wtr.write_all(b"fmt ").unwrap();
+    wtr.write_u32::<LittleEndian>(fmt_size).unwrap();
+    wtr.write_u16::<LittleEndian>(format_tag).unwrap();
+    wtr.write_u16::<LittleEndian>(channels).unwrap();
+    wtr.write_u32::<LittleEndian>(rate).unwrap();
+    wtr.write_u32::<LittleEndian>(rate * block_align as u32).unwrap();
+    wtr.write_u16::<LittleEndian>(block_align).unwrap();
+    wtr.write_u16::<LittleEndian>(bits).unwrap();
+    if extended {
+        wtr.write_u16::<LittleEndian>(0).unwrap();
+        let frames = if block_align > 0 { data_len / block_align as u32 } else { 0 };
+
+        /* fact chunk */
+        wtr.write_all(b"fact").unwrap();
+        wtr.write_u32::<LittleEndian>(4).unwrap();
+        wtr.write_u32::<LittleEndian>(frames).unwrap();
+    }
 
-struct RiffChunk {
-    id: [c_char; 4],
-    size: c_int
-}
-
-struct FmtChunk {
-    chunk: RiffChunk,
-    w_format_tag: c_ushort,     /* format type  */
-    n_channels: c_ushort,       /* number of channels (i.e. mono, stereo...)  */
-    n_samples_per_sec: c_uint,  /* sample rate  */
-    n_avg_bytes_per_sec: c_uint,/* for buffer estimation  */
-    n_block_align: c_ushort,    /* block size of data  */
-    w_bits_per_sample: c_ushort /* number of bits per sample of mono data */
-}
-
-struct DataChunk {
-    chunk: RiffChunk
+    /* data chunk */
+    wtr.write_all(b"data").unwrap();
+    wtr.write_u32::<LittleEndian>(data_len).unwrap();
+    wtr
 }
 
-struct WavHeader {
-    chunk: RiffChunk,
-    riff_type: [c_char; 4]
+/// Writes sample data to a wav file. FMOD 8 bits samples are signed whereas wav ones are
+/// unsigned.
+fn write_wav_data<W: Write>(writer: &mut W, data: &[u8], format: ::SoundFormat)
+                            -> Result<(), WavError> {
+    let ret = if format == ::SoundFormat::PCM8 {
+        let converted : Vec<u8> = data.iter().map(|b| b ^ 0x80).collect();
+
+        writer.write_all(&converted)
+    } else {
+        writer.write_all(data)
+    };
+
+    match ret {
+        Ok(()) => Ok(()),
+        Err(e) => Err(WavError::Io(e)),
+    }
 }
 
 /// Wrapper for SyncPoint object
@@ -70,7 +110,8 @@ pub struct FmodSyncPoint {
 }
 
 impl FmodSyncPoint {
-    fn from_ptr(pointer: *mut ffi::FMOD_SYNCPOINT) -> FmodSyncPoint {
+    #[doc(hidden)]
+    pub fn from_ptr(pointer: *mut ffi::FMOD_SYNCPOINT) -> FmodSyncPoint {
         FmodSyncPoint{sync_point: pointer}
     }
 }
@@ -156,11 +197,17 @@ pub struct Sound {
     sound: *mut ffi::FMOD_SOUND,
     can_be_deleted: bool,
     user_data: ffi::SoundData,
+    system_ref: Option<Arc<SystemRef>>
 }
 
 impl ffi::FFI<ffi::FMOD_SOUND> for Sound {
     fn wrap(s: *mut ffi::FMOD_SOUND) -> Sound {
-        Sound {sound: s, can_be_deleted: false, user_data: ffi::SoundData::new()}
+        Sound {
+            sound: s,
+            can_be_deleted: false,
+            user_data: ffi::SoundData::new(),
+            system_ref: get_system_ref(s)
+        }
     }
 
     fn unwrap(s: &Sound) -> *mut ffi::FMOD_SOUND {
@@ -172,8 +219,46 @@ pub fn get_fffi<'r>(sound: &'r mut Sound) -> &'r mut *mut ffi::FMOD_SOUND {
     &mut sound.sound
 }
 
-pub fn from_ptr_first(sound: *mut ffi::FMOD_SOUND) -> Sound {
-    Sound{sound: sound, can_be_deleted: true, user_data: ffi::SoundData::new()}
+pub fn from_ptr_first(sound: *mut ffi::FMOD_SOUND, system_ref: Option<Arc<SystemRef>>) -> Sound {
+    Sound {
+        sound: sound,
+        can_be_deleted: true,
+        user_data: ffi::SoundData::new(),
+        system_ref: system_ref
+    }
+}
+
+/// Wraps a sound returned by an object sharing `system_ref`, without looking it up again.
+pub fn from_ptr(sound: *mut ffi::FMOD_SOUND, system_ref: Option<Arc<SystemRef>>) -> Sound {
+    Sound {
+        sound: sound,
+        can_be_deleted: false,
+        user_data: ffi::SoundData::new(),
+        system_ref: system_ref
+    }
+}
+
+/// Wraps a sound given to a callback. It doesn't keep the system alive, the last reference
+/// mustn't be dropped from an FMOD thread.
+pub fn from_callback_ptr(sound: *mut ffi::FMOD_SOUND) -> Sound {
+    Sound {
+        sound: sound,
+        can_be_deleted: false,
+        user_data: ffi::SoundData::new(),
+        system_ref: None
+    }
+}
+
+fn get_system_ref(sound: *mut ffi::FMOD_SOUND) -> Option<Arc<SystemRef>> {
+    let mut system = ::std::ptr::null_mut();
+
+    if sound.is_null() {
+        return None;
+    }
+    match unsafe { ffi::FMOD_Sound_GetSystemObject(sound, &mut system) } {
+        ::Status::Ok => fmod_sys::get_system_ref(system),
+        _ => None,
+    }
 }
 
 pub fn get_user_data<'r>(sound: &'r mut Sound) -> &'r mut ffi::SoundData {
@@ -182,63 +267,73 @@ pub fn get_user_data<'r>(sound: &'r mut Sound) -> &'r mut ffi::SoundData {
 
 impl Drop for Sound {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl Sound {
-    pub fn get_system_object(&self) -> Result<Sys, ::Status> {
+    pub fn get_system_object(&self) -> Result<Sys, ::FmodError> {
         let mut system = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Sound_GetSystemObject(self.sound, &mut system) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(system)),
-            e => Err(e),
+            ::Status::Ok => Ok(fmod_sys::from_ptr(system, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_Sound_GetSystemObject", e)),
         }
     }
 
-    pub fn release(&mut self) -> ::Status {
+    pub fn release(&mut self) -> Result<(), ::FmodError> {
         if self.can_be_deleted && !self.sound.is_null() {
             match unsafe { ffi::FMOD_Sound_Release(self.sound) } {
                ::Status::Ok => {
                     self.sound = ::std::ptr::null_mut();
-                   ::Status::Ok
+                   Ok(())
                 }
-                e => e,
+                e => Err(::FmodError::new("FMOD_Sound_Release", e)),
             }
         } else {
-            ::Status::Ok
+            Ok(())
         }
     }
 
-    pub fn play(&self) -> Result<channel::Channel, ::Status> {
+    pub fn play(&self) -> Result<channel::Channel, ::FmodError> {
         let mut channel = ::std::ptr::null_mut();
+        let system = match self.get_system_object() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        match match self.get_system_object() {
-            Ok(s) => { 
-                unsafe { ffi::FMOD_System_PlaySound(ffi::FFI::unwrap(&s), ::ChannelIndex::Free, self.sound, 0, &mut channel) }
+        match unsafe { ffi::FMOD_System_PlaySound(ffi::FFI::unwrap(&system), ::ChannelIndex::Free,
+                                                  self.sound, 0, &mut channel) } {
+            ::Status::Ok => {
+                channel_handle::start_voice(channel);
+                Ok(channel::from_ptr(channel, self.system_ref.clone()))
             }
-            Err(e) => e,
-        } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_System_PlaySound", e)),
         }
     }
 
-    pub fn play_with_parameters(&self, paused: bool, channel: &mut channel::Channel) -> ::Status {
+    pub fn play_with_parameters(&self, paused: bool, channel: &mut channel::Channel)
+                               -> Result<(), ::FmodError> {
         let mut chan = ffi::FFI::unwrap(channel);
-        
-        match self.get_system_object() {
-            Ok(s) => { 
-                unsafe { ffi::FMOD_System_PlaySound(ffi::FFI::unwrap(&s), ::ChannelIndex::ReUse, self.sound, match paused {
-                    true => 1,
-                    false => 0,
-                }, &mut chan) }
+        let system = match self.get_system_object() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        match unsafe { ffi::FMOD_System_PlaySound(ffi::FFI::unwrap(&system), ::ChannelIndex::ReUse,
+                                                  self.sound, match paused {
+            true => 1,
+            false => 0,
+        }, &mut chan) } {
+            ::Status::Ok => {
+                channel_handle::start_voice(chan);
+                Ok(())
             }
-            Err(e) => e,
+            e => Err(::FmodError::new("FMOD_System_PlaySound", e)),
         }
     }
 
-    pub fn play_to_the_end(&self) -> ::Status {
+    pub fn play_to_the_end(&self) -> Result<(), ::FmodError> {
         match self.play() {
             Ok(mut chan) => {
                 loop {
@@ -250,21 +345,25 @@ impl Sound {
                                 break;
                             }
                         },
-                        Err(e) => return e,
+                        Err(e) => return Err(e),
                     }
                 }
                 chan.release();
-                ::Status::Ok
+                Ok(())
             }
-            Err(err) => err,
+            Err(err) => Err(err),
         }
     }
 
-    pub fn set_defaults(&self, frequency: f32, volume: f32, pan: f32, priority: i32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetDefaults(self.sound, frequency, volume, pan, priority) }
+    pub fn set_defaults(&self, frequency: f32, volume: f32, pan: f32, priority: i32)
+                       -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetDefaults(self.sound, frequency, volume, pan, priority) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetDefaults", e)),
+        }
     }
 
-    pub fn get_defaults(&self) -> Result<(f32, f32, f32, i32), ::Status> {
+    pub fn get_defaults(&self) -> Result<(f32, f32, f32, i32), ::FmodError> {
         let mut frequency = 0f32;
         let mut volume = 0f32;
         let mut pan = 0f32;
@@ -273,18 +372,22 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetDefaults(self.sound, &mut frequency, &mut volume,
                                                    &mut pan, &mut priority) } {
             ::Status::Ok => Ok((frequency, volume, pan, priority)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetDefaults", e)),
         }
     }
 
-    pub fn set_variations(&self, frequency_var: f32, volume_var: f32, pan_var: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetVariations(self.sound, frequency_var, volume_var, pan_var) }
+    pub fn set_variations(&self, frequency_var: f32, volume_var: f32, pan_var: f32)
+                         -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetVariations(self.sound, frequency_var, volume_var, pan_var) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetVariations", e)),
+        }
     }
 
     /// Returns:
     ///
     /// Ok(frequency_var, volume_var, pan_var)
-    pub fn get_variations(&self) -> Result<(f32, f32, f32), ::Status> {
+    pub fn get_variations(&self) -> Result<(f32, f32, f32), ::FmodError> {
         let mut frequency_var = 0f32;
         let mut volume_var = 0f32;
         let mut pan_var = 0f32;
@@ -292,37 +395,43 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetVariations(self.sound, &mut frequency_var,
                                                      &mut volume_var, &mut pan_var) } {
             ::Status::Ok => Ok((frequency_var, volume_var, pan_var)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetVariations", e)),
         }
     }
 
-    pub fn set_3D_min_max_distance(&self, min: f32, max: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_Set3DMinMaxDistance(self.sound, min, max) }
+    pub fn set_3D_min_max_distance(&self, min: f32, max: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_Set3DMinMaxDistance(self.sound, min, max) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_Set3DMinMaxDistance", e)),
+        }
     }
 
     /// Returns:
     ///
     /// Ok(min, max)
-    pub fn get_3D_min_max_distance(&self) -> Result<(f32, f32), ::Status> {
+    pub fn get_3D_min_max_distance(&self) -> Result<(f32, f32), ::FmodError> {
         let mut max = 0f32;
         let mut min = 0f32;
 
         match unsafe { ffi::FMOD_Sound_Get3DMinMaxDistance(self.sound, &mut min, &mut max) } {
             ::Status::Ok => Ok((min, max)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_Get3DMinMaxDistance", e)),
         }
     }
 
     pub fn set_3D_cone_settings(&self, inside_cone_angle: f32, outside_cone_angle: f32,
-                                outside_volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_Set3DConeSettings(self.sound, inside_cone_angle,
-                                                   outside_cone_angle, outside_volume) }
+                                outside_volume: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_Set3DConeSettings(self.sound, inside_cone_angle,
+                                                         outside_cone_angle, outside_volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_Set3DConeSettings", e)),
+        }
     }
 
     /// Returns:
     ///
     /// Ok(inside_cone_angle, outside_cone_angle, outside_volume)
-    pub fn get_3D_cone_settings(&self) -> Result<(f32, f32, f32), ::Status> {
+    pub fn get_3D_cone_settings(&self) -> Result<(f32, f32, f32), ::FmodError> {
         let mut inside_cone_angle = 0f32;
         let mut outside_cone_angle = 0f32;
         let mut outside_volume = 0f32;
@@ -331,22 +440,26 @@ impl Sound {
                                                          &mut outside_cone_angle,
                                                          &mut outside_volume) } {
             ::Status::Ok => Ok((inside_cone_angle, outside_cone_angle, outside_volume)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_Get3DConeSettings", e)),
         }
     }
 
-    pub fn set_3D_custom_rolloff(&self, points: Vec<vector::Vector>) -> ::Status {
+    pub fn set_3D_custom_rolloff(&self, points: Vec<vector::Vector>) -> Result<(), ::FmodError> {
         let mut points_vec = Vec::with_capacity(points.len());
 
         for tmp in points.into_iter() {
             points_vec.push(vector::get_ffi(&tmp));
         }
-        unsafe { ffi::FMOD_Sound_Set3DCustomRolloff(self.sound, points_vec.as_mut_ptr(),
-                                                    points_vec.len() as i32) }
+        match unsafe { ffi::FMOD_Sound_Set3DCustomRolloff(self.sound, points_vec.as_mut_ptr(),
+                                                          points_vec.len() as i32) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_Set3DCustomRolloff", e)),
+        }
     }
 
     // to test
-    pub fn get_3D_custom_rolloff(&self, num_points: u32) -> Result<Vec<vector::Vector>, ::Status> {
+    pub fn get_3D_custom_rolloff(&self, num_points: u32)
+                                -> Result<Vec<vector::Vector>, ::FmodError> {
         let mut points_vec = Vec::with_capacity(num_points as usize);
         let mut pointer = points_vec.as_mut_ptr();
 
@@ -360,24 +473,27 @@ impl Sound {
                 }
                 Ok(points)
             }
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_Get3DCustomRolloff", e)),
         }
     }
 
-    pub fn set_sub_sound(&self, index: i32, sub_sound: Sound) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetSubSound(self.sound, index, sub_sound.sound) }
+    pub fn set_sub_sound(&self, index: i32, sub_sound: Sound) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetSubSound(self.sound, index, sub_sound.sound) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetSubSound", e)),
+        }
     }
 
-    pub fn get_sub_sound(&self, index: i32) -> Result<Sound, ::Status> {
+    pub fn get_sub_sound(&self, index: i32) -> Result<Sound, ::FmodError> {
         let mut sub_sound = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Sound_GetSubSound(self.sound, index, &mut sub_sound) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sub_sound)),
-            e => Err(e),
+            ::Status::Ok => Ok(from_ptr(sub_sound, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_Sound_GetSubSound", e)),
         }
     }
 
-    pub fn get_name(&self, name_len: usize) -> Result<String, ::Status> {
+    pub fn get_name(&self, name_len: usize) -> Result<String, ::FmodError> {
         let mut c = Vec::with_capacity(name_len + 1);
 
         for _ in 0..(name_len + 1) {
@@ -387,23 +503,23 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetName(self.sound, c.as_mut_ptr() as *mut c_char,
                                                name_len as i32) } {
             ::Status::Ok => Ok(String::from_utf8(c).unwrap()),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetName", e)),
         }
     }
 
-    pub fn get_length(&self, TimeUnit(length_type): TimeUnit) -> Result<u32, ::Status> {
+    pub fn get_length(&self, TimeUnit(length_type): TimeUnit) -> Result<u32, ::FmodError> {
         let mut length = 0u32;
 
         match unsafe { ffi::FMOD_Sound_GetLength(self.sound, &mut length, length_type) } {
             ::Status::Ok => Ok(length),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetLength", e)),
         }
     }
 
     /// Returns:
     ///
     /// Ok(type, format, channels, bits)
-    pub fn get_format(&self) -> Result<(::SoundType, ::SoundFormat, i32, i32), ::Status> {
+    pub fn get_format(&self) -> Result<(::SoundType, ::SoundFormat, i32, i32), ::FmodError> {
         let mut _type = ::SoundType::Unknown;
         let mut format = ::SoundFormat::None;
         let mut channels = 0i32;
@@ -412,34 +528,34 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetFormat(self.sound, &mut _type, &mut format, &mut channels,
                                                  &mut bits) } {
             ::Status::Ok => Ok((_type, format, channels, bits)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetFormat", e)),
         }
     }
 
-    pub fn get_num_sub_sounds(&self) -> Result<i32, ::Status> {
+    pub fn get_num_sub_sounds(&self) -> Result<i32, ::FmodError> {
         let mut num_sub_sound = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetNumSubSounds(self.sound, &mut num_sub_sound) } {
             ::Status::Ok => Ok(num_sub_sound),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetNumSubSounds", e)),
         }
     }
 
     /// Returns:
     ///
     /// Ok(num_tags, num_tags_updated)
-    pub fn get_num_tags(&self) -> Result<(i32, i32), ::Status> {
+    pub fn get_num_tags(&self) -> Result<(i32, i32), ::FmodError> {
         let mut num_tags = 0i32;
         let mut num_tags_updated = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetNumTags(self.sound, &mut num_tags, &mut num_tags_updated) } {
             ::Status::Ok => Ok((num_tags, num_tags_updated)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetNumTags", e)),
         }
     }
 
     //to test if tag's data needs to be filled by user
-    pub fn get_tag(&self, name: &str, index: i32) -> Result<FmodTag, ::Status> {
+    pub fn get_tag(&self, name: &str, index: i32) -> Result<FmodTag, ::FmodError> {
         let mut tag = ffi::FMOD_TAG {
             _type: ::TagType::Unknown,
             datatype: ::TagDataType::Binary,
@@ -452,11 +568,11 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetTag(self.sound, name.as_ptr() as *const c_char, index,
                                               &mut tag) } {
             ::Status::Ok => Ok(FmodTag::from_ptr(tag)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetTag", e)),
         }
     }
 
-    pub fn get_open_state(&self) -> Result<(::OpenState, u32, bool, bool), ::Status> {
+    pub fn get_open_state(&self) -> Result<(::OpenState, u32, bool, bool), ::FmodError> {
         let mut open_state = ::OpenState::Ready;
         let mut percent_buffered = 0u32;
         let mut starving = 0;
@@ -476,43 +592,46 @@ impl Sound {
                 } else {
                     false
                 })),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetOpenState", e)),
         }
     }
 
-    pub fn set_sound_group(&self, sound_group: sound_group::SoundGroup) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetSoundGroup(self.sound, ffi::FFI::unwrap(&sound_group)) }
+    pub fn set_sound_group(&self, sound_group: sound_group::SoundGroup) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetSoundGroup(self.sound, ffi::FFI::unwrap(&sound_group)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetSoundGroup", e)),
+        }
     }
 
-    pub fn get_sound_group(&self) -> Result<sound_group::SoundGroup, ::Status> {
+    pub fn get_sound_group(&self) -> Result<sound_group::SoundGroup, ::FmodError> {
         let mut sound_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Sound_GetSoundGroup(self.sound, &mut sound_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound_group)),
-            e => Err(e),
+            ::Status::Ok => Ok(sound_group::from_ptr(sound_group, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_Sound_GetSoundGroup", e)),
         }
     }
 
-    pub fn get_num_sync_points(&self) -> Result<i32, ::Status> {
+    pub fn get_num_sync_points(&self) -> Result<i32, ::FmodError> {
         let mut num_sync_points = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetNumSyncPoints(self.sound, &mut num_sync_points) } {
             ::Status::Ok => Ok(num_sync_points),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetNumSyncPoints", e)),
         }
     }
 
-    pub fn get_sync_point(&self, index: i32) -> Result<FmodSyncPoint, ::Status> {
+    pub fn get_sync_point(&self, index: i32) -> Result<FmodSyncPoint, ::FmodError> {
         let mut sync_point = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Sound_GetSyncPoint(self.sound, index, &mut sync_point) } {
             ::Status::Ok => Ok(FmodSyncPoint::from_ptr(sync_point)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetSyncPoint", e)),
         }
     }
 
     pub fn get_sync_point_info(&self, sync_point: FmodSyncPoint, name_len: usize,
-                               TimeUnit(offset_type): TimeUnit) -> Result<(String, u32), ::Status> {
+                               TimeUnit(offset_type): TimeUnit) -> Result<(String, u32), ::FmodError> {
         let mut offset = 0u32;
         let mut c = Vec::with_capacity(name_len + 1);
 
@@ -525,117 +644,155 @@ impl Sound {
                                                         name_len as i32, &mut offset,
                                                         offset_type) } {
             ::Status::Ok => Ok((String::from_utf8(c).unwrap(), offset)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetSyncPointInfo", e)),
         }
     }
 
     pub fn add_sync_point(&self, offset: u32, TimeUnit(offset_type): TimeUnit,
-                          name: String) -> Result<FmodSyncPoint, ::Status> {
+                          name: String) -> Result<FmodSyncPoint, ::FmodError> {
         let mut sync_point = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Sound_AddSyncPoint(self.sound, offset, offset_type,
                                                     name.as_ptr() as *const c_char,
                                                     &mut sync_point) } {
             ::Status::Ok => Ok(FmodSyncPoint::from_ptr(sync_point)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_AddSyncPoint", e)),
         }
     }
 
-    pub fn delete_sync_point(&self, sync_point: FmodSyncPoint) -> ::Status {
-        unsafe { ffi::FMOD_Sound_DeleteSyncPoint(self.sound, sync_point.sync_point) }
+    pub fn delete_sync_point(&self, sync_point: FmodSyncPoint) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_DeleteSyncPoint(self.sound, sync_point.sync_point) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_DeleteSyncPoint", e)),
+        }
     }
 
-    pub fn set_mode(&self, Mode(mode): Mode) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetMode(self.sound, mode) }
+    pub fn set_mode(&self, Mode(mode): Mode) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetMode(self.sound, mode) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetMode", e)),
+        }
     }
 
-    pub fn get_mode(&self) -> Result<Mode, ::Status> {
+    pub fn get_mode(&self) -> Result<Mode, ::FmodError> {
         let mut mode = 0u32;
 
         match unsafe { ffi::FMOD_Sound_GetMode(self.sound, &mut mode) } {
             ::Status::Ok => Ok(Mode(mode)),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetMode", e)),
         }
     }
 
-    pub fn set_loop_count(&self, loop_count: i32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetLoopCount(self.sound, loop_count) }
+    pub fn set_loop_count(&self, loop_count: i32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetLoopCount(self.sound, loop_count) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetLoopCount", e)),
+        }
     }
 
-    pub fn get_loop_count(&self) -> Result<i32, ::Status> {
+    pub fn get_loop_count(&self) -> Result<i32, ::FmodError> {
         let mut loop_count = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetLoopCount(self.sound, &mut loop_count) } {
             ::Status::Ok => Ok(loop_count),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetLoopCount", e)),
         }
     }
 
     pub fn set_loop_points(&self, loop_start: u32, TimeUnit(loop_start_type): TimeUnit,
-                           loop_end: u32, TimeUnit(loop_end_type): TimeUnit) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetLoopPoints(self.sound, loop_start, loop_start_type, loop_end,
-                                               loop_end_type) }
+                           loop_end: u32, TimeUnit(loop_end_type): TimeUnit) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetLoopPoints(self.sound, loop_start, loop_start_type, loop_end,
+                                                     loop_end_type) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetLoopPoints", e)),
+        }
     }
 
     /// Returns:
     ///
     /// Ok(loop_start, loop_end)
     pub fn get_loop_points(&self, TimeUnit(loop_start_type): TimeUnit,
-                           TimeUnit(loop_end_type): TimeUnit) -> Result<(u32, u32), ::Status> {
+                           TimeUnit(loop_end_type): TimeUnit) -> Result<(u32, u32), ::FmodError> {
         let mut loop_start = 0u32;
         let mut loop_end = 0u32;
 
         match unsafe { ffi::FMOD_Sound_GetLoopPoints(self.sound, &mut loop_start, loop_start_type,
                                                      &mut loop_end, loop_end_type) } {
             ::Status::Ok => Ok((loop_start, loop_end)),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Sound_GetLoopPoints", e))
         }
     }
 
-    pub fn get_num_channels(&self) -> Result<i32, ::Status> {
+    pub fn get_num_channels(&self) -> Result<i32, ::FmodError> {
         let mut num_channels = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetMusicNumChannels(self.sound, &mut num_channels) } {
             ::Status::Ok => Ok(num_channels),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Sound_GetMusicNumChannels", e))
         }
     }
 
     // TODO: see how to replace i32 channel by Channel struct
-    pub fn set_music_channel_volume(&self, channel: i32, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetMusicChannelVolume(self.sound, channel, volume) }
+    pub fn set_music_channel_volume(&self, channel: i32, volume: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetMusicChannelVolume(self.sound, channel, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetMusicChannelVolume", e)),
+        }
     }
 
     // TODO: see how to replace i32 channel by Channel struct
-    pub fn get_music_channel_volume(&self, channel: i32) -> Result<f32, ::Status> {
+    pub fn get_music_channel_volume(&self, channel: i32) -> Result<f32, ::FmodError> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_Sound_GetMusicChannelVolume(self.sound, channel, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_Sound_GetMusicChannelVolume", e))
         }
     }
 
-    pub fn set_music_speed(&self, speed: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetMusicSpeed(self.sound, speed) }
+    pub fn set_music_speed(&self, speed: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetMusicSpeed(self.sound, speed) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetMusicSpeed", e)),
+        }
     }
 
-    pub fn get_music_speed(&self) -> Result<f32, ::Status> {
+    pub fn get_music_speed(&self) -> Result<f32, ::FmodError> {
         let mut speed = 0f32;
 
         match unsafe { ffi::FMOD_Sound_GetMusicSpeed(self.sound, &mut speed) } {
             ::Status::Ok => Ok(speed),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetMusicSpeed", e)),
         }
     }
 
-    pub fn set_sub_sound_sentence(&self, sub_sounds: &mut Vec<i32>) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetSubSoundSentence(self.sound, sub_sounds.as_mut_ptr(),
-                                                     sub_sounds.len() as c_int) }
+    pub fn set_sub_sound_sentence(&self, sub_sounds: &mut Vec<i32>) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SetSubSoundSentence(self.sound, sub_sounds.as_mut_ptr(),
+                                                           sub_sounds.len() as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetSubSoundSentence", e)),
+        }
     }
 
-    pub fn seek_data(&self, pcm: u32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SeekData(self.sound, pcm) }
+    /// Reads decoded data from a sound opened with [`OPENONLY`](../constant.OPENONLY.html) into
+    /// `buffer`, in the format returned by [`Sound::get_format`](#method.get_format).
+    ///
+    /// Returns the number of bytes read. Ok(0) means the end of the sound has been reached.
+    pub fn read_data(&self, buffer: &mut [u8]) -> Result<u32, ::FmodError> {
+        let mut read = 0u32;
+
+        match unsafe { ffi::FMOD_Sound_ReadData(self.sound, buffer.as_mut_ptr() as *mut c_void,
+                                                buffer.len() as c_uint, &mut read) } {
+            ::Status::Ok | ::Status::FileEOF => Ok(read),
+            e => Err(::FmodError::new("FMOD_Sound_ReadData", e)),
+        }
+    }
+
+    pub fn seek_data(&self, pcm: u32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_SeekData(self.sound, pcm) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SeekData", e)),
+        }
     }
 
     /// Returns:
@@ -643,14 +800,14 @@ impl Sound {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::FmodError> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_Sound_GetMemoryInfo(self.sound, memory_bits, event_memory_bits,
                                                      &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_GetMemoryInfo", e)),
         }
     }
 
@@ -662,7 +819,7 @@ impl Sound {
     ///
     /// ptr2: Address of a pointer that will point to the second part of the locked data. This will
     /// be null if the data locked hasn't wrapped at the end of the buffer.
-    pub fn lock(&self, offset: u32, length: u32) -> Result<(Vec<u8>, Vec<u8>), ::Status> {
+    pub fn lock(&self, offset: u32, length: u32) -> Result<(Vec<u8>, Vec<u8>), ::FmodError> {
         let mut len1 = 0u32;
         let mut len2 = 0u32;
         let mut ptr1 = ::std::ptr::null_mut();
@@ -676,20 +833,23 @@ impl Sound {
                         slice::from_raw_parts(ptr2 as *const u8, len2 as usize).clone().to_vec()))
                 }
             }
-            e => Err(e),
+            e => Err(::FmodError::new("FMOD_Sound_Lock", e)),
         }
     }
 
-    pub fn unlock(&self, v_ptr1: Vec<u8>, v_ptr2: Vec<u8>) -> ::Status {
-        unsafe { ffi::FMOD_Sound_Unlock(self.sound, v_ptr1.as_ptr() as *mut c_void,
-                                        v_ptr2.as_ptr() as *mut c_void, v_ptr1.len() as c_uint,
-                                        v_ptr2.len() as c_uint) }
+    pub fn unlock(&self, v_ptr1: Vec<u8>, v_ptr2: Vec<u8>) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_Sound_Unlock(self.sound, v_ptr1.as_ptr() as *mut c_void,
+                                              v_ptr2.as_ptr() as *mut c_void, v_ptr1.len() as c_uint,
+                                              v_ptr2.len() as c_uint) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_Unlock", e)),
+        }
     }
 
-    pub fn set_user_data<'r, T>(&'r mut self, user_data: &'r mut T) -> ::Status {
+    pub fn set_user_data<'r, T>(&'r mut self, user_data: &'r mut T) -> Result<(), ::FmodError> {
         let mut data : *mut c_void = ::std::ptr::null_mut();
 
-        unsafe {
+        let status = unsafe {
             match ffi::FMOD_Sound_GetUserData(self.sound, &mut data) {
                ::Status::Ok => {
                     if data.is_null() {
@@ -710,10 +870,16 @@ impl Sound {
                     ffi::FMOD_Sound_SetUserData(self.sound, transmute(&mut self.user_data))
                 }
             }
+        };
+
+        match status {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_Sound_SetUserData", e)),
         }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
+    /// Returns None if no user data has been set.
+    pub fn get_user_data<'r, T>(&'r self) -> Result<Option<&'r mut T>, ::FmodError> {
         unsafe {
             let mut user_data : *mut c_void = ::std::ptr::null_mut();
 
@@ -722,109 +888,141 @@ impl Sound {
                     if !user_data.is_null() {
                         let tmp: &mut ffi::SoundData =
                             transmute::<*mut c_void, &mut ffi::SoundData>(user_data);
-                        let tmp2: &mut T = transmute::<*mut c_void, &mut T>(tmp.user_data);
-                        
-                        Ok(tmp2)
+                        if tmp.user_data.is_null() {
+                            Ok(None)
+                        } else {
+                            Ok(Some(transmute::<*mut c_void, &mut T>(tmp.user_data)))
+                        }
                     } else {
-                        // ?
-                        Err(::Status::Ok)
+                        Ok(None)
                     }
                 },
-                e => Err(e)
+                e => Err(::FmodError::new("FMOD_Sound_GetUserData", e))
             }
         }
     }
 
-    pub fn save_to_wav(&self, file_name: &str) -> Result<bool, String> {
-        unsafe {
-            let mut channels = 0i32;
-            let mut bits = 0i32;
-            let mut rate = 0f32;
+    /// Exports the sound data to a wav file at `file_name`. See
+    /// [`write_wav`](#method.write_wav).
+    pub fn save_to_wav(&self, file_name: &str) -> Result<(), WavError> {
+        let mut file = match File::create(file_name) {
+            Ok(f) => f,
+            Err(e) => return Err(WavError::Io(e)),
+        };
+
+        self.write_wav(&mut file)
+    }
+
+    /// Writes the sound data as a wav file into `writer`.
+    ///
+    /// PCM sounds (8, 16, 24 and 32 bits) are written with the PCM format tag and
+    /// [`SoundFormat::PCMFloat`](enum.SoundFormat.html) ones with the IEEE float format tag. Other
+    /// formats return [`WavError::UnsupportedFormat`](error/enum.WavError.html).
+    ///
+    /// Samples are locked and copied out, except for sounds opened with
+    /// [`CREATESTREAM`](constant.CREATESTREAM.html) or [`OPENONLY`](constant.OPENONLY.html) which
+    /// are decoded from their beginning with [`read_data`](#method.read_data). As the length of a
+    /// stream may not be known ahead, the header is rewritten once all the data has been written,
+    /// hence the `Seek` bound.
+    pub fn write_wav<W: Write + Seek>(&self, writer: &mut W) -> Result<(), WavError> {
+        let (format, channels, bits) = match self.get_format() {
+            Ok((_, format, channels, bits)) => (format, channels as u16, bits as u16),
+            Err(e) => return Err(WavError::Fmod(e)),
+        };
+        let format_tag = match format {
+            ::SoundFormat::PCM8 | ::SoundFormat::PCM16 | ::SoundFormat::PCM24
+                | ::SoundFormat::PCM32 => WAVE_FORMAT_PCM,
+            ::SoundFormat::PCMFloat => WAVE_FORMAT_IEEE_FLOAT,
+            f => return Err(WavError::UnsupportedFormat(f)),
+        };
+        let rate = match self.get_defaults() {
+            Ok((frequency, _, _, _)) => frequency as u32,
+            Err(e) => return Err(WavError::Fmod(e)),
+        };
+        let Mode(mode) = match self.get_mode() {
+            Ok(m) => m,
+            Err(e) => return Err(WavError::Fmod(e)),
+        };
+        let start = match writer.seek(SeekFrom::Current(0)) {
+            Ok(s) => s,
+            Err(e) => return Err(WavError::Io(e)),
+        };
+        let mut data_len = 0u32;
+
+        if let Err(e) = writer.write_all(&wav_header(format_tag, channels, rate, bits, 0)) {
+            return Err(WavError::Io(e));
+        }
+        if mode & (::CREATESTREAM | ::OPENONLY) != 0 {
+            let mut buffer = vec![0u8; 16384];
+
+            match self.seek_data(0) {
+                Ok(()) => {}
+                Err(e) => return Err(WavError::Fmod(e)),
+            }
+            loop {
+                let read = match self.read_data(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(r) => r,
+                    Err(e) => return Err(WavError::Fmod(e)),
+                };
+
+                match write_wav_data(writer, &buffer[..read as usize], format) {
+                    Ok(()) => {}
+                    Err(e) => return Err(e),
+                }
+                data_len += read;
+            }
+        } else {
             let len_bytes = match self.get_length(::TIMEUNIT_PCMBYTES) {
                 Ok(l) => l,
-                Err(e) => return Err(format!("{:?}", e))
+                Err(e) => return Err(WavError::Fmod(e)),
             };
             let mut len1 = 0u32;
             let mut len2 = 0u32;
-            let mut ptr1: *mut c_void =::std::ptr::null_mut();
-            let mut ptr2: *mut c_void =::std::ptr::null_mut();
-
-            match ffi::FMOD_Sound_GetFormat(self.sound, ::std::ptr::null_mut(),
-                                            ::std::ptr::null_mut(), &mut channels, &mut bits) {
-               ::Status::Ok => match ffi::FMOD_Sound_GetDefaults(self.sound, &mut rate,
-                                                                 ::std::ptr::null_mut(),
-                                                                 ::std::ptr::null_mut(),
-                                                                 ::std::ptr::null_mut()) {
-                   ::Status::Ok => {}
-                    e => return Err(format!("{:?}", e))
-                },
-                e => return Err(format!("{:?}", e))
-            };
-            let fmt_chunk = FmtChunk {
-                chunk: RiffChunk {
-                    id: ['f' as i8, 'm' as i8, 't' as i8, ' ' as i8],
-                    size: mem::size_of::<FmtChunk>() as i32 - mem::size_of::<RiffChunk>() as i32
-                },
-                w_format_tag: 1,
-                n_channels: channels as u16,
-                n_samples_per_sec: rate as u32,
-                n_avg_bytes_per_sec: rate as u32 * channels as u32 * bits as u32 / 8u32,
-                n_block_align: 1u16 * channels as u16 * bits as u16 / 8u16,
-                w_bits_per_sample: bits as u16
-            };
-            let data_chunk = DataChunk {
-                chunk: RiffChunk {
-                    id: ['d' as i8, 'a' as i8, 't' as i8, 'a' as i8],
-                    size: len_bytes as i32
-                }
-            };
-            let wav_header = WavHeader {
-                chunk: RiffChunk {
-                    id: ['R' as i8, 'I' as i8, 'F' as i8, 'F' as i8],
-                    size: mem::size_of::<FmtChunk>() as i32 + mem::size_of::<RiffChunk>() as i32 + len_bytes as i32
-                },
-                riff_type: ['W' as i8, 'A' as i8, 'V' as i8, 'E' as i8]
-            };
-
-            let mut file = match File::create(file_name) {
-                Ok(f) => f,
-                Err(e) => return Err(format!("{}", e))
-            };
-            let mut wtr = vec![];
+            let mut ptr1: *mut c_void = ::std::ptr::null_mut();
+            let mut ptr2: *mut c_void = ::std::ptr::null_mut();
 
-            /* wav header */
-            for it in 0usize..4usize {
-                wtr.write_i8(wav_header.chunk.id[it]).unwrap();
-            }
-            wtr.write_i32::<LittleEndian>(wav_header.chunk.size).unwrap();
-            for it in 0usize..4usize {
-                wtr.write_i8(wav_header.riff_type[it]).unwrap();
+            match unsafe { ffi::FMOD_Sound_Lock(self.sound, 0, len_bytes, &mut ptr1, &mut ptr2,
+                                                &mut len1, &mut len2) } {
+                ::Status::Ok => {}
+                e => return Err(WavError::Fmod(::FmodError::new("FMOD_Sound_Lock", e))),
             }
 
-            /* wav chunk */
-            for it in 0usize..4usize {
-                wtr.write_i8(fmt_chunk.chunk.id[it]).unwrap();
+            let mut ret = Ok(());
+            for &(ptr, len) in [(ptr1, len1), (ptr2, len2)].iter() {
+                if ptr.is_null() || len == 0 || ret.is_err() {
+                    continue;
+                }
+                let data = unsafe { slice::from_raw_parts(ptr as *const u8, len as usize) };
+
+                ret = write_wav_data(writer, data, format);
+                data_len += len;
             }
-            wtr.write_i32::<LittleEndian>(fmt_chunk.chunk.size).unwrap();
-            wtr.write_u16::<LittleEndian>(fmt_chunk.w_format_tag).unwrap();
-            wtr.write_u16::<LittleEndian>(fmt_chunk.n_channels).unwrap();
-            wtr.write_u32::<LittleEndian>(fmt_chunk.n_samples_per_sec).unwrap();
-            wtr.write_u32::<LittleEndian>(fmt_chunk.n_avg_bytes_per_sec).unwrap();
-            wtr.write_u16::<LittleEndian>(fmt_chunk.n_block_align).unwrap();
-            wtr.write_u16::<LittleEndian>(fmt_chunk.w_bits_per_sample).unwrap();
-
-            /* wav data chunk */
-            for it in 0usize..4usize {
-                wtr.write_i8(data_chunk.chunk.id[it]).unwrap();
+            unsafe { ffi::FMOD_Sound_Unlock(self.sound, ptr1, ptr2, len1, len2) };
+            if let Err(e) = ret {
+                return Err(e);
             }
-            wtr.write_i32::<LittleEndian>(data_chunk.chunk.size).unwrap();
-
-            ffi::FMOD_Sound_Lock(self.sound, 0, len_bytes, &mut ptr1, &mut ptr2, &mut len1, &mut len2);
+        }
+        let pad = data_len as u64 & 1;
 
-            file.write_all(&wtr).unwrap();
+        if pad != 0 {
+            // chunks have to be word aligned
+            if let Err(e) = writer.write_all(&[0u8]) {
+                return Err(WavError::Io(e));
+            }
+        }
 
-            ffi::FMOD_Sound_Unlock(self.sound, ptr1, ptr2, len1, len2);
+        // now that the data length is known, the header can be completed, and the writer left
+        // after the wav data, which isn't the end of `writer` if it held more data already
+        let header = wav_header(format_tag, channels, rate, bits, data_len);
+        let end = start + header.len() as u64 + data_len as u64 + pad;
+        let ret = writer.seek(SeekFrom::Start(start))
+                        .and_then(|_| writer.write_all(&header))
+                        .and_then(|_| writer.seek(SeekFrom::Start(end)));
+
+        match ret {
+            Ok(_) => Ok(()),
+            Err(e) => Err(WavError::Io(e)),
         }
-        Ok(true)
     }
 }
@@ -27,7 +27,8 @@ use ffi;
 use sound;
 use libc::c_void;
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
+use fmod_sys::{MemoryUsageDetails, SystemRef};
+use std::sync::Arc;
 use std::mem::transmute;
 use libc::{c_char};
 use std::default::Default;
@@ -35,11 +36,12 @@ use std::default::Default;
 /// SoundGroup object
 pub struct SoundGroup {
     sound_group: *mut ffi::FMOD_SOUNDGROUP,
+    system_ref: Option<Arc<SystemRef>>
 }
 
 impl ffi::FFI<ffi::FMOD_SOUNDGROUP> for SoundGroup {
     fn wrap(s: *mut ffi::FMOD_SOUNDGROUP) -> SoundGroup {
-        SoundGroup {sound_group: s}
+        SoundGroup {sound_group: s, system_ref: get_system_ref(s)}
     }
 
     fn unwrap(s: &SoundGroup) -> *mut ffi::FMOD_SOUNDGROUP {
@@ -49,84 +51,118 @@ impl ffi::FFI<ffi::FMOD_SOUNDGROUP> for SoundGroup {
 
 impl Drop for SoundGroup {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
+    }
+}
+
+/// Wraps a sound group returned by an object sharing `system_ref`, without looking it up again.
+pub fn from_ptr(sound_group: *mut ffi::FMOD_SOUNDGROUP,
+                system_ref: Option<Arc<SystemRef>>) -> SoundGroup {
+    SoundGroup {sound_group: sound_group, system_ref: system_ref}
+}
+
+fn get_system_ref(sound_group: *mut ffi::FMOD_SOUNDGROUP) -> Option<Arc<SystemRef>> {
+    let mut system = ::std::ptr::null_mut();
+
+    if sound_group.is_null() {
+        return None;
+    }
+    match unsafe { ffi::FMOD_SoundGroup_GetSystemObject(sound_group, &mut system) } {
+        ::Status::Ok => fmod_sys::get_system_ref(system),
+        _ => None,
     }
 }
 
 impl SoundGroup {
-    pub fn release(&mut self) -> ::Status {
+    pub fn release(&mut self) -> Result<(), ::FmodError> {
         if !self.sound_group.is_null() {
             match unsafe { ffi::FMOD_SoundGroup_Release(self.sound_group) } {
                ::Status::Ok => {
                     self.sound_group =::std::ptr::null_mut();
-                   ::Status::Ok
+                   Ok(())
                 }
-                e => e
+                e => Err(::FmodError::new("FMOD_SoundGroup_Release", e))
             }
         } else {
-           ::Status::Ok
+           Ok(())
         }
     }
 
-    pub fn set_max_audible(&self, max_audible: i32) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetMaxAudible(self.sound_group, max_audible) }
+    pub fn set_max_audible(&self, max_audible: i32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_SoundGroup_SetMaxAudible(self.sound_group, max_audible) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_SoundGroup_SetMaxAudible", e)),
+        }
     }
 
-    pub fn get_max_audible(&self) -> Result<i32, ::Status> {
+    pub fn get_max_audible(&self) -> Result<i32, ::FmodError> {
         let mut max_audible = 0i32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetMaxAudible(self.sound_group, &mut max_audible) } {
             ::Status::Ok => Ok(max_audible),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_SoundGroup_GetMaxAudible", e))
         }
     }
 
-    pub fn set_max_audible_behavior(&self, max_audible_behavior: ::SoundGroupBehavior) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetMaxAudibleBehavior(self.sound_group,
-                                                            max_audible_behavior) }
+    pub fn set_max_audible_behavior(&self, max_audible_behavior: ::SoundGroupBehavior)
+                                   -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_SoundGroup_SetMaxAudibleBehavior(self.sound_group,
+                                                                  max_audible_behavior) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_SoundGroup_SetMaxAudibleBehavior", e)),
+        }
     }
 
-    pub fn get_max_audible_behavior(&self) -> Result<::SoundGroupBehavior, ::Status> {
+    pub fn get_max_audible_behavior(&self) -> Result<::SoundGroupBehavior, ::FmodError> {
         let mut max_audible_behavior = ::SoundGroupBehavior::Fail;
 
         match unsafe { ffi::FMOD_SoundGroup_GetMaxAudibleBehavior(self.sound_group,
                                                                   &mut max_audible_behavior) } {
             ::Status::Ok => Ok(max_audible_behavior),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_SoundGroup_GetMaxAudibleBehavior", e))
         }
     }
 
-    pub fn set_mute_fade_speed(&self, speed: f32) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetMuteFadeSpeed(self.sound_group, speed) }
+    pub fn set_mute_fade_speed(&self, speed: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_SoundGroup_SetMuteFadeSpeed(self.sound_group, speed) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_SoundGroup_SetMuteFadeSpeed", e)),
+        }
     }
 
-    pub fn get_mute_fade_speed(&self) -> Result<f32, ::Status> {
+    pub fn get_mute_fade_speed(&self) -> Result<f32, ::FmodError> {
         let mut speed = 0f32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetMuteFadeSpeed(self.sound_group, &mut speed) } {
             ::Status::Ok => Ok(speed),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_SoundGroup_GetMuteFadeSpeed", e))
         }
     }
 
-    pub fn set_volume(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetVolume(self.sound_group, volume) }
+    pub fn set_volume(&self, volume: f32) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_SoundGroup_SetVolume(self.sound_group, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_SoundGroup_SetVolume", e)),
+        }
     }
 
-    pub fn get_volume(&self) -> Result<f32, ::Status> {
+    pub fn get_volume(&self) -> Result<f32, ::FmodError> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetVolume(self.sound_group, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_SoundGroup_GetVolume", e))
         }
     }
 
-    pub fn stop(&self) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_Stop(self.sound_group) }
+    pub fn stop(&self) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_SoundGroup_Stop(self.sound_group) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_SoundGroup_Stop", e)),
+        }
     }
 
-    pub fn get_name(&self, name_len: usize) -> Result<String, ::Status> {
+    pub fn get_name(&self, name_len: usize) -> Result<String, ::FmodError> {
         let mut c = Vec::with_capacity(name_len + 1);
 
         for _ in 0..(name_len + 1) {
@@ -136,34 +172,34 @@ impl SoundGroup {
         match unsafe { ffi::FMOD_SoundGroup_GetName(self.sound_group, c.as_mut_ptr() as *mut c_char,
                                                     name_len as i32) } {
             ::Status::Ok => Ok(String::from_utf8(c).unwrap()),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_SoundGroup_GetName", e))
         }
     }
 
-    pub fn get_num_sounds(&self) -> Result<i32, ::Status> {
+    pub fn get_num_sounds(&self) -> Result<i32, ::FmodError> {
         let mut num_sounds = 0i32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetNumSounds(self.sound_group, &mut num_sounds) } {
             ::Status::Ok => Ok(num_sounds),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_SoundGroup_GetNumSounds", e))
         }
     }
 
-    pub fn get_sound(&self, index: i32) -> Result<sound::Sound, ::Status> {
+    pub fn get_sound(&self, index: i32) -> Result<sound::Sound, ::FmodError> {
         let mut sound = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_SoundGroup_GetSound(self.sound_group, index, &mut sound) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound)),
-            e => Err(e)
+            ::Status::Ok => Ok(sound::from_ptr(sound, self.system_ref.clone())),
+            e => Err(::FmodError::new("FMOD_SoundGroup_GetSound", e))
         }
     }
 
-    pub fn get_num_playing(&self) -> Result<i32, ::Status> {
+    pub fn get_num_playing(&self) -> Result<i32, ::FmodError> {
         let mut num_playing = 0i32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetNumPlaying(self.sound_group, &mut num_playing) } {
             ::Status::Ok => Ok(num_playing),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_SoundGroup_GetNumPlaying", e))
         }
     }
 
@@ -172,31 +208,37 @@ impl SoundGroup {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::FmodError> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetMemoryInfo(self.sound_group, memory_bits, event_memory_bits, &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::FmodError::new("FMOD_SoundGroup_GetMemoryInfo", e))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetUserData(self.sound_group, transmute(user_data)) }
+    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> Result<(), ::FmodError> {
+        match unsafe { ffi::FMOD_SoundGroup_SetUserData(self.sound_group, transmute(user_data)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::FmodError::new("FMOD_SoundGroup_SetUserData", e)),
+        }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
+    /// Returns None if no user data has been set.
+    pub fn get_user_data<'r, T>(&'r self) -> Result<Option<&'r mut T>, ::FmodError> {
         unsafe {
             let mut user_data : *mut c_void = ::std::ptr::null_mut();
 
             match ffi::FMOD_SoundGroup_GetUserData(self.sound_group, &mut user_data) {
                ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
+                    if user_data.is_null() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(transmute::<*mut c_void, &mut T>(user_data)))
+                    }
                 },
-                e => Err(e)
+                e => Err(::FmodError::new("FMOD_SoundGroup_GetUserData", e))
             }
         }
     }
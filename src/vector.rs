@@ -42,6 +42,7 @@ pub fn get_ffi(vec: &Vector) -> ffi::FMOD_VECTOR {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// Structure describing a point in 3D space.
 pub struct Vector {
     /// X co-ordinate in 3D space.
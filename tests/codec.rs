@@ -0,0 +1,222 @@
+extern crate rfmod;
+
+use rfmod::mock;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn init() -> rfmod::Sys {
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init_with_parameters(32, rfmod::InitFlag(rfmod::INIT_NORMAL)).unwrap();
+    fmod
+}
+
+const MAGIC : &'static [u8] = b"RAW1";
+
+/// Builds a file of the test codec: the magic, then each PCM16 mono sample followed by a padding
+/// byte the codec skips with a relative seek.
+fn raw_file(samples: &[i16]) -> Vec<u8> {
+    let mut data = MAGIC.to_vec();
+
+    for sample in samples {
+        data.push(*sample as u8);
+        data.push((*sample >> 8) as u8);
+        data.push(0xee);
+    }
+    data
+}
+
+fn pcm16(samples: &[i16]) -> Vec<u8> {
+    let mut data = Vec::new();
+
+    for sample in samples {
+        data.push(*sample as u8);
+        data.push((*sample >> 8) as u8);
+    }
+    data
+}
+
+/// Codec rejecting every file.
+struct Rejecting;
+
+impl rfmod::Codec for Rejecting {
+    fn get_description(&self) -> rfmod::FmodCodecDescription {
+        rfmod::FmodCodecDescription {
+            name: "rejecting".to_owned(),
+            .. Default::default()
+        }
+    }
+
+    fn open(&self, _file: &mut rfmod::CodecFile, _mode: rfmod::Mode)
+            -> Result<Box<dyn rfmod::CodecStream>, rfmod::Status> {
+        Err(rfmod::Status::Format)
+    }
+}
+
+/// Codec of the files built by `raw_file`, counting the streams it closed.
+struct Raw {
+    closed: Arc<AtomicUsize>,
+}
+
+impl rfmod::Codec for Raw {
+    fn get_description(&self) -> rfmod::FmodCodecDescription {
+        rfmod::FmodCodecDescription {
+            name: "raw".to_owned(),
+            version: 1,
+            time_units: rfmod::TIMEUNIT_PCM,
+            .. Default::default()
+        }
+    }
+
+    fn open(&self, file: &mut rfmod::CodecFile, _mode: rfmod::Mode)
+            -> Result<Box<dyn rfmod::CodecStream>, rfmod::Status> {
+        let mut magic = [0u8; 4];
+
+        match file.read_exact(&mut magic) {
+            Ok(()) if magic == MAGIC => {}
+            _ => return Err(rfmod::Status::Format),
+        }
+        let length = match file.seek(SeekFrom::End(0)) {
+            Ok(l) => l,
+            Err(_) => return Err(rfmod::Status::FileBad),
+        };
+        if file.seek(SeekFrom::Start(MAGIC.len() as u64)).is_err() {
+            return Err(rfmod::Status::FileBad);
+        }
+        Ok(Box::new(RawStream {
+            samples: ((length - MAGIC.len() as u64) / 3) as u32,
+            closed: self.closed.clone(),
+        }))
+    }
+}
+
+struct RawStream {
+    samples: u32,
+    closed: Arc<AtomicUsize>,
+}
+
+impl Drop for RawStream {
+    fn drop(&mut self) {
+        self.closed.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl rfmod::CodecStream for RawStream {
+    fn get_wave_format(&self) -> rfmod::CodecWaveFormat {
+        rfmod::CodecWaveFormat {
+            format: rfmod::SoundFormat::PCM16,
+            channels: 1,
+            frequency: 8000,
+            length_bytes: self.samples * 3,
+            length_pcm: self.samples,
+            block_align: 2,
+            .. Default::default()
+        }
+    }
+
+    fn read(&mut self, file: &mut rfmod::CodecFile, buffer: &mut [u8])
+            -> Result<usize, rfmod::Status> {
+        let mut read = 0;
+
+        // Reads a few samples per call, so the position has to be kept between calls.
+        while read + 2 <= buffer.len() && read < 4 {
+            let mut sample = [0u8; 2];
+
+            match file.read(&mut sample) {
+                Ok(2) => {}
+                Ok(_) => break,
+                Err(_) => return Err(rfmod::Status::FileBad),
+            }
+            if file.seek(SeekFrom::Current(1)).is_err() {
+                return Err(rfmod::Status::FileBad);
+            }
+            buffer[read..read + 2].copy_from_slice(&sample);
+            read += 2;
+        }
+        Ok(read)
+    }
+
+    fn set_position(&mut self, file: &mut rfmod::CodecFile, _sub_sound: i32, position: u32,
+                    time_unit: rfmod::TimeUnit) -> rfmod::Status {
+        if time_unit.0 != rfmod::TIMEUNIT_PCM.0 || position > self.samples {
+            return rfmod::Status::InvalidPosition;
+        }
+        match file.seek(SeekFrom::Start(MAGIC.len() as u64 + position as u64 * 3)) {
+            Ok(_) => rfmod::Status::Ok,
+            Err(_) => rfmod::Status::FileCouldNotSeek,
+        }
+    }
+}
+
+fn register(fmod: &rfmod::Sys) -> Arc<AtomicUsize> {
+    let closed = Arc::new(AtomicUsize::new(0));
+
+    fmod.register_codec(Box::new(Rejecting), 100).unwrap();
+    fmod.register_codec(Box::new(Raw { closed: closed.clone() }), 200).unwrap();
+    closed
+}
+
+#[test]
+fn sample_is_decoded_by_codec() {
+    let fmod = init();
+    let closed = register(&fmod);
+    let samples = [0i16, 1000, -1000, 32767, -32768, 12, 34];
+
+    mock::set_file("codec_sample.raw", raw_file(&samples));
+    let sound = fmod.create_sound("codec_sample.raw",
+                                  Some(rfmod::Mode(rfmod::CREATESAMPLE)), None).unwrap();
+
+    assert_eq!(mock::get_data(&sound), Some(pcm16(&samples)));
+    assert_eq!(sound.get_length(rfmod::TIMEUNIT_PCM).unwrap(), samples.len() as u32);
+    let (_, format, channels, _) = sound.get_format().unwrap();
+    assert_eq!(format, rfmod::SoundFormat::PCM16);
+    assert_eq!(channels, 1);
+    // A sample is decoded at once: the stream has been closed already.
+    assert_eq!(closed.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn unknown_format_is_rejected() {
+    let fmod = init();
+    let closed = register(&fmod);
+
+    mock::set_file("codec_unknown.raw", b"RIFF0000WAVE".to_vec());
+    match fmod.create_sound("codec_unknown.raw", Some(rfmod::Mode(rfmod::CREATESAMPLE)), None) {
+        Ok(_) => panic!("a file unknown to every codec was opened"),
+        Err(e) => assert_eq!(e.get_status(), rfmod::Status::Format),
+    }
+    assert_eq!(closed.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn stream_reads_and_seeks() {
+    let fmod = init();
+    let closed = register(&fmod);
+    let samples = [1i16, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+    mock::set_file("codec_stream.raw", raw_file(&samples));
+    let sound = fmod.create_sound("codec_stream.raw",
+                                  Some(rfmod::Mode(rfmod::OPENONLY)), None).unwrap();
+    let mut buffer = [0u8; 64];
+    let mut data = Vec::new();
+
+    loop {
+        let read = sound.read_data(&mut buffer).unwrap() as usize;
+
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&buffer[..read]);
+    }
+    assert_eq!(data, pcm16(&samples));
+
+    sound.seek_data(6).unwrap();
+    let read = sound.read_data(&mut buffer[..4]).unwrap() as usize;
+    assert_eq!(&buffer[..read], &pcm16(&samples[6..8])[..]);
+    assert!(sound.seek_data(11).is_err());
+
+    assert_eq!(closed.load(Ordering::SeqCst), 0);
+    drop(sound);
+    assert_eq!(closed.load(Ordering::SeqCst), 1);
+}
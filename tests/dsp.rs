@@ -0,0 +1,229 @@
+extern crate rfmod;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+use rfmod::mock::{self, Value};
+use rfmod::error::DspParamError;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn init() -> rfmod::Sys {
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init_with_parameters(32, rfmod::InitFlag(rfmod::INIT_NORMAL)).unwrap();
+    fmod
+}
+
+#[test]
+fn typed_dsp_sets_parameters() {
+    let fmod = init();
+    let echo : rfmod::EchoDsp = fmod.create_typed_DSP().unwrap();
+
+    echo.set(rfmod::DspTypeEcho::Delay, 250f32).unwrap();
+    assert_eq!(echo.get(rfmod::DspTypeEcho::Delay).unwrap(), 250f32);
+    assert!(mock::get_calls_of(echo.get_dsp()).iter().any(|c| {
+        c.function == "FMOD_DSP_SetParameter" && c.args == [Value::Int(0), Value::Float(250f32)]
+    }));
+}
+
+#[test]
+fn typed_dsp_checks_the_type() {
+    let fmod = init();
+    let low_pass = fmod.create_DSP_by_type(rfmod::DspType::LowPass).unwrap();
+
+    match rfmod::EchoDsp::new(low_pass) {
+        Err(DspParamError::WrongType { expected, found }) => {
+            assert_eq!(expected, rfmod::DspType::Echo);
+            assert_eq!(found, rfmod::DspType::LowPass);
+        }
+        _ => panic!("a low pass unit was accepted as an echo"),
+    }
+}
+
+#[test]
+fn typed_dsp_checks_the_range() {
+    let fmod = init();
+    let echo : rfmod::EchoDsp = fmod.create_typed_DSP().unwrap();
+
+    assert!(mock::set_value(echo.get_dsp(), "parameter0.min", Value::Float(10f32)));
+    assert!(mock::set_value(echo.get_dsp(), "parameter0.max", Value::Float(5000f32)));
+    assert_eq!(echo.get_range(rfmod::DspTypeEcho::Delay).unwrap(), (10f32, 5000f32));
+    match echo.set(rfmod::DspTypeEcho::Delay, 6000f32) {
+        Err(DspParamError::OutOfRange { value, min, max }) => {
+            assert_eq!((value, min, max), (6000f32, 10f32, 5000f32));
+        }
+        _ => panic!("an out of range value was accepted"),
+    }
+    assert!(mock::get_calls_of(echo.get_dsp()).iter()
+                                              .all(|c| c.function != "FMOD_DSP_SetParameter"));
+}
+
+#[test]
+fn untyped_param_checks_the_type() {
+    let fmod = init();
+    let dsp = fmod.create_DSP_by_type(rfmod::DspType::LowPass).unwrap();
+
+    assert!(dsp.set_param(rfmod::DspTypeEcho::Delay, 100f32).is_err());
+    assert!(dsp.set_param(rfmod::DspLowPass::Cutoff, 100f32).is_ok());
+    assert_eq!(dsp.get_param(rfmod::DspLowPass::Cutoff).unwrap(), 100f32);
+}
+
+struct Gain {
+    gain: Arc<Mutex<f32>>,
+    drops: Arc<AtomicUsize>,
+}
+
+impl rfmod::DspProcessor for Gain {
+    fn process(&mut self, input: &[f32], output: &mut [f32], _channels: usize) {
+        let gain = *self.gain.lock().unwrap();
+
+        for (o, i) in output.iter_mut().zip(input.iter()) {
+            *o = *i * gain;
+        }
+    }
+
+    fn set_param(&mut self, _index: i32, value: f32) {
+        *self.gain.lock().unwrap() = value;
+    }
+
+    fn get_param(&self, _index: i32) -> f32 {
+        *self.gain.lock().unwrap()
+    }
+
+    fn get_parameters(&self) -> Vec<rfmod::DspParameterDesc> {
+        vec![rfmod::DspParameterDesc { min: 0f32, max: 2f32, default_val: 1f32,
+                                       name: "Gain".to_owned(), ..Default::default() }]
+    }
+}
+
+impl Drop for Gain {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn processor_receives_parameters_and_is_dropped_once() {
+    let fmod = init();
+    let gain = Arc::new(Mutex::new(1f32));
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut dsp = fmod.create_DSP_from(Box::new(Gain { gain: gain.clone(),
+                                                       drops: drops.clone() })).unwrap();
+
+    assert_eq!(dsp.get_num_parameters().unwrap(), 1);
+    dsp.set_parameter(0, 0.5f32).unwrap();
+    assert_eq!(*gain.lock().unwrap(), 0.5f32);
+    assert_eq!(dsp.get_parameter(0, 16).unwrap().0, 0.5f32);
+    assert!(dsp.set_parameter(1, 0.5f32).is_err());
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+    dsp.release().unwrap();
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+    drop(dsp);
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn graph_of_the_mock_network() {
+    let fmod = init();
+    let echo = fmod.create_DSP_by_type(rfmod::DspType::Echo).unwrap();
+
+    echo.set_parameter(0, 250f32).unwrap();
+    echo.set_bypass(true).unwrap();
+    fmod.add_DSP(&echo).unwrap();
+
+    let graph = fmod.dsp_graph().unwrap();
+    let head = fmod.get_DSP_head().unwrap();
+    let head_id = mock::Handle::handle(&head);
+    let echo_id = mock::Handle::handle(&echo);
+
+    assert_eq!(graph.nodes[0].id, head_id);
+    assert!(graph.nodes[0].active);
+    let node = graph.get_node(echo_id).unwrap();
+    assert_eq!(node.dsp_type, rfmod::DspType::Echo);
+    assert!(node.bypass);
+    assert_eq!(node.parameters[0].value, 250f32);
+    let outputs = graph.get_outputs(echo_id);
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].output, head_id);
+    assert_eq!(outputs[0].mix, 1f32);
+}
+
+#[test]
+fn preset_round_trips_parameters() {
+    let fmod = init();
+    let echo = fmod.create_DSP_by_type(rfmod::DspType::Echo).unwrap();
+
+    echo.set_parameter(0, 250f32).unwrap();
+    echo.set_parameter(1, 0.25f32).unwrap();
+    assert!(mock::set_value(&echo, "parameter0.name", Value::Str("Delay".to_owned())));
+    let preset = rfmod::DspPreset::from_dsp(&echo).unwrap();
+
+    assert_eq!(preset.dsp_type, rfmod::DspType::Echo);
+    assert_eq!(preset.parameters.len(), 2);
+    assert_eq!(preset.parameters[0].name, "Delay");
+    assert_eq!(preset.get_parameter(0), Some(250f32));
+    assert_eq!(preset.get_parameter(1), Some(0.25f32));
+
+    let other = fmod.create_DSP_by_type(rfmod::DspType::Echo).unwrap();
+
+    assert!(mock::set_value(&other, "parameter0.name", Value::Str("Delay".to_owned())));
+    preset.apply(&other).unwrap();
+    assert_eq!(other.get_parameter(0, 16).unwrap().0, 250f32);
+    assert_eq!(other.get_parameter(1, 16).unwrap().0, 0.25f32);
+    assert_eq!(rfmod::DspPreset::from_dsp(&other).unwrap(), preset);
+
+    let created = preset.create(&fmod).unwrap();
+    assert_eq!(created.get_type().unwrap(), rfmod::DspType::Echo);
+    assert_eq!(created.get_parameter(0, 16).unwrap().0, 250f32);
+}
+
+#[test]
+fn preset_checks_the_type() {
+    let fmod = init();
+    let mut preset = rfmod::DspPreset::new(rfmod::DspType::Echo);
+    let low_pass = fmod.create_DSP_by_type(rfmod::DspType::LowPass).unwrap();
+
+    preset.set_parameter(0, 250f32);
+    match preset.apply(&low_pass) {
+        Err(DspParamError::WrongType { expected, found }) => {
+            assert_eq!(expected, rfmod::DspType::Echo);
+            assert_eq!(found, rfmod::DspType::LowPass);
+        }
+        _ => panic!("an echo preset was applied to a low pass unit"),
+    }
+    assert!(mock::get_calls_of(&low_pass).iter().all(|c| c.function != "FMOD_DSP_SetParameter"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let fmod = init();
+    let mut preset = rfmod::DspPreset::new(rfmod::DspType::Echo);
+
+    preset.set_parameter(0, 250f32);
+    preset.set_parameter(1, 0.25f32);
+    let json = serde_json::to_string(&preset).unwrap();
+    assert_eq!(serde_json::from_str::<rfmod::DspPreset>(&json).unwrap(), preset);
+    // parameter names can be left out
+    let preset : rfmod::DspPreset = serde_json::from_str(r#"{
+        "dsp_type": "Echo",
+        "parameters": [{ "index": 0, "value": 250.0 }]
+    }"#).unwrap();
+    assert_eq!(preset.get_parameter(0), Some(250f32));
+
+    let reverb = rfmod::ReverbProperties::bathroom();
+    let json = serde_json::to_string(&reverb).unwrap();
+    assert_eq!(serde_json::from_str::<rfmod::ReverbProperties>(&json).unwrap(), reverb);
+
+    let channel_reverb = rfmod::ReverbChannelProperties {
+        direct: -100,
+        room: -200,
+        flags: 1,
+        connection_point: fmod.create_DSP_by_type(rfmod::DspType::Echo).unwrap(),
+    };
+    let json = serde_json::to_string(&channel_reverb).unwrap();
+    assert!(!json.contains("connection_point"));
+    let channel_reverb : rfmod::ReverbChannelProperties = serde_json::from_str(&json).unwrap();
+    assert_eq!((channel_reverb.direct, channel_reverb.room, channel_reverb.flags), (-100, -200, 1));
+    assert_eq!(mock::Handle::handle(&channel_reverb.connection_point), 0);
+}
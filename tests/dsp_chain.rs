@@ -0,0 +1,171 @@
+extern crate rfmod;
+
+use rfmod::mock;
+
+fn init() -> rfmod::Sys {
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init_with_parameters(32, rfmod::InitFlag(rfmod::INIT_NORMAL)).unwrap();
+    fmod
+}
+
+fn chain(types: &[rfmod::DspType]) -> rfmod::DspChain {
+    let mut chain = rfmod::DspChain::new();
+
+    for &dsp_type in types.iter() {
+        chain.push(rfmod::DspEffect::new(dsp_type));
+    }
+    chain
+}
+
+/// Returns the ids of the units met following the first output of `dsp`.
+fn path_from(dsp: &rfmod::Dsp) -> Vec<usize> {
+    let mut path = vec![mock::Handle::handle(dsp)];
+    let mut current = match dsp.get_output(0) {
+        Ok((output, _)) => output,
+        Err(_) => return path,
+    };
+
+    loop {
+        path.push(mock::Handle::handle(&current));
+        current = match current.get_output(0) {
+            Ok((output, _)) => output,
+            Err(_) => return path,
+        };
+    }
+}
+
+fn unit_ids(applied: &rfmod::AppliedDspChain) -> Vec<usize> {
+    applied.get_units().iter().map(|&(_, ref dsp)| mock::Handle::handle(dsp)).collect()
+}
+
+fn count_calls(objects: &[usize], function: &str) -> usize {
+    mock::get_calls().iter().filter(|c| c.function == function && objects.contains(&c.object))
+                            .count()
+}
+
+fn count_created(fmod: &rfmod::Sys) -> usize {
+    count_calls(&[mock::Handle::handle(fmod)], "FMOD_System_CreateDSPByType")
+}
+
+#[test]
+fn channels_played_after_apply_go_through_the_chain() {
+    let fmod = init();
+    let group = fmod.create_channel_group("effects").unwrap();
+    let group_head = group.get_DSP_head().unwrap();
+    let applied = chain(&[rfmod::DspType::LowPass, rfmod::DspType::Echo])
+                      .apply_to_channel_group(&group).unwrap();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let mut channel = sound.play().unwrap();
+
+    channel.set_channel_group(&group).unwrap();
+    let path = path_from(&channel.get_DSP_head().unwrap());
+    let units = unit_ids(&applied);
+
+    assert_eq!(&path[1..4], &[mock::Handle::handle(&group_head), units[0], units[1]]);
+    assert_eq!(*path.last().unwrap(), mock::Handle::handle(&fmod.get_DSP_head().unwrap()));
+}
+
+#[test]
+fn dropping_the_chain_reconnects_the_head() {
+    let fmod = init();
+    let group = fmod.create_channel_group("effects").unwrap();
+    let head = group.get_DSP_head().unwrap();
+    let before = path_from(&head);
+
+    head.get_output(0).unwrap().1.set_mix(0.5f32).unwrap();
+    {
+        let applied = chain(&[rfmod::DspType::LowPass, rfmod::DspType::Echo])
+                          .apply_to_channel_group(&group).unwrap();
+
+        assert_eq!(path_from(&head).len(), before.len() + 2);
+        assert_eq!(applied.get_units()[1].1.get_output(0).unwrap().1.get_mix().unwrap(), 0.5f32);
+    }
+    assert_eq!(path_from(&head), before);
+    assert_eq!(head.get_output(0).unwrap().1.get_mix().unwrap(), 0.5f32);
+}
+
+#[test]
+fn the_system_head_is_refused() {
+    let fmod = init();
+    let head = fmod.get_DSP_head().unwrap();
+
+    assert!(chain(&[rfmod::DspType::Echo]).apply(&head).is_err());
+}
+
+#[test]
+fn inserting_creates_one_unit() {
+    let fmod = init();
+    let group = fmod.create_channel_group("effects").unwrap();
+    let mut applied = chain(&[rfmod::DspType::LowPass, rfmod::DspType::Echo])
+                          .apply_to_channel_group(&group).unwrap();
+    let old = unit_ids(&applied);
+    let created = count_created(&fmod);
+
+    applied.update(&chain(&[rfmod::DspType::LowPass, rfmod::DspType::Chorus,
+                            rfmod::DspType::Echo])).unwrap();
+    let units = unit_ids(&applied);
+
+    assert_eq!(count_created(&fmod), created + 1);
+    assert_eq!(count_calls(&old, "FMOD_DSP_Release"), 0);
+    assert_eq!((units[0], units[2]), (old[0], old[1]));
+    assert_eq!(&path_from(&group.get_DSP_head().unwrap())[1..4], &units[..]);
+}
+
+#[test]
+fn removing_releases_one_unit() {
+    let fmod = init();
+    let group = fmod.create_channel_group("effects").unwrap();
+    let mut applied = chain(&[rfmod::DspType::LowPass, rfmod::DspType::Chorus,
+                              rfmod::DspType::Echo]).apply_to_channel_group(&group).unwrap();
+    let old = unit_ids(&applied);
+    let created = count_created(&fmod);
+
+    applied.update(&chain(&[rfmod::DspType::LowPass, rfmod::DspType::Echo])).unwrap();
+
+    assert_eq!(count_created(&fmod), created);
+    assert_eq!(count_calls(&old[..1], "FMOD_DSP_Release"), 0);
+    assert_eq!(count_calls(&old[1..2], "FMOD_DSP_Release"), 1);
+    assert_eq!(count_calls(&old[2..], "FMOD_DSP_Release"), 0);
+    assert_eq!(unit_ids(&applied), vec![old[0], old[2]]);
+    assert_eq!(&path_from(&group.get_DSP_head().unwrap())[1..3], &[old[0], old[2]]);
+}
+
+#[test]
+fn reordering_moves_the_units() {
+    let fmod = init();
+    let group = fmod.create_channel_group("effects").unwrap();
+    let mut applied = chain(&[rfmod::DspType::LowPass, rfmod::DspType::Chorus,
+                              rfmod::DspType::Echo]).apply_to_channel_group(&group).unwrap();
+    let old = unit_ids(&applied);
+    let created = count_created(&fmod);
+    let mut reordered = chain(&[rfmod::DspType::Echo, rfmod::DspType::LowPass,
+                                rfmod::DspType::Chorus]);
+
+    reordered.get_effects_mut()[0].set_mix(0.25f32);
+    applied.update(&reordered).unwrap();
+    let units = unit_ids(&applied);
+
+    assert_eq!(count_created(&fmod), created);
+    assert_eq!(count_calls(&old, "FMOD_DSP_Release"), 0);
+    assert_eq!(units, vec![old[2], old[0], old[1]]);
+    assert_eq!(&path_from(&group.get_DSP_head().unwrap())[1..4], &units[..]);
+    assert_eq!(applied.get_units()[0].1.get_input(0).unwrap().1.get_mix().unwrap(), 0.25f32);
+}
+
+#[test]
+fn changed_parameters_are_the_only_ones_set() {
+    let fmod = init();
+    let group = fmod.create_channel_group("effects").unwrap();
+    let mut effects = chain(&[rfmod::DspType::Echo]);
+
+    effects.get_effects_mut()[0].set_parameter(0, 250f32);
+    effects.get_effects_mut()[0].set_parameter(1, 0.5f32);
+    let mut applied = effects.apply_to_channel_group(&group).unwrap();
+    let echo = mock::Handle::handle(&applied.get_units()[0].1);
+    let set_before = count_calls(&[echo], "FMOD_DSP_SetParameter");
+
+    effects.get_effects_mut()[0].set_parameter(1, 0.75f32);
+    applied.update(&effects).unwrap();
+    assert_eq!(count_calls(&[echo], "FMOD_DSP_SetParameter"), set_before + 1);
+}
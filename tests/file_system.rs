@@ -0,0 +1,146 @@
+extern crate rfmod;
+
+use rfmod::mock;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+/// Systems with a file system are limited: tests run one at a time.
+static LOCK : Mutex<()> = Mutex::new(());
+
+fn lock() -> MutexGuard<'static, ()> {
+    match LOCK.lock() {
+        Ok(l) => l,
+        Err(e) => e.into_inner(),
+    }
+}
+
+fn init() -> rfmod::Sys {
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init_with_parameters(32, rfmod::InitFlag(rfmod::INIT_NORMAL)).unwrap();
+    fmod
+}
+
+/// File system recording the names it opens, which only has `.wav` files.
+struct Recorder {
+    opened: Arc<Mutex<Vec<String>>>,
+}
+
+impl rfmod::FmodFileSystem for Recorder {
+    fn open(&self, name: &str) -> io::Result<Box<dyn rfmod::FmodFileStream>> {
+        self.opened.lock().unwrap().push(name.to_owned());
+        if name.ends_with(".wav") {
+            Ok(Box::new(io::Cursor::new(vec![0u8; 16])))
+        } else {
+            Err(io::Error::new(io::ErrorKind::NotFound, name.to_owned()))
+        }
+    }
+}
+
+fn recorder() -> (Recorder, Arc<Mutex<Vec<String>>>) {
+    let opened = Arc::new(Mutex::new(Vec::new()));
+
+    (Recorder { opened: opened.clone() }, opened)
+}
+
+struct Observer {
+    opened: Arc<Mutex<Vec<(String, u32)>>>,
+}
+
+impl rfmod::FileSystemObserver for Observer {
+    fn open(&self, file: &rfmod::ObservedFile) {
+        self.opened.lock().unwrap().push((file.get_name().to_owned(), file.get_size()));
+    }
+}
+
+fn non_blocking() -> Option<rfmod::Mode> {
+    Some(rfmod::Mode(rfmod::SOFTWARE | rfmod::NONBLOCKING))
+}
+
+#[test]
+fn each_system_opens_with_its_own_file_system() {
+    let _lock = lock();
+    let first = init();
+    let second = init();
+    let (file_system, first_opened) = recorder();
+    first.set_virtual_file_system(file_system, 0).unwrap();
+    let (file_system, second_opened) = recorder();
+    second.set_virtual_file_system(file_system, 0).unwrap();
+
+    first.create_sound("first.wav", None, None).unwrap();
+    second.create_sound("second.wav", None, None).unwrap();
+    assert_eq!(*first_opened.lock().unwrap(), vec!["first.wav".to_owned()]);
+    assert_eq!(*second_opened.lock().unwrap(), vec!["second.wav".to_owned()]);
+}
+
+#[test]
+fn non_blocking_opens_reach_the_system_which_created_the_sound() {
+    let _lock = lock();
+    let first = init();
+    let second = init();
+    let (file_system, first_opened) = recorder();
+    first.set_virtual_file_system(file_system, 0).unwrap();
+    let (file_system, second_opened) = recorder();
+    second.set_virtual_file_system(file_system, 0).unwrap();
+
+    let _sound = first.create_sound("late.wav", non_blocking(), None).unwrap();
+    assert!(first_opened.lock().unwrap().is_empty());
+    // the file is opened from another thread, outside of any call to the system
+    thread::spawn(|| mock::advance(0)).join().unwrap();
+    assert_eq!(*first_opened.lock().unwrap(), vec!["late.wav".to_owned()]);
+    assert!(second_opened.lock().unwrap().is_empty());
+}
+
+#[test]
+fn observers_belong_to_their_system() {
+    let _lock = lock();
+    let first = init();
+    let second = init();
+    let first_opened = Arc::new(Mutex::new(Vec::new()));
+    let second_opened = Arc::new(Mutex::new(Vec::new()));
+    let (file_system, _) = recorder();
+
+    first.set_virtual_file_system(file_system, 0).unwrap();
+    first.attach_file_system(Observer { opened: first_opened.clone() }).unwrap();
+    second.attach_file_system(Observer { opened: second_opened.clone() }).unwrap();
+    let _sound = first.create_sound("late.wav", non_blocking(), None).unwrap();
+    thread::spawn(|| mock::advance(0)).join().unwrap();
+    assert_eq!(*first_opened.lock().unwrap(), vec![("late.wav".to_owned(), 16)]);
+    assert!(second_opened.lock().unwrap().is_empty());
+}
+
+#[test]
+fn missing_files_are_not_found() {
+    let _lock = lock();
+    let fmod = init();
+    let (file_system, opened) = recorder();
+
+    fmod.set_virtual_file_system(file_system, 0).unwrap();
+    match fmod.create_sound("music.mp3", None, None) {
+        Err(e) => assert_eq!(e.get_status(), rfmod::Status::FileNotFound),
+        Ok(_) => panic!("a missing file was opened"),
+    }
+    assert_eq!(*opened.lock().unwrap(), vec!["music.mp3".to_owned()]);
+}
+
+#[test]
+fn released_systems_free_their_file_system() {
+    let _lock = lock();
+
+    for _ in 0..rfmod::MAX_FILE_SYSTEMS * 2 {
+        let fmod = init();
+        let (file_system, _) = recorder();
+
+        fmod.set_virtual_file_system(file_system, 0).unwrap();
+    }
+    let systems : Vec<rfmod::Sys> = (0..rfmod::MAX_FILE_SYSTEMS).map(|_| init()).collect();
+
+    for fmod in systems.iter() {
+        fmod.set_virtual_file_system(recorder().0, 0).unwrap();
+    }
+    match init().set_virtual_file_system(recorder().0, 0) {
+        Err(e) => assert_eq!(e.get_status(), rfmod::Status::Memory),
+        Ok(_) => panic!("more than MAX_FILE_SYSTEMS systems have a file system"),
+    }
+}
@@ -0,0 +1,244 @@
+extern crate rfmod;
+
+use rfmod::mock::{self, Value};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The mock state is global: tests taking or resetting calls run one at a time.
+static LOCK : Mutex<()> = Mutex::new(());
+
+fn lock() -> MutexGuard<'static, ()> {
+    match LOCK.lock() {
+        Ok(l) => l,
+        Err(e) => e.into_inner(),
+    }
+}
+
+fn init() -> rfmod::Sys {
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init_with_parameters(32, rfmod::InitFlag(rfmod::INIT_NORMAL)).unwrap();
+    fmod
+}
+
+#[test]
+fn volume_set_and_get() {
+    let _l = lock();
+    let fmod = init();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let channel = sound.play().unwrap();
+
+    channel.set_volume(0.25f32).unwrap();
+    assert_eq!(channel.get_volume().unwrap(), 0.25f32);
+    assert_eq!(mock::get_value(&channel, "volume"), Some(Value::Float(0.25f32)));
+}
+
+#[test]
+fn set_value_is_read_by_getters() {
+    let _l = lock();
+    let fmod = init();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let channel = sound.play().unwrap();
+
+    assert!(mock::set_value(&channel, "volume", Value::Float(0.75f32)));
+    assert_eq!(channel.get_volume().unwrap(), 0.75f32);
+    assert!(mock::get_calls_of(&channel).iter().all(|c| c.function != "FMOD_Channel_SetVolume"));
+}
+
+#[test]
+fn calls_are_recorded() {
+    let _l = lock();
+    let fmod = init();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let channel = sound.play().unwrap();
+
+    mock::take_calls();
+    channel.set_volume(0.5f32).unwrap();
+    channel.set_paused(true).unwrap();
+    channel.get_volume().unwrap();
+
+    let calls = mock::take_calls();
+    let functions : Vec<&str> = calls.iter().map(|c| c.function).collect();
+
+    assert_eq!(functions, ["FMOD_Channel_SetVolume", "FMOD_Channel_SetPaused",
+                           "FMOD_Channel_GetVolume"]);
+    assert_eq!(calls[0].args, [Value::Float(0.5f32)]);
+    assert_eq!(calls[1].args, [Value::Bool(true)]);
+    assert!(calls[2].args.is_empty());
+    assert!(calls.iter().all(|c| c.object == mock::Handle::handle(&channel)));
+    assert!(mock::get_calls().is_empty());
+}
+
+#[test]
+fn unsupported_calls_are_recorded() {
+    let _l = lock();
+    let fmod = init();
+
+    mock::take_calls();
+    assert_eq!(fmod.get_num_CDROM_drives().map_err(|e| e.get_status()),
+               Err(rfmod::Status::Unsupported));
+    assert_eq!(mock::take_calls()[0].function, "FMOD_System_GetNumCDROMDrives");
+}
+
+#[test]
+fn advance_moves_playing_channels() {
+    let _l = lock();
+    let fmod = init();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let playing = sound.play().unwrap();
+    let paused = sound.play().unwrap();
+
+    paused.set_paused(true).unwrap();
+    mock::advance(300);
+    assert_eq!(playing.get_position(rfmod::TIMEUNIT_MS).unwrap(), 300);
+    assert_eq!(paused.get_position(rfmod::TIMEUNIT_MS).unwrap(), 0);
+    assert!(playing.is_playing().unwrap());
+}
+
+#[test]
+fn advance_fires_end_callbacks() {
+    let _l = lock();
+    let fmod = init();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let channel = sound.play().unwrap();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let received = events.clone();
+
+    assert!(mock::set_value(&sound, "length", Value::Int(500)));
+    channel.set_callback(move |_, event| {
+        if let rfmod::ChannelEvent::End = event {
+            received.lock().unwrap().push("end");
+        }
+    }).unwrap();
+    mock::advance(499);
+    assert!(events.lock().unwrap().is_empty());
+    mock::advance(1);
+    assert_eq!(*events.lock().unwrap(), ["end"]);
+    mock::advance(1000);
+    assert_eq!(events.lock().unwrap().len(), 1);
+    assert!(!channel.is_playing().unwrap_or(false));
+}
+
+#[test]
+fn playing_steals_when_no_channel_is_free() {
+    let _l = lock();
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init().unwrap();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let first = sound.play().unwrap();
+    let second = sound.play().unwrap();
+
+    assert_eq!(first.get_volume().map_err(|e| e.get_status()),
+               Err(rfmod::Status::ChannelStolen));
+    assert!(second.is_playing().unwrap());
+}
+
+#[test]
+fn reset_invalidates_handles() {
+    let _l = lock();
+    let fmod = init();
+
+    mock::reset();
+    assert!(mock::get_calls().is_empty());
+    assert!(mock::get_value(&fmod, "initialized").is_none());
+    assert!(!mock::set_value(&fmod, "initialized", Value::Bool(true)));
+}
+
+fn is_released(system: usize) -> bool {
+    mock::get_calls().iter().any(|c| c.function == "FMOD_System_Release" && c.object == system)
+}
+
+#[test]
+fn release_fails_while_objects_are_alive() {
+    let _l = lock();
+    let mut fmod = init();
+    let system = mock::Handle::handle(&fmod);
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+
+    match fmod.release() {
+        Err(e) => assert_eq!(e.get_status(), rfmod::Status::InvalidHandle),
+        Ok(()) => panic!("the system was released while a sound is alive"),
+    }
+    assert!(!is_released(system));
+    fmod.create_sound("music.mp3", None, None).unwrap();
+    drop(sound);
+    fmod.release().unwrap();
+    assert!(is_released(system));
+}
+
+#[test]
+fn objects_from_getters_keep_the_system_alive() {
+    let _l = lock();
+    let fmod = init();
+    let system = mock::Handle::handle(&fmod);
+    let group = fmod.get_master_channel_group().unwrap();
+    let head = group.get_DSP_head().unwrap();
+
+    drop(group);
+    drop(fmod);
+    assert!(!is_released(system));
+    assert!(head.get_system_object().is_ok());
+    drop(head);
+    assert!(is_released(system));
+}
+
+#[test]
+fn handles_see_voices_restarted_on_their_channel() {
+    let _l = lock();
+    let fmod = init();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let handle = rfmod::ChannelHandle::new(sound.play().unwrap()).unwrap();
+    let mut channel = fmod.get_channel(handle.get_index()).unwrap();
+    let same_voice = rfmod::ChannelHandle::new(fmod.get_channel(handle.get_index()).unwrap())
+        .unwrap();
+
+    assert_eq!(same_voice.get_generation(), handle.get_generation());
+    assert!(handle.is_valid());
+    // same sound, same index and same raw channel: only the generation tells them apart
+    sound.play_with_parameters(false, &mut channel).unwrap();
+    assert_eq!(handle.verify(), Err(rfmod::error::ChannelError::Stolen));
+    let restarted = rfmod::ChannelHandle::new(channel).unwrap();
+
+    assert!(restarted.get_generation() != handle.get_generation());
+    assert!(restarted.is_valid());
+}
+
+#[test]
+fn channel_closures_are_dropped_with_their_voice() {
+    let _l = lock();
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init().unwrap();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let token = Arc::new(());
+    let first = sound.play().unwrap();
+    let owned = token.clone();
+
+    first.set_callback(move |_, _| { let _ = &owned; }).unwrap();
+    assert_eq!(Arc::strong_count(&token), 2);
+    // a single channel: the second voice steals the first one
+    let second = sound.play().unwrap();
+
+    assert_eq!(Arc::strong_count(&token), 1);
+    let owned = token.clone();
+
+    second.set_callback(move |_, _| { let _ = &owned; }).unwrap();
+    second.stop().unwrap();
+    assert_eq!(Arc::strong_count(&token), 1);
+}
+
+#[test]
+fn system_release_drops_channel_closures() {
+    let _l = lock();
+    let mut fmod = init();
+    let sound = fmod.create_sound("music.mp3", None, None).unwrap();
+    let channel = sound.play().unwrap();
+    let token = Arc::new(());
+    let owned = token.clone();
+
+    channel.set_callback(move |_, _| { let _ = &owned; }).unwrap();
+    drop(channel);
+    drop(sound);
+    fmod.release().unwrap();
+    assert_eq!(Arc::strong_count(&token), 1);
+}
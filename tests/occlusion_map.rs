@@ -0,0 +1,106 @@
+extern crate rfmod;
+
+use rfmod::Vector;
+
+fn init() -> rfmod::Sys {
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init_with_parameters(32, rfmod::InitFlag(rfmod::INIT_NORMAL)).unwrap();
+    fmod
+}
+
+fn vector(x: f32, y: f32, z: f32) -> Vector {
+    Vector { x: x, y: y, z: z }
+}
+
+/// Samples a 3x2x2 grid: x in {-1, 0, 1}, y in {0, 1} and z in {10, 2}, seen from the origin
+/// through a wall at z = 5 covering x >= 0.5 and y >= 0.25. Only the source at (1, 1, 10) is
+/// behind the wall.
+fn sample(fmod: &rfmod::Sys) -> (rfmod::Geometry, rfmod::OcclusionMap) {
+    let wall = fmod.create_geometry(1, 4).unwrap();
+
+    wall.add_polygon(0.5f32, 0.25f32, true,
+                     vec![vector(0.5f32, 0.25f32, 5f32), vector(10f32, 0.25f32, 5f32),
+                          vector(10f32, 10f32, 5f32), vector(0.5f32, 10f32, 5f32)]).unwrap();
+    let map = rfmod::OcclusionMap::sample(fmod, &Vector::new(), vector(-1f32, 0f32, 10f32),
+                                          vector(1f32, 1f32, -8f32), (3, 2, 2)).unwrap();
+    (wall, map)
+}
+
+#[test]
+fn cells_are_indexed_by_position() {
+    let fmod = init();
+    let (_wall, map) = sample(&fmod);
+
+    assert_eq!(map.get_size(), (3, 2, 2));
+    assert_eq!(map.get_position(2, 1, 1).z, 2f32);
+    for z in 0..2 {
+        for y in 0..2 {
+            for x in 0..3 {
+                let expected = if (x, y, z) == (2, 1, 0) {
+                    (0.5f32, 0.25f32)
+                } else {
+                    (0f32, 0f32)
+                };
+
+                assert_eq!(map.get(x, y, z), Some(expected), "cell {} {} {}", x, y, z);
+            }
+        }
+    }
+    assert_eq!(map.get(3, 0, 0), None);
+    assert_eq!(map.get(0, 2, 0), None);
+    assert_eq!(map.get(0, 0, 2), None);
+}
+
+#[test]
+fn inactive_geometry_doesnt_occlude() {
+    let fmod = init();
+    let (wall, _) = sample(&fmod);
+
+    wall.set_active(false).unwrap();
+    let map = rfmod::OcclusionMap::sample(&fmod, &Vector::new(), vector(1f32, 1f32, 10f32),
+                                          Vector::new(), (1, 1, 1)).unwrap();
+    assert_eq!(map.get(0, 0, 0), Some((0f32, 0f32)));
+}
+
+#[test]
+fn pgm_has_one_row_per_y() {
+    let fmod = init();
+    let (_wall, map) = sample(&fmod);
+    let mut pgm = Vec::new();
+
+    map.write_pgm(&mut pgm, 0, rfmod::OcclusionKind::Direct).unwrap();
+    let mut expected = b"P5\n3 2\n255\n".to_vec();
+    expected.extend_from_slice(&[255, 255, 255, 255, 255, 128]);
+    assert_eq!(pgm, expected);
+
+    pgm.clear();
+    map.write_pgm(&mut pgm, 0, rfmod::OcclusionKind::Reverb).unwrap();
+    assert_eq!(&pgm[11..], &[255, 255, 255, 255, 255, 191][..]);
+
+    pgm.clear();
+    map.write_pgm(&mut pgm, 1, rfmod::OcclusionKind::Direct).unwrap();
+    assert!(pgm[11..].iter().all(|&p| p == 255));
+    assert!(map.write_pgm(&mut pgm, 2, rfmod::OcclusionKind::Direct).is_err());
+}
+
+#[test]
+fn csv_has_one_line_per_cell() {
+    let fmod = init();
+    let (_wall, map) = sample(&fmod);
+    let mut csv = Vec::new();
+
+    map.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), 13);
+    assert_eq!(lines[0], "x,y,z,direct,reverb");
+    // x first, then y, then z
+    assert_eq!(lines[1], "-1,0,10,0,0");
+    assert_eq!(lines[2], "0,0,10,0,0");
+    assert_eq!(lines[4], "-1,1,10,0,0");
+    assert_eq!(lines[6], "1,1,10,0.5,0.25");
+    assert_eq!(lines[7], "-1,0,2,0,0");
+    assert_eq!(lines[12], "1,1,2,0,0");
+}
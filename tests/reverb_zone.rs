@@ -0,0 +1,84 @@
+extern crate rfmod;
+
+use rfmod::mock::{self, Value};
+use rfmod::{ReverbProperties, ReverbZone, ReverbZoneManager, Vector};
+
+fn init() -> rfmod::Sys {
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init_with_parameters(32, rfmod::InitFlag(rfmod::INIT_NORMAL)).unwrap();
+    fmod
+}
+
+fn at(x: f32) -> Vector {
+    let mut position = Vector::new();
+
+    position.x = x;
+    position
+}
+
+fn zone(x: f32, priority: i32) -> ReverbZone {
+    let mut zone = ReverbZone::new(at(x), 1f32, 20f32, ReverbProperties::cave());
+
+    zone.priority = priority;
+    zone
+}
+
+fn created_reverbs(fmod: &rfmod::Sys) -> usize {
+    mock::get_calls_of(fmod).iter().filter(|c| c.function == "FMOD_System_CreateReverb").count()
+}
+
+#[test]
+fn new_applies_the_ambient_properties() {
+    let fmod = init();
+    let zones = ReverbZoneManager::new(&fmod, 4).unwrap();
+
+    assert_eq!(zones.get_ambient_properties(), ReverbProperties::off());
+    assert_eq!(mock::get_value(&fmod, "reverb.environment"), Some(Value::Int(-1)));
+    assert!(mock::get_calls_of(&fmod).iter().any(|c| {
+        c.function == "FMOD_System_SetReverbAmbientProperties"
+    }));
+}
+
+#[test]
+fn update_orders_by_priority_then_distance() {
+    let fmod = init();
+    let mut zones = ReverbZoneManager::new(&fmod, 4).unwrap();
+    let far = zones.add_zone(zone(5f32, 0));
+    let near = zones.add_zone(zone(-1f32, 0));
+    let important = zones.add_zone(zone(8f32, 1));
+    let out_of_range = zones.add_zone(zone(50f32, 2));
+
+    zones.update(&Vector::new()).unwrap();
+    assert_eq!(zones.get_active_zones(), &[important, near, far][..]);
+    assert!(!zones.is_active(out_of_range));
+    assert_eq!(created_reverbs(&fmod), 3);
+    // the listener moves: only the order changes, the reverbs are kept
+    zones.update(&at(6f32)).unwrap();
+    assert_eq!(zones.get_active_zones(), &[important, far, near][..]);
+    assert_eq!(created_reverbs(&fmod), 3);
+}
+
+#[test]
+fn update_truncates_to_max_active() {
+    let fmod = init();
+    let mut zones = ReverbZoneManager::new(&fmod, 2).unwrap();
+    let far = zones.add_zone(zone(5f32, 0));
+    let near = zones.add_zone(zone(1f32, 0));
+    let important = zones.add_zone(zone(8f32, 1));
+
+    zones.update(&Vector::new()).unwrap();
+    assert_eq!(zones.get_active_zones(), &[important, near][..]);
+    assert!(!zones.is_active(far));
+    assert_eq!(created_reverbs(&fmod), 2);
+    zones.set_max_active(1);
+    zones.update(&Vector::new()).unwrap();
+    assert_eq!(zones.get_active_zones(), &[important][..]);
+    // the reverb of the nearest zone has been released: it gets a new one
+    zones.set_max_active(2);
+    zones.update(&Vector::new()).unwrap();
+    assert_eq!(zones.get_active_zones(), &[important, near][..]);
+    assert_eq!(created_reverbs(&fmod), 3);
+    zones.update(&at(100f32)).unwrap();
+    assert!(zones.get_active_zones().is_empty());
+}
@@ -0,0 +1,97 @@
+extern crate rfmod;
+
+use rfmod::error::CommandError;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+fn init() -> rfmod::Sys {
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init_with_parameters(32, rfmod::InitFlag(rfmod::INIT_NORMAL)).unwrap();
+    fmod
+}
+
+#[test]
+fn commands_run_on_the_owning_thread() {
+    let fmod = init();
+    let mut queue = rfmod::CommandQueue::new();
+    let shared = queue.get_shared();
+    let owner = thread::current().id();
+
+    let worker = thread::spawn(move || {
+        let sound = shared.create_sound("music.mp3", None).wait().unwrap();
+        let channel = shared.play_sound(sound).wait().unwrap();
+
+        shared.set_volume(channel, 0.5f32).wait().unwrap();
+        assert_eq!(shared.get_volume(channel).wait(), Ok(0.5f32));
+        assert_eq!(shared.is_playing(channel).wait(), Ok(true));
+        assert_eq!(shared.run(|_, _| Ok(thread::current().id())).wait(), Ok(owner));
+        shared.release_sound(sound).wait().unwrap();
+        assert_eq!(shared.release_sound(sound).wait(), Err(CommandError::UnknownId));
+    });
+
+    while !worker.is_finished() {
+        queue.update(&fmod).unwrap();
+        thread::sleep(Duration::from_millis(1));
+    }
+    worker.join().unwrap();
+    assert_eq!(queue.process(&fmod), 0);
+}
+
+#[test]
+fn try_get_before_and_after_process() {
+    let fmod = init();
+    let mut queue = rfmod::CommandQueue::new();
+    let shared = queue.get_shared();
+    let mut response = shared.run(|_, _| Ok(42));
+
+    assert!(!response.is_ready());
+    assert_eq!(response.try_get(), None);
+    assert_eq!(queue.process(&fmod), 1);
+    assert!(response.is_ready());
+    assert_eq!(response.try_get(), Some(Ok(42)));
+    // the result is only given once
+    assert_eq!(response.try_get(), None);
+}
+
+#[test]
+fn dropped_queue_disconnects() {
+    let queue = rfmod::CommandQueue::new();
+    let shared = queue.get_shared();
+    let pending = shared.run(|_, _| Ok(()));
+    let waiting = thread::spawn(move || pending.wait());
+
+    drop(queue);
+    assert_eq!(waiting.join().unwrap(), Err(CommandError::Disconnected));
+    // commands sent after the queue is gone are dropped right away
+    assert_eq!(shared.run(|_, _| Ok(())).wait(), Err(CommandError::Disconnected));
+}
+
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn response_wakes_its_task() {
+    let fmod = init();
+    let mut queue = rfmod::CommandQueue::new();
+    let shared = queue.get_shared();
+    let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(wakes.clone());
+    let mut context = Context::from_waker(&waker);
+    let mut response = shared.run(|_, _| Ok("done"));
+
+    assert_eq!(Pin::new(&mut response).poll(&mut context), Poll::Pending);
+    queue.process(&fmod);
+    assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+    assert_eq!(Pin::new(&mut response).poll(&mut context), Poll::Ready(Ok("done")));
+}
@@ -0,0 +1,210 @@
+extern crate rfmod;
+
+use rfmod::mock::{self, Value};
+use std::io::Cursor;
+
+fn init() -> rfmod::Sys {
+    let fmod = rfmod::Sys::new().unwrap();
+
+    fmod.init_with_parameters(32, rfmod::InitFlag(rfmod::INIT_NORMAL)).unwrap();
+    fmod
+}
+
+/// Creates a sound holding `data`, in `format` with `channels` channels at 8000 hz.
+fn sound_with_data(fmod: &rfmod::Sys, mode: u32, format: rfmod::SoundFormat, channels: i64,
+                   data: Vec<u8>) -> rfmod::Sound {
+    let sound = fmod.create_sound("data.wav", Some(rfmod::Mode(mode)), None).unwrap();
+
+    assert!(mock::set_value(&sound, "format", Value::Int(format as i64)));
+    assert!(mock::set_value(&sound, "channels", Value::Int(channels)));
+    assert!(mock::set_value(&sound, "frequency", Value::Float(8000f32)));
+    assert!(mock::set_data(&sound, data));
+    sound
+}
+
+/// Number of calls of `function` made on `sound`.
+fn count_calls(sound: &rfmod::Sound, function: &str) -> usize {
+    mock::get_calls_of(sound).iter().filter(|c| c.function == function).count()
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    data[offset] as u16 | (data[offset + 1] as u16) << 8
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u16_at(data, offset) as u32 | (u16_at(data, offset + 2) as u32) << 16
+}
+
+/// Checks the RIFF and fmt chunks of `wav`, and returns the offset of the chunk following them.
+fn check_header(wav: &[u8], format_tag: u16, channels: u16, bits: u16) -> usize {
+    let block_align = channels * bits / 8;
+
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(u32_at(wav, 4) as usize, wav.len() - 8);
+    assert_eq!(&wav[8..12], b"WAVE");
+    assert_eq!(&wav[12..16], b"fmt ");
+    let fmt_size = u32_at(wav, 16) as usize;
+    assert_eq!(u16_at(wav, 20), format_tag);
+    assert_eq!(u16_at(wav, 22), channels);
+    assert_eq!(u32_at(wav, 24), 8000);
+    assert_eq!(u32_at(wav, 28), 8000 * block_align as u32);
+    assert_eq!(u16_at(wav, 32), block_align);
+    assert_eq!(u16_at(wav, 34), bits);
+    20 + fmt_size
+}
+
+#[test]
+fn sample_pcm16() {
+    let fmod = init();
+    let data = vec![1u8, 2, 3, 4, 5, 6, 7, 8];
+    let sound = sound_with_data(&fmod, rfmod::CREATESAMPLE, rfmod::SoundFormat::PCM16, 2,
+                                data.clone());
+    let mut wav = Cursor::new(Vec::new());
+
+    sound.write_wav(&mut wav).unwrap();
+    let wav = wav.into_inner();
+    let offset = check_header(&wav, 1, 2, 16);
+
+    assert_eq!(offset, 36);
+    assert_eq!(&wav[offset..offset + 4], b"data");
+    assert_eq!(u32_at(&wav, offset + 4), 8);
+    assert_eq!(&wav[offset + 8..], &data[..]);
+    assert_eq!(count_calls(&sound, "FMOD_Sound_Lock"), 1);
+    assert_eq!(count_calls(&sound, "FMOD_Sound_Unlock"), 1);
+}
+
+#[test]
+fn sample_pcm8_is_unsigned_and_padded() {
+    let fmod = init();
+    let sound = sound_with_data(&fmod, rfmod::CREATESAMPLE, rfmod::SoundFormat::PCM8, 1,
+                                vec![0x00, 0x7f, 0x80]);
+    let mut wav = Cursor::new(Vec::new());
+
+    sound.write_wav(&mut wav).unwrap();
+    let wav = wav.into_inner();
+    let offset = check_header(&wav, 1, 1, 8);
+
+    assert_eq!(&wav[offset..offset + 4], b"data");
+    // the data chunk size doesn't count the pad byte, the RIFF one does
+    assert_eq!(u32_at(&wav, offset + 4), 3);
+    assert_eq!(&wav[offset + 8..], &[0x80, 0xff, 0x00, 0x00][..]);
+    assert_eq!(u32_at(&wav, 4) as usize, wav.len() - 8);
+}
+
+#[test]
+fn stream_float_has_fact_chunk() {
+    let fmod = init();
+    let mut data = Vec::new();
+
+    for sample in &[0.5f32, -0.25f32, 1f32] {
+        let bits = sample.to_bits();
+
+        data.extend_from_slice(&[bits as u8, (bits >> 8) as u8, (bits >> 16) as u8,
+                                 (bits >> 24) as u8]);
+    }
+    let sound = sound_with_data(&fmod, rfmod::CREATESTREAM, rfmod::SoundFormat::PCMFloat, 1,
+                                data.clone());
+    let mut wav = Cursor::new(Vec::new());
+
+    sound.write_wav(&mut wav).unwrap();
+    let wav = wav.into_inner();
+    let offset = check_header(&wav, 3, 1, 32);
+
+    // IEEE float data has the cbSize field and a fact chunk with the number of frames
+    assert_eq!(offset, 38);
+    assert_eq!(u16_at(&wav, 36), 0);
+    assert_eq!(&wav[offset..offset + 4], b"fact");
+    assert_eq!(u32_at(&wav, offset + 4), 4);
+    assert_eq!(u32_at(&wav, offset + 8), 3);
+    assert_eq!(&wav[offset + 12..offset + 16], b"data");
+    assert_eq!(u32_at(&wav, offset + 16), 12);
+    assert_eq!(&wav[offset + 20..], &data[..]);
+    // streams are decoded rather than locked
+    assert_eq!(count_calls(&sound, "FMOD_Sound_Lock"), 0);
+}
+
+#[test]
+fn writer_is_left_after_wav() {
+    let fmod = init();
+    let sound = sound_with_data(&fmod, rfmod::CREATESTREAM, rfmod::SoundFormat::PCM16, 1,
+                                vec![1, 2, 3, 4, 5]);
+    let mut wav = Cursor::new(vec![0xaau8; 200]);
+
+    wav.set_position(10);
+    sound.write_wav(&mut wav).unwrap();
+    // 44 bytes of header, 5 of data and the pad byte
+    assert_eq!(wav.position(), 10 + 44 + 5 + 1);
+    let wav = wav.into_inner();
+
+    assert_eq!(wav.len(), 200);
+    assert!(wav[..10].iter().all(|&b| b == 0xaa));
+    assert_eq!(&wav[10..14], b"RIFF");
+    assert_eq!(u32_at(&wav, 14), 36 + 5 + 1);
+    assert_eq!(&wav[10 + 44..10 + 50], &[1, 2, 3, 4, 5, 0][..]);
+    assert!(wav[60..].iter().all(|&b| b == 0xaa));
+}
+
+fn pcm16(samples: &[i16]) -> Vec<u8> {
+    let mut data = Vec::new();
+
+    for sample in samples {
+        data.push(*sample as u8);
+        data.push((*sample >> 8) as u8);
+    }
+    data
+}
+
+#[test]
+fn read_data_returns_partial_buffer() {
+    let fmod = init();
+    let sound = sound_with_data(&fmod, rfmod::OPENONLY | rfmod::CREATESTREAM,
+                                rfmod::SoundFormat::PCM16, 1, vec![1, 2, 3, 4, 5, 6]);
+    let mut buffer = [0u8; 4];
+
+    assert_eq!(sound.read_data(&mut buffer).unwrap(), 4);
+    assert_eq!(buffer, [1, 2, 3, 4]);
+    // FMOD returns FileEOF with the last bytes, which are still data
+    assert_eq!(sound.read_data(&mut buffer).unwrap(), 2);
+    assert_eq!(&buffer[..2], &[5, 6][..]);
+    assert_eq!(sound.read_data(&mut buffer).unwrap(), 0);
+}
+
+#[test]
+fn reader_decodes_range_as_i16() {
+    let fmod = init();
+    let samples = [0i16, 1, 10, 11, 20, 21, 30, 31, 40, 41, 50, 51];
+    let sound = sound_with_data(&fmod, rfmod::OPENONLY | rfmod::CREATESTREAM,
+                                rfmod::SoundFormat::PCM16, 2, pcm16(&samples));
+    let mut reader : rfmod::PcmReader<i16> = rfmod::PcmReader::with_range(&sound, 2, 3).unwrap();
+
+    assert_eq!(reader.get_format(), rfmod::SoundFormat::PCM16);
+    assert_eq!(reader.get_channels(), 2);
+    assert_eq!(reader.read_to_end().unwrap(), vec![20, 21, 30, 31, 40, 41]);
+    assert_eq!(reader.next(), None);
+    assert!(reader.get_error().is_none());
+    // the end of the range stops the reader before the end of the sound
+    let mut buffer = [0u8; 8];
+    assert_eq!(sound.read_data(&mut buffer).unwrap(), 4);
+    assert_eq!(&buffer[..4], &pcm16(&[50, 51])[..]);
+}
+
+#[test]
+fn reader_decodes_as_f32() {
+    let fmod = init();
+    let sound = sound_with_data(&fmod, rfmod::OPENONLY | rfmod::CREATESTREAM,
+                                rfmod::SoundFormat::PCM16, 1,
+                                pcm16(&[16384, -32768, 0, -8192, 8192]));
+    let mut reader : rfmod::PcmReader<f32> = rfmod::PcmReader::new(&sound).unwrap();
+    let mut out = [0f32; 3];
+
+    assert_eq!(reader.read(&mut out).unwrap(), 3);
+    assert_eq!(out, [0.5f32, -1f32, 0f32]);
+    // the sound ends before `out` is full
+    assert_eq!(reader.read(&mut out).unwrap(), 2);
+    assert_eq!(&out[..2], &[-0.25f32, 0.25f32][..]);
+    assert_eq!(reader.read(&mut out).unwrap(), 0);
+
+    // a range going past the end of the sound stops at its end
+    let reader : rfmod::PcmReader<f32> = rfmod::PcmReader::with_range(&sound, 3, 10).unwrap();
+    assert_eq!(reader.collect::<Vec<f32>>(), vec![-0.25f32, 0.25f32]);
+}